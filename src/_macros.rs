@@ -91,6 +91,35 @@ macro_rules! raw_metadata_getter_for_tables {
     };
 }
 
+macro_rules! build_table_copy_row {
+    ($idtype: ty, $extend: ident) => {
+        /// Append a copy of a row of `other`, including its
+        /// ragged columns and metadata, and return the new row id.
+        ///
+        /// The row is copied without decoding, so references to other
+        /// tables, such as node ids, are copied unchanged.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError::ErrorCode`]($crate::TskitError::ErrorCode)
+        ///   if `row` is out of range.
+        pub fn copy_row<I: Into<$idtype>>(
+            &mut self,
+            other: &Self,
+            row: I,
+        ) -> Result<$idtype, $crate::TskitError> {
+            let row: $crate::sys::bindings::tsk_id_t = row.into().into();
+            let id = self.as_ref().num_rows as $crate::sys::bindings::tsk_id_t;
+            // SAFETY: both tables are initialized and cannot
+            // be the same table, and row points to one id.
+            let rv = unsafe {
+                $crate::sys::bindings::$extend(self.table_.as_mut(), other.as_ref(), 1, &row, 0)
+            };
+            handle_tsk_return_value!(rv, id.into())
+        }
+    };
+}
+
 macro_rules! row_lending_iterator_get {
     () => {
         fn get(&self) -> Option<&Self::Item> {
@@ -135,6 +164,121 @@ macro_rules! build_table_column_slice_getter {
     };
 }
 
+// A ragged column has a data column and an offset column with
+// one more value than the number of rows, so that the values of
+// row `i` are `data[offset[i]..offset[i + 1]]`.
+macro_rules! build_ragged_column_slice_getters {
+    ($(#[$attr:meta])* => $column: ident, $length: ident, $name: ident, $cast: ty,
+     $(#[$offset_attr:meta])* => $offset: ident, $offset_name: ident) => {
+        $(#[$attr])*
+        pub fn $name(&self) -> &[$cast] {
+            if self.as_ref().$length == 0 {
+                return &[];
+            }
+            $crate::sys::generate_slice(self.as_ref().$column, self.as_ref().$length)
+        }
+
+        $(#[$offset_attr])*
+        pub fn $offset_name(&self) -> &[$crate::sys::bindings::tsk_size_t] {
+            if self.as_ref().$offset.is_null() {
+                return &[];
+            }
+            $crate::sys::generate_slice(self.as_ref().$offset, self.as_ref().num_rows + 1)
+        }
+    };
+}
+
+macro_rules! build_table_dump_text {
+    ($dump: ident) => {
+        /// Write the table in the text format of `tskit`.
+        ///
+        /// The metadata schema comes first, followed by a header
+        /// line with the column names and one tab-separated line per row.
+        /// Floating point values are rounded and metadata is written
+        /// as raw bytes, so the text is for inspection and for other
+        /// tools rather than for storage.
+        ///
+        /// See [`TableCollection::dump_text`](crate::TableCollection::dump_text)
+        /// for an example.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError`](crate::TskitError) if writing fails.
+        pub fn dump_text<W: std::io::Write>(
+            &self,
+            mut writer: W,
+        ) -> Result<(), $crate::TskitError> {
+            // SAFETY: the table is initialized and file is an open stream
+            $crate::sys::dump_text(&mut writer, |file| unsafe {
+                $crate::sys::bindings::$dump(self.as_ref(), file)
+            })
+        }
+    };
+}
+
+macro_rules! build_table_metadata_schema_api {
+    ($set_schema: ident) => {
+        /// Get the metadata schema of the table.
+        ///
+        /// Returns `None` if the table has no schema, or if the
+        /// schema is not valid UTF-8.
+        pub fn metadata_schema(&self) -> Option<&str> {
+            $crate::metadata::schema_str(
+                self.as_ref().metadata_schema,
+                self.as_ref().metadata_schema_length,
+            )
+        }
+
+        /// Set the metadata schema of the table.
+        ///
+        /// An empty `schema` removes the schema.
+        /// See [`MetadataSchema`](crate::metadata::MetadataSchema)
+        /// for schemas that are checked when they are created.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError`](crate::TskitError) if the `C` back end is unable
+        ///   to copy the schema.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// let mut nodes = tskit::NodeTable::new().unwrap();
+        /// assert!(nodes.metadata_schema().is_none());
+        /// nodes.set_metadata_schema(r#"{"codec":"json"}"#).unwrap();
+        /// assert_eq!(nodes.metadata_schema(), Some(r#"{"codec":"json"}"#));
+        /// ```
+        pub fn set_metadata_schema<S: AsRef<str>>(&mut self, schema: S) -> $crate::TskReturnValue {
+            let schema = schema.as_ref();
+            // SAFETY: the table is initialized and schema has
+            // schema.len() bytes, which are copied.
+            let rv = unsafe {
+                $crate::sys::bindings::$set_schema(
+                    self.table_.as_mut(),
+                    schema.as_ptr().cast(),
+                    schema.len() as $crate::sys::bindings::tsk_size_t,
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! build_table_collection_metadata_schema_setter {
+    ($(#[$attr:meta])* => $name: ident, $table: ident) => {
+        $(#[$attr])*
+        ///
+        /// An empty `schema` removes the schema.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError`] if the `C` back end is unable to copy the schema.
+        pub fn $name<S: AsRef<str>>(&mut self, schema: S) -> TskReturnValue {
+            self.views.$table().set_metadata_schema(schema)
+        }
+    };
+}
+
 macro_rules! build_table_column_slice_mut_getter {
     ($(#[$attr:meta])* => $column: ident, $name: ident, $cast: ty) => {
         $(#[$attr])*
@@ -188,6 +332,50 @@ macro_rules! delegate_table_view_api {
                 /// Return an iterator over provenances
                 pub fn provenances_iter(&self,) -> impl Iterator<Item = crate::provenance::ProvenanceTableRow> + '_;
 
+                /// Call the methods of `visitor` for each table,
+                /// in the order of the `tskit` file format.
+                ///
+                /// See [`TableVisitor`](crate::TableVisitor) for an example.
+                ///
+                /// # Errors
+                ///
+                /// The first error returned by `visitor`.
+                pub fn visit_tables<V: crate::TableVisitor>(&self, visitor: &mut V) -> Result<(), crate::TskitError>;
+
+                /// Write each table in the text format of `tskit`.
+                ///
+                /// Each table is written as by the `dump_text` method of
+                /// the table, such as [`NodeTable::dump_text`](crate::NodeTable::dump_text),
+                /// between a `#<table>#` line and a `#end#<table>` line,
+                /// where `<table>` is the plural name of the table.
+                /// The tables are written in the order of the `tskit` file format.
+                /// The provenance table is only written with the
+                /// `provenance` feature.
+                ///
+                /// # Errors
+                ///
+                /// * [`TskitError`](crate::TskitError) if writing fails.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+                /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+                /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+                /// tables.add_edge(0., 100., parent, child).unwrap();
+                ///
+                /// let mut text = vec![];
+                /// tables.dump_text(&mut text).unwrap();
+                /// let text = String::from_utf8(text).unwrap();
+                /// assert!(text.contains("#edges#\n"));
+                /// assert!(text.contains("id\tleft\tright\tparent\tchild\tmetadata\n0\t0.000\t100.000\t0\t1\t\n"));
+                ///
+                /// let mut nodes = vec![];
+                /// tables.nodes().dump_text(&mut nodes).unwrap();
+                /// assert!(String::from_utf8(nodes).unwrap().ends_with("1\t1\t0.000000\t-1\t-1\t\n"));
+                /// ```
+                pub fn dump_text<W: std::io::Write>(&self, writer: W) -> Result<(), crate::TskitError>;
+
                 /// Obtain a vector containing the indexes ("ids")
                 /// of all nodes for which [`crate::NodeFlags::is_sample`]
                 /// is `true`.
@@ -0,0 +1,264 @@
+//! Counts of derived alleles in sample sets.
+
+use streaming_iterator::StreamingIterator;
+
+use crate::NodeId;
+use crate::SiteId;
+use crate::TreeSequence;
+use crate::TskitError;
+use crate::Windows;
+
+/// Counts of derived alleles, with one row per site
+/// of each window and one column per sample set.
+///
+/// See [`TreeSequence::allele_counts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlleleCountMatrix {
+    /// The site id of each row. A site appears once for
+    /// each window that contains it.
+    pub sites: Vec<SiteId>,
+    /// The number of sample sets
+    pub num_sample_sets: usize,
+    /// The counts, stored with one row of
+    /// `num_sample_sets` values per site of each window.
+    pub counts: Vec<u32>,
+    /// The offsets of the rows of each window,
+    /// with one more value than there are windows.
+    pub window_offsets: Vec<usize>,
+}
+
+impl AlleleCountMatrix {
+    /// The number of rows, which is the number of sites
+    /// if there is a single unmasked window.
+    pub fn num_sites(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// The number of windows
+    pub fn num_windows(&self) -> usize {
+        self.window_offsets.len() - 1
+    }
+
+    /// The counts of row `index`, which are those of
+    /// site `sites[index]`.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn site_counts(&self, index: usize) -> Option<&[u32]> {
+        let start = index.checked_mul(self.num_sample_sets)?;
+        self.counts.get(start..start + self.num_sample_sets)
+    }
+
+    /// The ids of the sites in window `window`.
+    ///
+    /// Returns `None` if `window` is out of range.
+    pub fn window_sites(&self, window: usize) -> Option<&[SiteId]> {
+        let end = *self.window_offsets.get(window + 1)?;
+        Some(&self.sites[self.window_offsets[window]..end])
+    }
+
+    /// The counts of the sites in window `window`,
+    /// with one row per site.
+    ///
+    /// Returns `None` if `window` is out of range.
+    pub fn window_counts(&self, window: usize) -> Option<&[u32]> {
+        let end = *self.window_offsets.get(window + 1)?;
+        Some(
+            &self.counts
+                [self.window_offsets[window] * self.num_sample_sets..end * self.num_sample_sets],
+        )
+    }
+}
+
+// The number of nodes of each sample set in the subtree
+// of each node, updated as edges are inserted and removed
+struct SubtreeCounts {
+    parent: Vec<NodeId>,
+    counts: Vec<u32>,
+    num_sample_sets: usize,
+}
+
+impl SubtreeCounts {
+    fn new(num_nodes: usize, sample_sets: &[&[NodeId]]) -> Result<Self, TskitError> {
+        let num_sample_sets = sample_sets.len();
+        let mut counts = vec![0; num_nodes * num_sample_sets];
+        for (k, set) in sample_sets.iter().enumerate() {
+            for u in set.iter() {
+                match u.to_usize().filter(|&u| u < num_nodes) {
+                    Some(u) => counts[u * num_sample_sets + k] += 1,
+                    None => return Err(TskitError::IndexError),
+                }
+            }
+        }
+        Ok(Self {
+            parent: vec![NodeId::NULL; num_nodes],
+            counts,
+            num_sample_sets,
+        })
+    }
+
+    fn node_counts(&self, u: NodeId) -> &[u32] {
+        let start = u.as_usize() * self.num_sample_sets;
+        &self.counts[start..start + self.num_sample_sets]
+    }
+
+    // Add the counts of child to the ancestors of child
+    fn update(&mut self, child: NodeId, add: bool) {
+        let start = child.as_usize() * self.num_sample_sets;
+        let mut u = self.parent[child.as_usize()];
+        while !u.is_null() {
+            for k in 0..self.num_sample_sets {
+                let delta = self.counts[start + k];
+                let count = &mut self.counts[u.as_usize() * self.num_sample_sets + k];
+                match add {
+                    true => *count += delta,
+                    false => *count -= delta,
+                }
+            }
+            u = self.parent[u.as_usize()];
+        }
+    }
+
+    fn remove_edge(&mut self, child: NodeId) {
+        self.update(child, false);
+        self.parent[child.as_usize()] = NodeId::NULL;
+    }
+
+    fn insert_edge(&mut self, parent: NodeId, child: NodeId) {
+        self.parent[child.as_usize()] = parent;
+        self.update(child, true);
+    }
+}
+
+pub(crate) fn allele_counts(
+    treeseq: &TreeSequence,
+    sample_sets: &[&[NodeId]],
+    windows: &Windows,
+) -> Result<AlleleCountMatrix, TskitError> {
+    let sequence_length = treeseq.inner.sequence_length();
+    let intervals = windows.resolve(sequence_length);
+    let mask = crate::stats::check_windows(sequence_length, &intervals, windows.genome_mask())?;
+    let sites = treeseq.sites();
+    let mutations = treeseq.mutations();
+    let positions = sites.position_slice();
+    let mutation_sites = mutations.site_slice();
+    let num_sample_sets = sample_sets.len();
+    let carriers = mutation_carrier_counts(treeseq, sample_sets)?;
+
+    let mut counts = vec![0_u32; positions.len() * num_sample_sets];
+    for (m, site) in mutation_sites.iter().enumerate() {
+        let ancestral_state = sites.ancestral_state(*site).unwrap_or_default();
+        if mutations.derived_state(m as i32).unwrap_or_default() == ancestral_state {
+            continue;
+        }
+        let row = site.as_usize() * num_sample_sets;
+        for (count, &c) in counts[row..row + num_sample_sets]
+            .iter_mut()
+            .zip(&carriers[m * num_sample_sets..(m + 1) * num_sample_sets])
+        {
+            *count += c;
+        }
+    }
+
+    let mut rv = AlleleCountMatrix {
+        sites: vec![],
+        num_sample_sets,
+        counts: vec![],
+        window_offsets: vec![0],
+    };
+    for (left, right) in intervals {
+        let start = positions.partition_point(|&p| p < left);
+        let stop = positions.partition_point(|&p| p < right);
+        for site in start..stop {
+            if mask.is_some_and(|m| m.is_masked(positions[site])) {
+                continue;
+            }
+            rv.sites.push(SiteId::from(site as i32));
+            rv.counts
+                .extend_from_slice(&counts[site * num_sample_sets..(site + 1) * num_sample_sets]);
+        }
+        rv.window_offsets.push(rv.sites.len());
+    }
+    Ok(rv)
+}
+
+/// The number of nodes of each sample set that carry the state
+/// of each mutation, stored with one row of `sample_sets.len()`
+/// values per mutation.
+///
+/// A node carries the state of mutation `m` if `m` is the nearest
+/// mutation of its site on the path from the node to the root
+/// of the tree covering the site.
+/// Of several mutations on the same node, only the last one is
+/// carried.
+/// The mutation parent column is not used.
+pub(crate) fn mutation_carrier_counts(
+    treeseq: &TreeSequence,
+    sample_sets: &[&[NodeId]],
+) -> Result<Vec<u32>, TskitError> {
+    let positions = treeseq.sites().position_slice();
+    let mutation_sites = sites_of_mutations(positions.len(), treeseq.mutations().site_slice());
+    let mutation_nodes = treeseq.mutations().node_slice();
+    let num_sample_sets = sample_sets.len();
+    let mut subtrees = SubtreeCounts::new(treeseq.nodes().num_rows().as_usize(), sample_sets)?;
+
+    let mut rv = vec![0_u32; mutation_nodes.len() * num_sample_sets];
+    let mut site = 0;
+    let mut diffs = treeseq.edge_differences_iter()?;
+    while let Some(diffs) = diffs.next() {
+        for edge in diffs.edge_removals() {
+            subtrees.remove_edge(edge.child());
+        }
+        for edge in diffs.edge_insertions() {
+            subtrees.insert_edge(edge.parent(), edge.child());
+        }
+        while site < positions.len() && positions[site] < diffs.right() {
+            // The last mutation of the site on each node
+            let mut site_mutations = std::collections::HashMap::new();
+            for m in mutation_sites[site].clone() {
+                site_mutations.insert(mutation_nodes[m], m);
+            }
+            let mut carried = site_mutations
+                .keys()
+                .map(|&node| (node, vec![0_i64; num_sample_sets]))
+                .collect::<std::collections::HashMap<_, _>>();
+            for &node in site_mutations.keys() {
+                // The nodes below node carry its mutation rather
+                // than that of the nearest mutation above node.
+                let mut above = subtrees.parent[node.as_usize()];
+                while !above.is_null() && !site_mutations.contains_key(&above) {
+                    above = subtrees.parent[above.as_usize()];
+                }
+                for (k, &c) in subtrees.node_counts(node).iter().enumerate() {
+                    carried.get_mut(&node).unwrap()[k] += i64::from(c);
+                    if let Some(counts) = carried.get_mut(&above) {
+                        counts[k] -= i64::from(c);
+                    }
+                }
+            }
+            for (node, counts) in carried {
+                let m = site_mutations[&node];
+                for (count, c) in rv[m * num_sample_sets..(m + 1) * num_sample_sets]
+                    .iter_mut()
+                    .zip(counts)
+                {
+                    *count = c as u32;
+                }
+            }
+            site += 1;
+        }
+    }
+    Ok(rv)
+}
+
+// The range of mutations of each site, in a mutation table sorted by site
+fn sites_of_mutations(num_sites: usize, mutation_sites: &[SiteId]) -> Vec<std::ops::Range<usize>> {
+    let mut rv = vec![0..0; num_sites];
+    let mut start = 0;
+    while start < mutation_sites.len() {
+        let site = mutation_sites[start];
+        let end = start + mutation_sites[start..].partition_point(|&s| s == site);
+        rv[site.as_usize()] = start..end;
+        start = end;
+    }
+    rv
+}
@@ -0,0 +1,71 @@
+//! Command line interface to [`tskit::tools`].
+
+use std::io::Write;
+
+use clap::Parser;
+
+#[derive(clap::Parser)]
+#[command(version, about = "Tools for tree sequence files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Print the sizes of the tables
+    Summary {
+        /// Tree sequence file name
+        treefile: String,
+    },
+    /// Print a line for each tree, found by traversing it
+    Traverse {
+        /// Tree sequence file name
+        treefile: String,
+    },
+    /// Write the sites in VCF format
+    Vcf {
+        /// Tree sequence file name
+        treefile: String,
+        /// The name of the chromosome
+        #[arg(short, long, default_value = "1")]
+        contig: String,
+        /// Output file name, or standard output if not given
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let mut stdout = std::io::stdout().lock();
+    match cli.command {
+        Command::Summary { treefile } => {
+            let treeseq = tskit::TreeSequence::load(treefile)?;
+            write!(stdout, "{}", tskit::tools::summary(&treeseq))?;
+        }
+        Command::Traverse { treefile } => {
+            let treeseq = tskit::TreeSequence::load(treefile)?;
+            writeln!(stdout, "{}", tskit::tools::TreeReport::HEADER)?;
+            for tree in tskit::tools::traversal_report(&treeseq)? {
+                writeln!(stdout, "{}", tree)?;
+            }
+        }
+        Command::Vcf {
+            treefile,
+            contig,
+            output,
+        } => {
+            let treeseq = tskit::TreeSequence::load(treefile)?;
+            match output {
+                Some(output) => {
+                    let mut file = std::io::BufWriter::new(std::fs::File::create(output)?);
+                    tskit::tools::write_vcf(&treeseq, &contig, &mut file)?;
+                    file.flush()?;
+                }
+                None => tskit::tools::write_vcf(&treeseq, &contig, stdout)?,
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Canonical dumps of table collections.
+
+use crate::sys::bindings as ll_bindings;
+use crate::SubsetOptions;
+use crate::TableCollection;
+use crate::TableOutputOptions;
+use crate::TskReturnValue;
+
+// The provenance timestamp of fixed_provenance_timestamps
+const FIXED_TIMESTAMP: &str = "1970-01-01T00:00:00Z";
+
+/// Options for [`TableCollection::dump_canonical`].
+///
+/// # Examples
+///
+/// ```
+/// let options = tskit::CanonicalDumpOptions::default()
+///     .sort_rows(true)
+///     .fixed_provenance_timestamps(true);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalDumpOptions {
+    sort_rows: bool,
+    fixed_provenance_timestamps: bool,
+}
+
+impl CanonicalDumpOptions {
+    /// If `true`, write the rows of the tables in a stable order,
+    /// as given by [`TableCollection::canonicalise`] with
+    /// [`KEEP_UNREFERENCED`](crate::SubsetOptions::KEEP_UNREFERENCED),
+    /// so that tables that only differ by the order of their rows
+    /// are written as the same bytes.
+    ///
+    /// If `false`, the default, the order of rows is not changed.
+    pub fn sort_rows(self, sort_rows: bool) -> Self {
+        Self { sort_rows, ..self }
+    }
+
+    /// If `true`, write the timestamps of all provenance
+    /// records as `1970-01-01T00:00:00Z`.
+    pub fn fixed_provenance_timestamps(self, fixed_provenance_timestamps: bool) -> Self {
+        Self {
+            fixed_provenance_timestamps,
+            ..self
+        }
+    }
+}
+
+fn column<'a, T>(data: *const T, length: ll_bindings::tsk_size_t) -> &'a [T] {
+    if length == 0 {
+        &[]
+    } else {
+        crate::sys::generate_slice(data, length)
+    }
+}
+
+fn column_mut<'a>(data: *mut f64, length: ll_bindings::tsk_size_t) -> &'a mut [f64] {
+    if length == 0 {
+        &mut []
+    } else {
+        crate::sys::generate_slice_mut(data, length)
+    }
+}
+
+// Replace NaN payloads by that of f64::NAN, keeping unknown times
+fn normalise_nans(values: &mut [f64]) {
+    for x in values.iter_mut() {
+        if x.is_nan() && x.to_bits() != ll_bindings::TSK_UNKNOWN_TIME_HEX {
+            *x = f64::NAN;
+        }
+    }
+}
+
+fn fix_provenance_timestamps(tables: &mut ll_bindings::tsk_table_collection_t) -> TskReturnValue {
+    let provenances = &mut tables.provenances;
+    let num_rows = provenances.num_rows;
+    let record = column(provenances.record, provenances.record_length).to_vec();
+    let record_offset = column(provenances.record_offset, num_rows + 1).to_vec();
+    let timestamp = FIXED_TIMESTAMP.repeat(num_rows as usize);
+    let timestamp_offset = (0..=num_rows)
+        .map(|i| i * FIXED_TIMESTAMP.len() as ll_bindings::tsk_size_t)
+        .collect::<Vec<_>>();
+    // SAFETY: the columns have num_rows rows and are not part of the table
+    let rv = unsafe {
+        ll_bindings::tsk_provenance_table_set_columns(
+            provenances,
+            num_rows,
+            timestamp.as_ptr().cast(),
+            timestamp_offset.as_ptr(),
+            record.as_ptr().cast(),
+            record_offset.as_ptr(),
+        )
+    };
+    handle_tsk_return_value!(rv)
+}
+
+// Dump a copy of the tables changed as required by `options`
+pub(crate) fn dump(
+    tables: &TableCollection,
+    filename: &str,
+    options: CanonicalDumpOptions,
+) -> TskReturnValue {
+    let mut copy = tables.deepcopy()?;
+    if options.sort_rows {
+        copy.canonicalise(SubsetOptions::default().keep_unreferenced())?;
+    }
+    // SAFETY: the copy is initialized
+    let ll = unsafe { &mut *copy.as_mut_ptr() };
+    normalise_nans(column_mut(
+        ll.individuals.location,
+        ll.individuals.location_length,
+    ));
+    normalise_nans(column_mut(ll.nodes.time, ll.nodes.num_rows));
+    normalise_nans(column_mut(ll.edges.left, ll.edges.num_rows));
+    normalise_nans(column_mut(ll.edges.right, ll.edges.num_rows));
+    normalise_nans(column_mut(ll.migrations.left, ll.migrations.num_rows));
+    normalise_nans(column_mut(ll.migrations.right, ll.migrations.num_rows));
+    normalise_nans(column_mut(ll.migrations.time, ll.migrations.num_rows));
+    normalise_nans(column_mut(ll.sites.position, ll.sites.num_rows));
+    normalise_nans(column_mut(ll.mutations.time, ll.mutations.num_rows));
+    if options.fixed_provenance_timestamps {
+        fix_provenance_timestamps(ll)?;
+    }
+    crate::kastore::dump_with_canonical_uuid(filename, |tmp| {
+        copy.dump(tmp, TableOutputOptions::default())
+    })
+}
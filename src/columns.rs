@@ -0,0 +1,180 @@
+//! Descriptions of the columns of tables.
+
+/// The type of the values stored in a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ColumnDataType {
+    /// [`f64`]
+    Float64,
+    /// [`i32`], used for row ids
+    Int32,
+    /// [`u32`], used for flags
+    UInt32,
+    /// [`u8`], used for metadata and text
+    UInt8,
+}
+
+/// Description of a column of a table.
+///
+/// See [`TableColumns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnDescriptor {
+    /// The name of the column, as in the `tskit` file format.
+    pub name: &'static str,
+    /// The type of the values stored in the column.
+    pub data_type: ColumnDataType,
+    /// The name of the type of the values of the column
+    /// in this crate, such as `"NodeId"` or `"Time"`.
+    /// For ragged columns, this is the type of each value
+    /// in a row, except for text, which is `"str"`.
+    pub rust_type: &'static str,
+    /// `true` if each row has a variable number of values,
+    /// stored with an offset column.
+    pub ragged: bool,
+}
+
+const fn column(
+    name: &'static str,
+    data_type: ColumnDataType,
+    rust_type: &'static str,
+    ragged: bool,
+) -> ColumnDescriptor {
+    ColumnDescriptor {
+        name,
+        data_type,
+        rust_type,
+        ragged,
+    }
+}
+
+const METADATA: ColumnDescriptor = column("metadata", ColumnDataType::UInt8, "u8", true);
+
+/// The columns of a table.
+///
+/// Generic code may use this trait to process the columns of
+/// any table.
+/// Columns are listed in the order of the `tskit` file format.
+/// Offset columns of ragged columns are not listed.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::{ColumnDataType, TableColumns};
+///
+/// fn header<T: TableColumns>() -> String {
+///     T::COLUMNS
+///         .iter()
+///         .map(|c| c.name)
+///         .collect::<Vec<_>>()
+///         .join("\t")
+/// }
+///
+/// assert_eq!(header::<tskit::EdgeTable>(), "left\tright\tparent\tchild\tmetadata");
+/// assert_eq!(tskit::NodeTable::TABLE_NAME, "nodes");
+/// let time = tskit::NodeTable::column("time").unwrap();
+/// assert_eq!(time.data_type, ColumnDataType::Float64);
+/// assert_eq!(time.rust_type, "Time");
+/// assert!(!time.ragged);
+/// assert!(tskit::SiteTable::column("ancestral_state").unwrap().ragged);
+/// assert!(tskit::SiteTable::column("time").is_none());
+/// ```
+pub trait TableColumns {
+    /// The name of the table, as in the `tskit` file format.
+    const TABLE_NAME: &'static str;
+    /// The columns of the table.
+    const COLUMNS: &'static [ColumnDescriptor];
+
+    /// Get the column named `name`.
+    fn column(name: &str) -> Option<&'static ColumnDescriptor> {
+        Self::COLUMNS.iter().find(|c| c.name == name)
+    }
+}
+
+macro_rules! impl_table_columns {
+    ($table: ty, $name: literal, [$(($column: literal, $data_type: ident, $rust_type: literal, $ragged: literal)),*]) => {
+        impl TableColumns for $table {
+            const TABLE_NAME: &'static str = $name;
+            const COLUMNS: &'static [ColumnDescriptor] = &[
+                $(column($column, ColumnDataType::$data_type, $rust_type, $ragged),)*
+                METADATA,
+            ];
+        }
+    };
+}
+
+impl_table_columns!(
+    crate::IndividualTable,
+    "individuals",
+    [
+        ("flags", UInt32, "IndividualFlags", false),
+        ("location", Float64, "Location", true),
+        ("parents", Int32, "IndividualId", true)
+    ]
+);
+
+impl_table_columns!(
+    crate::NodeTable,
+    "nodes",
+    [
+        ("flags", UInt32, "NodeFlags", false),
+        ("time", Float64, "Time", false),
+        ("population", Int32, "PopulationId", false),
+        ("individual", Int32, "IndividualId", false)
+    ]
+);
+
+impl_table_columns!(
+    crate::EdgeTable,
+    "edges",
+    [
+        ("left", Float64, "Position", false),
+        ("right", Float64, "Position", false),
+        ("parent", Int32, "NodeId", false),
+        ("child", Int32, "NodeId", false)
+    ]
+);
+
+impl_table_columns!(
+    crate::MigrationTable,
+    "migrations",
+    [
+        ("left", Float64, "Position", false),
+        ("right", Float64, "Position", false),
+        ("node", Int32, "NodeId", false),
+        ("source", Int32, "PopulationId", false),
+        ("dest", Int32, "PopulationId", false),
+        ("time", Float64, "Time", false)
+    ]
+);
+
+impl_table_columns!(
+    crate::SiteTable,
+    "sites",
+    [
+        ("position", Float64, "Position", false),
+        ("ancestral_state", UInt8, "str", true)
+    ]
+);
+
+impl_table_columns!(
+    crate::MutationTable,
+    "mutations",
+    [
+        ("site", Int32, "SiteId", false),
+        ("node", Int32, "NodeId", false),
+        ("parent", Int32, "MutationId", false),
+        ("time", Float64, "Time", false),
+        ("derived_state", UInt8, "str", true)
+    ]
+);
+
+impl_table_columns!(crate::PopulationTable, "populations", []);
+
+#[cfg(feature = "provenance")]
+impl TableColumns for crate::provenance::ProvenanceTable {
+    const TABLE_NAME: &'static str = "provenances";
+    const COLUMNS: &'static [ColumnDescriptor] = &[
+        column("timestamp", ColumnDataType::UInt8, "str", true),
+        column("record", ColumnDataType::UInt8, "str", true),
+    ];
+}
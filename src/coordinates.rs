@@ -0,0 +1,398 @@
+//! Transformations of genomic coordinates.
+
+use crate::Position;
+use crate::TskitError;
+
+/// A monotonic, piecewise-linear map between two
+/// coordinate systems.
+///
+/// The map is defined by knots, `(from, to)` pairs, that are
+/// strictly increasing in both coordinates.
+/// Positions between knots are linearly interpolated and
+/// positions outside of the knots are extrapolated using
+/// the slope of the first or last segment.
+///
+/// Such maps describe, for example, the conversion of
+/// physical positions to genetic map (cM) coordinates or
+/// a liftover between genome assemblies.
+/// See [`TableCollection::transform_coordinates`](crate::TableCollection::transform_coordinates).
+///
+/// # Examples
+///
+/// ```
+/// let map = tskit::CoordinateMap::new([(0., 0.), (100., 1.), (200., 3.)]).unwrap();
+/// assert_eq!(map.forward(50.), 0.5);
+/// assert_eq!(map.forward(150.), 2.0);
+/// let inverse = map.inverse();
+/// assert_eq!(inverse.forward(2.0), 150.);
+/// ```
+///
+/// Knots must be strictly increasing:
+///
+/// ```
+/// assert!(tskit::CoordinateMap::new([(0., 0.), (100., 0.)]).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordinateMap {
+    from: Vec<Position>,
+    to: Vec<Position>,
+}
+
+impl CoordinateMap {
+    /// Create a new map from `(from, to)` knots.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if there are fewer than two knots,
+    ///   if any value is not finite, or if the knots are not strictly
+    ///   increasing in both coordinates.
+    pub fn new<P: Into<Position>>(
+        knots: impl IntoIterator<Item = (P, P)>,
+    ) -> Result<Self, TskitError> {
+        let (from, to): (Vec<Position>, Vec<Position>) =
+            knots.into_iter().map(|(f, t)| (f.into(), t.into())).unzip();
+        if from.len() < 2 {
+            return Err(TskitError::RangeError(format!(
+                "a coordinate map requires at least 2 knots, got {}",
+                from.len()
+            )));
+        }
+        for column in [&from, &to] {
+            if column.iter().any(|x| !f64::from(*x).is_finite()) {
+                return Err(TskitError::RangeError(
+                    "coordinate map knots must be finite".to_string(),
+                ));
+            }
+            if column.windows(2).any(|w| w[0] >= w[1]) {
+                return Err(TskitError::RangeError(
+                    "coordinate map knots must be strictly increasing".to_string(),
+                ));
+            }
+        }
+        Ok(Self { from, to })
+    }
+
+    /// The knots of the map.
+    pub fn knots(&self) -> impl Iterator<Item = (Position, Position)> + '_ {
+        self.from.iter().copied().zip(self.to.iter().copied())
+    }
+
+    /// Map `position` to the new coordinate system.
+    pub fn forward<P: Into<Position>>(&self, position: P) -> Position {
+        let x = f64::from(position.into());
+        // The segment used for interpolation
+        let i = self
+            .from
+            .partition_point(|f| *f <= x)
+            .clamp(1, self.from.len() - 1);
+        let (x0, x1) = (f64::from(self.from[i - 1]), f64::from(self.from[i]));
+        let (y0, y1) = (f64::from(self.to[i - 1]), f64::from(self.to[i]));
+        if x == x0 {
+            // Knots map exactly
+            return y0.into();
+        }
+        (y0 + (x - x0) * (y1 - y0) / (x1 - x0)).into()
+    }
+
+    /// The inverse map.
+    ///
+    /// # Note
+    ///
+    /// Due to floating-point rounding, `inverse().forward(forward(x))`
+    /// may differ from `x` in the last few bits except at the knots.
+    pub fn inverse(&self) -> Self {
+        Self {
+            from: self.to.clone(),
+            to: self.from.clone(),
+        }
+    }
+}
+
+/// A piecewise-constant rate along the genome, such as
+/// a recombination map.
+///
+/// The map divides `[0, sequence_length)` into intervals, each with
+/// a rate per unit of sequence length.
+/// For recombination maps, rates are crossovers per base pair
+/// per generation, so that the cumulative mass of the rate
+/// is the genetic position in Morgans.
+///
+/// Use [`RateMap::cumulative_mass`] to convert physical positions to
+/// genetic positions, and [`RateMap::position_at_mass`] for the
+/// inverse, for example to place breakpoints in a simulation.
+///
+/// # Examples
+///
+/// ```
+/// // No recombination in [10, 20)
+/// let map = tskit::RateMap::new([0., 10., 20., 40.], [0.5, 0.0, 0.25]).unwrap();
+/// assert_eq!(map.sequence_length(), 40.);
+/// assert_eq!(map.total_mass(), 10.);
+/// assert_eq!(map.cumulative_mass(15.).unwrap(), 5.);
+/// assert_eq!(map.cumulative_mass(30.).unwrap(), 7.5);
+/// assert_eq!(map.position_at_mass(7.5).unwrap(), 30.);
+/// // The first position with the mass
+/// assert_eq!(map.position_at_mass(5.).unwrap(), 10.);
+/// assert!(map.cumulative_mass(41.).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateMap {
+    positions: Vec<Position>,
+    rates: Vec<f64>,
+    // The cumulative mass at each position
+    masses: Vec<f64>,
+}
+
+impl RateMap {
+    /// Create a new map.
+    ///
+    /// # Parameters
+    ///
+    /// * `positions`: the boundaries of the intervals, starting at zero,
+    ///   with the sequence length last.
+    /// * `rates`: the rate in each interval.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if `positions` does not start at zero,
+    ///   is not finite and strictly increasing, or does not have one more
+    ///   value than `rates`, or if any rate is negative or not finite.
+    pub fn new<P: Into<Position>>(
+        positions: impl IntoIterator<Item = P>,
+        rates: impl IntoIterator<Item = f64>,
+    ) -> Result<Self, TskitError> {
+        let positions = positions
+            .into_iter()
+            .map(|p| p.into())
+            .collect::<Vec<Position>>();
+        let rates = rates.into_iter().collect::<Vec<f64>>();
+        if positions.len() != rates.len() + 1 || rates.is_empty() {
+            return Err(TskitError::RangeError(format!(
+                "a rate map requires one more position than rates and at least one rate, got {} positions and {} rates",
+                positions.len(),
+                rates.len()
+            )));
+        }
+        if positions[0] != 0.0
+            || positions.iter().any(|p| !f64::from(*p).is_finite())
+            || positions.windows(2).any(|w| w[0] >= w[1])
+        {
+            return Err(TskitError::RangeError(
+                "rate map positions must start at zero and be finite and strictly increasing"
+                    .to_string(),
+            ));
+        }
+        if rates.iter().any(|r| !(r.is_finite() && *r >= 0.0)) {
+            return Err(TskitError::RangeError(
+                "rates must be finite and non-negative".to_string(),
+            ));
+        }
+        let mut masses = Vec::with_capacity(positions.len());
+        masses.push(0.0);
+        for (w, rate) in positions.windows(2).zip(&rates) {
+            let mass = masses.last().unwrap() + rate * f64::from(w[1] - w[0]);
+            masses.push(mass);
+        }
+        Ok(Self {
+            positions,
+            rates,
+            masses,
+        })
+    }
+
+    /// Read a genetic map in HapMap format.
+    ///
+    /// The format has a header line followed by lines of four
+    /// whitespace-separated columns: the chromosome, the position in
+    /// base pairs, the rate in cM/Mb, and the genetic position in cM.
+    /// Empty lines are skipped.
+    /// Rates are calculated from the genetic positions, which
+    /// must not decrease, and are converted to Morgans per base pair.
+    /// The rate before the first position is zero, so the
+    /// genetic position of the first line does not matter.
+    /// The chromosome and rate columns are not used.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader`: the input.
+    /// * `sequence_length`: the sequence length of the map. If `None`,
+    ///   this is the last position. The rate after the last position
+    ///   is zero.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] for lines without a valid
+    ///   position and genetic position.
+    /// * [`TskitError::RangeError`] if positions are not strictly
+    ///   increasing, if genetic positions decrease, or if
+    ///   `sequence_length` is less than the last position.
+    /// * [`TskitError::LibraryError`] for errors reading from `reader`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let hapmap = "\
+    /// Chromosome\tPosition(bp)\tRate(cM/Mb)\tMap(cM)
+    /// chr1\t100\t2.0\t0.0
+    /// chr1\t200\t0.5\t0.0002
+    /// chr1\t300\t0.0\t0.00025
+    /// ";
+    /// let map = tskit::RateMap::read_hapmap(hapmap.as_bytes(), Some(400.)).unwrap();
+    /// assert_eq!(map.positions(), [0., 100., 200., 300., 400.].map(tskit::Position::from));
+    /// assert_eq!(map.rates()[0], 0.0);
+    /// assert!((map.rates()[1] - 2e-8).abs() < 1e-20);
+    /// assert!((map.cumulative_mass(300.).unwrap() - 2.5e-6).abs() < 1e-18);
+    /// ```
+    pub fn read_hapmap<R: std::io::BufRead, P: Into<Position>>(
+        reader: R,
+        sequence_length: Option<P>,
+    ) -> Result<Self, TskitError> {
+        // The position and genetic position in Morgans of each line
+        let mut rows = vec![];
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| TskitError::LibraryError(e.to_string()))?;
+            if i == 0 || line.trim().is_empty() {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match (
+                fields.get(1).and_then(|f| f.parse::<f64>().ok()),
+                fields.get(3).and_then(|f| f.parse::<f64>().ok()),
+            ) {
+                (Some(position), Some(cm)) if fields.len() == 4 => {
+                    rows.push((position, cm / 100.0))
+                }
+                _ => {
+                    return Err(TskitError::ValueError {
+                        got: format!("{:?} on line {}", line, i + 1),
+                        expected: "a HapMap genetic map line".to_string(),
+                    })
+                }
+            }
+        }
+        let mut positions = vec![];
+        let mut rates = vec![];
+        if rows.first().map_or(true, |row| row.0 != 0.0) {
+            positions.push(0.0);
+        }
+        positions.extend(rows.iter().map(|row| row.0));
+        rates.resize(positions.len().saturating_sub(rows.len()), 0.0);
+        for w in rows.windows(2) {
+            let rate = (w[1].1 - w[0].1) / (w[1].0 - w[0].0);
+            if rate < 0.0 {
+                return Err(TskitError::RangeError(format!(
+                    "genetic positions decrease between positions {} and {}",
+                    w[0].0, w[1].0
+                )));
+            }
+            rates.push(rate);
+        }
+        if let Some(sequence_length) = sequence_length {
+            let sequence_length = f64::from(sequence_length.into());
+            let last = *positions.last().unwrap();
+            if sequence_length < last {
+                return Err(TskitError::RangeError(format!(
+                    "sequence length {} is less than the last position {}",
+                    sequence_length, last
+                )));
+            }
+            if sequence_length > last {
+                positions.push(sequence_length);
+                rates.push(0.0);
+            }
+        }
+        Self::new(positions, rates)
+    }
+
+    /// The sequence length.
+    pub fn sequence_length(&self) -> Position {
+        *self.positions.last().unwrap()
+    }
+
+    /// The boundaries of the intervals, from zero
+    /// to the sequence length.
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    /// The rate in each interval.
+    pub fn rates(&self) -> &[f64] {
+        &self.rates
+    }
+
+    /// The cumulative mass over the whole sequence, such as
+    /// the total genetic length in Morgans.
+    pub fn total_mass(&self) -> f64 {
+        *self.masses.last().unwrap()
+    }
+
+    /// The cumulative mass of the rate over `[0, position)`, such
+    /// as the genetic position in Morgans of a physical position.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::RangeError`] if `position` is not
+    /// in `[0, sequence_length]`.
+    pub fn cumulative_mass<P: Into<Position>>(&self, position: P) -> Result<f64, TskitError> {
+        let position = position.into();
+        if !(position >= 0.0 && position <= self.sequence_length()) {
+            return Err(TskitError::RangeError(format!(
+                "position {} is not in [0, {}]",
+                position,
+                self.sequence_length()
+            )));
+        }
+        let i = self
+            .positions
+            .partition_point(|p| *p <= position)
+            .min(self.rates.len());
+        Ok(self.masses[i - 1] + self.rates[i - 1] * f64::from(position - self.positions[i - 1]))
+    }
+
+    /// The first position at which the cumulative mass is `mass`.
+    ///
+    /// This is the inverse of [`RateMap::cumulative_mass`].
+    /// To draw breakpoints in a simulation, pass uniform
+    /// random values in `[0, total_mass)`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::RangeError`] if `mass` is not in `[0, total_mass]`.
+    pub fn position_at_mass(&self, mass: f64) -> Result<Position, TskitError> {
+        if !(mass >= 0.0 && mass <= self.total_mass()) {
+            return Err(TskitError::RangeError(format!(
+                "mass {} is not in [0, {}]",
+                mass,
+                self.total_mass()
+            )));
+        }
+        // The first position with at least the mass
+        let i = self.masses.partition_point(|m| *m < mass);
+        if self.masses[i] == mass {
+            return Ok(self.positions[i]);
+        }
+        // Otherwise, the mass is inside an interval with a positive rate
+        let x = f64::from(self.positions[i - 1]) + (mass - self.masses[i - 1]) / self.rates[i - 1];
+        Ok(x.min(f64::from(self.positions[i])).into())
+    }
+
+    /// The map from positions to cumulative masses, such as
+    /// from physical to genetic positions.
+    ///
+    /// Use the result with
+    /// [`TableCollection::transform_coordinates`](crate::TableCollection::transform_coordinates)
+    /// to convert tables to genetic coordinates.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::RangeError`] if any rate is zero, as the
+    /// map would not be strictly increasing.
+    pub fn coordinate_map(&self) -> Result<CoordinateMap, TskitError> {
+        CoordinateMap::new(
+            self.positions
+                .iter()
+                .zip(&self.masses)
+                .map(|(p, m)| (*p, Position::from(*m))),
+        )
+    }
+}
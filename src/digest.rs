@@ -0,0 +1,321 @@
+//! Content digests of table collections.
+
+use sha2::{Digest, Sha256};
+
+use crate::sys::bindings as ll_bindings;
+use crate::TableEqualityOptions;
+
+// Changing the encoding below requires changing this tag,
+// so that digests from different encodings never collide.
+const ENCODING_TAG: &[u8] = b"tskit-rust table collection digest v1";
+
+/// A SHA-256 digest of the contents of a table collection.
+///
+/// Two table collections that are
+/// [equal](crate::TableCollection::equals) under the
+/// [`TableEqualityOptions`] used to compute their digests
+/// have the same digest.
+/// The digest depends only on the table data, and not on the
+/// platform or on the memory allocated for the tables,
+/// so it may be used as a cache key or to check that two
+/// runs of a program give identical output.
+///
+/// The [`Display`](std::fmt::Display) implementation writes
+/// the digest as lowercase hexadecimal.
+///
+/// See [`TableCollection::digest`](crate::TableCollection::digest)
+/// and [`TreeSequence::digest`](crate::TreeSequence::digest).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TableCollectionDigest([u8; 32]);
+
+impl TableCollectionDigest {
+    /// The bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<TableCollectionDigest> for [u8; 32] {
+    fn from(value: TableCollectionDigest) -> Self {
+        value.0
+    }
+}
+
+impl std::fmt::Display for TableCollectionDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for TableCollectionDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TableCollectionDigest({})", self)
+    }
+}
+
+// Column elements, written as little-endian bytes
+trait Element: Copy {
+    fn write(self, sha: &mut Sha256);
+}
+
+macro_rules! impl_element {
+    ($($t: ty),*) => {
+        $(
+            impl Element for $t {
+                fn write(self, sha: &mut Sha256) {
+                    sha.update(&self.to_le_bytes())
+                }
+            }
+        )*
+    };
+}
+
+impl_element!(i32, u32, u64);
+
+impl Element for f64 {
+    // The bits are hashed so that, like the comparisons
+    // of tables, NaN values with the same bits are equal.
+    fn write(self, sha: &mut Sha256) {
+        self.to_bits().write(sha)
+    }
+}
+
+fn column<'a, T>(data: *const T, length: ll_bindings::tsk_size_t) -> &'a [T] {
+    if length == 0 {
+        &[]
+    } else {
+        crate::sys::generate_slice(data, length)
+    }
+}
+
+// The offset of row 0 is always 0, and is not part of the encoding,
+// so that empty tables do not read the offset column.
+fn offsets<'a>(
+    offset: *const ll_bindings::tsk_size_t,
+    num_rows: ll_bindings::tsk_size_t,
+) -> &'a [ll_bindings::tsk_size_t] {
+    if num_rows == 0 {
+        &[]
+    } else {
+        // SAFETY: offset columns have num_rows + 1 values
+        crate::sys::generate_slice(unsafe { offset.add(1) }, num_rows)
+    }
+}
+
+// Each value is prefixed by a name and a length, so that
+// distinct table collections have distinct encodings.
+struct Encoder {
+    sha: Sha256,
+}
+
+impl Encoder {
+    fn name(&mut self, name: &str) {
+        self.bytes(name.as_bytes())
+    }
+
+    fn values<T: Element>(&mut self, values: &[T]) {
+        (values.len() as u64).write(&mut self.sha);
+        for &v in values {
+            v.write(&mut self.sha)
+        }
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        (bytes.len() as u64).write(&mut self.sha);
+        self.sha.update(bytes)
+    }
+
+    fn chars(&mut self, data: *const std::os::raw::c_char, length: ll_bindings::tsk_size_t) {
+        self.bytes(column(data.cast::<u8>(), length))
+    }
+
+    fn column<T: Element>(&mut self, name: &str, data: *const T, length: ll_bindings::tsk_size_t) {
+        self.name(name);
+        self.values(column(data, length))
+    }
+
+    fn ragged<T: Element>(
+        &mut self,
+        name: &str,
+        data: *const T,
+        length: ll_bindings::tsk_size_t,
+        offset: *const ll_bindings::tsk_size_t,
+        num_rows: ll_bindings::tsk_size_t,
+    ) {
+        self.column(name, data, length);
+        self.values(offsets(offset, num_rows));
+    }
+
+    fn ragged_chars(
+        &mut self,
+        name: &str,
+        data: *const std::os::raw::c_char,
+        length: ll_bindings::tsk_size_t,
+        offset: *const ll_bindings::tsk_size_t,
+        num_rows: ll_bindings::tsk_size_t,
+    ) {
+        self.name(name);
+        self.chars(data, length);
+        self.values(offsets(offset, num_rows));
+    }
+
+    fn table(&mut self, name: &str, num_rows: ll_bindings::tsk_size_t) {
+        self.name(name);
+        num_rows.write(&mut self.sha);
+    }
+
+    fn schema(&mut self, data: *const std::os::raw::c_char, length: ll_bindings::tsk_size_t) {
+        self.name("metadata_schema");
+        self.chars(data, length);
+    }
+}
+
+macro_rules! table_metadata {
+    ($encoder: ident, $table: expr, $options: ident) => {
+        if !$options.contains(TableEqualityOptions::IGNORE_METADATA) {
+            $encoder.ragged_chars(
+                "metadata",
+                $table.metadata,
+                $table.metadata_length,
+                $table.metadata_offset,
+                $table.num_rows,
+            );
+            $encoder.schema($table.metadata_schema, $table.metadata_schema_length);
+        }
+    };
+}
+
+pub(crate) fn digest(
+    tables: &ll_bindings::tsk_table_collection_t,
+    options: TableEqualityOptions,
+) -> TableCollectionDigest {
+    let mut encoder = Encoder { sha: Sha256::new() };
+    let e = &mut encoder;
+    e.bytes(ENCODING_TAG);
+
+    e.name("sequence_length");
+    tables.sequence_length.write(&mut e.sha);
+    e.name("time_units");
+    e.chars(tables.time_units, tables.time_units_length);
+    if !options.intersects(
+        TableEqualityOptions::IGNORE_METADATA | TableEqualityOptions::IGNORE_TS_METADATA,
+    ) {
+        e.name("metadata");
+        e.chars(tables.metadata, tables.metadata_length);
+        e.schema(tables.metadata_schema, tables.metadata_schema_length);
+    }
+
+    let t = &tables.individuals;
+    e.table("individuals", t.num_rows);
+    e.column("flags", t.flags, t.num_rows);
+    e.ragged(
+        "location",
+        t.location,
+        t.location_length,
+        t.location_offset,
+        t.num_rows,
+    );
+    e.ragged(
+        "parents",
+        t.parents,
+        t.parents_length,
+        t.parents_offset,
+        t.num_rows,
+    );
+    table_metadata!(e, t, options);
+
+    let t = &tables.nodes;
+    e.table("nodes", t.num_rows);
+    e.column("flags", t.flags, t.num_rows);
+    e.column("time", t.time, t.num_rows);
+    e.column("population", t.population, t.num_rows);
+    e.column("individual", t.individual, t.num_rows);
+    table_metadata!(e, t, options);
+
+    let t = &tables.edges;
+    e.table("edges", t.num_rows);
+    e.column("left", t.left, t.num_rows);
+    e.column("right", t.right, t.num_rows);
+    e.column("parent", t.parent, t.num_rows);
+    e.column("child", t.child, t.num_rows);
+    table_metadata!(e, t, options);
+
+    let t = &tables.migrations;
+    e.table("migrations", t.num_rows);
+    e.column("left", t.left, t.num_rows);
+    e.column("right", t.right, t.num_rows);
+    e.column("node", t.node, t.num_rows);
+    e.column("source", t.source, t.num_rows);
+    e.column("dest", t.dest, t.num_rows);
+    e.column("time", t.time, t.num_rows);
+    table_metadata!(e, t, options);
+
+    let t = &tables.sites;
+    e.table("sites", t.num_rows);
+    e.column("position", t.position, t.num_rows);
+    e.ragged_chars(
+        "ancestral_state",
+        t.ancestral_state,
+        t.ancestral_state_length,
+        t.ancestral_state_offset,
+        t.num_rows,
+    );
+    table_metadata!(e, t, options);
+
+    let t = &tables.mutations;
+    e.table("mutations", t.num_rows);
+    e.column("site", t.site, t.num_rows);
+    e.column("node", t.node, t.num_rows);
+    e.column("parent", t.parent, t.num_rows);
+    e.column("time", t.time, t.num_rows);
+    e.ragged_chars(
+        "derived_state",
+        t.derived_state,
+        t.derived_state_length,
+        t.derived_state_offset,
+        t.num_rows,
+    );
+    table_metadata!(e, t, options);
+
+    let t = &tables.populations;
+    e.table("populations", t.num_rows);
+    table_metadata!(e, t, options);
+
+    if !options.contains(TableEqualityOptions::IGNORE_PROVENANCE) {
+        let t = &tables.provenances;
+        e.table("provenances", t.num_rows);
+        if !options.contains(TableEqualityOptions::IGNORE_TIMESTAMPS) {
+            e.ragged_chars(
+                "timestamp",
+                t.timestamp,
+                t.timestamp_length,
+                t.timestamp_offset,
+                t.num_rows,
+            );
+        }
+        e.ragged_chars(
+            "record",
+            t.record,
+            t.record_length,
+            t.record_offset,
+            t.num_rows,
+        );
+    }
+
+    let r = &tables.reference_sequence;
+    e.name("reference_sequence");
+    e.name("data");
+    e.chars(r.data, r.data_length);
+    e.name("url");
+    e.chars(r.url, r.url_length);
+    if !options.contains(TableEqualityOptions::IGNORE_METADATA) {
+        e.name("metadata");
+        e.chars(r.metadata, r.metadata_length);
+        e.schema(r.metadata_schema, r.metadata_schema_length);
+    }
+
+    TableCollectionDigest(encoder.sha.finalize().into())
+}
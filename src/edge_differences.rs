@@ -234,6 +234,83 @@ impl EdgeDifferencesIterator {
     pub fn edge_insertions(&self) -> impl Iterator<Item = EdgeInsertion> + '_ {
         EdgeDifferences::<Insertion>::new(&self.insertion)
     }
+
+    /// The number of edges removed from the previous tree.
+    pub fn num_edge_removals(&self) -> usize {
+        self.edge_removals().count()
+    }
+
+    /// The number of edges inserted into the current tree.
+    pub fn num_edge_insertions(&self) -> usize {
+        self.edge_insertions().count()
+    }
+
+    /// The number of nodes whose parent differs between
+    /// the previous tree and the current tree.
+    ///
+    /// This is the number of entries that differ between the
+    /// parent arrays of the two trees.
+    /// An edge that is removed and inserted again with the
+    /// same parent and child does not change the parent array.
+    pub fn num_parent_changes(&self) -> usize {
+        let mut removed = self
+            .edge_removals()
+            .map(|e| (e.child(), e.parent()))
+            .collect::<Vec<_>>();
+        removed.sort_unstable();
+        let mut rv = 0;
+        let mut reinserted = 0;
+        for e in self.edge_insertions() {
+            match removed.binary_search_by_key(&e.child(), |&(c, _)| c) {
+                Ok(i) => {
+                    reinserted += 1;
+                    if removed[i].1 != e.parent() {
+                        rv += 1;
+                    }
+                }
+                Err(_) => rv += 1,
+            }
+        }
+        // Children that lost their parent
+        rv + removed.len() - reinserted
+    }
+
+    /// Summarize the edge differences leading to the current tree.
+    ///
+    /// `num_edges` is the number of edges in the previous tree,
+    /// which is 0 for the first tree.
+    pub fn transition(&self, num_edges: usize) -> TreeTransition {
+        let num_edge_removals = self.num_edge_removals();
+        let num_edge_insertions = self.num_edge_insertions();
+        TreeTransition {
+            left: self.left(),
+            right: self.right(),
+            num_edge_removals,
+            num_edge_insertions,
+            num_parent_changes: self.num_parent_changes(),
+            num_edges: num_edges + num_edge_insertions - num_edge_removals,
+        }
+    }
+}
+
+/// The size of the change from one tree to the next.
+///
+/// See [`TreeSequence::transition_sizes`](crate::TreeSequence::transition_sizes)
+/// and [`EdgeDifferencesIterator::transition`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TreeTransition {
+    /// The left coordinate of the tree after the transition
+    pub left: Position,
+    /// The right coordinate of the tree after the transition
+    pub right: Position,
+    /// The number of edges removed
+    pub num_edge_removals: usize,
+    /// The number of edges inserted
+    pub num_edge_insertions: usize,
+    /// The number of nodes whose parent changed
+    pub num_parent_changes: usize,
+    /// The number of edges in the tree after the transition
+    pub num_edges: usize,
 }
 
 impl streaming_iterator::StreamingIterator for EdgeDifferencesIterator {
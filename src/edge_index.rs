@@ -0,0 +1,213 @@
+//! Interval queries on edge tables.
+
+use crate::EdgeId;
+use crate::EdgeTable;
+use crate::NodeId;
+use crate::Position;
+
+// Edges sorted by group and then by left coordinate.
+// Each group has a segment tree of the maximum right
+// coordinate, so that queries visit only the subtrees
+// containing overlapping edges.
+#[derive(Debug, Clone)]
+struct GroupedIntervals {
+    edges: Vec<EdgeId>,
+    left: Vec<f64>,
+    // The edges of group g are edges[offsets[g]..offsets[g + 1]]
+    offsets: Vec<usize>,
+    max_right: Vec<f64>,
+    // The tree of group g is max_right[tree_offsets[g]..tree_offsets[g + 1]]
+    tree_offsets: Vec<usize>,
+}
+
+impl GroupedIntervals {
+    fn new(edges: &EdgeTable, num_groups: usize, group: impl Fn(usize) -> usize) -> Self {
+        let left = edges.left_slice_raw();
+        let right = edges.right_slice_raw();
+
+        let mut offsets = vec![0; num_groups + 1];
+        for e in 0..left.len() {
+            offsets[group(e) + 1] += 1;
+        }
+        for g in 0..num_groups {
+            offsets[g + 1] += offsets[g];
+        }
+        let mut next = offsets.clone();
+        let mut sorted = vec![0; left.len()];
+        for e in 0..left.len() {
+            let g = group(e);
+            sorted[next[g]] = e;
+            next[g] += 1;
+        }
+        for g in 0..num_groups {
+            // Stable, so that ties keep the order of edge ids
+            sorted[offsets[g]..offsets[g + 1]].sort_by(|&a, &b| left[a].total_cmp(&left[b]));
+        }
+
+        let mut tree_offsets = vec![0; num_groups + 1];
+        for g in 0..num_groups {
+            let n = offsets[g + 1] - offsets[g];
+            let size = if n == 0 { 0 } else { 2 * n.next_power_of_two() };
+            tree_offsets[g + 1] = tree_offsets[g] + size;
+        }
+        let mut max_right = vec![f64::NEG_INFINITY; tree_offsets[num_groups]];
+        for g in 0..num_groups {
+            let tree = &mut max_right[tree_offsets[g]..tree_offsets[g + 1]];
+            let size = tree.len() / 2;
+            for (i, &e) in sorted[offsets[g]..offsets[g + 1]].iter().enumerate() {
+                tree[size + i] = right[e];
+            }
+            for i in (1..size).rev() {
+                tree[i] = tree[2 * i].max(tree[2 * i + 1]);
+            }
+        }
+
+        Self {
+            edges: sorted.iter().map(|&e| EdgeId::from(e as i32)).collect(),
+            left: sorted.iter().map(|&e| left[e]).collect(),
+            offsets,
+            max_right,
+            tree_offsets,
+        }
+    }
+
+    fn num_groups(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn overlapping(&self, group: usize, left: f64, right: f64) -> Vec<EdgeId> {
+        let mut rv = vec![];
+        if group >= self.num_groups() || left >= right {
+            return rv;
+        }
+        let start = self.offsets[group];
+        let stop = self.offsets[group + 1];
+        let tree = &self.max_right[self.tree_offsets[group]..self.tree_offsets[group + 1]];
+        if tree.is_empty() {
+            return rv;
+        }
+        // Only the edges starting before `right` may overlap
+        let candidates = self.left[start..stop].partition_point(|&l| l < right);
+        let mut stack = vec![(1, 0, tree.len() / 2)];
+        while let Some((node, lo, hi)) = stack.pop() {
+            if lo >= candidates || tree[node] <= left {
+                continue;
+            }
+            if hi - lo == 1 {
+                rv.push(self.edges[start + lo]);
+            } else {
+                let mid = (lo + hi) / 2;
+                stack.push((2 * node + 1, mid, hi));
+                stack.push((2 * node, lo, mid));
+            }
+        }
+        rv
+    }
+}
+
+/// An index of the genomic intervals of edges.
+///
+/// The index answers queries for the edges overlapping a
+/// genomic interval without scanning the whole edge table.
+/// Query results are in increasing order of left coordinate,
+/// with ties in order of edge id.
+///
+/// The index is a snapshot of the edge table when it was built
+/// and does not change if the table changes.
+///
+/// See [`EdgeTable::interval_index`] and
+/// [`TreeSequenceCache::edge_interval_index`](crate::TreeSequenceCache::edge_interval_index).
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.add_edge(0., 40., 0, 1).unwrap();
+/// tables.add_edge(30., 100., 0, 2).unwrap();
+/// tables.add_edge(50., 100., 3, 1).unwrap();
+///
+/// let index = tables.edges().interval_index();
+/// assert_eq!(index.overlapping(35., 45.), [0, 1].map(tskit::EdgeId::from));
+/// assert_eq!(index.overlapping(40., 50.), [tskit::EdgeId::from(1)]);
+/// assert_eq!(index.parent_edges(3, 0., 60.), [tskit::EdgeId::from(2)]);
+/// assert_eq!(index.child_edges(1, 0., 100.), [0, 2].map(tskit::EdgeId::from));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EdgeIntervalIndex {
+    all: GroupedIntervals,
+    by_parent: GroupedIntervals,
+    by_child: GroupedIntervals,
+}
+
+impl EdgeIntervalIndex {
+    pub(crate) fn new(edges: &EdgeTable) -> Self {
+        let parent = edges.parent_slice();
+        let child = edges.child_slice();
+        let num_nodes = parent
+            .iter()
+            .chain(child.iter())
+            .filter_map(|n| n.to_usize())
+            .max()
+            .map_or(0, |n| n + 1);
+        let group = |nodes: &[NodeId], e: usize| nodes[e].to_usize().unwrap_or(num_nodes);
+        Self {
+            all: GroupedIntervals::new(edges, 1, |_| 0),
+            // Edges with NULL nodes go to an extra, unqueried group
+            by_parent: GroupedIntervals::new(edges, num_nodes + 1, |e| group(parent, e)),
+            by_child: GroupedIntervals::new(edges, num_nodes + 1, |e| group(child, e)),
+        }
+    }
+
+    /// The number of edges in the index.
+    pub fn num_edges(&self) -> usize {
+        self.all.edges.len()
+    }
+
+    /// The edges overlapping `[left, right)`.
+    ///
+    /// An edge overlaps the interval if it covers any position of it.
+    /// The result is empty if `left >= right`.
+    pub fn overlapping<P: Into<Position>>(&self, left: P, right: P) -> Vec<EdgeId> {
+        self.all
+            .overlapping(0, left.into().into(), right.into().into())
+    }
+
+    /// The edges with parent `node` overlapping `[left, right)`.
+    ///
+    /// The result is empty if `node` is not the parent of any edge.
+    pub fn parent_edges<N: Into<NodeId>, P: Into<Position>>(
+        &self,
+        node: N,
+        left: P,
+        right: P,
+    ) -> Vec<EdgeId> {
+        Self::node_edges(&self.by_parent, node.into(), left.into(), right.into())
+    }
+
+    /// The edges with child `node` overlapping `[left, right)`.
+    ///
+    /// The result is empty if `node` is not the child of any edge.
+    pub fn child_edges<N: Into<NodeId>, P: Into<Position>>(
+        &self,
+        node: N,
+        left: P,
+        right: P,
+    ) -> Vec<EdgeId> {
+        Self::node_edges(&self.by_child, node.into(), left.into(), right.into())
+    }
+
+    fn node_edges(
+        intervals: &GroupedIntervals,
+        node: NodeId,
+        left: Position,
+        right: Position,
+    ) -> Vec<EdgeId> {
+        match node.to_usize() {
+            // The last group holds edges with NULL nodes
+            Some(u) if u + 1 < intervals.num_groups() => {
+                intervals.overlapping(u, left.into(), right.into())
+            }
+            _ => vec![],
+        }
+    }
+}
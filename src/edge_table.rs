@@ -18,6 +18,14 @@ pub struct EdgeTableRow {
     pub metadata: Option<Vec<u8>>,
 }
 
+impl EdgeTableRow {
+    /// The length of the genomic interval of the edge,
+    /// `right - left`.
+    pub fn span(&self) -> Position {
+        self.right - self.left
+    }
+}
+
 impl PartialEq for EdgeTableRow {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -87,6 +95,12 @@ impl<'a> EdgeTableRowView<'a> {
             metadata: None,
         }
     }
+
+    /// The length of the genomic interval of the edge,
+    /// `right - left`.
+    pub fn span(&self) -> Position {
+        self.right - self.left
+    }
 }
 
 impl<'a> PartialEq for EdgeTableRowView<'a> {
@@ -192,6 +206,11 @@ pub struct EdgeTable {
 }
 
 impl EdgeTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
     pub fn new() -> Result<Self, TskitError> {
         let table_ = sys::EdgeTable::new(0)?;
         Ok(Self { table_ })
@@ -200,7 +219,8 @@ impl EdgeTable {
     pub(crate) fn new_from_table(
         edges: *mut ll_bindings::tsk_edge_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(edges).unwrap();
+        let ptr = std::ptr::NonNull::new(edges)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::EdgeTable::new_borrowed(ptr) };
         Ok(EdgeTable { table_ })
     }
@@ -220,7 +240,7 @@ impl EdgeTable {
     ///
     /// # Returns
     ///
-    /// * `Some(parent)` if `u` is valid.
+    /// * `Some(parent)` if `row` is valid.
     /// * `None` otherwise.
     pub fn parent<E: Into<EdgeId> + Copy>(&self, row: E) -> Option<NodeId> {
         sys::tsk_column_access::<NodeId, _, _, _>(row.into(), self.as_ref().parent, self.num_rows())
@@ -230,7 +250,7 @@ impl EdgeTable {
     ///
     /// # Returns
     ///
-    /// * `Some(child)` if `u` is valid.
+    /// * `Some(child)` if `row` is valid.
     /// * `None` otherwise.
     pub fn child<E: Into<EdgeId> + Copy>(&self, row: E) -> Option<NodeId> {
         sys::tsk_column_access::<NodeId, _, _, _>(row.into(), self.as_ref().child, self.num_rows())
@@ -240,7 +260,7 @@ impl EdgeTable {
     ///
     /// # Returns
     ///
-    /// * `Some(position)` if `u` is valid.
+    /// * `Some(position)` if `row` is valid.
     /// * `None` otherwise.
     pub fn left<E: Into<EdgeId> + Copy>(&self, row: E) -> Option<Position> {
         sys::tsk_column_access::<Position, _, _, _>(row.into(), self.as_ref().left, self.num_rows())
@@ -250,7 +270,7 @@ impl EdgeTable {
     ///
     /// # Returns
     ///
-    /// * `Some(position)` if `u` is valid.
+    /// * `Some(position)` if `row` is valid.
     /// * `None` otherwise.
     pub fn right<E: Into<EdgeId> + Copy>(&self, row: E) -> Option<Position> {
         sys::tsk_column_access::<Position, _, _, _>(
@@ -260,6 +280,37 @@ impl EdgeTable {
         )
     }
 
+    /// Return the ``(left, right)`` interval of row ``row`` of the table.
+    ///
+    /// # Returns
+    ///
+    /// * `Some((left, right))` if `row` is valid.
+    /// * `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut edges = tskit::EdgeTable::default();
+    /// edges.add_row(5., 20., 2, 1).unwrap();
+    /// assert_eq!(edges.interval(0), Some((5.0.into(), 20.0.into())));
+    /// assert_eq!(edges.span(0), Some(15.0.into()));
+    /// assert_eq!(edges.row(0).unwrap().span(), 15.0);
+    /// assert!(edges.interval(1).is_none());
+    /// ```
+    pub fn interval<E: Into<EdgeId> + Copy>(&self, row: E) -> Option<(Position, Position)> {
+        Some((self.left(row)?, self.right(row)?))
+    }
+
+    /// Return ``right - left`` for row ``row`` of the table.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(span)` if `row` is valid.
+    /// * `None` otherwise.
+    pub fn span<E: Into<EdgeId> + Copy>(&self, row: E) -> Option<Position> {
+        self.interval(row).map(|(left, right)| right - left)
+    }
+
     /// Retrieve decoded metadata for a `row`.
     ///
     /// # Returns
@@ -332,6 +383,26 @@ impl EdgeTable {
         Some(view)
     }
 
+    /// Get the sum of `right - left` over all edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut edges = tskit::EdgeTable::default();
+    /// assert_eq!(edges.total_span(), 0.0);
+    /// edges.add_row(0., 10., 2, 0).unwrap();
+    /// edges.add_row(5., 20., 2, 1).unwrap();
+    /// assert_eq!(edges.total_span(), 25.0);
+    /// ```
+    pub fn total_span(&self) -> Position {
+        self.left_slice()
+            .iter()
+            .zip(self.right_slice())
+            .fold(Position::from(0.0), |span, (left, right)| {
+                span + (*right - *left)
+            })
+    }
+
     build_table_column_slice_getter!(
         /// Get the left column as a slice
         => left, left_slice, Position);
@@ -342,7 +413,7 @@ impl EdgeTable {
         /// Get the right column as a slice
         => right, right_slice, Position);
     build_table_column_slice_getter!(
-        /// Get the left column as a slice of [`f64`] 
+        /// Get the right column as a slice of [`f64`]
         => right, right_slice_raw, f64);
     build_table_column_slice_getter!(
         /// Get the parent column as a slice
@@ -356,12 +427,33 @@ impl EdgeTable {
     build_table_column_slice_getter!(
         /// Get the child column as a slice of the underlying integer type
         => child, child_slice_raw, ll_bindings::tsk_id_t);
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_edge_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_edge_table_set_metadata_schema);
+
+    /// Build an index for queries of the edges
+    /// overlapping genomic intervals.
+    ///
+    /// See [`EdgeIntervalIndex`](crate::EdgeIntervalIndex).
+    pub fn interval_index(&self) -> crate::EdgeIntervalIndex {
+        crate::EdgeIntervalIndex::new(self)
+    }
 
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(EdgeId, tsk_edge_table_extend);
+
     /// Add a row without metadata.
     ///
     /// See [crate::TableCollection::add_edge] for examples
@@ -0,0 +1,252 @@
+//! Conversion of trees and tree sequences to and from `petgraph` graphs.
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Graph;
+
+use crate::NodeFlags;
+use crate::NodeId;
+use crate::NodeTable;
+use crate::Position;
+use crate::TableCollection;
+use crate::TableSortOptions;
+use crate::Time;
+use crate::Tree;
+use crate::TreeSequence;
+use crate::TskitError;
+
+/// The weight of a node of a [`petgraph::Graph`].
+///
+/// Requires the `petgraph` feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphNode {
+    /// The id of the node in the node table.
+    pub id: NodeId,
+    /// The time of the node.
+    pub time: Time,
+    /// The flags of the node.
+    pub flags: NodeFlags,
+}
+
+/// The weight of an edge of a [`petgraph::Graph`],
+/// which is the genomic interval of the edge.
+///
+/// Edges of a graph point from parents to children.
+///
+/// Requires the `petgraph` feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphEdge {
+    /// The left coordinate of the edge.
+    pub left: Position,
+    /// The right coordinate of the edge.
+    pub right: Position,
+}
+
+impl GraphEdge {
+    /// The span of the edge.
+    pub fn span(&self) -> Position {
+        self.right - self.left
+    }
+}
+
+// Adds the nodes for which keep is true, in order of id,
+// and returns the index of each node in the graph
+fn add_nodes(
+    graph: &mut Graph<GraphNode, GraphEdge>,
+    nodes: &NodeTable,
+    keep: &[bool],
+) -> Vec<NodeIndex> {
+    let times = nodes.time_slice();
+    let flags = nodes.flags_slice();
+    let mut indexes = vec![NodeIndex::end(); keep.len()];
+    for (u, _) in keep.iter().enumerate().filter(|(_, &keep)| keep) {
+        indexes[u] = graph.add_node(GraphNode {
+            id: NodeId::from(u as i32),
+            time: times[u],
+            flags: flags[u],
+        });
+    }
+    indexes
+}
+
+impl Tree<'_> {
+    /// Convert the tree to a [`petgraph::Graph`].
+    ///
+    /// The graph contains the nodes of the tree, in order of id,
+    /// and an edge from the parent of each node to the node,
+    /// whose interval is that of the tree.
+    ///
+    /// Requires the `petgraph` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the node table cannot be accessed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let ts = tskit::testing::balanced(4, 10.).unwrap();
+    /// let mut trees = ts.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// let tree = trees.next().unwrap();
+    /// let graph = tree.to_petgraph().unwrap();
+    /// assert_eq!(graph.node_count(), 7);
+    /// assert_eq!(graph.edge_count(), 6);
+    /// // The root is the only node without incoming edges
+    /// let roots = petgraph::algo::toposort(&graph, None).unwrap();
+    /// assert_eq!(graph[roots[0]].id, 6);
+    /// ```
+    pub fn to_petgraph(&self) -> Result<Graph<GraphNode, GraphEdge>, TskitError> {
+        let tables = self.inner.treeseq().as_ref().tables;
+        // SAFETY: the tables of a tree sequence are not null
+        let nodes = NodeTable::new_from_table(unsafe { std::ptr::addr_of_mut!((*tables).nodes) })?;
+        let mut keep = vec![false; nodes.num_rows().as_usize()];
+        for u in self.traverse_nodes(crate::NodeTraversalOrder::Preorder) {
+            keep[u.as_usize()] = true;
+        }
+        let mut graph = Graph::new();
+        let indexes = add_nodes(&mut graph, &nodes, &keep);
+        let (left, right) = self.interval();
+        for (u, _) in keep.iter().enumerate().filter(|(_, &keep)| keep) {
+            if let Some(p) = self
+                .parent(NodeId::from(u as i32))
+                .and_then(|p| p.to_usize())
+            {
+                graph.add_edge(indexes[p], indexes[u], GraphEdge { left, right });
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl TreeSequence {
+    /// Convert the edges overlapping a genomic interval to a [`petgraph::Graph`].
+    ///
+    /// The graph contains the samples and the nodes of the edges,
+    /// in order of id, and an edge from parent to child for
+    /// each edge, whose interval is restricted to `[left, right)`.
+    /// Over the whole genome, the graph is the ancestral
+    /// recombination graph of the tree sequence.
+    ///
+    /// Requires the `petgraph` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if `left >= right`, `left < 0`,
+    ///   or `right` is greater than the sequence length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(50., 100., parent, c1).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let graph = ts.to_petgraph(25., 75.).unwrap();
+    /// assert_eq!(graph.node_count(), 3);
+    /// let spans = graph
+    ///     .edge_weights()
+    ///     .map(|e| f64::from(e.span()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(spans, [50., 25.]);
+    ///
+    /// // Only the samples and the first edge
+    /// let graph = ts.to_petgraph(0., 50.).unwrap();
+    /// assert_eq!(graph.node_count(), 3);
+    /// assert_eq!(graph.edge_count(), 1);
+    /// ```
+    pub fn to_petgraph<L: Into<Position>, R: Into<Position>>(
+        &self,
+        left: L,
+        right: R,
+    ) -> Result<Graph<GraphNode, GraphEdge>, TskitError> {
+        let (left, right) = (left.into(), right.into());
+        if !(left >= 0.0 && left < right && right <= self.inner.sequence_length()) {
+            return Err(TskitError::RangeError(format!(
+                "invalid interval [{}, {})",
+                left, right
+            )));
+        }
+        let nodes = self.nodes();
+        let edges = self.edges();
+        let clipped = (0..edges.num_rows().as_usize())
+            .filter_map(|e| {
+                let edge = GraphEdge {
+                    left: f64::from(edges.left_slice()[e]).max(left.into()).into(),
+                    right: f64::from(edges.right_slice()[e]).min(right.into()).into(),
+                };
+                (edge.left < edge.right)
+                    .then(|| (edges.parent_slice()[e], edges.child_slice()[e], edge))
+            })
+            .collect::<Vec<_>>();
+        let mut keep = nodes
+            .flags_slice()
+            .iter()
+            .map(|f| f.is_sample())
+            .collect::<Vec<_>>();
+        for (parent, child, _) in &clipped {
+            keep[parent.as_usize()] = true;
+            keep[child.as_usize()] = true;
+        }
+        let mut graph = Graph::new();
+        let indexes = add_nodes(&mut graph, nodes, &keep);
+        for (parent, child, edge) in clipped {
+            graph.add_edge(indexes[parent.as_usize()], indexes[child.as_usize()], edge);
+        }
+        Ok(graph)
+    }
+}
+
+impl TableCollection {
+    /// Create tables from a [`petgraph::Graph`].
+    ///
+    /// Each node of the graph is added to the node table,
+    /// in order of node index, with the time and flags of its weight.
+    /// The `id` of the weight is not used.
+    /// Each edge of the graph, from parent to child, is added to the
+    /// edge table.
+    /// The tables are sorted and indexed.
+    ///
+    /// Requires the `petgraph` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the sequence length is not positive,
+    ///   or if sorting or indexing the tables fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ts = tskit::testing::caterpillar(5, 10.).unwrap();
+    /// let graph = ts.to_petgraph(0., 10.).unwrap();
+    /// let tables = tskit::TableCollection::from_petgraph(&graph, 10.).unwrap();
+    /// assert!(tables.equals(&ts.dump_tables().unwrap(), tskit::TableEqualityOptions::default()));
+    /// ```
+    pub fn from_petgraph<P: Into<Position>>(
+        graph: &Graph<GraphNode, GraphEdge>,
+        sequence_length: P,
+    ) -> Result<Self, TskitError> {
+        let mut tables = Self::new(sequence_length)?;
+        for node in graph.node_weights() {
+            tables.add_node(node.flags, node.time, -1, -1)?;
+        }
+        for edge in graph.edge_references() {
+            let weight = edge.weight();
+            tables.add_edge(
+                weight.left,
+                weight.right,
+                edge.source().index() as i32,
+                edge.target().index() as i32,
+            )?;
+        }
+        tables.full_sort(TableSortOptions::default())?;
+        tables.build_index()?;
+        Ok(tables)
+    }
+}
@@ -0,0 +1,147 @@
+//! Export of haplotypes as byte strings.
+
+use std::collections::BTreeMap;
+
+use crate::sys::bindings as ll_bindings;
+use crate::TskitError;
+use crate::Variant;
+
+/// How to encode alleles that are not a single byte
+/// and have no encoding set by [`HaplotypeOptions::allele_encoding`].
+///
+/// See [`HaplotypeOptions::multi_character_alleles`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum MultiCharacterAlleles {
+    /// Return an error.
+    #[default]
+    Error,
+    /// Encode all such alleles with the same byte.
+    Replace(u8),
+}
+
+/// Options for writing haplotypes.
+///
+/// By default, single-byte alleles are written as themselves,
+/// missing genotypes are written as `N`, and any other
+/// allele is an error.
+///
+/// See [`TreeSequence::haplotypes`](crate::TreeSequence::haplotypes).
+///
+/// # Examples
+///
+/// ```
+/// use tskit::{HaplotypeOptions, MultiCharacterAlleles};
+///
+/// // Microsatellite repeat counts as digits, with `-` for missing data
+/// let options = HaplotypeOptions::default()
+///     .missing_character(b'-')
+///     .allele_encoding("10", b'0')
+///     .allele_encoding("11", b'1')
+///     .multi_character_alleles(MultiCharacterAlleles::Replace(b'?'));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HaplotypeOptions {
+    missing_character: u8,
+    alleles: BTreeMap<Vec<u8>, u8>,
+    multi_character: MultiCharacterAlleles,
+}
+
+impl Default for HaplotypeOptions {
+    fn default() -> Self {
+        Self {
+            missing_character: b'N',
+            alleles: BTreeMap::new(),
+            multi_character: MultiCharacterAlleles::default(),
+        }
+    }
+}
+
+impl HaplotypeOptions {
+    /// Set the byte written for missing genotypes.
+    pub fn missing_character(self, missing_character: u8) -> Self {
+        Self {
+            missing_character,
+            ..self
+        }
+    }
+
+    /// Write `allele` as `byte`.
+    ///
+    /// This takes precedence over writing single-byte
+    /// alleles as themselves.
+    pub fn allele_encoding<A: AsRef<[u8]>>(mut self, allele: A, byte: u8) -> Self {
+        self.alleles.insert(allele.as_ref().to_vec(), byte);
+        self
+    }
+
+    /// Set how to write alleles that are not a single byte
+    /// and have no encoding.
+    ///
+    /// Empty alleles are not single bytes.
+    pub fn multi_character_alleles(self, multi_character: MultiCharacterAlleles) -> Self {
+        Self {
+            multi_character,
+            ..self
+        }
+    }
+
+    fn encode(&self, allele: &[u8]) -> Option<u8> {
+        match (self.alleles.get(allele), allele, self.multi_character) {
+            (Some(&byte), _, _) => Some(byte),
+            (None, &[byte], _) => Some(byte),
+            (None, _, MultiCharacterAlleles::Replace(byte)) => Some(byte),
+            (None, _, MultiCharacterAlleles::Error) => None,
+        }
+    }
+
+    // The byte of each allele of the decoded site
+    fn encode_site(&self, variant: &Variant) -> Result<Vec<u8>, TskitError> {
+        variant
+            .alleles()
+            .map(|allele| {
+                let byte = self.encode(allele).ok_or_else(|| TskitError::ValueError {
+                    got: format!(
+                        "allele {:?} at site {}",
+                        String::from_utf8_lossy(allele),
+                        variant.site()
+                    ),
+                    expected: "a single-character allele or an allele encoding".to_string(),
+                })?;
+                if byte == self.missing_character {
+                    return Err(TskitError::ValueError {
+                        got: format!(
+                            "allele {:?} at site {} encoded as the missing character {:?}",
+                            String::from_utf8_lossy(allele),
+                            variant.site(),
+                            byte as char
+                        ),
+                        expected: "alleles distinct from missing data".to_string(),
+                    });
+                }
+                Ok(byte)
+            })
+            .collect()
+    }
+}
+
+// One haplotype per sample, with one byte per site
+pub(crate) fn haplotypes(
+    mut variant: Variant<'_>,
+    options: &HaplotypeOptions,
+) -> Result<Vec<Vec<u8>>, TskitError> {
+    let num_sites = variant.num_sites();
+    let mut rv = vec![Vec::with_capacity(num_sites); variant.samples().len()];
+    for site in 0..num_sites {
+        variant.decode(site as ll_bindings::tsk_id_t)?;
+        let bytes = options.encode_site(&variant)?;
+        for (haplotype, &g) in rv.iter_mut().zip(variant.genotypes()) {
+            haplotype.push(if g == ll_bindings::TSK_MISSING_DATA {
+                options.missing_character
+            } else {
+                bytes[g as usize]
+            });
+        }
+    }
+    Ok(rv)
+}
@@ -0,0 +1,206 @@
+//! Identity by descent (IBD) segments.
+
+use crate::sys::bindings as ll_bindings;
+use crate::sys::LLIdentitySegments;
+use crate::NodeId;
+use crate::Position;
+use crate::Time;
+use crate::TskitError;
+
+/// Options for finding IBD segments.
+///
+/// By default, segments of any span and with MRCAs of any age
+/// are found, and the segments are stored.
+///
+/// See [`TreeSequence::ibd_segments_within`](crate::TreeSequence::ibd_segments_within).
+///
+/// # Examples
+///
+/// ```
+/// // Segments at least 1000 units long with an MRCA no older than 50
+/// let options = tskit::IbdOptions::default().min_span(1000.).max_time(50.);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IbdOptions {
+    min_span: f64,
+    max_time: Time,
+    store_segments: bool,
+}
+
+impl Default for IbdOptions {
+    fn default() -> Self {
+        Self {
+            min_span: 0.0,
+            max_time: f64::MAX.into(),
+            store_segments: true,
+        }
+    }
+}
+
+impl IbdOptions {
+    /// Only find segments with a span greater than `min_span`.
+    pub fn min_span(self, min_span: f64) -> Self {
+        Self { min_span, ..self }
+    }
+
+    /// Only find segments whose MRCA is at most `max_time` ago.
+    pub fn max_time<T: Into<Time>>(self, max_time: T) -> Self {
+        Self {
+            max_time: max_time.into(),
+            ..self
+        }
+    }
+
+    /// If `false`, only the number and total span of the
+    /// segments are stored.
+    pub fn store_segments(self, store_segments: bool) -> Self {
+        Self {
+            store_segments,
+            ..self
+        }
+    }
+
+    fn flags(&self) -> ll_bindings::tsk_flags_t {
+        if self.store_segments {
+            ll_bindings::TSK_IBD_STORE_SEGMENTS
+        } else {
+            0
+        }
+    }
+}
+
+/// A segment of the genome inherited by two samples from
+/// the same ancestor.
+///
+/// See [`IdentitySegments`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdentitySegment {
+    /// The left coordinate of the segment
+    pub left: Position,
+    /// The right coordinate of the segment
+    pub right: Position,
+    /// The first sample of the pair, with the smaller id
+    pub sample_a: NodeId,
+    /// The second sample of the pair
+    pub sample_b: NodeId,
+    /// The most recent common ancestor of the pair on the segment
+    pub node: NodeId,
+}
+
+impl IdentitySegment {
+    /// The length of the segment.
+    pub fn span(&self) -> f64 {
+        f64::from(self.right - self.left)
+    }
+}
+
+/// The IBD segments of pairs of samples.
+///
+/// See [`TreeSequence::ibd_segments_within`](crate::TreeSequence::ibd_segments_within)
+/// and [`TreeSequence::ibd_segments_between`](crate::TreeSequence::ibd_segments_between).
+pub struct IdentitySegments {
+    inner: LLIdentitySegments,
+}
+
+impl IdentitySegments {
+    pub(crate) fn new(inner: LLIdentitySegments) -> Self {
+        Self { inner }
+    }
+
+    /// The number of segments, over all pairs.
+    pub fn num_segments(&self) -> usize {
+        self.inner.num_segments() as usize
+    }
+
+    /// The sum of the spans of the segments, over all pairs.
+    pub fn total_span(&self) -> f64 {
+        self.inner.total_span()
+    }
+
+    /// The pairs of samples with at least one segment,
+    /// sorted, with the smaller id first.
+    ///
+    /// Empty if segments are not stored.
+    pub fn pairs(&self) -> &[(NodeId, NodeId)] {
+        self.inner.pairs()
+    }
+
+    /// Iterate over the segments of all pairs, in the order
+    /// of [`IdentitySegments::pairs`].
+    ///
+    /// The iterator is empty if segments are not stored.
+    pub fn iter(&self) -> impl Iterator<Item = IdentitySegment> + '_ {
+        (0..self.pairs().len()).flat_map(move |i| self.pair_segments(i))
+    }
+
+    /// Iterate over the segments of samples `a` and `b`,
+    /// in either order.
+    ///
+    /// The iterator is empty if the pair has no segments or
+    /// if segments are not stored.
+    pub fn get<A: Into<NodeId>, B: Into<NodeId>>(
+        &self,
+        a: A,
+        b: B,
+    ) -> impl Iterator<Item = IdentitySegment> + '_ {
+        let (a, b) = (a.into(), b.into());
+        let pair = if a < b { (a, b) } else { (b, a) };
+        let index = self.pairs().binary_search(&pair).ok();
+        index.into_iter().flat_map(move |i| self.pair_segments(i))
+    }
+
+    fn pair_segments(&self, index: usize) -> impl Iterator<Item = IdentitySegment> + '_ {
+        let (sample_a, sample_b) = self.pairs()[index];
+        self.inner
+            .list(index)
+            .into_iter()
+            .flat_map(move |list| self.inner.segments(list))
+            .map(move |s| IdentitySegment {
+                left: s.left.into(),
+                right: s.right.into(),
+                sample_a,
+                sample_b,
+                node: s.node.into(),
+            })
+    }
+}
+
+impl std::fmt::Debug for IdentitySegments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentitySegments")
+            .field("num_segments", &self.num_segments())
+            .field("total_span", &self.total_span())
+            .field("num_pairs", &self.pairs().len())
+            .finish()
+    }
+}
+
+pub(crate) fn within(
+    treeseq: &crate::TreeSequence,
+    samples: Option<&[NodeId]>,
+    options: IbdOptions,
+) -> Result<IdentitySegments, TskitError> {
+    LLIdentitySegments::within(
+        &treeseq.inner,
+        samples,
+        options.min_span,
+        options.max_time.into(),
+        options.flags(),
+    )
+    .map(IdentitySegments::new)
+}
+
+pub(crate) fn between(
+    treeseq: &crate::TreeSequence,
+    sample_sets: &[&[NodeId]],
+    options: IbdOptions,
+) -> Result<IdentitySegments, TskitError> {
+    LLIdentitySegments::between(
+        &treeseq.inner,
+        sample_sets,
+        options.min_span,
+        options.max_time.into(),
+        options.flags(),
+    )
+    .map(IdentitySegments::new)
+}
@@ -182,11 +182,172 @@ impl Iterator for IndividualTableIterator {
     }
 }
 
+/// Breadth-first traversal of a pedigree.
+///
+/// Each individual is visited at most once,
+/// so that the traversal ends even if the pedigree
+/// contains cycles.
+struct PedigreeTraversal<'a> {
+    table: &'a IndividualTable,
+    // If None, we traverse parents.
+    // Else, we traverse children.
+    children: Option<Vec<Vec<IndividualId>>>,
+    queue: std::collections::VecDeque<IndividualId>,
+    visited: Vec<bool>,
+}
+
+impl<'a> PedigreeTraversal<'a> {
+    fn new(
+        table: &'a IndividualTable,
+        individual: IndividualId,
+        children: Option<Vec<Vec<IndividualId>>>,
+    ) -> Option<Self> {
+        let num_rows = table.num_rows().as_usize();
+        if individual.to_usize()? >= num_rows {
+            return None;
+        }
+        let mut rv = Self {
+            table,
+            children,
+            queue: std::collections::VecDeque::new(),
+            visited: vec![false; num_rows],
+        };
+        rv.visited[individual.as_usize()] = true;
+        rv.enqueue_neighbors(individual);
+        Some(rv)
+    }
+
+    fn enqueue_neighbors(&mut self, individual: IndividualId) {
+        let neighbors = match &self.children {
+            Some(children) => &children[individual.as_usize()],
+            None => self.table.parents(individual).unwrap_or(&[]),
+        };
+        for &n in neighbors {
+            match n.to_usize() {
+                Some(i) if i < self.visited.len() && !self.visited[i] => {
+                    self.visited[i] = true;
+                    self.queue.push_back(n);
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl Iterator for PedigreeTraversal<'_> {
+    type Item = IndividualId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let individual = self.queue.pop_front()?;
+        self.enqueue_neighbors(individual);
+        Some(individual)
+    }
+}
+
+/// The locations of individuals as spatial coordinates,
+/// with one row per individual.
+///
+/// The coordinates are stored in row-major order.
+/// With the `ndarray` feature, `LocationMatrix::to_ndarray`
+/// copies them into an `ndarray::Array2`.
+///
+/// See [`IndividualTable::location_matrix`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocationMatrix {
+    /// The number of individuals
+    pub num_individuals: usize,
+    /// The number of coordinates per individual
+    pub dimension: usize,
+    /// The coordinates, with one row of `dimension`
+    /// values per individual.
+    pub coordinates: Vec<f64>,
+}
+
+impl LocationMatrix {
+    /// The number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.num_individuals, self.dimension)
+    }
+
+    /// The coordinates of the individual with index `index`.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn location(&self, index: usize) -> Option<&[f64]> {
+        if index >= self.num_individuals {
+            return None;
+        }
+        let start = index * self.dimension;
+        Some(&self.coordinates[start..start + self.dimension])
+    }
+
+    /// The Euclidean distance between the individuals
+    /// with indexes `a` and `b`.
+    ///
+    /// Returns `None` if either index is out of range.
+    pub fn distance(&self, a: usize, b: usize) -> Option<f64> {
+        Some(euclidean_distance(self.location(a)?, self.location(b)?))
+    }
+}
+
+#[cfg(feature = "ndarray")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "ndarray")))]
+impl LocationMatrix {
+    /// Copy the coordinates into an array with one row per individual.
+    ///
+    /// Requires the `ndarray` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the number of coordinates
+    ///   does not match [`LocationMatrix::shape`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::new().unwrap();
+    /// individuals.add_row(0, [0.0, 1.0], None).unwrap();
+    /// individuals.add_row(0, [2.0, 3.0], None).unwrap();
+    /// let array = individuals.location_matrix().unwrap().to_ndarray().unwrap();
+    /// assert_eq!(array.shape(), &[2, 2]);
+    /// assert_eq!(array[[1, 0]], 2.0);
+    /// ```
+    pub fn to_ndarray(&self) -> Result<ndarray::Array2<f64>, TskitError> {
+        ndarray::Array2::from_shape_vec(self.shape(), self.coordinates.clone()).map_err(|_| {
+            TskitError::ValueError {
+                got: format!("{} coordinates", self.coordinates.len()),
+                expected: format!(
+                    "{} individuals with {} coordinates each",
+                    self.num_individuals, self.dimension
+                ),
+            }
+        })
+    }
+}
+
+fn euclidean_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt()
+}
+
 impl IndividualTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
+    pub fn new() -> Result<Self, TskitError> {
+        let table_ = sys::IndividualTable::new(0)?;
+        Ok(Self { table_ })
+    }
+
     pub(crate) fn new_from_table(
         individuals: *mut ll_bindings::tsk_individual_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(individuals).unwrap();
+        let ptr = std::ptr::NonNull::new(individuals)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::IndividualTable::new_borrowed(ptr) };
         Ok(IndividualTable { table_ })
     }
@@ -232,6 +393,122 @@ impl IndividualTable {
         )
     }
 
+    /// The number of coordinates in the location of
+    /// every individual.
+    ///
+    /// Returns 0 for an empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if individuals have
+    ///   different numbers of coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::new().unwrap();
+    /// individuals.add_row(0, [0.0, 1.0], None).unwrap();
+    /// assert_eq!(individuals.location_dimension().unwrap(), 2);
+    /// individuals.add_row(0, [0.0, 1.0, 2.0], None).unwrap();
+    /// assert!(individuals.location_dimension().is_err());
+    /// ```
+    pub fn location_dimension(&self) -> Result<usize, TskitError> {
+        let mut dimension = None;
+        for i in 0..self.as_ref().num_rows as tsk_id_t {
+            let len = self.location(i).map_or(0, |l| l.len());
+            match dimension {
+                None => dimension = Some(len),
+                Some(d) if d != len => {
+                    return Err(TskitError::ValueError {
+                        got: format!("{len} coordinates for individual {i}"),
+                        expected: format!("{d} coordinates"),
+                    })
+                }
+                Some(_) => (),
+            }
+        }
+        Ok(dimension.unwrap_or(0))
+    }
+
+    /// The Euclidean distance between the locations
+    /// of two individuals.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `a` or `b` is out of range.
+    /// * [`TskitError::ValueError`] if the locations have
+    ///   different numbers of coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::new().unwrap();
+    /// let a = individuals.add_row(0, [0.0, 0.0], None).unwrap();
+    /// let b = individuals.add_row(0, [3.0, 4.0], None).unwrap();
+    /// assert_eq!(individuals.location_distance(a, b).unwrap(), 5.0);
+    /// ```
+    pub fn location_distance<A, B>(&self, a: A, b: B) -> Result<f64, TskitError>
+    where
+        A: Into<IndividualId>,
+        B: Into<IndividualId>,
+    {
+        let coordinates = |i: IndividualId| -> Result<Vec<f64>, TskitError> {
+            if i.is_null() || i.as_usize() >= self.num_rows().as_usize() {
+                return Err(TskitError::IndexError);
+            }
+            Ok(self
+                .location(i)
+                .unwrap_or_default()
+                .iter()
+                .map(|&x| f64::from(x))
+                .collect())
+        };
+        let (la, lb) = (coordinates(a.into())?, coordinates(b.into())?);
+        if la.len() != lb.len() {
+            return Err(TskitError::ValueError {
+                got: format!("locations with {} and {} coordinates", la.len(), lb.len()),
+                expected: "locations with the same number of coordinates".to_string(),
+            });
+        }
+        Ok(euclidean_distance(&la, &lb))
+    }
+
+    /// Copy the locations of all individuals into a [`LocationMatrix`].
+    ///
+    /// # Errors
+    ///
+    /// * See [`IndividualTable::location_dimension`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::new().unwrap();
+    /// individuals.add_row(0, [0.0, 1.0], None).unwrap();
+    /// individuals.add_row(0, [2.0, 3.0], None).unwrap();
+    /// let matrix = individuals.location_matrix().unwrap();
+    /// assert_eq!(matrix.shape(), (2, 2));
+    /// assert_eq!(matrix.coordinates, [0.0, 1.0, 2.0, 3.0]);
+    /// assert_eq!(matrix.location(1), Some(&[2.0, 3.0][..]));
+    /// ```
+    pub fn location_matrix(&self) -> Result<LocationMatrix, TskitError> {
+        let dimension = self.location_dimension()?;
+        let num_individuals = self.num_rows().as_usize();
+        let mut coordinates = Vec::with_capacity(num_individuals * dimension);
+        for i in 0..num_individuals as tsk_id_t {
+            coordinates.extend(
+                self.location(i)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|&x| f64::from(x)),
+            );
+        }
+        Ok(LocationMatrix {
+            num_individuals,
+            dimension,
+            coordinates,
+        })
+    }
+
     /// Return the parents for a given row.
     ///
     /// # Returns
@@ -248,6 +525,83 @@ impl IndividualTable {
         )
     }
 
+    /// Return an iterator over the ancestors of `individual`,
+    /// obtained by following the `parents` column.
+    ///
+    /// Ancestors are visited in breadth-first order, starting with
+    /// the parents of `individual`.
+    /// Each ancestor is yielded once, even if reachable via more
+    /// than one path, and `individual` itself is never yielded.
+    /// Thus, the iteration terminates even if the pedigree
+    /// contains cycles.
+    /// Null or out-of-range parent ids are skipped.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(iterator)` if `individual` is valid.
+    /// * `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::default();
+    /// let i0 = individuals.add_row(0, None, None).unwrap();
+    /// let i1 = individuals.add_row(0, None, None).unwrap();
+    /// let i2 = individuals.add_row(0, None, &[i0, i1]).unwrap();
+    /// let i3 = individuals.add_row(0, None, &[i2, tskit::IndividualId::NULL]).unwrap();
+    /// let ancestors = individuals.ancestors(i3).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(ancestors, vec![i2, i0, i1]);
+    /// assert_eq!(individuals.ancestors(i0).unwrap().count(), 0);
+    /// assert!(individuals.ancestors(4).is_none());
+    /// ```
+    pub fn ancestors<I: Into<IndividualId>>(
+        &self,
+        individual: I,
+    ) -> Option<impl Iterator<Item = IndividualId> + '_> {
+        PedigreeTraversal::new(self, individual.into(), None)
+    }
+
+    /// Return an iterator over the descendants of `individual`,
+    /// obtained by following the `parents` column in reverse.
+    ///
+    /// The traversal semantics are the same as for
+    /// [`IndividualTable::ancestors`].
+    ///
+    /// # Returns
+    ///
+    /// * `Some(iterator)` if `individual` is valid.
+    /// * `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::default();
+    /// let i0 = individuals.add_row(0, None, None).unwrap();
+    /// let i1 = individuals.add_row(0, None, None).unwrap();
+    /// let i2 = individuals.add_row(0, None, &[i0, i1]).unwrap();
+    /// let i3 = individuals.add_row(0, None, &[i2, i0]).unwrap();
+    /// let descendants = individuals.descendants(i0).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(descendants, vec![i2, i3]);
+    /// assert_eq!(individuals.descendants(i3).unwrap().count(), 0);
+    /// ```
+    pub fn descendants<I: Into<IndividualId>>(
+        &self,
+        individual: I,
+    ) -> Option<impl Iterator<Item = IndividualId> + '_> {
+        let num_rows = self.num_rows().as_usize();
+        let mut children = vec![vec![]; num_rows];
+        for child in 0..num_rows {
+            let child = IndividualId::from(child as tsk_id_t);
+            for parent in self.parents(child).unwrap_or(&[]) {
+                match parent.to_usize() {
+                    Some(p) if p < num_rows => children[p].push(child),
+                    _ => (),
+                }
+            }
+        }
+        PedigreeTraversal::new(self, individual.into(), Some(children))
+    }
+
     /// Return the metadata for a given row.
     ///
     /// # Returns
@@ -480,12 +834,95 @@ match tables.individuals().metadata::<MutationMetadata>(0)
     build_table_column_slice_getter!(
         /// Get the flags column as a slice
         => flags, flags_slice_raw, ll_bindings::tsk_flags_t);
+    build_ragged_column_slice_getters!(
+        /// Get the location column as a slice of the values of all rows
+        => location, location_length, location_slice, Location,
+        /// Get the offsets of the location of each row into
+        /// [`location_slice`](Self::location_slice).
+        /// There is one more offset than there are rows.
+        => location_offset, location_offset_slice);
+    build_ragged_column_slice_getters!(
+        /// Get the parents column as a slice of the values of all rows
+        => parents, parents_length, parents_slice, IndividualId,
+        /// Get the offsets of the parents of each row into
+        /// [`parents_slice`](Self::parents_slice).
+        /// There is one more offset than there are rows.
+        => parents_offset, parents_offset_slice);
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_individual_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_individual_table_set_metadata_schema);
 
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(IndividualId, tsk_individual_table_extend);
+
+    /// `true` if no individual has a parent with an equal or larger id.
+    ///
+    /// This is the ordering required by
+    /// [`TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING`](crate::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING).
+    pub fn is_topologically_sorted(&self) -> bool {
+        (0..self.as_ref().num_rows as tsk_id_t).all(|i| {
+            self.parents(i)
+                .unwrap_or_default()
+                .iter()
+                .all(|&p| p.is_null() || p < i)
+        })
+    }
+
+    /// Sort the table so that parents come before their children.
+    ///
+    /// Rows are otherwise kept in their input order, as in
+    /// [`TableCollection::topological_sort_individuals`](crate::TableCollection::topological_sort_individuals).
+    /// Parent ids are updated to refer to the sorted rows.
+    ///
+    /// # Returns
+    ///
+    /// The new id of each row, indexed by its old id.
+    /// Use it to update other references to the individuals,
+    /// such as the individual column of a node table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut individuals = tskit::IndividualTable::new().unwrap();
+    /// let child = individuals
+    ///     .add_row(0, None, [tskit::IndividualId::from(1)])
+    ///     .unwrap();
+    /// let parent = individuals.add_row(0, None, None).unwrap();
+    /// assert!(!individuals.is_topologically_sorted());
+    ///
+    /// let new_ids = individuals
+    ///     .topological_sort(tskit::IndividualTableSortOptions::default())
+    ///     .unwrap();
+    /// assert!(individuals.is_topologically_sorted());
+    /// assert_eq!(new_ids[usize::try_from(parent).unwrap()], 0);
+    /// assert_eq!(new_ids[usize::try_from(child).unwrap()], 1);
+    /// assert_eq!(individuals.parents(1), Some(&[tskit::IndividualId::from(0)][..]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ErrorCode`] if a parent id is out of range
+    ///   or if individuals are their own ancestors.
+    pub fn topological_sort<O: Into<crate::IndividualTableSortOptions>>(
+        &mut self,
+        options: O,
+    ) -> Result<Vec<IndividualId>, TskitError> {
+        let new_ids = self.table_.topological_sort(options.into().bits())?;
+        Ok(new_ids.into_iter().map(IndividualId::from).collect())
+    }
+
     pub fn add_row<F, L, P>(
         &mut self,
         flags: F,
@@ -0,0 +1,817 @@
+//! JSON snapshots of tables.
+//!
+//! Tables are written as arrays of rows, which keeps small
+//! snapshots readable and easy to edit by hand.
+//! Metadata, ancestral states, and derived states are written
+//! as strings when they are valid UTF-8 and as arrays of bytes
+//! otherwise.
+//! Non-finite floats are written as the strings `"NaN"`,
+//! `"inf"`, and `"-inf"`, as JSON has no such numbers.
+
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::MetadataError;
+use crate::metadata::MetadataRoundtrip;
+use crate::EdgeTable;
+use crate::IndividualFlags;
+use crate::IndividualId;
+use crate::IndividualTable;
+use crate::Location;
+use crate::MigrationTable;
+use crate::MutationTable;
+use crate::NodeFlags;
+use crate::NodeTable;
+use crate::PopulationTable;
+use crate::SiteTable;
+use crate::TableCollection;
+use crate::TskitError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Bytes {
+    Text(String),
+    Raw(Vec<u8>),
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl Bytes {
+    fn new(bytes: Option<&[u8]>) -> Self {
+        let bytes = bytes.unwrap_or_default();
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Raw(bytes.to_vec()),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Raw(raw) => raw,
+        }
+    }
+
+    fn as_state(&self) -> Option<&[u8]> {
+        match self.as_bytes() {
+            [] => None,
+            bytes => Some(bytes),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+}
+
+// Passes stored metadata through the typed metadata API unchanged.
+struct RawMetadata(Vec<u8>);
+
+impl MetadataRoundtrip for RawMetadata {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        Ok(self.0.clone())
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        Ok(Self(md.to_vec()))
+    }
+}
+
+impl crate::metadata::EdgeMetadata for RawMetadata {}
+impl crate::metadata::IndividualMetadata for RawMetadata {}
+impl crate::metadata::MigrationMetadata for RawMetadata {}
+impl crate::metadata::MutationMetadata for RawMetadata {}
+impl crate::metadata::NodeMetadata for RawMetadata {}
+impl crate::metadata::PopulationMetadata for RawMetadata {}
+impl crate::metadata::SiteMetadata for RawMetadata {}
+
+// A float that is written as a string if it is not finite
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Float(f64);
+
+impl Serialize for Float {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            x if x.is_finite() => serializer.serialize_f64(x),
+            x if x.is_nan() => serializer.serialize_str("NaN"),
+            x if x > 0.0 => serializer.serialize_str("inf"),
+            _ => serializer.serialize_str("-inf"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Float {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(f64),
+            Text(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(x) => Ok(Self(x)),
+            Repr::Text(text) => match text.as_str() {
+                "NaN" | "inf" | "-inf" => Ok(Self(text.parse().unwrap())),
+                _ => Err(serde::de::Error::custom(format!(
+                    "expected a number, \"NaN\", \"inf\", or \"-inf\", got {:?}",
+                    text
+                ))),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NodeRow {
+    flags: u32,
+    time: Float,
+    #[serde(default = "null_id")]
+    population: i32,
+    #[serde(default = "null_id")]
+    individual: i32,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EdgeRow {
+    left: Float,
+    right: Float,
+    parent: i32,
+    child: i32,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SiteRow {
+    position: Float,
+    #[serde(default)]
+    ancestral_state: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MutationRow {
+    site: i32,
+    node: i32,
+    #[serde(default = "null_id")]
+    parent: i32,
+    // Unknown times are null
+    #[serde(default)]
+    time: Option<Float>,
+    #[serde(default)]
+    derived_state: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndividualRow {
+    #[serde(default)]
+    flags: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    location: Vec<Float>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    parents: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PopulationRow {
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MigrationRow {
+    left: Float,
+    right: Float,
+    node: i32,
+    source: i32,
+    dest: i32,
+    time: Float,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TableCollectionSnapshot {
+    sequence_length: f64,
+    #[serde(
+        default = "unknown_time_units",
+        skip_serializing_if = "is_unknown_time_units"
+    )]
+    time_units: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata_schema: Bytes,
+    #[serde(default, skip_serializing_if = "MetadataSchemas::is_empty")]
+    metadata_schemas: MetadataSchemas,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reference_sequence: Option<ReferenceSequence>,
+    #[serde(default)]
+    nodes: Vec<NodeRow>,
+    #[serde(default)]
+    edges: Vec<EdgeRow>,
+    #[serde(default)]
+    sites: Vec<SiteRow>,
+    #[serde(default)]
+    mutations: Vec<MutationRow>,
+    #[serde(default)]
+    individuals: Vec<IndividualRow>,
+    #[serde(default)]
+    populations: Vec<PopulationRow>,
+    #[serde(default)]
+    migrations: Vec<MigrationRow>,
+}
+
+// The metadata schemas of the tables
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct MetadataSchemas {
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    nodes: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    edges: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    sites: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    mutations: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    individuals: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    populations: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    migrations: Bytes,
+}
+
+impl MetadataSchemas {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReferenceSequence {
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    data: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    url: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata: Bytes,
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    metadata_schema: Bytes,
+}
+
+fn null_id() -> i32 {
+    crate::sys::TSK_NULL
+}
+
+fn unknown_time_units() -> Bytes {
+    Bytes::Text("unknown".to_string())
+}
+
+fn is_unknown_time_units(time_units: &Bytes) -> bool {
+    *time_units == unknown_time_units()
+}
+
+// The bytes of a text column of the C tables
+fn chars(data: *const libc::c_char, length: crate::sys::bindings::tsk_size_t) -> Bytes {
+    match length {
+        0 => Bytes::default(),
+        _ => Bytes::new(Some(crate::sys::generate_slice(data, length))),
+    }
+}
+
+type SetChars<T> = unsafe extern "C" fn(
+    *mut T,
+    *const libc::c_char,
+    crate::sys::bindings::tsk_size_t,
+) -> libc::c_int;
+
+// Copy `bytes` to a text column of the C tables with `set`
+fn set_chars<T>(target: *mut T, bytes: &Bytes, set: SetChars<T>) -> Result<(), TskitError> {
+    let bytes = bytes.as_bytes();
+    // SAFETY: target points to an initialized part of a table
+    // collection and bytes has bytes.len() bytes, which are copied.
+    let code = unsafe { set(target, bytes.as_ptr().cast(), bytes.len() as _) };
+    if code < 0 {
+        return Err(TskitError::ErrorCode { code });
+    }
+    Ok(())
+}
+
+fn to_string<T: Serialize>(value: &T) -> Result<String, TskitError> {
+    serde_json::to_string_pretty(value).map_err(|e| TskitError::LibraryError(e.to_string()))
+}
+
+fn from_str<'a, T: Deserialize<'a>>(json: &'a str, expected: &str) -> Result<T, TskitError> {
+    serde_json::from_str(json).map_err(|e| TskitError::ValueError {
+        got: e.to_string(),
+        expected: expected.to_string(),
+    })
+}
+
+fn node_rows(table: &NodeTable) -> Vec<NodeRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        rows.push(NodeRow {
+            flags: row.flags.bits(),
+            time: Float(row.time.into()),
+            population: row.population.into(),
+            individual: row.individual.into(),
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn node_table(rows: &[NodeRow]) -> Result<NodeTable, TskitError> {
+    let mut table = NodeTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(
+            NodeFlags::from_bits_retain(row.flags),
+            row.time.0,
+            row.population,
+            row.individual,
+            &RawMetadata(row.metadata.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(table)
+}
+
+fn edge_rows(table: &EdgeTable) -> Vec<EdgeRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        rows.push(EdgeRow {
+            left: Float(row.left.into()),
+            right: Float(row.right.into()),
+            parent: row.parent.into(),
+            child: row.child.into(),
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn edge_table(rows: &[EdgeRow]) -> Result<EdgeTable, TskitError> {
+    let mut table = EdgeTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(
+            row.left.0,
+            row.right.0,
+            row.parent,
+            row.child,
+            &RawMetadata(row.metadata.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(table)
+}
+
+fn site_rows(table: &SiteTable) -> Vec<SiteRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        rows.push(SiteRow {
+            position: Float(row.position.into()),
+            ancestral_state: Bytes::new(row.ancestral_state),
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn site_table(rows: &[SiteRow]) -> Result<SiteTable, TskitError> {
+    let mut table = SiteTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(
+            row.position.0,
+            row.ancestral_state.as_state(),
+            &RawMetadata(row.metadata.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(table)
+}
+
+fn mutation_rows(table: &MutationTable) -> Vec<MutationRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        let time = f64::from(row.time);
+        rows.push(MutationRow {
+            site: row.site.into(),
+            node: row.node.into(),
+            parent: row.parent.into(),
+            time: if time.to_bits() == crate::sys::bindings::TSK_UNKNOWN_TIME_HEX {
+                None
+            } else {
+                Some(Float(time))
+            },
+            derived_state: Bytes::new(row.derived_state),
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn mutation_table(rows: &[MutationRow]) -> Result<MutationTable, TskitError> {
+    let mut table = MutationTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(
+            row.site,
+            row.node,
+            row.parent,
+            row.time.map_or(
+                f64::from_bits(crate::sys::bindings::TSK_UNKNOWN_TIME_HEX),
+                |t| t.0,
+            ),
+            row.derived_state.as_state(),
+            &RawMetadata(row.metadata.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(table)
+}
+
+fn individual_rows(table: &IndividualTable) -> Vec<IndividualRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        rows.push(IndividualRow {
+            flags: row.flags.bits(),
+            location: row
+                .location
+                .unwrap_or_default()
+                .iter()
+                .map(|&x| Float(x.into()))
+                .collect(),
+            parents: row
+                .parents
+                .unwrap_or_default()
+                .iter()
+                .map(|&p| p.into())
+                .collect(),
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn individual_table(rows: &[IndividualRow]) -> Result<IndividualTable, TskitError> {
+    let mut table = IndividualTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(
+            IndividualFlags::from_bits_retain(row.flags),
+            row.location
+                .iter()
+                .map(|&x| Location::from(x.0))
+                .collect::<Vec<_>>(),
+            row.parents
+                .iter()
+                .map(|&p| IndividualId::from(p))
+                .collect::<Vec<_>>(),
+            &RawMetadata(row.metadata.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(table)
+}
+
+fn population_rows(table: &PopulationTable) -> Vec<PopulationRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        rows.push(PopulationRow {
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn population_table(rows: &[PopulationRow]) -> Result<PopulationTable, TskitError> {
+    let mut table = PopulationTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(&RawMetadata(row.metadata.as_bytes().to_vec()))?;
+    }
+    Ok(table)
+}
+
+fn migration_rows(table: &MigrationTable) -> Vec<MigrationRow> {
+    use streaming_iterator::StreamingIterator;
+    let mut rows = vec![];
+    let mut iter = table.lending_iter();
+    while let Some(row) = iter.next() {
+        rows.push(MigrationRow {
+            left: Float(row.left.into()),
+            right: Float(row.right.into()),
+            node: row.node.into(),
+            source: row.source.into(),
+            dest: row.dest.into(),
+            time: Float(row.time.into()),
+            metadata: Bytes::new(row.metadata),
+        });
+    }
+    rows
+}
+
+fn migration_table(rows: &[MigrationRow]) -> Result<MigrationTable, TskitError> {
+    let mut table = MigrationTable::new()?;
+    for row in rows {
+        table.add_row_with_metadata(
+            (row.left.0, row.right.0),
+            row.node,
+            (row.source, row.dest),
+            row.time.0,
+            &RawMetadata(row.metadata.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(table)
+}
+
+macro_rules! impl_table_json {
+    ($table: ty, $row: ty, $to_rows: ident, $from_rows: ident) => {
+        impl $table {
+            /// Write the table as a JSON array of rows.
+            ///
+            /// Requires the `serde` feature.
+            /// See [`TableCollection::to_json`] for the format.
+            ///
+            /// # Errors
+            ///
+            /// * [`TskitError::LibraryError`] if serialization fails.
+            pub fn to_json(&self) -> Result<String, TskitError> {
+                to_string(&$to_rows(self))
+            }
+
+            /// Create a table from the output of `to_json`.
+            ///
+            /// Requires the `serde` feature.
+            ///
+            /// # Errors
+            ///
+            /// * [`TskitError::ValueError`] if `json` is not a valid snapshot.
+            pub fn from_json(json: &str) -> Result<Self, TskitError> {
+                $from_rows(&from_str::<Vec<$row>>(json, "a JSON array of table rows")?)
+            }
+        }
+    };
+}
+
+impl_table_json!(NodeTable, NodeRow, node_rows, node_table);
+impl_table_json!(EdgeTable, EdgeRow, edge_rows, edge_table);
+impl_table_json!(SiteTable, SiteRow, site_rows, site_table);
+impl_table_json!(MutationTable, MutationRow, mutation_rows, mutation_table);
+impl_table_json!(
+    IndividualTable,
+    IndividualRow,
+    individual_rows,
+    individual_table
+);
+impl_table_json!(
+    PopulationTable,
+    PopulationRow,
+    population_rows,
+    population_table
+);
+impl_table_json!(
+    MigrationTable,
+    MigrationRow,
+    migration_rows,
+    migration_table
+);
+
+impl TableCollection {
+    /// Write the tables as JSON.
+    ///
+    /// The output is an object with the sequence length,
+    /// the fields of the table collection itself, and
+    /// one array of rows for each table.
+    /// It is intended for small snapshots, such as the
+    /// expected values of tests.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Details
+    ///
+    /// * The time units, top-level metadata and metadata schema,
+    ///   the metadata schemas of the tables, under `metadata_schemas`,
+    ///   and the reference sequence are written when they are set.
+    ///   Time units of `"unknown"`, the default, are omitted.
+    /// * Metadata, schemas, ancestral states, derived states, and
+    ///   the other text fields are strings if they are valid UTF-8
+    ///   and arrays of bytes otherwise.
+    ///   Empty metadata are omitted.
+    /// * Unknown mutation times are `null`.
+    /// * Non-finite floats are the strings `"NaN"`, `"inf"`,
+    ///   and `"-inf"`.
+    /// * The provenance table and indexes are not written.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::LibraryError`] if serialization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// let site = tables.add_site(5., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, child, -1, 0.5, Some(b"T")).unwrap();
+    ///
+    /// let json = tables.to_json().unwrap();
+    /// let restored = tskit::TableCollection::from_json(&json).unwrap();
+    /// assert!(restored.equals(&tables, tskit::TableEqualityOptions::default()));
+    ///
+    /// // Snapshots may be written by hand and omit empty tables
+    /// let json = r#"{
+    ///     "sequence_length": 10.0,
+    ///     "nodes": [
+    ///         {"flags": 0, "time": 1.0},
+    ///         {"flags": 1, "time": 0.0}
+    ///     ],
+    ///     "edges": [{"left": 0.0, "right": 10.0, "parent": 0, "child": 1}],
+    ///     "sites": [{"position": 5.0, "ancestral_state": "A"}],
+    ///     "mutations": [{"site": 0, "node": 1, "time": 0.5, "derived_state": "T"}]
+    /// }"#;
+    /// let from_hand = tskit::TableCollection::from_json(json).unwrap();
+    /// assert!(from_hand.equals(&tables, tskit::TableEqualityOptions::default()));
+    /// ```
+    pub fn to_json(&self) -> Result<String, TskitError> {
+        // SAFETY: the tables are initialized
+        let tables = unsafe { &*self.as_ptr() };
+        let reference = &tables.reference_sequence;
+        // SAFETY: the reference sequence is initialized
+        let has_reference =
+            !unsafe { crate::sys::bindings::tsk_reference_sequence_is_null(reference) };
+        to_string(&TableCollectionSnapshot {
+            sequence_length: self.sequence_length().into(),
+            time_units: chars(tables.time_units, tables.time_units_length),
+            metadata: chars(tables.metadata, tables.metadata_length),
+            metadata_schema: chars(tables.metadata_schema, tables.metadata_schema_length),
+            metadata_schemas: MetadataSchemas {
+                nodes: chars(
+                    tables.nodes.metadata_schema,
+                    tables.nodes.metadata_schema_length,
+                ),
+                edges: chars(
+                    tables.edges.metadata_schema,
+                    tables.edges.metadata_schema_length,
+                ),
+                sites: chars(
+                    tables.sites.metadata_schema,
+                    tables.sites.metadata_schema_length,
+                ),
+                mutations: chars(
+                    tables.mutations.metadata_schema,
+                    tables.mutations.metadata_schema_length,
+                ),
+                individuals: chars(
+                    tables.individuals.metadata_schema,
+                    tables.individuals.metadata_schema_length,
+                ),
+                populations: chars(
+                    tables.populations.metadata_schema,
+                    tables.populations.metadata_schema_length,
+                ),
+                migrations: chars(
+                    tables.migrations.metadata_schema,
+                    tables.migrations.metadata_schema_length,
+                ),
+            },
+            reference_sequence: has_reference.then(|| ReferenceSequence {
+                data: chars(reference.data, reference.data_length),
+                url: chars(reference.url, reference.url_length),
+                metadata: chars(reference.metadata, reference.metadata_length),
+                metadata_schema: chars(reference.metadata_schema, reference.metadata_schema_length),
+            }),
+            nodes: node_rows(self.nodes()),
+            edges: edge_rows(self.edges()),
+            sites: site_rows(self.sites()),
+            mutations: mutation_rows(self.mutations()),
+            individuals: individual_rows(self.individuals()),
+            populations: population_rows(self.populations()),
+            migrations: migration_rows(self.migrations()),
+        })
+    }
+
+    /// Create tables from the output of [`TableCollection::to_json`].
+    ///
+    /// Missing tables are empty, and missing fields of the
+    /// table collection have their default values.
+    /// The tables are not checked for integrity or indexed.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `json` is not a valid snapshot.
+    /// * [`TskitError`] if the sequence length is not positive.
+    pub fn from_json(json: &str) -> Result<Self, TskitError> {
+        let snapshot = from_str::<TableCollectionSnapshot>(json, "a JSON table collection")?;
+        let mut tables = Self::new(snapshot.sequence_length)?;
+        tables.set_nodes(&node_table(&snapshot.nodes)?)?;
+        tables.set_edges(&edge_table(&snapshot.edges)?)?;
+        tables.set_sites(&site_table(&snapshot.sites)?)?;
+        tables.set_mutations(&mutation_table(&snapshot.mutations)?)?;
+        tables.set_individuals(&individual_table(&snapshot.individuals)?)?;
+        tables.set_populations(&population_table(&snapshot.populations)?)?;
+        tables.set_migrations(&migration_table(&snapshot.migrations)?)?;
+
+        use crate::sys::bindings as ll;
+        let ptr = tables.as_mut_ptr();
+        // SAFETY: the tables are initialized, so that pointers
+        // to their parts are valid.
+        let (nodes, edges, sites, mutations, individuals, populations, migrations, reference) = unsafe {
+            (
+                std::ptr::addr_of_mut!((*ptr).nodes),
+                std::ptr::addr_of_mut!((*ptr).edges),
+                std::ptr::addr_of_mut!((*ptr).sites),
+                std::ptr::addr_of_mut!((*ptr).mutations),
+                std::ptr::addr_of_mut!((*ptr).individuals),
+                std::ptr::addr_of_mut!((*ptr).populations),
+                std::ptr::addr_of_mut!((*ptr).migrations),
+                std::ptr::addr_of_mut!((*ptr).reference_sequence),
+            )
+        };
+        set_chars(
+            ptr,
+            &snapshot.time_units,
+            ll::tsk_table_collection_set_time_units,
+        )?;
+        set_chars(
+            ptr,
+            &snapshot.metadata,
+            ll::tsk_table_collection_set_metadata,
+        )?;
+        set_chars(
+            ptr,
+            &snapshot.metadata_schema,
+            ll::tsk_table_collection_set_metadata_schema,
+        )?;
+        let schemas = &snapshot.metadata_schemas;
+        set_chars(
+            nodes,
+            &schemas.nodes,
+            ll::tsk_node_table_set_metadata_schema,
+        )?;
+        set_chars(
+            edges,
+            &schemas.edges,
+            ll::tsk_edge_table_set_metadata_schema,
+        )?;
+        set_chars(
+            sites,
+            &schemas.sites,
+            ll::tsk_site_table_set_metadata_schema,
+        )?;
+        set_chars(
+            mutations,
+            &schemas.mutations,
+            ll::tsk_mutation_table_set_metadata_schema,
+        )?;
+        set_chars(
+            individuals,
+            &schemas.individuals,
+            ll::tsk_individual_table_set_metadata_schema,
+        )?;
+        set_chars(
+            populations,
+            &schemas.populations,
+            ll::tsk_population_table_set_metadata_schema,
+        )?;
+        set_chars(
+            migrations,
+            &schemas.migrations,
+            ll::tsk_migration_table_set_metadata_schema,
+        )?;
+        if let Some(r) = &snapshot.reference_sequence {
+            set_chars(reference, &r.data, ll::tsk_reference_sequence_set_data)?;
+            set_chars(reference, &r.url, ll::tsk_reference_sequence_set_url)?;
+            set_chars(
+                reference,
+                &r.metadata,
+                ll::tsk_reference_sequence_set_metadata,
+            )?;
+            set_chars(
+                reference,
+                &r.metadata_schema,
+                ll::tsk_reference_sequence_set_metadata_schema,
+            )?;
+        }
+        Ok(tables)
+    }
+}
@@ -0,0 +1,406 @@
+//! Preserve kastore arrays that tskit does not use.
+
+use std::collections::BTreeMap;
+
+use crate::sys::bindings as ll_bindings;
+use crate::TskReturnValue;
+use crate::TskitError;
+
+// Keys, and groups of keys, written by tskit
+const TSKIT_KEYS: [&str; 5] = [
+    "sequence_length",
+    "uuid",
+    "time_units",
+    "metadata",
+    "metadata_schema",
+];
+
+const TSKIT_GROUPS: [&str; 11] = [
+    "format",
+    "indexes",
+    "reference_sequence",
+    "individuals",
+    "nodes",
+    "edges",
+    "migrations",
+    "sites",
+    "mutations",
+    "populations",
+    "provenances",
+];
+
+// The file uuid of canonical dumps
+const CANONICAL_UUID: &str = "00000000-0000-0000-0000-000000000000";
+
+fn kastore_error(code: i32) -> TskitError {
+    // SAFETY: only changes the bits of the error code
+    TskitError::ErrorCode {
+        code: unsafe { ll_bindings::tsk_set_kas_error(code) },
+    }
+}
+
+fn c_filename(filename: &str) -> Result<std::ffi::CString, TskitError> {
+    std::ffi::CString::new(filename)
+        .map_err(|_| TskitError::LibraryError("call to ffi::CString::new failed".to_string()))
+}
+
+// An open kastore, which is closed on drop
+struct Store(ll_bindings::kastore_t);
+
+impl Store {
+    // mode is a nul-terminated kastore mode
+    fn open(filename: &str, mode: &[u8], flags: u32) -> Result<Self, TskitError> {
+        let c_str = c_filename(filename)?;
+        let mut store = std::mem::MaybeUninit::<ll_bindings::kastore_t>::uninit();
+        // kastore_open initializes the store, even on error
+        let rv = unsafe {
+            ll_bindings::kastore_open(
+                store.as_mut_ptr(),
+                c_str.as_ptr(),
+                mode.as_ptr().cast(),
+                flags as i32,
+            )
+        };
+        // SAFETY: see above
+        let store = Self(unsafe { store.assume_init() });
+        if rv != 0 {
+            return Err(kastore_error(rv));
+        }
+        Ok(store)
+    }
+
+    // Read all keys and arrays of a file
+    fn read_all(filename: &str) -> Result<Vec<(String, KastoreArray)>, TskitError> {
+        let store = Self::open(filename, b"r\0", ll_bindings::KAS_READ_ALL)?;
+        let items: &[ll_bindings::kaitem_t] = if store.0.num_items == 0 {
+            &[]
+        } else {
+            // SAFETY: an open store has num_items items
+            unsafe { std::slice::from_raw_parts(store.0.items, store.0.num_items) }
+        };
+        let mut rv = Vec::with_capacity(items.len());
+        for item in items {
+            // SAFETY: keys have key_len bytes
+            let key = unsafe { std::slice::from_raw_parts(item.key.cast::<u8>(), item.key_len) };
+            let key = std::str::from_utf8(key).map_err(|_| {
+                TskitError::LibraryError("kastore key is not valid UTF-8".to_string())
+            })?;
+            // SAFETY: all arrays are read into memory with KAS_READ_ALL
+            let array =
+                unsafe { KastoreArray::from_raw_parts(item.array, item.array_len, item.type_) }?;
+            rv.push((key.to_string(), array));
+        }
+        store.close()?;
+        Ok(rv)
+    }
+
+    fn put(&mut self, key: &str, array: &KastoreArray) -> TskReturnValue {
+        let (ptr, len, kas_type) = array.raw_parts();
+        // SAFETY: the array is copied by the store
+        let rv = unsafe {
+            ll_bindings::kastore_put(
+                &mut self.0,
+                key.as_ptr().cast(),
+                key.len(),
+                ptr,
+                len,
+                kas_type,
+                0,
+            )
+        };
+        if rv != 0 {
+            return Err(kastore_error(rv));
+        }
+        Ok(0)
+    }
+
+    fn close(mut self) -> TskReturnValue {
+        let rv = unsafe { ll_bindings::kastore_close(&mut self.0) };
+        if rv != 0 {
+            return Err(kastore_error(rv));
+        }
+        Ok(0)
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        // Closing twice is a no-op, as the store is zeroed on close.
+        // Errors are reported by Store::close.
+        let _ = unsafe { ll_bindings::kastore_close(&mut self.0) };
+    }
+}
+
+/// An array stored in a kastore file.
+///
+/// There is one variant for each kastore data type.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum KastoreArray {
+    /// Values of type [`i8`]
+    Int8(Vec<i8>),
+    /// Values of type [`u8`]
+    UInt8(Vec<u8>),
+    /// Values of type [`i16`]
+    Int16(Vec<i16>),
+    /// Values of type [`u16`]
+    UInt16(Vec<u16>),
+    /// Values of type [`i32`]
+    Int32(Vec<i32>),
+    /// Values of type [`u32`]
+    UInt32(Vec<u32>),
+    /// Values of type [`i64`]
+    Int64(Vec<i64>),
+    /// Values of type [`u64`]
+    UInt64(Vec<u64>),
+    /// Values of type [`f32`]
+    Float32(Vec<f32>),
+    /// Values of type [`f64`]
+    Float64(Vec<f64>),
+}
+
+macro_rules! impl_kastore_array {
+    ($(($variant: ident, $t: ty, $kas_type: ident)),*) => {
+        $(
+            impl From<Vec<$t>> for KastoreArray {
+                fn from(value: Vec<$t>) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )*
+
+        impl KastoreArray {
+            /// The number of values in the array.
+            pub fn len(&self) -> usize {
+                match self {
+                    $(Self::$variant(v) => v.len(),)*
+                }
+            }
+
+            /// `true` if the array has no values.
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            // The pointer, length, and kastore type of the array
+            fn raw_parts(&self) -> (*const std::os::raw::c_void, usize, i32) {
+                match self {
+                    $(Self::$variant(v) => {
+                        (v.as_ptr().cast(), v.len(), ll_bindings::$kas_type as i32)
+                    })*
+                }
+            }
+
+            // SAFETY: if len > 0, array must point to len values of the kastore type
+            unsafe fn from_raw_parts(
+                array: *const std::os::raw::c_void,
+                len: usize,
+                kas_type: i32,
+            ) -> Result<Self, TskitError> {
+                $(
+                    if kas_type == ll_bindings::$kas_type as i32 {
+                        let values = if len == 0 {
+                            vec![]
+                        } else {
+                            std::slice::from_raw_parts(array.cast::<$t>(), len).to_vec()
+                        };
+                        return Ok(Self::$variant(values));
+                    }
+                )*
+                Err(kastore_error(ll_bindings::KAS_ERR_BAD_TYPE))
+            }
+        }
+    };
+}
+
+impl_kastore_array!(
+    (Int8, i8, KAS_INT8),
+    (UInt8, u8, KAS_UINT8),
+    (Int16, i16, KAS_INT16),
+    (UInt16, u16, KAS_UINT16),
+    (Int32, i32, KAS_INT32),
+    (UInt32, u32, KAS_UINT32),
+    (Int64, i64, KAS_INT64),
+    (UInt64, u64, KAS_UINT64),
+    (Float32, f32, KAS_FLOAT32),
+    (Float64, f64, KAS_FLOAT64)
+);
+
+/// Arrays of a kastore file stored under keys that tskit does not use.
+///
+/// Files written by other tools may add their own arrays to
+/// the tskit file format.
+/// Loading a [`TableCollection`](crate::TableCollection) ignores
+/// these arrays and dumping it does not write them.
+/// To keep them, read them with [`KastoreExtras::from_file`] and
+/// write them back with
+/// [`TableCollection::dump_with_extras`](crate::TableCollection::dump_with_extras)
+/// or [`TreeSequence::dump_with_extras`](crate::TreeSequence::dump_with_extras).
+///
+/// # Examples
+///
+/// ```
+/// use tskit::{KastoreArray, KastoreExtras, TableOutputOptions};
+///
+/// let tables = tskit::TableCollection::new(100.).unwrap();
+/// let mut extras = KastoreExtras::default();
+/// extras.insert("mytool/version", vec![1_u32, 2]).unwrap();
+/// let file = std::env::temp_dir().join("tskit_kastore_extras.trees");
+/// let filename = file.to_str().unwrap();
+/// tables
+///     .dump_with_extras(filename, TableOutputOptions::default(), &extras)
+///     .unwrap();
+///
+/// let tables = tskit::TableCollection::new_from_file(filename).unwrap();
+/// assert_eq!(tables.sequence_length(), 100.0);
+/// let extras = KastoreExtras::from_file(filename).unwrap();
+/// assert_eq!(
+///     extras.get("mytool/version"),
+///     Some(&KastoreArray::UInt32(vec![1, 2]))
+/// );
+///
+/// // Keys used by tskit are not extras
+/// assert!(extras.get("nodes/time").is_none());
+/// assert!(KastoreExtras::default()
+///     .insert("nodes/time", vec![0.0])
+///     .is_err());
+/// # std::fs::remove_file(file).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KastoreExtras {
+    arrays: BTreeMap<String, KastoreArray>,
+}
+
+impl KastoreExtras {
+    /// Read the arrays of a kastore file that tskit does not use.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ErrorCode`] if the file cannot be read as a kastore.
+    /// * [`TskitError::LibraryError`] if a key is not valid UTF-8.
+    pub fn from_file(filename: impl AsRef<str>) -> Result<Self, TskitError> {
+        let items = Store::read_all(filename.as_ref())?;
+        let arrays = items
+            .into_iter()
+            .filter(|(key, _)| !Self::is_tskit_key(key))
+            .collect();
+        Ok(Self { arrays })
+    }
+
+    /// `true` if tskit uses `key` in its file format.
+    ///
+    /// Such keys are the names of the columns of the tables, of
+    /// the table indexes, and of the top-level values, such as
+    /// `"sequence_length"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert!(tskit::KastoreExtras::is_tskit_key("edges/left"));
+    /// assert!(tskit::KastoreExtras::is_tskit_key("sequence_length"));
+    /// assert!(!tskit::KastoreExtras::is_tskit_key("mytool/edges/left"));
+    /// ```
+    pub fn is_tskit_key(key: &str) -> bool {
+        TSKIT_KEYS.contains(&key)
+            || key
+                .split_once('/')
+                .is_some_and(|(group, _)| TSKIT_GROUPS.contains(&group))
+    }
+
+    /// Add an array, returning the previous array with the same key.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] if tskit uses `key`
+    /// or if `key` is empty.
+    pub fn insert<K: Into<String>, A: Into<KastoreArray>>(
+        &mut self,
+        key: K,
+        array: A,
+    ) -> Result<Option<KastoreArray>, TskitError> {
+        let key = key.into();
+        if key.is_empty() || Self::is_tskit_key(&key) {
+            return Err(TskitError::ValueError {
+                got: format!("key {key:?}"),
+                expected: "a non-empty key not used by tskit".to_string(),
+            });
+        }
+        Ok(self.arrays.insert(key, array.into()))
+    }
+
+    /// Get the array stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&KastoreArray> {
+        self.arrays.get(key)
+    }
+
+    /// Remove and return the array stored under `key`.
+    pub fn remove(&mut self, key: &str) -> Option<KastoreArray> {
+        self.arrays.remove(key)
+    }
+
+    /// Iterate over keys and arrays, in order of key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &KastoreArray)> + '_ {
+        self.arrays.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// The number of arrays.
+    pub fn len(&self) -> usize {
+        self.arrays.len()
+    }
+
+    /// `true` if there are no arrays.
+    pub fn is_empty(&self) -> bool {
+        self.arrays.is_empty()
+    }
+
+    // Add the arrays to an existing kastore file
+    pub(crate) fn append_to_file(&self, filename: &str) -> TskReturnValue {
+        if self.is_empty() {
+            return Ok(0);
+        }
+        let mut store = Store::open(filename, b"a\0", 0)?;
+        for (key, array) in &self.arrays {
+            store.put(key, array)?;
+        }
+        store.close()
+    }
+}
+
+// Write a file with `dump`, replacing the random uuid written
+// by tskit with a fixed value, so that the file only depends on
+// its tables. kastore writes arrays in order of key, so rewriting
+// the file changes nothing else.
+// The file is written and rewritten next to `filename` and then
+// renamed, so that `filename` is never partially written.
+pub(crate) fn dump_with_canonical_uuid<F: FnOnce(&str) -> TskReturnValue>(
+    filename: &str,
+    dump: F,
+) -> TskReturnValue {
+    let tmp = format!("{}.{}.tmp", filename, std::process::id());
+    let rv = dump(&tmp)
+        .and_then(|_| set_canonical_uuid(&tmp))
+        .and_then(|_| {
+            std::fs::rename(&tmp, filename)
+                .map(|_| 0)
+                .map_err(|e| TskitError::LibraryError(e.to_string()))
+        });
+    if rv.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    rv
+}
+
+fn set_canonical_uuid(filename: &str) -> TskReturnValue {
+    let items = Store::read_all(filename)?;
+    let mut store = Store::open(filename, b"w\0", 0)?;
+    for (key, array) in &items {
+        if key == "uuid" {
+            // tskit writes the uuid as characters
+            let uuid = CANONICAL_UUID.bytes().map(|b| b as i8).collect();
+            store.put(key, &KastoreArray::Int8(uuid))?;
+        } else {
+            store.put(key, array)?;
+        }
+    }
+    store.close()
+}
@@ -56,6 +56,25 @@
 //!
 //!     To see these derive macros in action, take a look
 //!     [`here`](metadata).
+//! * `indicatif`
+//!     * Implements [`ScanProgressReporter`] for `indicatif::ProgressBar`,
+//!       so that a progress bar can follow a [`GenomeScan`].
+//! * `petgraph`
+//!     * Adds `to_petgraph` to [`Tree`] and [`TreeSequence`], and
+//!       `from_petgraph` to [`TableCollection`], to convert trees and
+//!       tree sequences to and from `petgraph::Graph`.
+//! * `ndarray`
+//!     * Adds [`LocationMatrix::to_ndarray`], which copies the locations
+//!       of individuals into an `ndarray::Array2`.
+//! * `tools`
+//!     * Enables [`tools`], which summarizes tree sequences, reports
+//!       tree traversals, and exports VCF, and builds the `tskit-tools`
+//!       program that runs them on tree sequence files.
+//! * `serde`
+//!     * Adds `to_json` and `from_json` to [`TableCollection`] and
+//!       the table types, for small, human-readable snapshots of tables.
+//!     * Adds [`MetadataSchema::new`](metadata::MetadataSchema::new),
+//!       which checks metadata schemas.
 //!         
 //! To add features to your `Cargo.toml` file:
 //!
@@ -78,44 +97,100 @@
 pub use sys::bindings;
 
 mod _macros; // Starts w/_ to be sorted at front by rustfmt!
+mod allele_counts;
+mod canonical;
+mod columns;
+mod coordinates;
+mod digest;
 mod edge_differences;
+mod edge_index;
 mod edge_table;
 pub mod error;
+#[cfg(feature = "petgraph")]
+mod graph;
+mod haplotypes;
+mod ibd;
 mod individual_table;
+#[cfg(feature = "serde")]
+mod json;
+mod kastore;
+mod memory_usage;
 pub mod metadata;
 mod migration_table;
 mod mutation_table;
+mod newick;
 mod newtypes;
 mod node_table;
+mod packed_genotypes;
 mod population_table;
 pub mod prelude;
+mod repair;
+mod scan;
 mod site_table;
+mod stats;
+mod strip_metadata;
+mod subset;
 mod sys;
 mod table_collection;
 mod table_iterator;
 mod table_views;
+pub mod testing;
+#[cfg(feature = "tools")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "tools")))]
+pub mod tools;
 mod traits;
 mod tree_interface;
 mod trees;
 pub mod types;
 mod util;
+mod variant;
+mod visitor;
 
+pub use allele_counts::AlleleCountMatrix;
+pub use canonical::CanonicalDumpOptions;
+pub use columns::{ColumnDataType, ColumnDescriptor, TableColumns};
+pub use coordinates::{CoordinateMap, RateMap};
+pub use digest::TableCollectionDigest;
 pub use edge_differences::*;
+pub use edge_index::EdgeIntervalIndex;
 pub use edge_table::{EdgeTable, EdgeTableRow};
 pub use error::TskitError;
-pub use individual_table::{IndividualTable, IndividualTableRow};
-pub use migration_table::{MigrationTable, MigrationTableRow};
+#[cfg(feature = "petgraph")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "petgraph")))]
+pub use graph::{GraphEdge, GraphNode};
+pub use haplotypes::{HaplotypeOptions, MultiCharacterAlleles};
+pub use ibd::{IbdOptions, IdentitySegment, IdentitySegments};
+pub use individual_table::{IndividualTable, IndividualTableRow, LocationMatrix};
+pub use kastore::{KastoreArray, KastoreExtras};
+pub use memory_usage::TableMemoryUsage;
+pub use migration_table::{MigrationEvent, MigrationTable, MigrationTableRow};
 pub use mutation_table::{MutationTable, MutationTableRow};
+pub use newick::{NewickLabel, NewickOptions, NewickSample, NewickWriter};
 pub use newtypes::*;
 pub use node_table::{NodeDefaults, NodeDefaultsWithMetadata, NodeTable, NodeTableRow};
+pub use packed_genotypes::PackedGenotypes;
 pub use population_table::{PopulationTable, PopulationTableRow};
+pub use repair::TimeAdjustment;
+pub use scan::{GenomeScan, ScanProgress, ScanProgressReporter, ScanSegment};
 pub use site_table::{SiteTable, SiteTableRow};
+pub use stats::{
+    read_bed_windows, GenomeMask, MissingData, SampleSets, StatValue, StatWindow, StatsMode,
+    WindowedStatistic, Windows,
+};
+pub use subset::SubsetIdMaps;
 pub use sys::flags::*;
 pub use table_collection::TableCollection;
 pub use traits::IndividualLocation;
 pub use traits::IndividualParents;
-pub use tree_interface::{NodeTraversalOrder, TreeInterface};
-pub use trees::{Tree, TreeSequence};
+pub use tree_interface::{BranchPoint, NodeTraversalOrder, TreeInterface};
+pub use trees::{
+    ArgEdge, EdgeSpanSummary, MutationPopulationFrequencies, NodeSpanSummary, PopulationSummary,
+    Tree, TreeSequence, TreeSequenceCache, TreeSite, TreeSiteIterator,
+};
+pub use variant::{
+    DosageMatrix, Genotype, GenotypeChunk, GenotypeChunks, IndividualSamples, Variant,
+};
+pub use visitor::TableVisitor;
 
 // Optional features
 #[cfg(feature = "provenance")]
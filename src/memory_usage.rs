@@ -0,0 +1,133 @@
+//! Memory used by the tables of a table collection.
+
+use crate::sys::bindings as ll_bindings;
+
+/// The number of bytes used by the rows of each table of a
+/// [`TableCollection`](crate::TableCollection).
+///
+/// The size of a table is its number of rows times the size of
+/// a row, plus the lengths of its ragged columns, such as metadata.
+/// Offset columns, which have one more value than there are rows,
+/// are included.
+/// The memory allocated for a table may be larger, as
+/// tables allocate space for rows in advance.
+///
+/// See [`TableCollection::memory_usage`](crate::TableCollection::memory_usage).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TableMemoryUsage {
+    /// Bytes used by the individual table.
+    pub individuals: usize,
+    /// Bytes used by the node table.
+    pub nodes: usize,
+    /// Bytes used by the edge table.
+    pub edges: usize,
+    /// Bytes used by the migration table.
+    pub migrations: usize,
+    /// Bytes used by the site table.
+    pub sites: usize,
+    /// Bytes used by the mutation table.
+    pub mutations: usize,
+    /// Bytes used by the population table.
+    pub populations: usize,
+    /// Bytes used by the provenance table.
+    pub provenances: usize,
+    /// Bytes used by the edge insertion and removal indexes.
+    pub indexes: usize,
+}
+
+impl TableMemoryUsage {
+    /// The total number of bytes.
+    pub fn total(&self) -> usize {
+        self.iter().map(|(_, bytes)| bytes).sum()
+    }
+
+    /// Iterate over the names of the tables, as in the `tskit`
+    /// file format, and their numbers of bytes.
+    /// The indexes are named `"indexes"`.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, usize)> {
+        [
+            ("individuals", self.individuals),
+            ("nodes", self.nodes),
+            ("edges", self.edges),
+            ("migrations", self.migrations),
+            ("sites", self.sites),
+            ("mutations", self.mutations),
+            ("populations", self.populations),
+            ("provenances", self.provenances),
+            ("indexes", self.indexes),
+        ]
+        .into_iter()
+    }
+
+    /// The table using the most memory, and its number of bytes.
+    pub fn largest(&self) -> (&'static str, usize) {
+        // The first of the largest tables
+        self.iter()
+            .reduce(|largest, table| if table.1 > largest.1 { table } else { largest })
+            .unwrap()
+    }
+}
+
+// Bytes used by num_rows rows of fixed-size columns
+// whose sizes sum to row_size
+fn fixed(num_rows: ll_bindings::tsk_size_t, row_size: usize) -> usize {
+    num_rows as usize * row_size
+}
+
+// Bytes used by a ragged column and its offsets
+fn ragged<T>(num_rows: ll_bindings::tsk_size_t, length: ll_bindings::tsk_size_t) -> usize {
+    (num_rows as usize + 1) * std::mem::size_of::<ll_bindings::tsk_size_t>()
+        + length as usize * std::mem::size_of::<T>()
+}
+
+pub(crate) fn memory_usage(tables: &ll_bindings::tsk_table_collection_t) -> TableMemoryUsage {
+    use std::mem::size_of;
+    let id = size_of::<ll_bindings::tsk_id_t>();
+    let flags = size_of::<ll_bindings::tsk_flags_t>();
+    let double = size_of::<f64>();
+
+    let t = &tables.individuals;
+    let individuals = fixed(t.num_rows, flags)
+        + ragged::<f64>(t.num_rows, t.location_length)
+        + ragged::<ll_bindings::tsk_id_t>(t.num_rows, t.parents_length)
+        + ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.nodes;
+    let nodes =
+        fixed(t.num_rows, flags + double + 2 * id) + ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.edges;
+    let edges =
+        fixed(t.num_rows, 2 * double + 2 * id) + ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.migrations;
+    let migrations =
+        fixed(t.num_rows, 3 * double + 3 * id) + ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.sites;
+    let sites = fixed(t.num_rows, double)
+        + ragged::<u8>(t.num_rows, t.ancestral_state_length)
+        + ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.mutations;
+    let mutations = fixed(t.num_rows, double + 3 * id)
+        + ragged::<u8>(t.num_rows, t.derived_state_length)
+        + ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.populations;
+    let populations = ragged::<u8>(t.num_rows, t.metadata_length);
+    let t = &tables.provenances;
+    let provenances =
+        ragged::<u8>(t.num_rows, t.timestamp_length) + ragged::<u8>(t.num_rows, t.record_length);
+    let indexes = if tables.indexes.edge_insertion_order.is_null() {
+        0
+    } else {
+        fixed(tables.indexes.num_edges, 2 * id)
+    };
+
+    TableMemoryUsage {
+        individuals,
+        nodes,
+        edges,
+        migrations,
+        sites,
+        mutations,
+        populations,
+        provenances,
+        indexes,
+    }
+}
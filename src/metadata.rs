@@ -208,10 +208,136 @@ pub trait SiteMetadata: MetadataRoundtrip {}
 /// for the individual table of a [`TableCollection`](crate::TableCollection).
 pub trait IndividualMetadata: MetadataRoundtrip {}
 
+/// Individual metadata that records a name,
+/// such as a sample name used by external data.
+///
+/// Implementing this trait enables name-based functions
+/// such as [`TreeSequence::sample_names`](crate::TreeSequence::sample_names).
+pub trait NamedIndividualMetadata: IndividualMetadata {
+    /// The name of the individual
+    fn individual_name(&self) -> &str;
+}
+
 /// Marker trait indicating [`MetadataRoundtrip`]
 /// for the population table of a [`TableCollection`](crate::TableCollection).
 pub trait PopulationMetadata: MetadataRoundtrip {}
 
+/// Population metadata that records a population name.
+///
+/// Implementing this trait enables name-based lookups
+/// such as [`PopulationTable::find_by_name`](crate::PopulationTable::find_by_name).
+pub trait NamedPopulationMetadata: PopulationMetadata {
+    /// The name of the population
+    fn population_name(&self) -> &str;
+}
+
+/// Marker trait indicating [`MetadataRoundtrip`]
+/// for the top-level metadata of a [`TableCollection`](crate::TableCollection).
+///
+/// See [`TableCollection::set_metadata`](crate::TableCollection::set_metadata).
+pub trait TableCollectionMetadata: MetadataRoundtrip {}
+
+/// A metadata schema.
+///
+/// `tskit` stores metadata schemas as JSON text.
+/// Other `tskit` interfaces, such as the Python API, decode metadata
+/// according to the schema of its table and refuse to decode
+/// metadata without a schema.
+///
+/// A schema is set with the `set_metadata_schema` method of a
+/// table, such as [`NodeTable::set_metadata_schema`](crate::NodeTable::set_metadata_schema),
+/// or of the [`TableCollection`](crate::TableCollection), which accept any
+/// string.
+/// With the `serde` feature, [`MetadataSchema::new`] checks a schema
+/// before it is set.
+///
+/// # Examples
+///
+/// ```
+/// let schema = tskit::metadata::MetadataSchema::permissive_json();
+/// assert_eq!(schema.as_str(), r#"{"codec":"json"}"#);
+///
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.set_metadata_schema(&schema).unwrap();
+/// assert_eq!(tables.metadata_schema(), Some(schema.as_str()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MetadataSchema(String);
+
+impl MetadataSchema {
+    /// A schema for metadata encoded as JSON, with no constraints
+    /// on the values.
+    ///
+    /// This is the schema for metadata encoded with `serde_json`
+    /// by the `derive` feature.
+    pub fn permissive_json() -> Self {
+        Self(r#"{"codec":"json"}"#.to_string())
+    }
+
+    /// Create a schema from JSON text.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`](crate::TskitError::ValueError) if
+    ///   `schema` is not a JSON object with a `codec` of `json` or `struct`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::metadata::MetadataSchema;
+    ///
+    /// let schema = r#"{"codec":"json","type":"object","properties":{"name":{"type":"string"}}}"#;
+    /// assert!(MetadataSchema::new(schema).is_ok());
+    /// assert!(MetadataSchema::new(r#"{"type":"object"}"#).is_err());
+    /// assert!(MetadataSchema::new("json").is_err());
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+    pub fn new(schema: &str) -> Result<Self, crate::TskitError> {
+        let invalid = |got: String| crate::TskitError::ValueError {
+            got,
+            expected: "a JSON object with a codec of \"json\" or \"struct\"".to_string(),
+        };
+        let value = serde_json::from_str::<serde_json::Value>(schema)
+            .map_err(|e| invalid(format!("invalid JSON ({})", e)))?;
+        match value.get("codec") {
+            Some(serde_json::Value::String(codec)) if codec == "json" || codec == "struct" => {
+                Ok(Self(schema.to_string()))
+            }
+            Some(codec) => Err(invalid(format!("codec {}", codec))),
+            None => Err(invalid("no codec".to_string())),
+        }
+    }
+
+    /// The schema as JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for MetadataSchema {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for MetadataSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// A schema stored by tskit, or None if there is no schema
+pub(crate) fn schema_str<'a>(
+    schema: *const libc::c_char,
+    length: crate::sys::bindings::tsk_size_t,
+) -> Option<&'a str> {
+    if length == 0 {
+        return None;
+    }
+    std::str::from_utf8(crate::sys::generate_slice(schema, length)).ok()
+}
+
 pub(crate) struct EncodedMetadata {
     encoded: Vec<u8>,
 }
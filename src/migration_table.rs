@@ -35,6 +35,106 @@ impl PartialEq for MigrationTableRow {
     }
 }
 
+/// A migration of a node between populations, to be added
+/// with [`TableCollection::add_migrations`](crate::TableCollection::add_migrations).
+///
+/// # Examples
+///
+/// ```
+/// use tskit::MigrationEvent;
+///
+/// // A pulse of migration of several nodes at the same time
+/// let pulse = MigrationEvent::new((0., 100.), tskit::NodeId::NULL, (0, 1), 10.0);
+/// let events = [3, 4, 5]
+///     .map(|node| MigrationEvent {
+///         node: node.into(),
+///         ..pulse
+///     });
+/// assert_eq!(events[2].node, 5);
+/// assert_eq!(events[2].dest, 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MigrationEvent {
+    /// The left end of the genomic interval that migrates
+    pub left: Position,
+    /// The right end of the genomic interval that migrates
+    pub right: Position,
+    /// The migrating node
+    pub node: NodeId,
+    /// The population the node migrates from
+    pub source: PopulationId,
+    /// The population the node migrates to
+    pub dest: PopulationId,
+    /// The time of the migration
+    pub time: Time,
+}
+
+impl MigrationEvent {
+    /// Create a new event, with arguments ordered as
+    /// for [`MigrationTable::add_row`].
+    pub fn new<LEFT, RIGHT, N, SOURCE, DEST, T>(
+        span: (LEFT, RIGHT),
+        node: N,
+        source_dest: (SOURCE, DEST),
+        time: T,
+    ) -> Self
+    where
+        LEFT: Into<Position>,
+        RIGHT: Into<Position>,
+        N: Into<NodeId>,
+        SOURCE: Into<PopulationId>,
+        DEST: Into<PopulationId>,
+        T: Into<Time>,
+    {
+        Self {
+            left: span.0.into(),
+            right: span.1.into(),
+            node: node.into(),
+            source: source_dest.0.into(),
+            dest: source_dest.1.into(),
+            time: time.into(),
+        }
+    }
+
+    // Check the event against the tables it is added to
+    pub(crate) fn validate(
+        &self,
+        sequence_length: Position,
+        node_times: &[Time],
+        num_populations: usize,
+    ) -> Result<(), TskitError> {
+        if !(self.left >= 0.0 && self.left < self.right && self.right <= sequence_length) {
+            return Err(TskitError::RangeError(format!(
+                "migration span [{}, {}) is not within [0, {})",
+                self.left, self.right, sequence_length
+            )));
+        }
+        let node_time = match self.node.to_usize().and_then(|u| node_times.get(u)) {
+            Some(&t) => t,
+            None => return Err(TskitError::IndexError),
+        };
+        if [self.source, self.dest]
+            .iter()
+            .any(|p| !p.to_usize().is_some_and(|p| p < num_populations))
+        {
+            return Err(TskitError::IndexError);
+        }
+        if self.source == self.dest {
+            return Err(TskitError::ValueError {
+                got: format!("source and destination population {}", self.source),
+                expected: "distinct populations".to_string(),
+            });
+        }
+        if !(f64::from(self.time).is_finite() && self.time >= node_time) {
+            return Err(TskitError::ValueError {
+                got: format!("migration of node {} at time {}", self.node, self.time),
+                expected: format!("a finite time no less than the node time {}", node_time),
+            });
+        }
+        Ok(())
+    }
+}
+
 fn make_migration_table_row(table: &MigrationTable, pos: tsk_id_t) -> Option<MigrationTableRow> {
     Some(MigrationTableRow {
         id: pos.into(),
@@ -211,10 +311,21 @@ pub struct MigrationTable {
 }
 
 impl MigrationTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
+    pub fn new() -> Result<Self, TskitError> {
+        let table_ = sys::MigrationTable::new(0)?;
+        Ok(Self { table_ })
+    }
+
     pub(crate) fn new_from_table(
         migrations: *mut ll_bindings::tsk_migration_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(migrations).unwrap();
+        let ptr = std::ptr::NonNull::new(migrations)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::MigrationTable::new_borrowed(ptr) };
         Ok(MigrationTable { table_ })
     }
@@ -412,12 +523,25 @@ impl MigrationTable {
     build_table_column_slice_getter!(
         /// Get the dest column as a slice
         => dest, dest_slice_raw, ll_bindings::tsk_id_t);
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_migration_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_migration_table_set_metadata_schema);
 
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(MigrationId, tsk_migration_table_extend);
+
     pub fn add_row<LEFT, RIGHT, N, SOURCE, DEST, T>(
         &mut self,
         span: (LEFT, RIGHT),
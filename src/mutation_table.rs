@@ -207,10 +207,21 @@ pub struct MutationTable {
 }
 
 impl MutationTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
+    pub fn new() -> Result<Self, TskitError> {
+        let table_ = sys::MutationTable::new(0)?;
+        Ok(Self { table_ })
+    }
+
     pub(crate) fn new_from_table(
         mutations: *mut ll_bindings::tsk_mutation_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(mutations).unwrap();
+        let ptr = std::ptr::NonNull::new(mutations)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::MutationTable::new_borrowed(ptr) };
         Ok(MutationTable { table_ })
     }
@@ -290,6 +301,108 @@ impl MutationTable {
         )
     }
 
+    /// Return an iterator over the ancestral mutations of `mutation`,
+    /// obtained by following the `parent` column.
+    ///
+    /// The first value is the parent of `mutation`,
+    /// the second is the grandparent, etc..
+    ///
+    /// Valid tables require that a mutation's parent
+    /// precedes it in the table.
+    /// The iteration stops if that requirement is violated,
+    /// so that malformed tables cannot cause an infinite loop.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(iterator)` if `mutation` is valid.
+    /// * `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut mutations = tskit::MutationTable::default();
+    /// let m0 = mutations.add_row(0, 0, -1, 3.0, None).unwrap();
+    /// let m1 = mutations.add_row(0, 1, m0, 2.0, None).unwrap();
+    /// let m2 = mutations.add_row(0, 2, m1, 1.0, None).unwrap();
+    /// assert_eq!(mutations.ancestors(m2).unwrap().collect::<Vec<_>>(), vec![m1, m0]);
+    /// assert_eq!(mutations.ancestors(m0).unwrap().count(), 0);
+    /// assert!(mutations.ancestors(3).is_none());
+    /// ```
+    pub fn ancestors<M: Into<MutationId> + Copy>(
+        &self,
+        mutation: M,
+    ) -> Option<impl Iterator<Item = MutationId> + '_> {
+        let mutation = mutation.into();
+        // Validate the input row
+        self.parent(mutation)?;
+        Some(
+            std::iter::successors(Some(mutation), move |&m| match self.parent(m) {
+                Some(p) if !p.is_null() && p < m => Some(p),
+                _ => None,
+            })
+            .skip(1),
+        )
+    }
+
+    /// Obtain a vector containing the indexes ("ids") of all
+    /// mutations at `site`.
+    ///
+    /// The mutations are ordered from oldest to youngest.
+    /// Mutations with equal times are kept in table order,
+    /// as are all mutations if any time is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut mutations = tskit::MutationTable::default();
+    /// let m0 = mutations.add_row(0, 0, -1, 1.0, None).unwrap();
+    /// let m1 = mutations.add_row(1, 0, -1, 1.0, None).unwrap();
+    /// let m2 = mutations.add_row(0, 1, -1, 2.0, None).unwrap();
+    /// assert_eq!(mutations.site_mutations(0), vec![m2, m0]);
+    /// assert_eq!(mutations.site_mutations(1), vec![m1]);
+    /// assert!(mutations.site_mutations(2).is_empty());
+    /// ```
+    pub fn site_mutations<S: Into<SiteId>>(&self, site: S) -> Vec<MutationId> {
+        let site = site.into();
+        let time = self.time_slice();
+        let mut rv = self
+            .site_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == site)
+            .map(|(i, _)| MutationId::from(i as tsk_id_t))
+            .collect::<Vec<_>>();
+        let time = |m: &MutationId| f64::from(time[m.as_usize()]);
+        if !rv.iter().any(|m| time(m).is_nan()) {
+            rv.sort_by(|a, b| time(b).total_cmp(&time(a)));
+        }
+        rv
+    }
+
+    /// Count the mutations at each site.
+    ///
+    /// Sites without mutations are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut mutations = tskit::MutationTable::default();
+    /// mutations.add_row(0, 0, -1, 1.0, None).unwrap();
+    /// mutations.add_row(0, 1, -1, 1.0, None).unwrap();
+    /// mutations.add_row(2, 0, -1, 1.0, None).unwrap();
+    /// let counts = mutations.count_by_site();
+    /// assert_eq!(counts.len(), 2);
+    /// assert_eq!(counts[&0.into()], 2);
+    /// assert_eq!(counts[&2.into()], 1);
+    /// ```
+    pub fn count_by_site(&self) -> std::collections::HashMap<SiteId, usize> {
+        let mut rv = std::collections::HashMap::new();
+        for site in self.site_slice() {
+            *rv.entry(*site).or_insert(0) += 1;
+        }
+        rv
+    }
+
     /// Retrieve decoded metadata for a `row`.
     ///
     /// # Returns
@@ -387,12 +500,32 @@ impl MutationTable {
     build_table_column_slice_getter!(
         /// Get the parent column as a slice
         => parent, parent_slice_raw, crate::sys::bindings::tsk_id_t);
+    build_ragged_column_slice_getters!(
+        /// Get the derived state column as a slice of the values of all rows
+        => derived_state, derived_state_length, derived_state_slice, u8,
+        /// Get the offsets of the derived state of each row into
+        /// [`derived_state_slice`](Self::derived_state_slice).
+        /// There is one more offset than there are rows.
+        => derived_state_offset, derived_state_offset_slice);
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_mutation_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_mutation_table_set_metadata_schema);
 
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(MutationId, tsk_mutation_table_extend);
+
     pub fn add_row<S, N, P, T>(
         &mut self,
         site: S,
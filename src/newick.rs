@@ -0,0 +1,696 @@
+//! Import and export of trees in Newick format.
+
+use std::collections::HashMap;
+
+use crate::metadata::IndividualMetadata;
+use crate::metadata::MetadataError;
+use crate::metadata::MetadataRoundtrip;
+use crate::metadata::NamedIndividualMetadata;
+use crate::metadata::NamedPopulationMetadata;
+use crate::metadata::NodeMetadata;
+use crate::metadata::PopulationMetadata;
+use crate::IndividualId;
+use crate::NodeFlags;
+use crate::NodeId;
+use crate::PopulationId;
+use crate::Position;
+use crate::TableCollection;
+use crate::TableSortOptions;
+use crate::Time;
+use crate::TreeInterface;
+use crate::TskitError;
+
+/// A label from a Newick tree, or a name from
+/// a label mapping.
+///
+/// This type is the node metadata of labelled nodes
+/// and the individual and population metadata of tables created by
+/// [`TableCollection::from_newick`].
+/// The label is stored as UTF-8 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::metadata::MetadataRoundtrip;
+/// let label = tskit::NewickLabel::from("tip_1");
+/// assert_eq!(label.encode().unwrap(), b"tip_1");
+/// assert_eq!(tskit::NewickLabel::decode(b"tip_1").unwrap(), label);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NewickLabel(pub String);
+
+impl NewickLabel {
+    /// The label
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for NewickLabel {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<String> for NewickLabel {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl std::fmt::Display for NewickLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl MetadataRoundtrip for NewickLabel {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        Ok(self.0.as_bytes().to_vec())
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        match String::from_utf8(md.to_vec()) {
+            Ok(label) => Ok(Self(label)),
+            Err(e) => Err(MetadataError::RoundtripError { value: Box::new(e) }),
+        }
+    }
+}
+
+impl NodeMetadata for NewickLabel {}
+impl IndividualMetadata for NewickLabel {}
+impl PopulationMetadata for NewickLabel {}
+
+impl NamedIndividualMetadata for NewickLabel {
+    fn individual_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl NamedPopulationMetadata for NewickLabel {
+    fn population_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// The individual and population of a labelled
+/// node of a Newick tree.
+///
+/// See [`TableCollection::from_newick`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewickSample {
+    /// The name of the individual.
+    /// Nodes with the same individual name are
+    /// assigned to the same individual.
+    pub individual: Option<String>,
+    /// The name of the population.
+    pub population: Option<String>,
+}
+
+#[derive(Default)]
+struct ParsedNode {
+    label: Option<String>,
+    branch_length: Option<f64>,
+    parent: Option<usize>,
+    is_leaf: bool,
+}
+
+fn parse_error(got: impl std::fmt::Display, offset: usize, expected: &str) -> TskitError {
+    TskitError::ValueError {
+        got: format!("{got} at byte {offset} of the Newick string"),
+        expected: expected.to_string(),
+    }
+}
+
+fn is_delimiter(c: u8) -> bool {
+    matches!(c, b'(' | b')' | b',' | b':' | b';' | b'[') || c.is_ascii_whitespace()
+}
+
+struct Parser<'s> {
+    input: &'s str,
+    offset: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn peek(&mut self) -> Result<Option<u8>, TskitError> {
+        let bytes = self.input.as_bytes();
+        loop {
+            match bytes.get(self.offset) {
+                Some(c) if c.is_ascii_whitespace() => self.offset += 1,
+                Some(b'[') => match self.input[self.offset..].find(']') {
+                    // Comments are skipped
+                    Some(end) => self.offset += end + 1,
+                    None => return Err(parse_error("an unclosed comment", self.offset, "']'")),
+                },
+                Some(&c) => return Ok(Some(c)),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn label(&mut self) -> Result<String, TskitError> {
+        let bytes = self.input.as_bytes();
+        if bytes.get(self.offset) == Some(&b'\'') {
+            let start = self.offset;
+            let mut label = String::new();
+            let mut rest = &self.input[start + 1..];
+            loop {
+                match rest.find('\'') {
+                    Some(i) if rest[i + 1..].starts_with('\'') => {
+                        // An escaped quote
+                        label.push_str(&rest[..i + 1]);
+                        rest = &rest[i + 2..];
+                    }
+                    Some(i) => {
+                        label.push_str(&rest[..i]);
+                        self.offset = self.input.len() - rest.len() + i + 1;
+                        return Ok(label);
+                    }
+                    None => return Err(parse_error("an unclosed quote", start, "'''")),
+                }
+            }
+        }
+        let start = self.offset;
+        while bytes.get(self.offset).is_some_and(|&c| !is_delimiter(c)) {
+            self.offset += 1;
+        }
+        Ok(self.input[start..self.offset].to_string())
+    }
+
+    fn branch_length(&mut self) -> Result<f64, TskitError> {
+        let start = self.offset;
+        let bytes = self.input.as_bytes();
+        while bytes.get(self.offset).is_some_and(|&c| !is_delimiter(c)) {
+            self.offset += 1;
+        }
+        let text = &self.input[start..self.offset];
+        match text.parse::<f64>() {
+            Ok(x) if x.is_finite() && x >= 0.0 => Ok(x),
+            _ => Err(parse_error(
+                format!("{text:?}"),
+                start,
+                "a finite, non-negative branch length",
+            )),
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<ParsedNode>, TskitError> {
+        let mut nodes: Vec<ParsedNode> = vec![];
+        // The internal nodes whose children are being parsed
+        let mut open: Vec<usize> = vec![];
+        // The node that labels and branch lengths refer to
+        let mut last = 0;
+        // A new node starts at the beginning of the input
+        // and after each '(' and ','
+        let mut expect_node = true;
+        loop {
+            let c = self.peek()?;
+            if expect_node {
+                nodes.push(ParsedNode {
+                    parent: open.last().copied(),
+                    is_leaf: c != Some(b'('),
+                    ..Default::default()
+                });
+                last = nodes.len() - 1;
+                expect_node = c == Some(b'(');
+                if expect_node {
+                    self.offset += 1;
+                    open.push(last);
+                    continue;
+                }
+            }
+            match c {
+                Some(b',') if !open.is_empty() => {
+                    self.offset += 1;
+                    expect_node = true;
+                }
+                Some(b')') if !open.is_empty() => {
+                    self.offset += 1;
+                    last = open.pop().unwrap();
+                }
+                Some(b':') => {
+                    self.offset += 1;
+                    if nodes[last].branch_length.is_some() {
+                        return Err(parse_error("a second branch length", self.offset, "','"));
+                    }
+                    nodes[last].branch_length = Some(self.branch_length()?);
+                }
+                Some(b';') if open.is_empty() => {
+                    self.offset += 1;
+                    if self.peek()?.is_some() {
+                        return Err(parse_error(
+                            "trailing characters",
+                            self.offset,
+                            "the end of the Newick string",
+                        ));
+                    }
+                    return Ok(nodes);
+                }
+                Some(b',') | Some(b')') | Some(b';') | None => {
+                    return Err(parse_error(
+                        "an unbalanced tree",
+                        self.offset,
+                        "balanced parentheses and a final ';'",
+                    ))
+                }
+                Some(_) => {
+                    if nodes[last].label.is_some() || nodes[last].branch_length.is_some() {
+                        return Err(parse_error("a second label", self.offset, "','"));
+                    }
+                    nodes[last].label = Some(self.label()?);
+                }
+            }
+        }
+    }
+}
+
+/// Build tables from a Newick tree.
+///
+/// Nodes are added in preorder, so that the root is node 0.
+/// Node times are the distance to the node furthest from the root,
+/// making the deepest leaf time 0.
+pub(crate) fn tables_from_newick<P: Into<Position>>(
+    newick: &str,
+    sequence_length: P,
+    samples: &HashMap<String, NewickSample>,
+) -> Result<TableCollection, TskitError> {
+    let sequence_length = sequence_length.into();
+    let nodes = Parser {
+        input: newick,
+        offset: 0,
+    }
+    .parse()?;
+
+    // Parents precede children, so depths can be filled in order
+    let mut depth = vec![0.0; nodes.len()];
+    for (i, node) in nodes.iter().enumerate().skip(1) {
+        let parent = node.parent.unwrap();
+        match node.branch_length {
+            Some(length) => depth[i] = depth[parent] + length,
+            None => {
+                return Err(TskitError::ValueError {
+                    got: format!(
+                        "no branch length for node {}",
+                        node.label.as_deref().unwrap_or("(unlabelled)")
+                    ),
+                    expected: "branch lengths for all non-root nodes".to_string(),
+                })
+            }
+        }
+    }
+    let height = depth.iter().copied().fold(0.0, f64::max);
+
+    let mut leaf_labels = std::collections::HashSet::new();
+    for label in nodes
+        .iter()
+        .filter(|n| n.is_leaf)
+        .filter_map(|n| n.label.as_ref())
+    {
+        if !leaf_labels.insert(label) {
+            return Err(TskitError::ValueError {
+                got: format!("duplicate leaf label {label:?}"),
+                expected: "unique leaf labels".to_string(),
+            });
+        }
+    }
+
+    let mut tables = TableCollection::new(sequence_length)?;
+    let mut individuals: HashMap<&str, IndividualId> = HashMap::new();
+    let mut populations: HashMap<&str, PopulationId> = HashMap::new();
+    let mut node_ids = Vec::with_capacity(nodes.len());
+    for (i, node) in nodes.iter().enumerate() {
+        let flags = if node.is_leaf {
+            NodeFlags::new_sample()
+        } else {
+            NodeFlags::default()
+        };
+        let time = height - depth[i];
+        let sample = node.label.as_ref().and_then(|label| samples.get(label));
+        let mut individual = IndividualId::NULL;
+        let mut population = PopulationId::NULL;
+        if let Some(name) = sample.and_then(|s| s.individual.as_deref()) {
+            individual = match individuals.get(name) {
+                Some(&id) => id,
+                None => {
+                    let id = tables.add_individual_with_metadata(
+                        0,
+                        None,
+                        None,
+                        &NewickLabel::from(name),
+                    )?;
+                    individuals.insert(name, id);
+                    id
+                }
+            };
+        }
+        if let Some(name) = sample.and_then(|s| s.population.as_deref()) {
+            population = match populations.get(name) {
+                Some(&id) => id,
+                None => {
+                    let id = tables.add_population_with_metadata(&NewickLabel::from(name))?;
+                    populations.insert(name, id);
+                    id
+                }
+            };
+        }
+        let id: NodeId = match &node.label {
+            Some(label) => tables.add_node_with_metadata(
+                flags,
+                time,
+                population,
+                individual,
+                &NewickLabel::from(label.as_str()),
+            )?,
+            None => tables.add_node(flags, time, population, individual)?,
+        };
+        node_ids.push(id);
+    }
+    for (i, node) in nodes.iter().enumerate().skip(1) {
+        let parent = node_ids[node.parent.unwrap()];
+        tables.add_edge(0., sequence_length, parent, node_ids[i])?;
+    }
+    tables.full_sort(TableSortOptions::default())?;
+    tables.build_index()?;
+    Ok(tables)
+}
+
+/// Options for writing trees in Newick format.
+///
+/// By default, sample nodes are labelled with their node ids,
+/// other nodes are unlabelled, and branch lengths are written
+/// with 14 digits after the decimal point.
+///
+/// See [`TreeInterface::as_newick`].
+///
+/// # Examples
+///
+/// ```
+/// let options = tskit::NewickOptions::default()
+///     .precision(3)
+///     .node_labels(|u| Some(format!("node_{u}")));
+/// ```
+pub struct NewickOptions<'a> {
+    precision: usize,
+    root: Option<NodeId>,
+    labels: Option<Box<dyn Fn(NodeId) -> Option<String> + 'a>>,
+}
+
+impl Default for NewickOptions<'_> {
+    fn default() -> Self {
+        Self {
+            precision: 14,
+            root: None,
+            labels: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for NewickOptions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewickOptions")
+            .field("precision", &self.precision)
+            .field("root", &self.root)
+            .field("labels", &self.labels.as_ref().map(|_| "Fn(NodeId)"))
+            .finish()
+    }
+}
+
+impl<'a> NewickOptions<'a> {
+    /// Set the number of digits after the decimal point
+    /// of branch lengths.
+    pub fn precision(self, precision: usize) -> Self {
+        Self { precision, ..self }
+    }
+
+    /// Write the subtree of `root` rather than the whole tree.
+    ///
+    /// This is required for trees with more than one root.
+    pub fn root<N: Into<NodeId>>(self, root: N) -> Self {
+        Self {
+            root: Some(root.into()),
+            ..self
+        }
+    }
+
+    /// Label nodes with the result of `labels`.
+    ///
+    /// Nodes for which `labels` returns `None` are unlabelled.
+    /// Labels are quoted if they contain spaces or Newick
+    /// punctuation.
+    pub fn node_labels<F: Fn(NodeId) -> Option<String> + 'a>(self, labels: F) -> Self {
+        Self {
+            labels: Some(Box::new(labels)),
+            ..self
+        }
+    }
+}
+
+/// Write trees in Newick or Nexus format, one tree at a time.
+///
+/// Trees are written as they are passed to
+/// [`NewickWriter::write_tree`], typically while advancing a
+/// tree iterator, so that exporting many trees does not
+/// require holding their Newick strings in memory.
+///
+/// In Newick format, there is one tree per line.
+/// In Nexus format, a `TAXA` block lists the labels of the
+/// samples, and the trees are written to a `TREES` block,
+/// named `t{left}^{right}` after their genomic intervals,
+/// which is closed by [`NewickWriter::finish`].
+///
+/// See [`TreeInterface::as_newick`] for how each tree is written.
+///
+/// # Examples
+///
+/// ```
+/// use streaming_iterator::StreamingIterator;
+///
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// let root = tables.add_node(0, 1.0, -1, -1).unwrap();
+/// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+/// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+/// tables.add_edge(0., 100., root, c0).unwrap();
+/// tables.add_edge(0., 100., root, c1).unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+///
+/// let options = tskit::NewickOptions::default().precision(1);
+/// let mut writer =
+///     tskit::NewickWriter::nexus(vec![], options, treeseq.sample_nodes()).unwrap();
+/// let mut trees = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+/// while let Some(tree) = trees.next() {
+///     writer.write_tree(tree).unwrap();
+/// }
+/// assert_eq!(writer.num_trees(), 1);
+/// let nexus = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// assert_eq!(
+///     nexus,
+///     "#NEXUS\n\
+///      BEGIN TAXA;\n  DIMENSIONS NTAX=2;\n  TAXLABELS 1 2;\nEND;\n\
+///      BEGIN TREES;\n  TREE t0^100 = [&R] (1:1.0,2:1.0);\nEND;\n"
+/// );
+/// ```
+pub struct NewickWriter<'a, W: std::io::Write> {
+    writer: W,
+    options: NewickOptions<'a>,
+    nexus: bool,
+    num_trees: usize,
+}
+
+impl<W: std::io::Write> std::fmt::Debug for NewickWriter<'_, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewickWriter")
+            .field("options", &self.options)
+            .field("nexus", &self.nexus)
+            .field("num_trees", &self.num_trees)
+            .finish()
+    }
+}
+
+fn io_error(e: std::io::Error) -> TskitError {
+    TskitError::LibraryError(e.to_string())
+}
+
+impl<'a, W: std::io::Write> NewickWriter<'a, W> {
+    /// Write trees in Newick format, one per line.
+    pub fn newick(writer: W, options: NewickOptions<'a>) -> Self {
+        Self {
+            writer,
+            options,
+            nexus: false,
+            num_trees: 0,
+        }
+    }
+
+    /// Write trees in Nexus format.
+    ///
+    /// The `TAXA` block lists the labels of `samples`, as given
+    /// by `options`.
+    /// Samples without a label are not listed.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::LibraryError`] if writing the header fails.
+    pub fn nexus(
+        mut writer: W,
+        options: NewickOptions<'a>,
+        samples: &[NodeId],
+    ) -> Result<Self, TskitError> {
+        let mut labels = String::new();
+        let mut num_taxa = 0;
+        for &u in samples {
+            let label = match &options.labels {
+                Some(labels) => labels(u),
+                None => Some(u.to_string()),
+            };
+            if let Some(label) = label {
+                labels.push(' ');
+                push_label(&mut labels, &label);
+                num_taxa += 1;
+            }
+        }
+        write!(
+            writer,
+            "#NEXUS\nBEGIN TAXA;\n  DIMENSIONS NTAX={num_taxa};\n  TAXLABELS{labels};\nEND;\nBEGIN TREES;\n"
+        )
+        .map_err(io_error)?;
+        Ok(Self {
+            writer,
+            options,
+            nexus: true,
+            num_trees: 0,
+        })
+    }
+
+    /// Write a tree.
+    ///
+    /// # Errors
+    ///
+    /// * See [`TreeInterface::as_newick`].
+    /// * [`TskitError::LibraryError`] if writing fails.
+    pub fn write_tree(&mut self, tree: &TreeInterface) -> Result<(), TskitError> {
+        let newick = tree.as_newick(&self.options)?;
+        if self.nexus {
+            let (left, right) = tree.interval();
+            writeln!(
+                self.writer,
+                "  TREE t{}^{} = [&R] {}",
+                f64::from(left),
+                f64::from(right),
+                newick
+            )
+        } else {
+            writeln!(self.writer, "{}", newick)
+        }
+        .map_err(io_error)?;
+        self.num_trees += 1;
+        Ok(())
+    }
+
+    /// The number of trees written.
+    pub fn num_trees(&self) -> usize {
+        self.num_trees
+    }
+
+    /// Finish writing, returning the underlying writer.
+    ///
+    /// For Nexus format, this closes the `TREES` block,
+    /// so it must be called for the output to be complete.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::LibraryError`] if writing or flushing fails.
+    pub fn finish(mut self) -> Result<W, TskitError> {
+        if self.nexus {
+            self.writer.write_all(b"END;\n").map_err(io_error)?;
+        }
+        self.writer.flush().map_err(io_error)?;
+        Ok(self.writer)
+    }
+}
+
+fn push_label(newick: &mut String, label: &str) {
+    if !label.is_empty() && !label.bytes().any(|c| is_delimiter(c) || c == b'\'') {
+        newick.push_str(label);
+    } else {
+        newick.push('\'');
+        newick.push_str(&label.replace('\'', "''"));
+        newick.push('\'');
+    }
+}
+
+enum NewickStep {
+    Enter(NodeId),
+    Exit(NodeId),
+    Comma,
+}
+
+pub(crate) fn tree_to_newick(
+    tree: &TreeInterface,
+    time: &[Time],
+    options: &NewickOptions,
+) -> Result<String, TskitError> {
+    let root = match options.root {
+        Some(root) => {
+            if root.to_usize().map_or(true, |r| r >= time.len()) {
+                return Err(TskitError::IndexError {});
+            }
+            root
+        }
+        None => match tree.roots_to_vec()[..] {
+            [root] => root,
+            ref roots => {
+                return Err(TskitError::ValueError {
+                    got: format!("{} roots", roots.len()),
+                    expected: "a single root or NewickOptions::root".to_string(),
+                })
+            }
+        },
+    };
+    let label = |u: NodeId| match &options.labels {
+        Some(labels) => labels(u),
+        // SAFETY: u is a valid node of the tree
+        None => unsafe { crate::sys::bindings::tsk_tree_is_sample(tree.as_ptr(), u.into()) }
+            .then(|| u.to_string()),
+    };
+
+    let mut newick = String::new();
+    // Iterative, so that deep trees cannot overflow the stack
+    let mut steps = vec![NewickStep::Enter(root)];
+    while let Some(step) = steps.pop() {
+        let u = match step {
+            NewickStep::Comma => {
+                newick.push(',');
+                continue;
+            }
+            NewickStep::Enter(u) => {
+                let children = tree.children(u).collect::<Vec<_>>();
+                if !children.is_empty() {
+                    newick.push('(');
+                    steps.push(NewickStep::Exit(u));
+                    for (i, &c) in children.iter().enumerate().rev() {
+                        steps.push(NewickStep::Enter(c));
+                        if i > 0 {
+                            steps.push(NewickStep::Comma);
+                        }
+                    }
+                    continue;
+                }
+                u
+            }
+            NewickStep::Exit(u) => {
+                newick.push(')');
+                u
+            }
+        };
+        if let Some(label) = label(u) {
+            push_label(&mut newick, &label);
+        }
+        if u != root {
+            let parent = tree.parent(u).ok_or(TskitError::IndexError {})?;
+            let length = f64::from(time[parent.as_usize()] - time[u.as_usize()]);
+            newick.push_str(&format!(":{:.*}", options.precision, length));
+        }
+    }
+    newick.push(';');
+    Ok(newick)
+}
@@ -434,6 +434,11 @@ pub struct NodeTable {
 }
 
 impl NodeTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
     pub fn new() -> Result<Self, TskitError> {
         let table_ = sys::NodeTable::new(0)?;
         Ok(Self { table_ })
@@ -442,7 +447,8 @@ impl NodeTable {
     pub(crate) fn new_from_table(
         nodes: *mut ll_bindings::tsk_node_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(nodes).unwrap();
+        let ptr = std::ptr::NonNull::new(nodes)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::NodeTable::new_borrowed(ptr) };
         Ok(NodeTable { table_ })
     }
@@ -671,6 +677,95 @@ impl NodeTable {
             .collect::<Vec<_>>()
     }
 
+    /// Obtain a vector containing the indexes ("ids") of all nodes
+    /// satisfying a certain criterion.
+    ///
+    /// This is an alias for [`NodeTable::create_node_id_vector`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::NodeTable::default();
+    /// nodes.add_row(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 1.0, -1, -1).unwrap();
+    /// let old = nodes.ids_where(|row| row.time > 0.0);
+    /// assert_eq!(old, vec![tskit::NodeId::from(1)]);
+    /// ```
+    pub fn ids_where(&self, f: impl FnMut(&NodeTableRow) -> bool) -> Vec<NodeId> {
+        self.create_node_id_vector(f)
+    }
+
+    /// Obtain a vector containing the indexes ("ids") of all nodes
+    /// assigned to `population`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::NodeTable::default();
+    /// nodes.add_row(0, 0.0, 0, -1).unwrap();
+    /// nodes.add_row(0, 0.0, 1, -1).unwrap();
+    /// nodes.add_row(0, 1.0, 0, -1).unwrap();
+    /// let pop0 = nodes.nodes_by_population(0);
+    /// assert_eq!(pop0, vec![tskit::NodeId::from(0), tskit::NodeId::from(2)]);
+    /// ```
+    pub fn nodes_by_population<P: Into<PopulationId>>(&self, population: P) -> Vec<NodeId> {
+        let population = population.into();
+        self.population_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| **p == population)
+            .map(|(i, _)| NodeId::from(i as tsk_id_t))
+            .collect::<Vec<_>>()
+    }
+
+    /// Obtain a vector containing the indexes ("ids") of all nodes
+    /// whose time lies in the half-open interval `[range.start, range.end)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::NodeTable::default();
+    /// nodes.add_row(0, 0.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 1.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 2.0, -1, -1).unwrap();
+    /// let ids = nodes.nodes_in_time_range(0.5..2.0);
+    /// assert_eq!(ids, vec![tskit::NodeId::from(1)]);
+    /// ```
+    pub fn nodes_in_time_range<T: Into<Time>>(&self, range: std::ops::Range<T>) -> Vec<NodeId> {
+        let start = range.start.into();
+        let end = range.end.into();
+        self.time_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| **t >= start && **t < end)
+            .map(|(i, _)| NodeId::from(i as tsk_id_t))
+            .collect::<Vec<_>>()
+    }
+
+    /// Get the minimum and maximum node times.
+    ///
+    /// Returns `None` if the table is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::NodeTable::default();
+    /// assert!(nodes.time_minmax().is_none());
+    /// nodes.add_row(0, 1.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 0.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 3.0, -1, -1).unwrap();
+    /// assert_eq!(nodes.time_minmax(), Some((0.0.into(), 3.0.into())));
+    /// ```
+    pub fn time_minmax(&self) -> Option<(Time, Time)> {
+        let (first, rest) = self.time_slice().split_first()?;
+        Some(rest.iter().fold((*first, *first), |(min, max), t| {
+            (
+                if *t < min { *t } else { min },
+                if *t > max { *t } else { max },
+            )
+        }))
+    }
+
     build_table_column_slice_getter!(
         /// Get the time column as a slice
         => time, time_slice, Time);
@@ -813,12 +908,25 @@ impl NodeTable {
     build_table_column_slice_getter!(
         /// Get the population column as a slice
         => population, population_slice_raw, crate::sys::bindings::tsk_id_t);
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_node_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_node_table_set_metadata_schema);
 
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(NodeId, tsk_node_table_extend);
+
     pub fn add_row<F, T, P, I>(
         &mut self,
         flags: F,
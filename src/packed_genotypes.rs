@@ -0,0 +1,283 @@
+//! Bit-packed genotypes of biallelic sites.
+
+use crate::sys::bindings as ll_bindings;
+use crate::GenotypeChunk;
+use crate::TskitError;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Bit-packed genotypes of biallelic sites.
+///
+/// Genotypes are stored with one row per site and one bit per sample,
+/// which is set when the sample carries the derived allele (allele 1).
+/// If any genotype is missing, a second bit per sample records
+/// missing data, making two bits per genotype.
+///
+/// Counts of alleles and of two-site haplotypes use population counts
+/// of whole words, which is much faster than scanning a dense
+/// genotype matrix.
+/// Use [`PackedGenotypes::transpose`] to store one row per sample,
+/// so that the same methods compare samples, as for identity by state.
+///
+/// # Examples
+///
+/// ```
+/// // Two sites and four samples, with -1 for missing data
+/// let missing = -1;
+/// let dense = [0, 1, 1, 0, 0, 1, missing, 0];
+/// let packed = tskit::PackedGenotypes::from_dense(&dense, 4).unwrap();
+/// assert_eq!(packed.bits_per_genotype(), 2);
+/// assert_eq!(packed.allele_counts(0), Some([2, 2]));
+/// assert_eq!(packed.num_missing(1), Some(1));
+/// // Haplotypes 00, 01, 10, and 11 among samples without missing data
+/// assert_eq!(packed.haplotype_counts(0, 1), Some([2, 0, 0, 1]));
+/// assert_eq!(packed.to_dense(), dense);
+///
+/// // Samples 0 and 1 differ at both sites
+/// let by_sample = packed.transpose();
+/// assert_eq!(by_sample.haplotype_counts(0, 1), Some([0, 2, 0, 0]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedGenotypes {
+    num_sites: usize,
+    num_samples: usize,
+    words_per_site: usize,
+    derived: Vec<u64>,
+    missing: Option<Vec<u64>>,
+}
+
+impl PackedGenotypes {
+    fn new(num_sites: usize, num_samples: usize, with_missing: bool) -> Self {
+        let words_per_site = (num_samples + WORD_BITS - 1) / WORD_BITS;
+        let num_words = num_sites * words_per_site;
+        Self {
+            num_sites,
+            num_samples,
+            words_per_site,
+            derived: vec![0; num_words],
+            missing: with_missing.then(|| vec![0; num_words]),
+        }
+    }
+
+    /// Pack a dense genotype matrix.
+    ///
+    /// # Parameters
+    ///
+    /// * `genotypes`: the genotypes, with one row of `num_samples`
+    ///   values per site, as in [`GenotypeChunk::genotypes`].
+    /// * `num_samples`: the number of samples.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the length of `genotypes` is not
+    ///   a multiple of `num_samples` or if a genotype is not 0, 1,
+    ///   or [`TSK_MISSING_DATA`](crate::bindings::TSK_MISSING_DATA).
+    pub fn from_dense(genotypes: &[i32], num_samples: usize) -> Result<Self, TskitError> {
+        if num_samples == 0 || genotypes.len() % num_samples != 0 {
+            return Err(TskitError::ValueError {
+                got: format!("{} genotypes", genotypes.len()),
+                expected: format!("a multiple of {} genotypes", num_samples),
+            });
+        }
+        let with_missing = genotypes.contains(&ll_bindings::TSK_MISSING_DATA);
+        let mut packed = Self::new(genotypes.len() / num_samples, num_samples, with_missing);
+        for (site, row) in genotypes.chunks(num_samples).enumerate() {
+            for (sample, &g) in row.iter().enumerate() {
+                let word = site * packed.words_per_site + sample / WORD_BITS;
+                let bit = 1 << (sample % WORD_BITS);
+                match g {
+                    0 => (),
+                    1 => packed.derived[word] |= bit,
+                    ll_bindings::TSK_MISSING_DATA => {
+                        if let Some(missing) = packed.missing.as_mut() {
+                            missing[word] |= bit
+                        }
+                    }
+                    _ => {
+                        return Err(TskitError::ValueError {
+                            got: format!("genotype {g} at site index {site}"),
+                            expected: "biallelic genotypes".to_string(),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(packed)
+    }
+
+    /// Pack the genotypes of a [`GenotypeChunk`].
+    ///
+    /// # Errors
+    ///
+    /// See [`PackedGenotypes::from_dense`].
+    pub fn from_chunk(chunk: &GenotypeChunk) -> Result<Self, TskitError> {
+        if chunk.num_sites() == 0 {
+            return Ok(Self::new(0, chunk.num_samples, false));
+        }
+        Self::from_dense(&chunk.genotypes, chunk.num_samples)
+    }
+
+    /// Unpack to a dense genotype matrix.
+    ///
+    /// The result has one row of [`PackedGenotypes::num_samples`]
+    /// values per site.
+    pub fn to_dense(&self) -> Vec<i32> {
+        let mut dense = Vec::with_capacity(self.num_sites * self.num_samples);
+        for site in 0..self.num_sites {
+            for sample in 0..self.num_samples {
+                let word = site * self.words_per_site + sample / WORD_BITS;
+                let bit = 1 << (sample % WORD_BITS);
+                let g = if self.missing.as_ref().is_some_and(|m| m[word] & bit != 0) {
+                    ll_bindings::TSK_MISSING_DATA
+                } else if self.derived[word] & bit != 0 {
+                    1
+                } else {
+                    0
+                };
+                dense.push(g);
+            }
+        }
+        dense
+    }
+
+    /// The number of sites
+    pub fn num_sites(&self) -> usize {
+        self.num_sites
+    }
+
+    /// The number of samples
+    pub fn num_samples(&self) -> usize {
+        self.num_samples
+    }
+
+    /// The number of bits used per genotype.
+    ///
+    /// This is 2 if any genotype is missing and 1 otherwise.
+    pub fn bits_per_genotype(&self) -> usize {
+        if self.missing.is_some() {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// `true` if any genotype is missing.
+    pub fn has_missing_data(&self) -> bool {
+        self.missing.is_some()
+    }
+
+    fn words(&self, site: usize) -> Option<std::ops::Range<usize>> {
+        (site < self.num_sites)
+            .then(|| site * self.words_per_site..(site + 1) * self.words_per_site)
+    }
+
+    // The bits of samples in the last word
+    fn tail_mask(&self, word: usize) -> u64 {
+        let used = self.num_samples % WORD_BITS;
+        if word + 1 == self.words_per_site && used != 0 {
+            (1 << used) - 1
+        } else {
+            u64::MAX
+        }
+    }
+
+    // The bits of non-missing genotypes
+    fn known(&self, word: usize, offset: usize) -> u64 {
+        let missing = self.missing.as_ref().map_or(0, |m| m[offset + word]);
+        !missing & self.tail_mask(word)
+    }
+
+    /// The number of samples carrying alleles 0 and 1 at
+    /// the site with index `site`.
+    ///
+    /// Missing genotypes are not counted.
+    /// Returns `None` if `site` is out of range.
+    pub fn allele_counts(&self, site: usize) -> Option<[usize; 2]> {
+        let words = self.words(site)?;
+        let mut counts = [0; 2];
+        for (w, i) in words.clone().enumerate() {
+            let known = self.known(w, words.start);
+            counts[1] += (self.derived[i] & known).count_ones() as usize;
+            counts[0] += (!self.derived[i] & known).count_ones() as usize;
+        }
+        Some(counts)
+    }
+
+    /// The number of missing genotypes at the site with index `site`.
+    ///
+    /// Returns `None` if `site` is out of range.
+    pub fn num_missing(&self, site: usize) -> Option<usize> {
+        let words = self.words(site)?;
+        Some(self.missing.as_ref().map_or(0, |m| {
+            m[words].iter().map(|w| w.count_ones() as usize).sum()
+        }))
+    }
+
+    /// Count the two-site haplotypes of the sites with
+    /// indexes `a` and `b`.
+    ///
+    /// The counts are of the haplotypes `00`, `01`, `10`, and `11`,
+    /// in that order, where the first allele is that of site `a`.
+    /// Samples with a missing genotype at either site are not counted.
+    /// Returns `None` if `a` or `b` is out of range.
+    pub fn haplotype_counts(&self, a: usize, b: usize) -> Option<[usize; 4]> {
+        let words_a = self.words(a)?;
+        let words_b = self.words(b)?;
+        let mut counts = [0; 4];
+        for w in 0..self.words_per_site {
+            let known = self.known(w, words_a.start) & self.known(w, words_b.start);
+            let x = self.derived[words_a.start + w];
+            let y = self.derived[words_b.start + w];
+            counts[0] += (!x & !y & known).count_ones() as usize;
+            counts[1] += (!x & y & known).count_ones() as usize;
+            counts[2] += (x & !y & known).count_ones() as usize;
+            counts[3] += (x & y & known).count_ones() as usize;
+        }
+        Some(counts)
+    }
+
+    /// The squared correlation, `r^2`, of the alleles at the sites
+    /// with indexes `a` and `b`.
+    ///
+    /// Samples with a missing genotype at either site are ignored.
+    /// The value is `NaN` if either site is monomorphic among
+    /// the remaining samples.
+    /// Returns `None` if `a` or `b` is out of range.
+    pub fn r2(&self, a: usize, b: usize) -> Option<f64> {
+        let [n00, n01, n10, n11] = self.haplotype_counts(a, b)?.map(|c| c as f64);
+        let n = n00 + n01 + n10 + n11;
+        let p_a = (n10 + n11) / n;
+        let p_b = (n01 + n11) / n;
+        let d = n11 / n - p_a * p_b;
+        Some(d * d / (p_a * (1. - p_a) * p_b * (1. - p_b)))
+    }
+
+    /// Transpose the genotypes, so that there is one row
+    /// per sample and one column per site.
+    ///
+    /// After transposing, methods taking site indexes take sample
+    /// indexes. For example, the number of sites at which two samples
+    /// differ is the sum of the `01` and `10` haplotype counts.
+    pub fn transpose(&self) -> Self {
+        let mut transposed = Self::new(self.num_samples, self.num_sites, self.missing.is_some());
+        for site in 0..self.num_sites {
+            for sample in 0..self.num_samples {
+                let word = site * self.words_per_site + sample / WORD_BITS;
+                let bit = 1 << (sample % WORD_BITS);
+                let t_word = sample * transposed.words_per_site + site / WORD_BITS;
+                let t_bit = 1 << (site % WORD_BITS);
+                if self.derived[word] & bit != 0 {
+                    transposed.derived[t_word] |= t_bit;
+                }
+                if let (Some(missing), Some(t_missing)) =
+                    (self.missing.as_ref(), transposed.missing.as_mut())
+                {
+                    if missing[word] & bit != 0 {
+                        t_missing[t_word] |= t_bit;
+                    }
+                }
+            }
+        }
+        transposed
+    }
+}
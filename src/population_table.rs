@@ -157,10 +157,21 @@ pub struct PopulationTable {
 }
 
 impl PopulationTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
+    pub fn new() -> Result<Self, TskitError> {
+        let table_ = sys::PopulationTable::new(0)?;
+        Ok(Self { table_ })
+    }
+
     pub(crate) fn new_from_table(
         populations: *mut ll_bindings::tsk_population_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(populations).unwrap();
+        let ptr = std::ptr::NonNull::new(populations)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::PopulationTable::new_borrowed(ptr) };
         Ok(PopulationTable { table_ })
     }
@@ -176,6 +187,18 @@ impl PopulationTable {
         self.as_ref().num_rows.into()
     }
 
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_population_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_population_table_set_metadata_schema);
+
     /// Retrieve decoded metadata for a `row`.
     ///
     /// # Returns
@@ -200,6 +223,91 @@ impl PopulationTable {
         Some(decode_metadata_row!(T, buffer).map_err(TskitError::from))
     }
 
+    /// Find the first population whose metadata has name `name`.
+    ///
+    /// Rows without metadata are skipped.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(PopulationId))` if a population is found.
+    /// * `Ok(None)` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::MetadataError`] if decoding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// struct Name(String);
+    ///
+    /// impl tskit::metadata::MetadataRoundtrip for Name {
+    ///     fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+    ///         Ok(self.0.as_bytes().to_vec())
+    ///     }
+    ///     fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+    ///         Ok(Self(String::from_utf8_lossy(md).to_string()))
+    ///     }
+    /// }
+    /// impl tskit::metadata::PopulationMetadata for Name {}
+    /// impl tskit::metadata::NamedPopulationMetadata for Name {
+    ///     fn population_name(&self) -> &str {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let mut populations = tskit::PopulationTable::default();
+    /// populations.add_row_with_metadata(&Name("YRI".to_string())).unwrap();
+    /// populations.add_row_with_metadata(&Name("CEU".to_string())).unwrap();
+    /// // No metadata
+    /// populations.add_row().unwrap();
+    ///
+    /// assert_eq!(populations.find_by_name::<Name>("CEU").unwrap(), Some(1.into()));
+    /// assert!(populations.find_by_name::<Name>("CHB").unwrap().is_none());
+    ///
+    /// let names = populations.name_map::<Name>().unwrap();
+    /// assert_eq!(names.len(), 2);
+    /// assert_eq!(names[&tskit::PopulationId::from(0)], "YRI");
+    /// ```
+    pub fn find_by_name<M: metadata::NamedPopulationMetadata>(
+        &self,
+        name: &str,
+    ) -> Result<Option<PopulationId>, TskitError> {
+        for row in 0..self.num_rows().as_usize() {
+            let id = PopulationId::from(row as tsk_id_t);
+            if let Some(md) = self.metadata::<M>(id) {
+                if md?.population_name() == name {
+                    return Ok(Some(id));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Build a map from population id to population name.
+    ///
+    /// Rows without metadata are not included in the map.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::MetadataError`] if decoding fails.
+    ///
+    /// # Examples
+    ///
+    /// See [`PopulationTable::find_by_name`].
+    pub fn name_map<M: metadata::NamedPopulationMetadata>(
+        &self,
+    ) -> Result<std::collections::HashMap<PopulationId, String>, TskitError> {
+        let mut rv = std::collections::HashMap::new();
+        for row in 0..self.num_rows().as_usize() {
+            let id = PopulationId::from(row as tsk_id_t);
+            if let Some(md) = self.metadata::<M>(id) {
+                rv.insert(id, md?.population_name().to_string());
+            }
+        }
+        Ok(rv)
+    }
+
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`PopulationTableRow`].
     pub fn iter(&self) -> impl Iterator<Item = PopulationTableRow> + '_ {
@@ -265,4 +373,6 @@ impl PopulationTable {
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
+
+    build_table_copy_row!(PopulationId, tsk_population_table_extend);
 }
@@ -8,6 +8,8 @@
 //! * [`ProvenanceTable`].
 //! * [`ProvenanceTableRow`], which is the value type returned by
 //!   [`ProvenanceTable::iter`].
+//! * [`ProvenanceHistoryEntry`], which is the value type returned by
+//!   [`ProvenanceTable::history`].
 //!
 
 use crate::sys;
@@ -128,6 +130,87 @@ impl<'a> streaming_iterator::StreamingIterator for ProvenanceTableRowView<'a> {
     }
 }
 
+/// A structured description of one step in the history
+/// of a table collection.
+///
+/// Provenance records written by `tskit` and related tools are
+/// `JSON` documents following the
+/// [tskit provenance schema](https://tskit.dev/tskit/docs/stable/provenance.html).
+/// The fields and [`parameters`](ProvenanceHistoryEntry::parameters)
+/// of this type are extracted from such records,
+/// and are `None` if a record does not provide them.
+///
+/// See [`ProvenanceTable::history`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProvenanceHistoryEntry {
+    /// The row id
+    pub id: ProvenanceId,
+    /// ISO-formatted time stamp
+    pub timestamp: String,
+    /// The parsed time stamp
+    pub time: Option<std::time::SystemTime>,
+    /// The name of the software that created the record
+    pub software_name: Option<String>,
+    /// The version of the software that created the record
+    pub software_version: Option<String>,
+    parameters: Option<String>,
+    /// The previous step, from whose output this step's input was derived
+    pub derived_from: Option<ProvenanceId>,
+    /// The raw provenance record
+    pub record: String,
+}
+
+impl ProvenanceHistoryEntry {
+    fn new(row: ProvenanceTableRow, derived_from: Option<ProvenanceId>) -> Self {
+        let json = serde_json::from_str::<serde_json::Value>(&row.record).ok();
+        let software = |field: &str| {
+            json.as_ref()
+                .and_then(|j| j.get("software"))
+                .and_then(|s| s.get(field))
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string())
+        };
+        Self {
+            id: row.id,
+            time: humantime::parse_rfc3339_weak(&row.timestamp).ok(),
+            software_name: software("name"),
+            software_version: software("version"),
+            parameters: json
+                .as_ref()
+                .and_then(|j| j.get("parameters"))
+                .map(|p| p.to_string()),
+            derived_from,
+            timestamp: row.timestamp,
+            record: row.record,
+        }
+    }
+
+    /// The parameters of the step, such as a command and its arguments,
+    /// as a `JSON` document.
+    pub fn parameters(&self) -> Option<&str> {
+        self.parameters.as_deref()
+    }
+}
+
+impl std::fmt::Display for ProvenanceHistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} {} at {}",
+            self.id,
+            self.software_name.as_deref().unwrap_or("unknown software"),
+            self.software_version
+                .as_deref()
+                .unwrap_or("(unknown version)"),
+            self.timestamp,
+        )?;
+        if let Some(parent) = self.derived_from {
+            write!(f, ", derived from {}", parent)?;
+        }
+        Ok(())
+    }
+}
+
 /// A provenance table.
 ///
 /// # Notes
@@ -154,10 +237,21 @@ pub struct ProvenanceTable {
 }
 
 impl ProvenanceTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`](crate::TskitError) if the table cannot be allocated.
+    pub fn new() -> Result<Self, crate::TskitError> {
+        let table_ = sys::ProvenanceTable::new(0)?;
+        Ok(Self { table_ })
+    }
+
     pub(crate) fn new_from_table(
         provenances: *mut ll_bindings::tsk_provenance_table_t,
     ) -> Result<Self, crate::TskitError> {
-        let ptr = std::ptr::NonNull::new(provenances).unwrap();
+        let ptr = std::ptr::NonNull::new(provenances)
+            .ok_or_else(|| crate::TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::ProvenanceTable::new_borrowed(ptr) };
         Ok(ProvenanceTable { table_ })
     }
@@ -171,6 +265,23 @@ impl ProvenanceTable {
         self.as_ref().num_rows.into()
     }
 
+    build_ragged_column_slice_getters!(
+        /// Get the timestamp column as a slice of the values of all rows
+        => timestamp, timestamp_length, timestamp_slice, u8,
+        /// Get the offsets of the timestamp of each row into
+        /// [`timestamp_slice`](Self::timestamp_slice).
+        /// There is one more offset than there are rows.
+        => timestamp_offset, timestamp_offset_slice);
+    build_ragged_column_slice_getters!(
+        /// Get the record column as a slice of the values of all rows
+        => record, record_length, record_slice, u8,
+        /// Get the offsets of the record of each row into
+        /// [`record_slice`](Self::record_slice).
+        /// There is one more offset than there are rows.
+        => record_offset, record_offset_slice);
+
+    build_table_dump_text!(tsk_provenance_table_dump_text);
+
     /// Get the ISO-formatted time stamp for row `row`.
     ///
     /// # Returns
@@ -280,11 +391,57 @@ impl ProvenanceTable {
         ProvenanceTableRowView::new(self)
     }
 
+    /// Describe the history recorded in the table.
+    ///
+    /// Rows are returned in order, and each row is derived from
+    /// the one before it.
+    /// Records that are not valid `JSON` are included,
+    /// with all optional fields set to `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables
+    ///     .add_provenance(
+    ///         r#"{"schema_version": "1.0.0",
+    ///             "software": {"name": "msprime", "version": "1.3.0"},
+    ///             "parameters": {"command": "sim_ancestry", "samples": 10}}"#,
+    ///     )
+    ///     .unwrap();
+    /// tables.add_provenance("not json").unwrap();
+    /// let history = tables.provenances().history();
+    /// assert_eq!(history.len(), 2);
+    /// assert_eq!(history[0].software_name.as_deref(), Some("msprime"));
+    /// assert_eq!(history[0].software_version.as_deref(), Some("1.3.0"));
+    /// assert_eq!(
+    ///     history[0].parameters(),
+    ///     Some(r#"{"command":"sim_ancestry","samples":10}"#)
+    /// );
+    /// assert!(history[0].time.is_some());
+    /// assert!(history[0].derived_from.is_none());
+    /// assert!(history[1].software_name.is_none());
+    /// assert_eq!(history[1].derived_from, Some(0.into()));
+    /// ```
+    pub fn history(&self) -> Vec<ProvenanceHistoryEntry> {
+        let mut derived_from = None;
+        self.iter()
+            .map(|row| {
+                let id = row.id;
+                let entry = ProvenanceHistoryEntry::new(row, derived_from);
+                derived_from = Some(id);
+                entry
+            })
+            .collect()
+    }
+
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, crate::TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(ProvenanceId, tsk_provenance_table_extend);
+
     pub fn add_row(&mut self, record: &str) -> Result<ProvenanceId, crate::TskitError> {
         if record.is_empty() {
             return Err(crate::TskitError::ValueError {
@@ -349,4 +506,30 @@ mod test_provenances {
             }
         }
     }
+
+    #[test]
+    fn test_history() {
+        let mut tables = crate::TableCollection::new(10.).unwrap();
+        tables
+            .add_provenance(r#"{"software": {"name": "sim", "version": "0.1"}}"#)
+            .unwrap();
+        tables.build_index().unwrap();
+        let mut ts = tables
+            .tree_sequence(crate::TreeSequenceFlags::default())
+            .unwrap();
+        ts.add_provenance(r#"{"software": {"name": "filter"}, "parameters": [1, 2]}"#)
+            .unwrap();
+
+        let history = ts.provenances().history();
+        assert_eq!(history.len(), 2);
+        let ids = history.iter().map(|h| h.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(history[1].derived_from, Some(history[0].id));
+        assert!(history[0].parameters().is_none());
+        assert_eq!(history[1].parameters(), Some("[1,2]"));
+        assert!(history[1].software_version.is_none());
+        assert!(history.iter().all(|h| h.time.is_some()));
+        assert!(format!("{}", history[0]).starts_with("0: sim 0.1 at "));
+        assert!(format!("{}", history[1]).ends_with(", derived from 0"));
+    }
 }
@@ -0,0 +1,189 @@
+//! Repairing the times of nodes and mutations.
+
+use crate::sys::bindings as ll_bindings;
+use crate::MutationId;
+use crate::NodeId;
+use crate::TableCollection;
+use crate::Time;
+use crate::TskitError;
+
+/// A change to a time made by
+/// [`TableCollection::repair_times`](crate::TableCollection::repair_times).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeAdjustment {
+    /// The time of a node was increased.
+    Node {
+        /// The node
+        id: NodeId,
+        /// The time before the repair
+        old: Time,
+        /// The time after the repair
+        new: Time,
+    },
+    /// The time of a mutation was increased.
+    Mutation {
+        /// The mutation
+        id: MutationId,
+        /// The time before the repair
+        old: Time,
+        /// The time after the repair
+        new: Time,
+    },
+}
+
+impl TimeAdjustment {
+    /// The increase of the time.
+    pub fn change(&self) -> f64 {
+        match self {
+            Self::Node { old, new, .. } | Self::Mutation { old, new, .. } => {
+                f64::from(*new) - f64::from(*old)
+            }
+        }
+    }
+}
+
+// The smallest f64 greater than x, for finite x
+fn next_up(x: f64) -> f64 {
+    if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+fn column_mut<'a>(data: *mut f64, length: ll_bindings::tsk_size_t) -> &'a mut [f64] {
+    if length == 0 {
+        &mut []
+    } else {
+        crate::sys::generate_slice_mut(data, length)
+    }
+}
+
+// The nodes, children before parents
+fn nodes_in_time_order(tables: &TableCollection) -> Result<Vec<usize>, TskitError> {
+    let num_nodes = tables.nodes().num_rows().as_usize();
+    let edges = tables.edges();
+    let mut parents = vec![vec![]; num_nodes];
+    let mut num_children = vec![0; num_nodes];
+    for (&p, &c) in edges.parent_slice().iter().zip(edges.child_slice()) {
+        match (p.to_usize(), c.to_usize()) {
+            (Some(p), Some(c)) if p < num_nodes && c < num_nodes => {
+                parents[c].push(p);
+                num_children[p] += 1;
+            }
+            _ => return Err(TskitError::IndexError),
+        }
+    }
+    let mut order = (0..num_nodes)
+        .filter(|&u| num_children[u] == 0)
+        .collect::<Vec<_>>();
+    let mut next = 0;
+    while next < order.len() {
+        for &p in &parents[order[next]] {
+            num_children[p] -= 1;
+            if num_children[p] == 0 {
+                order.push(p);
+            }
+        }
+        next += 1;
+    }
+    if order.len() < num_nodes {
+        return Err(TskitError::ValueError {
+            got: "a node that is its own ancestor".to_string(),
+            expected: "edges without cycles".to_string(),
+        });
+    }
+    Ok(order)
+}
+
+pub(crate) fn repair_times(
+    tables: &mut TableCollection,
+    tolerance: f64,
+) -> Result<Vec<TimeAdjustment>, TskitError> {
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(TskitError::ValueError {
+            got: format!("{}", tolerance),
+            expected: "a non-negative tolerance".to_string(),
+        });
+    }
+    let order = nodes_in_time_order(tables)?;
+    let num_nodes = tables.nodes().num_rows().as_usize();
+    let num_mutations = tables.mutations().num_rows().as_usize();
+    let mut node_time = tables.nodes().time_slice_raw().to_vec();
+    let mut mutation_time = tables.mutations().time_slice_raw().to_vec();
+    let mutation_parent = tables.mutations().parent_slice();
+    let is_known = |t: f64| t.to_bits() != ll_bindings::TSK_UNKNOWN_TIME_HEX;
+
+    let mut node_mutations = vec![vec![]; num_nodes];
+    for (m, u) in tables.mutations().node_slice().iter().enumerate() {
+        match u.to_usize().filter(|&u| u < num_nodes) {
+            Some(u) => node_mutations[u].push(m),
+            None => return Err(TskitError::IndexError),
+        }
+    }
+    let mut children = vec![vec![]; num_nodes];
+    let edges = tables.edges();
+    for (&p, &c) in edges.parent_slice().iter().zip(edges.child_slice()) {
+        children[p.as_usize()].push(c.as_usize());
+    }
+
+    // The oldest time of a node and its mutations, which
+    // its parents must be older than
+    let mut oldest = vec![f64::NEG_INFINITY; num_nodes];
+    // The oldest time of the child mutations of each mutation
+    let mut oldest_child_mutation = vec![f64::NEG_INFINITY; num_mutations];
+    let mut rv = vec![];
+    for u in order {
+        let old = node_time[u];
+        for &c in &children[u] {
+            if oldest[c] >= node_time[u] {
+                node_time[u] = next_up(oldest[c]);
+            }
+        }
+        if node_time[u] != old {
+            rv.push(TimeAdjustment::Node {
+                id: NodeId::from(u as ll_bindings::tsk_id_t),
+                old: old.into(),
+                new: node_time[u].into(),
+            });
+        }
+        oldest[u] = node_time[u];
+        // Child mutations on the same node come later in the table
+        for &m in node_mutations[u].iter().rev() {
+            let old = mutation_time[m];
+            if !is_known(old) {
+                continue;
+            }
+            mutation_time[m] = old.max(node_time[u]).max(oldest_child_mutation[m]);
+            if mutation_time[m] != old {
+                rv.push(TimeAdjustment::Mutation {
+                    id: MutationId::from(m as ll_bindings::tsk_id_t),
+                    old: old.into(),
+                    new: mutation_time[m].into(),
+                });
+            }
+            if let Some(parent) = mutation_parent[m].to_usize().filter(|&p| p < num_mutations) {
+                oldest_child_mutation[parent] = oldest_child_mutation[parent].max(mutation_time[m]);
+            }
+            oldest[u] = oldest[u].max(mutation_time[m]);
+        }
+    }
+
+    if let Some(adjustment) = rv
+        .iter()
+        .find(|a| a.change().is_nan() || a.change() > tolerance)
+    {
+        return Err(TskitError::ValueError {
+            got: format!("{:?}", adjustment),
+            expected: format!("time adjustments of at most {}", tolerance),
+        });
+    }
+
+    // SAFETY: the table collection is initialized
+    let ll = unsafe { &mut *tables.as_mut_ptr() };
+    column_mut(ll.nodes.time, ll.nodes.num_rows).copy_from_slice(&node_time);
+    column_mut(ll.mutations.time, ll.mutations.num_rows).copy_from_slice(&mutation_time);
+    Ok(rv)
+}
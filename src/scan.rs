@@ -0,0 +1,246 @@
+//! Genome-wide scans with progress reporting.
+
+use crate::Position;
+use crate::Tree;
+use crate::TreeFlags;
+use crate::TreeSequence;
+use crate::TskitError;
+
+/// The progress of a [`GenomeScan`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScanProgress {
+    /// The number of trees processed
+    pub trees_completed: usize,
+    /// The number of trees in the tree sequence
+    pub num_trees: usize,
+    /// The right coordinate of the last tree processed
+    pub position: Position,
+    /// The sequence length
+    pub sequence_length: Position,
+}
+
+impl ScanProgress {
+    /// The fraction of the genome scanned.
+    pub fn fraction(&self) -> f64 {
+        f64::from(self.position) / f64::from(self.sequence_length)
+    }
+}
+
+/// Receives progress reports from a [`GenomeScan`].
+///
+/// This trait is implemented for closures taking a
+/// [`ScanProgress`] and, with the `indicatif` feature,
+/// for `indicatif::ProgressBar`.
+pub trait ScanProgressReporter {
+    /// Report progress.
+    fn report(&mut self, progress: &ScanProgress);
+
+    /// Called once after the last tree has been processed.
+    ///
+    /// Not called if the scan ends with an error.
+    /// The default implementation does nothing.
+    fn finish(&mut self) {}
+}
+
+impl<F: FnMut(&ScanProgress)> ScanProgressReporter for F {
+    fn report(&mut self, progress: &ScanProgress) {
+        self(progress)
+    }
+}
+
+#[cfg(feature = "indicatif")]
+impl ScanProgressReporter for indicatif::ProgressBar {
+    fn report(&mut self, progress: &ScanProgress) {
+        self.set_length(progress.num_trees as u64);
+        self.set_position(progress.trees_completed as u64);
+    }
+
+    fn finish(&mut self) {
+        indicatif::ProgressBar::finish(self)
+    }
+}
+
+/// The part of a window covered by a tree.
+///
+/// See [`GenomeScan::run`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScanSegment {
+    /// The index of the window
+    pub window: usize,
+    /// The left coordinate of the segment
+    pub left: Position,
+    /// The right coordinate of the segment
+    pub right: Position,
+}
+
+/// A driver for scans over all trees of a [`TreeSequence`].
+///
+/// Created by [`TreeSequence::genome_scan`].
+pub struct GenomeScan<'treeseq> {
+    treeseq: &'treeseq TreeSequence,
+    windows: Vec<Position>,
+    reporter: Option<Box<dyn ScanProgressReporter + 'treeseq>>,
+    report_every: usize,
+}
+
+// Collect and check window breakpoints, which must
+// strictly increase from 0 to the sequence length.
+pub(crate) fn window_breakpoints<P: Into<Position>>(
+    breakpoints: impl IntoIterator<Item = P>,
+    sequence_length: f64,
+) -> Result<Vec<Position>, TskitError> {
+    let breakpoints = breakpoints
+        .into_iter()
+        .map(|p| p.into())
+        .collect::<Vec<Position>>();
+    if breakpoints.len() < 2
+        || breakpoints[0] != 0.0
+        || breakpoints[breakpoints.len() - 1] != sequence_length
+        || breakpoints.windows(2).any(|w| w[0] >= w[1])
+    {
+        return Err(TskitError::ValueError {
+            got: format!("{:?}", breakpoints),
+            expected: format!(
+                "strictly increasing window breakpoints from 0 to {}",
+                sequence_length
+            ),
+        });
+    }
+    Ok(breakpoints)
+}
+
+impl<'treeseq> GenomeScan<'treeseq> {
+    pub(crate) fn new(treeseq: &'treeseq TreeSequence) -> Self {
+        Self {
+            treeseq,
+            windows: vec![0.0.into(), treeseq.inner.sequence_length().into()],
+            reporter: None,
+            report_every: 1,
+        }
+    }
+
+    /// Set the window breakpoints.
+    ///
+    /// By default, the whole genome is a single window.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `breakpoints` do not start at 0,
+    ///   end at the sequence length, and strictly increase.
+    pub fn windows<P: Into<Position>>(
+        mut self,
+        breakpoints: impl IntoIterator<Item = P>,
+    ) -> Result<Self, TskitError> {
+        self.windows = window_breakpoints(breakpoints, self.treeseq.inner.sequence_length())?;
+        Ok(self)
+    }
+
+    /// Set the progress reporter.
+    pub fn progress<R: ScanProgressReporter + 'treeseq>(mut self, reporter: R) -> Self {
+        self.reporter = Some(Box::new(reporter));
+        self
+    }
+
+    /// Report progress after every `num_trees` trees.
+    ///
+    /// Progress is always reported after the last tree.
+    /// The default is to report after every tree.
+    /// A value of 0 is treated as 1.
+    pub fn report_every(mut self, num_trees: usize) -> Self {
+        self.report_every = num_trees.max(1);
+        self
+    }
+
+    /// Run the scan.
+    ///
+    /// For each tree, `f` is called once for each window that the
+    /// tree overlaps, in order along the genome, with the part of the
+    /// window that the tree covers.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if creating the tree iterator fails.
+    /// * Any error returned by `f`, which stops the scan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 30., parent, c1).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// // The span of each window covered by trees with two samples
+    /// let mut spans = vec![0.0; 2];
+    /// let mut reports = vec![];
+    /// treeseq
+    ///     .genome_scan()
+    ///     .windows([0., 50., 100.])
+    ///     .unwrap()
+    ///     .progress(|p: &tskit::ScanProgress| reports.push(p.fraction()))
+    ///     .run(tskit::TreeFlags::default(), |segment, tree| {
+    ///         if tree.children(parent).count() == 2 {
+    ///             spans[segment.window] += f64::from(segment.right - segment.left);
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(spans, vec![30.0, 0.0]);
+    /// assert_eq!(reports, vec![0.3, 1.0]);
+    /// ```
+    pub fn run<F, G>(mut self, flags: F, mut f: G) -> Result<(), TskitError>
+    where
+        F: Into<TreeFlags>,
+        G: FnMut(&ScanSegment, &Tree) -> Result<(), TskitError>,
+    {
+        use streaming_iterator::StreamingIterator;
+
+        let num_trees = self.treeseq.num_trees().as_usize();
+        let sequence_length = self.treeseq.inner.sequence_length().into();
+        let mut trees = self.treeseq.tree_iterator(flags)?;
+        let mut window = 0;
+        let mut trees_completed = 0;
+        while let Some(tree) = trees.next() {
+            let (left, right) = tree.interval();
+            while self.windows[window + 1] <= left {
+                window += 1;
+            }
+            let mut w = window;
+            while w + 1 < self.windows.len() && self.windows[w] < right {
+                let segment = ScanSegment {
+                    window: w,
+                    left: if self.windows[w] > left {
+                        self.windows[w]
+                    } else {
+                        left
+                    },
+                    right: if self.windows[w + 1] < right {
+                        self.windows[w + 1]
+                    } else {
+                        right
+                    },
+                };
+                f(&segment, tree)?;
+                w += 1;
+            }
+            trees_completed += 1;
+            if trees_completed % self.report_every == 0 || trees_completed == num_trees {
+                if let Some(reporter) = self.reporter.as_mut() {
+                    reporter.report(&ScanProgress {
+                        trees_completed,
+                        num_trees,
+                        position: right,
+                        sequence_length,
+                    });
+                }
+            }
+        }
+        if let Some(reporter) = self.reporter.as_mut() {
+            reporter.finish();
+        }
+        Ok(())
+    }
+}
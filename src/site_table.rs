@@ -176,10 +176,21 @@ pub struct SiteTable {
 }
 
 impl SiteTable {
+    /// Create a new, empty table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the table cannot be allocated.
+    pub fn new() -> Result<Self, TskitError> {
+        let table_ = sys::SiteTable::new(0)?;
+        Ok(Self { table_ })
+    }
+
     pub(crate) fn new_from_table(
         sites: *mut ll_bindings::tsk_site_table_t,
     ) -> Result<Self, TskitError> {
-        let ptr = std::ptr::NonNull::new(sites).unwrap();
+        let ptr = std::ptr::NonNull::new(sites)
+            .ok_or_else(|| TskitError::LibraryError("null table pointer".to_string()))?;
         let table_ = unsafe { sys::SiteTable::new_borrowed(ptr) };
         Ok(SiteTable { table_ })
     }
@@ -301,12 +312,32 @@ impl SiteTable {
     build_table_column_slice_getter!(
         /// Get the position column as a slice
         => position, position_slice_raw, f64);
+    build_ragged_column_slice_getters!(
+        /// Get the ancestral state column as a slice of the values of all rows
+        => ancestral_state, ancestral_state_length, ancestral_state_slice, u8,
+        /// Get the offsets of the ancestral state of each row into
+        /// [`ancestral_state_slice`](Self::ancestral_state_slice).
+        /// There is one more offset than there are rows.
+        => ancestral_state_offset, ancestral_state_offset_slice);
+    build_ragged_column_slice_getters!(
+        /// Get the metadata column as a slice of the values of all rows
+        => metadata, metadata_length, metadata_slice, u8,
+        /// Get the offsets of the metadata of each row into
+        /// [`metadata_slice`](Self::metadata_slice).
+        /// There is one more offset than there are rows.
+        => metadata_offset, metadata_offset_slice);
+
+    build_table_dump_text!(tsk_site_table_dump_text);
+
+    build_table_metadata_schema_api!(tsk_site_table_set_metadata_schema);
 
     /// Clear all data from the table
     pub fn clear(&mut self) -> Result<i32, TskitError> {
         handle_tsk_return_value!(self.table_.clear())
     }
 
+    build_table_copy_row!(SiteId, tsk_site_table_extend);
+
     pub fn add_row<P: Into<Position>>(
         &mut self,
         position: P,
@@ -0,0 +1,936 @@
+//! Support for tree sequence statistics.
+
+use crate::sys;
+use crate::NodeId;
+use crate::Position;
+use crate::SiteId;
+use crate::StatisticsOptions;
+use crate::TreeSequence;
+use crate::TskitError;
+use crate::VariantOptions;
+
+/// A set of genomic intervals to exclude from statistics.
+///
+/// Intervals are half-open, `[left, right)`, and must be
+/// sorted and non-overlapping.
+/// Masked regions contribute neither sites nor span
+/// to statistics calculated with a mask, so span-normalised
+/// statistics are an error for windows that are entirely masked.
+/// See [`Windows::mask`].
+///
+/// # Examples
+///
+/// ```
+/// let mask = tskit::GenomeMask::new([(10., 20.), (50., 60.)]).unwrap();
+/// assert!(mask.is_masked(15.));
+/// assert!(!mask.is_masked(20.));
+/// assert_eq!(mask.masked_span(), 20.);
+/// assert_eq!(mask.accessible_span(100.), 80.);
+/// ```
+///
+/// Invalid intervals are an error:
+///
+/// ```
+/// // overlapping
+/// assert!(tskit::GenomeMask::new([(10., 20.), (15., 30.)]).is_err());
+/// // left >= right
+/// assert!(tskit::GenomeMask::new([(10., 10.)]).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenomeMask {
+    intervals: Vec<(Position, Position)>,
+}
+
+impl GenomeMask {
+    /// Create a new mask from `(left, right)` intervals.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if any `left` is negative,
+    ///   if any `left >= right`, or if the intervals
+    ///   are not sorted and non-overlapping.
+    pub fn new<P: Into<Position>>(
+        intervals: impl IntoIterator<Item = (P, P)>,
+    ) -> Result<Self, TskitError> {
+        let intervals = intervals
+            .into_iter()
+            .map(|(l, r)| (l.into(), r.into()))
+            .collect::<Vec<(Position, Position)>>();
+        let mut last_right = Position::from(0.0);
+        for &(left, right) in &intervals {
+            if !(left >= last_right && left < right) {
+                return Err(TskitError::RangeError(format!(
+                    "invalid mask interval [{}, {})",
+                    left, right
+                )));
+            }
+            last_right = right;
+        }
+        Ok(Self { intervals })
+    }
+
+    /// The masked intervals.
+    pub fn intervals(&self) -> &[(Position, Position)] {
+        &self.intervals
+    }
+
+    /// Returns `true` if `position` is masked.
+    pub fn is_masked<P: Into<Position>>(&self, position: P) -> bool {
+        let position = position.into();
+        let i = self.intervals.partition_point(|(_, r)| *r <= position);
+        i < self.intervals.len() && self.intervals[i].0 <= position
+    }
+
+    /// The total length of the masked intervals.
+    pub fn masked_span(&self) -> f64 {
+        self.intervals.iter().map(|(l, r)| f64::from(*r - *l)).sum()
+    }
+
+    /// The length of the genome not covered by the mask
+    /// for a sequence of length `sequence_length`.
+    ///
+    /// Any part of the mask beyond `sequence_length` is ignored.
+    pub fn accessible_span<P: Into<Position>>(&self, sequence_length: P) -> f64 {
+        let sequence_length = sequence_length.into();
+        let masked: f64 = self
+            .intervals
+            .iter()
+            .filter(|(l, _)| *l < sequence_length)
+            .map(|(l, r)| {
+                f64::from(
+                    if *r < sequence_length {
+                        *r
+                    } else {
+                        sequence_length
+                    } - *l,
+                )
+            })
+            .sum();
+        f64::from(sequence_length) - masked
+    }
+}
+
+/// The mode of a statistic.
+///
+/// See the `tskit` documentation of
+/// [statistics modes](https://tskit.dev/tskit/docs/stable/stats.html#sec-stats-mode).
+///
+/// # Examples
+///
+/// ```
+/// let options = tskit::StatisticsOptions::from(tskit::StatsMode::Branch);
+/// assert_eq!(options, tskit::StatisticsOptions::BRANCH);
+/// assert_eq!(tskit::StatsMode::default(), tskit::StatsMode::Site);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StatsMode {
+    /// Statistics of the alleles at sites.
+    #[default]
+    Site,
+    /// Statistics of the branch lengths of the trees.
+    Branch,
+    /// Statistics for each node, of the samples below the node.
+    Node,
+}
+
+impl From<StatsMode> for StatisticsOptions {
+    fn from(mode: StatsMode) -> Self {
+        match mode {
+            StatsMode::Site => StatisticsOptions::SITE,
+            StatsMode::Branch => StatisticsOptions::BRANCH,
+            StatsMode::Node => StatisticsOptions::NODE,
+        }
+    }
+}
+
+// The options of a statistic, with the mode set by `mode`
+pub(crate) fn with_mode(options: StatisticsOptions, mode: StatsMode) -> StatisticsOptions {
+    options
+        .difference(StatisticsOptions::SITE | StatisticsOptions::BRANCH | StatisticsOptions::NODE)
+        | mode.into()
+}
+
+/// The sets of sample nodes of a statistic.
+///
+/// # Examples
+///
+/// ```
+/// let sample_sets = tskit::SampleSets::new()
+///     .add_set([0, 1])
+///     .add_set([2, 3, 4]);
+/// assert_eq!(sample_sets.num_sets(), 2);
+/// assert_eq!(sample_sets.iter().nth(1).unwrap(), [2, 3, 4]);
+///
+/// let samples = vec![tskit::NodeId::from(0), 1.into()];
+/// let sample_sets = [&samples].into_iter().collect::<tskit::SampleSets>();
+/// assert_eq!(sample_sets.num_sets(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SampleSets {
+    sets: Vec<Vec<NodeId>>,
+}
+
+impl SampleSets {
+    /// Create an empty list of sample sets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a set of sample nodes.
+    pub fn add_set<N: Into<NodeId>>(self, set: impl IntoIterator<Item = N>) -> Self {
+        let mut sets = self.sets;
+        sets.push(set.into_iter().map(|u| u.into()).collect());
+        Self { sets }
+    }
+
+    /// The number of sample sets.
+    pub fn num_sets(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Iterate over the sample sets.
+    pub fn iter(&self) -> impl Iterator<Item = &[NodeId]> + '_ {
+        self.sets.iter().map(|s| s.as_slice())
+    }
+
+    pub(crate) fn as_slices(&self) -> Vec<&[NodeId]> {
+        self.iter().collect()
+    }
+}
+
+impl<S: AsRef<[NodeId]>> FromIterator<S> for SampleSets {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        Self {
+            sets: iter.into_iter().map(|s| s.as_ref().to_vec()).collect(),
+        }
+    }
+}
+
+impl From<&[&[NodeId]]> for SampleSets {
+    fn from(sets: &[&[NodeId]]) -> Self {
+        sets.iter().collect()
+    }
+}
+
+/// The genomic windows of a statistic.
+///
+/// Statistics have one row of values per window.
+/// Windows are half-open, `[left, right)`, may be in any order,
+/// and may overlap.
+/// By default, there is a single window covering the whole genome.
+///
+/// A [`GenomeMask`] excludes regions from every window.
+/// Masked regions contribute neither sites nor span, so that
+/// span-normalised values are divided by the accessible span
+/// of each window.
+///
+/// Windows are checked against the sequence length
+/// when a statistic is calculated.
+///
+/// # Examples
+///
+/// ```
+/// // [0, 10) and [10, 100)
+/// let windows = tskit::Windows::breakpoints([0., 10., 100.]);
+/// assert_eq!(windows, tskit::Windows::intervals([(0., 10.), (10., 100.)]));
+///
+/// let mask = tskit::GenomeMask::new([(5., 10.)]).unwrap();
+/// let windows = tskit::Windows::genome().mask(mask);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Windows {
+    intervals: Option<Vec<(Position, Position)>>,
+    mask: Option<GenomeMask>,
+}
+
+impl Windows {
+    /// A single window covering the whole genome.
+    pub fn genome() -> Self {
+        Self::default()
+    }
+
+    /// Windows from `(left, right)` intervals.
+    pub fn intervals<P: Into<Position>>(intervals: impl IntoIterator<Item = (P, P)>) -> Self {
+        Self {
+            intervals: Some(
+                intervals
+                    .into_iter()
+                    .map(|(left, right)| (left.into(), right.into()))
+                    .collect(),
+            ),
+            mask: None,
+        }
+    }
+
+    /// Windows between consecutive `breakpoints`,
+    /// as used by the `tskit` `C` and Python APIs.
+    pub fn breakpoints<P: Into<Position>>(breakpoints: impl IntoIterator<Item = P>) -> Self {
+        let breakpoints = breakpoints
+            .into_iter()
+            .map(|b| b.into())
+            .collect::<Vec<Position>>();
+        Self::intervals(breakpoints.windows(2).map(|w| (w[0], w[1])))
+    }
+
+    /// Exclude the regions of `mask` from the windows.
+    pub fn mask(self, mask: GenomeMask) -> Self {
+        Self {
+            mask: Some(mask),
+            ..self
+        }
+    }
+
+    // The (left, right) intervals of the windows
+    pub(crate) fn resolve(&self, sequence_length: f64) -> Vec<(Position, Position)> {
+        match &self.intervals {
+            Some(intervals) => intervals.clone(),
+            None => vec![(0.0.into(), sequence_length.into())],
+        }
+    }
+
+    pub(crate) fn genome_mask(&self) -> Option<&GenomeMask> {
+        self.mask.as_ref()
+    }
+}
+
+// Check `windows` and `mask` against the sequence length,
+// returning the mask if it masks anything.
+pub(crate) fn check_windows<'mask>(
+    sequence_length: f64,
+    windows: &[(Position, Position)],
+    mask: Option<&'mask GenomeMask>,
+) -> Result<Option<&'mask GenomeMask>, TskitError> {
+    if windows.is_empty() {
+        return Err(TskitError::ValueError {
+            got: "no windows".to_string(),
+            expected: "at least one window".to_string(),
+        });
+    }
+    for &(left, right) in windows {
+        if !(left >= 0.0 && left < right && right <= sequence_length) {
+            return Err(TskitError::RangeError(format!(
+                "invalid window [{}, {}) for sequence length {}",
+                left, right, sequence_length
+            )));
+        }
+    }
+    let mask = mask.filter(|m| !m.intervals.is_empty());
+    if let Some(&(_, right)) = mask.and_then(|m| m.intervals.last()) {
+        if right > sequence_length {
+            return Err(TskitError::RangeError(format!(
+                "mask extends beyond the sequence length {}",
+                sequence_length
+            )));
+        }
+    }
+    Ok(mask)
+}
+
+// Calculate a statistic in each of `windows`.
+//
+// `raw` calculates the unnormalised values of the statistic between
+// consecutive breakpoints, as the C API does for windows.
+// The values are summed over the breakpoints of each window,
+// skipping regions masked by `mask`, and divided by the accessible
+// span of the window if SPAN_NORMALISE is set.
+pub(crate) fn windowed_values<F>(
+    sequence_length: f64,
+    windows: &[(Position, Position)],
+    mask: Option<&GenomeMask>,
+    options: StatisticsOptions,
+    raw: F,
+) -> Result<Vec<Vec<f64>>, TskitError>
+where
+    F: FnOnce(&[f64], StatisticsOptions) -> Result<Vec<f64>, TskitError>,
+{
+    let mask = check_windows(sequence_length, windows, mask)?;
+
+    // Masked regions start and end at breakpoints, so that each
+    // region between consecutive breakpoints is either masked or not.
+    let mut breakpoints = vec![0.0, sequence_length];
+    for &(left, right) in windows.iter().chain(mask.iter().flat_map(|m| &m.intervals)) {
+        breakpoints.push(left.into());
+        breakpoints.push(right.into());
+    }
+    breakpoints.sort_by(f64::total_cmp);
+    breakpoints.dedup();
+    let raw = raw(
+        &breakpoints,
+        options.difference(StatisticsOptions::SPAN_NORMALISE),
+    )?;
+    let row_len = raw.len() / (breakpoints.len() - 1);
+
+    windows
+        .iter()
+        .map(|&(left, right)| {
+            let start = breakpoints.partition_point(|&b| b < f64::from(left));
+            let stop = breakpoints.partition_point(|&b| b < f64::from(right));
+            let mut values = vec![0.0; row_len];
+            let mut span = 0.0;
+            for (i, row) in raw[start * row_len..stop * row_len]
+                .chunks_exact(row_len)
+                .enumerate()
+            {
+                let (l, r) = (breakpoints[start + i], breakpoints[start + i + 1]);
+                if mask.is_some_and(|m| m.is_masked(l)) {
+                    continue;
+                }
+                span += r - l;
+                values.iter_mut().zip(row).for_each(|(x, y)| *x += y);
+            }
+            if options.contains(StatisticsOptions::SPAN_NORMALISE) {
+                if span == 0.0 {
+                    return Err(TskitError::ValueError {
+                        got: format!("window [{}, {}) with no accessible span", left, right),
+                        expected: "windows that are not entirely masked".to_string(),
+                    });
+                }
+                values.iter_mut().for_each(|x| *x /= span);
+            }
+            Ok(values)
+        })
+        .collect()
+}
+
+/// How statistics calculated from genotypes treat missing data.
+///
+/// A sample's genotype is missing at a site if the sample is
+/// isolated in the tree covering the site and has no mutation
+/// above it.
+/// See [`TreeSequence::site_diversity`].
+///
+/// # Examples
+///
+/// ```
+/// let options = tskit::VariantOptions::from(tskit::MissingData::Ancestral);
+/// assert_eq!(options, tskit::VariantOptions::ISOLATED_NOT_MISSING);
+/// assert_eq!(tskit::MissingData::default(), tskit::MissingData::Exclude);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MissingData {
+    /// Samples with missing genotypes are left out
+    /// of the statistic at that site.
+    #[default]
+    Exclude,
+    /// Samples with missing genotypes are assigned the
+    /// ancestral state of the site.
+    Ancestral,
+}
+
+impl From<MissingData> for VariantOptions {
+    fn from(missing: MissingData) -> Self {
+        match missing {
+            MissingData::Exclude => VariantOptions::NONE,
+            MissingData::Ancestral => VariantOptions::ISOLATED_NOT_MISSING,
+        }
+    }
+}
+
+// The probability that two distinct samples with known
+// genotypes differ, at each site
+pub(crate) fn site_diversity(
+    treeseq: &TreeSequence,
+    samples: Option<&[NodeId]>,
+    missing: MissingData,
+) -> Result<Vec<f64>, TskitError> {
+    let mut variant = treeseq.variants(samples, missing)?;
+    (0..treeseq.num_sites().as_usize())
+        .map(|site| {
+            variant.decode(SiteId::from(site as i32))?;
+            let counts = variant.allele_counts();
+            let n = counts.iter().sum::<usize>();
+            if n < 2 {
+                return Ok(f64::NAN);
+            }
+            let same = counts
+                .iter()
+                .map(|&c| c * c.saturating_sub(1))
+                .sum::<usize>();
+            Ok(1.0 - same as f64 / (n * (n - 1)) as f64)
+        })
+        .collect()
+}
+
+// Calculate a one way statistic in each of `windows`,
+// returning the values of all windows, row-major.
+pub(crate) fn one_way_stat(
+    treeseq: &TreeSequence,
+    stat: sys::OneWayStatFn,
+    sample_sets: &[&[NodeId]],
+    windows: &Windows,
+    options: StatisticsOptions,
+) -> Result<Vec<f64>, TskitError> {
+    let sequence_length = treeseq.inner.sequence_length();
+    Ok(windowed_values(
+        sequence_length,
+        &windows.resolve(sequence_length),
+        windows.mask.as_ref(),
+        options,
+        |breakpoints, options| {
+            treeseq
+                .inner
+                .one_way_stat(stat, sample_sets, breakpoints, options)
+        },
+    )?
+    .concat())
+}
+
+// Calculate a two way statistic in each of `windows`,
+// returning the values of all windows, row-major.
+pub(crate) fn two_way_stat(
+    treeseq: &TreeSequence,
+    stat: sys::TwoWayStatFn,
+    sample_sets: &[&[NodeId]],
+    indexes: &[(usize, usize)],
+    windows: &Windows,
+    options: StatisticsOptions,
+) -> Result<Vec<f64>, TskitError> {
+    let sequence_length = treeseq.inner.sequence_length();
+    Ok(windowed_values(
+        sequence_length,
+        &windows.resolve(sequence_length),
+        windows.mask.as_ref(),
+        options,
+        |breakpoints, options| {
+            treeseq
+                .inner
+                .two_way_stat(stat, sample_sets, indexes, breakpoints, options)
+        },
+    )?
+    .concat())
+}
+
+// The allele frequency spectrum in each of `windows`,
+// returning the values of all windows, row-major.
+pub(crate) fn allele_frequency_spectrum(
+    treeseq: &TreeSequence,
+    sample_sets: &[&[NodeId]],
+    windows: &Windows,
+    options: StatisticsOptions,
+) -> Result<Vec<f64>, TskitError> {
+    let sequence_length = treeseq.inner.sequence_length();
+    Ok(windowed_values(
+        sequence_length,
+        &windows.resolve(sequence_length),
+        windows.mask.as_ref(),
+        options,
+        |breakpoints, options| {
+            treeseq
+                .inner
+                .allele_frequency_spectrum(sample_sets, breakpoints, options)
+        },
+    )?
+    .concat())
+}
+
+// Statistics derived from other statistics are not defined
+// separately for each node.
+pub(crate) fn reject_node_mode(mode: StatsMode) -> Result<(), TskitError> {
+    if mode == StatsMode::Node {
+        return Err(TskitError::ValueError {
+            got: "StatsMode::Node".to_string(),
+            expected: "site or branch mode".to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Tajima's D from the unnormalised diversity and number of
+// segregating sites, following the tskit python API.
+pub(crate) fn tajimas_d(
+    treeseq: &TreeSequence,
+    sample_sets: &[&[NodeId]],
+    windows: &Windows,
+    mode: StatsMode,
+) -> Result<Vec<f64>, TskitError> {
+    reject_node_mode(mode)?;
+    let options = StatisticsOptions::from(mode);
+    let diversity = one_way_stat(
+        treeseq,
+        sys::bindings::tsk_treeseq_diversity,
+        sample_sets,
+        windows,
+        options,
+    )?;
+    let segregating_sites = one_way_stat(
+        treeseq,
+        sys::bindings::tsk_treeseq_segregating_sites,
+        sample_sets,
+        windows,
+        options,
+    )?;
+    Ok(sample_sets
+        .iter()
+        .cycle()
+        .zip(diversity.iter().zip(segregating_sites.iter()))
+        .map(|(sample_set, (&t, &s))| {
+            let n = sample_set.len() as f64;
+            let h: f64 = (1..sample_set.len()).map(|i| 1.0 / i as f64).sum();
+            let g: f64 = (1..sample_set.len()).map(|i| 1.0 / (i * i) as f64).sum();
+            let a = (n + 1.0) / (3.0 * (n - 1.0) * h) - 1.0 / (h * h);
+            let b =
+                2.0 * (n * n + n + 3.0) / (9.0 * n * (n - 1.0)) - (n + 2.0) / (h * n) + g / (h * h);
+            (t - s / h) / (a * s + b / (h * h + g) * s * (s - 1.0)).sqrt()
+        })
+        .collect())
+}
+
+// Fst between pairs of sample sets from their diversities and
+// divergence, following the tskit python API.
+pub(crate) fn fst(
+    treeseq: &TreeSequence,
+    sample_sets: &[&[NodeId]],
+    indexes: &[(usize, usize)],
+    windows: &Windows,
+    mode: StatsMode,
+) -> Result<Vec<f64>, TskitError> {
+    reject_node_mode(mode)?;
+    let options = StatisticsOptions::from(mode);
+    let diversity = one_way_stat(
+        treeseq,
+        sys::bindings::tsk_treeseq_diversity,
+        sample_sets,
+        windows,
+        options,
+    )?;
+    let divergence = two_way_stat(
+        treeseq,
+        sys::bindings::tsk_treeseq_divergence,
+        sample_sets,
+        indexes,
+        windows,
+        options,
+    )?;
+    let num_sets = sample_sets.len();
+    Ok(divergence
+        .chunks_exact(indexes.len().max(1))
+        .zip(diversity.chunks_exact(num_sets.max(1)))
+        .flat_map(|(divergence, diversity)| {
+            indexes.iter().zip(divergence).map(|(&(i, j), d)| {
+                let within = diversity[i] + diversity[j];
+                1.0 - 2.0 * within / (within + 2.0 * d)
+            })
+        })
+        .collect())
+}
+
+/// A genomic window for [`WindowedStatistic`].
+///
+/// Implemented for `(left, right)` pairs and for the results of
+/// parsing windows, such as those of [`read_bed_windows`], so that
+/// parse errors are passed on by the statistic.
+pub trait StatWindow {
+    /// Convert to a `(left, right)` pair.
+    fn into_window(self) -> Result<(Position, Position), TskitError>;
+}
+
+impl<P: Into<Position>> StatWindow for (P, P) {
+    fn into_window(self) -> Result<(Position, Position), TskitError> {
+        Ok((self.0.into(), self.1.into()))
+    }
+}
+
+impl<P: Into<Position>> StatWindow for Result<(P, P), TskitError> {
+    fn into_window(self) -> Result<(Position, Position), TskitError> {
+        self.map(|(left, right)| (left.into(), right.into()))
+    }
+}
+
+/// Read windows from the BED intervals of `reader`.
+///
+/// Empty lines and `#`, `track`, and `browser` lines are skipped.
+/// The chromosome name, the first field, is ignored, and the
+/// second and third fields are the left and right coordinates.
+/// Lines are read as the iterator advances, so that very large
+/// files, or standard input, may be streamed.
+///
+/// # Errors
+///
+/// The iterator returns [`TskitError::ValueError`] for lines
+/// without valid coordinates and [`TskitError::LibraryError`]
+/// for errors reading from `reader`.
+///
+/// # Examples
+///
+/// ```
+/// let bed = "# comment\nchr1\t0\t10\nchr1 20 30 name\n";
+/// let windows = tskit::read_bed_windows(bed.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(windows, [(0.0.into(), 10.0.into()), (20.0.into(), 30.0.into())]);
+/// ```
+pub fn read_bed_windows<R: std::io::BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<(Position, Position), TskitError>> {
+    reader.lines().enumerate().filter_map(|(i, line)| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(TskitError::LibraryError(e.to_string()))),
+        };
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            None => return None,
+            Some(f) if f.starts_with('#') || f == "track" || f == "browser" => return None,
+            Some(_) => (),
+        }
+        let mut coordinate = || fields.next().and_then(|f| f.parse::<f64>().ok());
+        Some(match (coordinate(), coordinate()) {
+            (Some(left), Some(right)) => Ok((left.into(), right.into())),
+            _ => Err(TskitError::ValueError {
+                got: format!("{:?} on line {}", line, i + 1),
+                expected: "a BED interval".to_string(),
+            }),
+        })
+    })
+}
+
+/// A type that the values of statistics may be stored as.
+///
+/// Statistics are calculated as [`f64`] and then converted.
+/// Implemented for `f64` and `f32`.
+///
+/// See [`WindowedStatistic::values_as`].
+pub trait StatValue: Copy {
+    /// Convert from the calculated value.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl StatValue for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl StatValue for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+// A window and its values
+type WindowValues<V> = Result<(Position, Position, Vec<V>), TskitError>;
+
+/// An [`Iterator`] over the values of a statistic in
+/// a stream of windows.
+///
+/// Windows are read from the input iterator in batches,
+/// and each batch is calculated with a single pass over
+/// the trees, so that the windows are never all in memory.
+/// Windows may be in any order and may overlap.
+///
+/// Each item is the window and its values, as returned by the
+/// whole-genome statistic, such as [`TreeSequence::diversity`].
+/// The iterator ends after returning an error.
+///
+/// Values are [`f64`] by default.
+/// See [`WindowedStatistic::values_as`] to store them as [`f32`].
+///
+/// See [`TreeSequence::windowed_diversity`].
+pub struct WindowedStatistic<'treeseq, I, V = f64> {
+    treeseq: &'treeseq TreeSequence,
+    stat: sys::OneWayStatFn,
+    sample_sets: SampleSets,
+    options: StatisticsOptions,
+    windows: I,
+    mask: Option<GenomeMask>,
+    batch_size: usize,
+    values: std::collections::VecDeque<WindowValues<V>>,
+    done: bool,
+}
+
+impl<'treeseq, I> WindowedStatistic<'treeseq, I>
+where
+    I: Iterator,
+    I::Item: StatWindow,
+{
+    pub(crate) fn new(
+        treeseq: &'treeseq TreeSequence,
+        stat: sys::OneWayStatFn,
+        sample_sets: &SampleSets,
+        options: StatisticsOptions,
+        windows: I,
+    ) -> Self {
+        Self {
+            treeseq,
+            stat,
+            sample_sets: sample_sets.clone(),
+            options,
+            windows,
+            mask: None,
+            batch_size: 4096,
+            values: std::collections::VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Store the values of the windows as `W`.
+    ///
+    /// Storing values as [`f32`] halves the memory used by the
+    /// values of each window, for example when collecting the
+    /// values of millions of windows.
+    /// Values are calculated and summed over the trees as `f64`,
+    /// and rounded to the nearest `f32` once per window, so
+    /// rounding errors do not accumulate.
+    /// The precision trade-offs are those of `f32`:
+    ///
+    /// * The relative error of each value is at most 2<sup>-24</sup>,
+    ///   about 6e-8, or about 7 significant decimal digits.
+    /// * Values larger than [`f32::MAX`] become infinite and values
+    ///   smaller than about 1e-45 become zero. Such values are unusual
+    ///   for span-normalised statistics, but large unnormalised sums,
+    ///   such as branch lengths over long windows, lose absolute precision.
+    /// * Differences between values of windows may not be
+    ///   meaningful below the precision of the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let samples = tskit::SampleSets::new().add_set(treeseq.sample_nodes().iter().copied());
+    /// let branch = tskit::StatsMode::Branch;
+    /// let options = tskit::StatisticsOptions::default().span_normalise();
+    /// let pi = treeseq
+    ///     .windowed_diversity(&samples, branch, options, [(0., 30.), (30., 100.)])
+    ///     .values_as::<f32>()
+    ///     .map(|w| w.unwrap().2)
+    ///     .collect::<Vec<Vec<f32>>>();
+    /// assert_eq!(pi, [[2.0], [2.0]]);
+    /// ```
+    pub fn values_as<W: StatValue>(self) -> WindowedStatistic<'treeseq, I, W> {
+        WindowedStatistic {
+            treeseq: self.treeseq,
+            stat: self.stat,
+            sample_sets: self.sample_sets,
+            options: self.options,
+            windows: self.windows,
+            mask: self.mask,
+            batch_size: self.batch_size,
+            values: self
+                .values
+                .into_iter()
+                .map(|w| {
+                    w.map(|(left, right, values)| {
+                        (left, right, values.into_iter().map(W::from_f64).collect())
+                    })
+                })
+                .collect(),
+            done: self.done,
+        }
+    }
+}
+
+impl<'treeseq, I, V> WindowedStatistic<'treeseq, I, V>
+where
+    I: Iterator,
+    I::Item: StatWindow,
+    V: StatValue,
+{
+    /// Set the number of windows calculated in each pass
+    /// over the trees.
+    ///
+    /// The default is 4096. A value of 0 is treated as 1.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Exclude the regions of `mask` from the windows,
+    /// as for [`Windows::mask`].
+    ///
+    /// The iterator returns [`TskitError::RangeError`] if the
+    /// mask extends beyond the sequence length.
+    pub fn mask(mut self, mask: GenomeMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    fn next_batch(&mut self) {
+        let sequence_length = Position::from(self.treeseq.inner.sequence_length());
+        let mut batch = vec![];
+        for window in self.windows.by_ref() {
+            match window.into_window() {
+                Ok((left, right)) if left >= 0.0 && left < right && right <= sequence_length => {
+                    batch.push((left, right))
+                }
+                Ok((left, right)) => {
+                    self.done = true;
+                    self.values.extend(self.calculate(&batch));
+                    self.values.push_back(Err(TskitError::RangeError(format!(
+                        "invalid window [{}, {}) for sequence length {}",
+                        left, right, sequence_length
+                    ))));
+                    return;
+                }
+                Err(e) => {
+                    self.done = true;
+                    self.values.extend(self.calculate(&batch));
+                    self.values.push_back(Err(e));
+                    return;
+                }
+            }
+            if batch.len() == self.batch_size {
+                break;
+            }
+        }
+        if batch.len() < self.batch_size {
+            self.done = true;
+        }
+        self.values.extend(self.calculate(&batch));
+    }
+
+    fn calculate(&self, batch: &[(Position, Position)]) -> Vec<WindowValues<V>> {
+        if batch.is_empty() {
+            return vec![];
+        }
+        let sample_sets = self.sample_sets.as_slices();
+        let values = windowed_values(
+            self.treeseq.inner.sequence_length(),
+            batch,
+            self.mask.as_ref(),
+            self.options,
+            |breakpoints, options| {
+                self.treeseq
+                    .inner
+                    .one_way_stat(self.stat, &sample_sets, breakpoints, options)
+            },
+        );
+        match values {
+            Ok(values) => batch
+                .iter()
+                .zip(values)
+                .map(|(&(left, right), values)| {
+                    Ok((left, right, values.into_iter().map(V::from_f64).collect()))
+                })
+                .collect(),
+            Err(e) => vec![Err(e)],
+        }
+    }
+}
+
+impl<I, V> Iterator for WindowedStatistic<'_, I, V>
+where
+    I: Iterator,
+    I::Item: StatWindow,
+    V: StatValue,
+{
+    type Item = WindowValues<V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.values.is_empty() && !self.done {
+            self.next_batch();
+        }
+        let rv = self.values.pop_front();
+        if matches!(rv, Some(Err(_))) {
+            self.values.clear();
+            self.done = true;
+        }
+        rv
+    }
+}
@@ -0,0 +1,96 @@
+//! Removal of metadata from table collections.
+
+use crate::sys::bindings as ll_bindings;
+use crate::StripMetadataOptions;
+use crate::TskReturnValue;
+
+// Remove the metadata of all rows of a table, and its schema
+macro_rules! strip_table_metadata {
+    ($table: expr, $set_schema: ident) => {{
+        let table = $table;
+        let offsets = crate::sys::generate_slice_mut::<_, _, ll_bindings::tsk_size_t>(
+            table.metadata_offset,
+            table.num_rows + 1,
+        );
+        offsets.fill(0);
+        table.metadata_length = 0;
+        // SAFETY: the table is initialized and the schema is empty
+        unsafe { ll_bindings::$set_schema(table, std::ptr::null(), 0) }
+    }};
+}
+
+pub(crate) fn strip_metadata(
+    tables: &mut ll_bindings::tsk_table_collection_t,
+    options: StripMetadataOptions,
+) -> TskReturnValue {
+    let mut rv = 0;
+    if options.contains(StripMetadataOptions::INDIVIDUALS) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.individuals,
+            tsk_individual_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::NODES) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.nodes,
+            tsk_node_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::EDGES) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.edges,
+            tsk_edge_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::MIGRATIONS) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.migrations,
+            tsk_migration_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::SITES) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.sites,
+            tsk_site_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::MUTATIONS) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.mutations,
+            tsk_mutation_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::POPULATIONS) {
+        rv = rv.min(strip_table_metadata!(
+            &mut tables.populations,
+            tsk_population_table_set_metadata_schema
+        ));
+    }
+    if options.contains(StripMetadataOptions::TOP_LEVEL) {
+        // SAFETY: the tables are initialized and the values are empty
+        unsafe {
+            rv = rv.min(ll_bindings::tsk_table_collection_set_metadata(
+                tables,
+                std::ptr::null(),
+                0,
+            ));
+            rv = rv.min(ll_bindings::tsk_table_collection_set_metadata_schema(
+                tables,
+                std::ptr::null(),
+                0,
+            ));
+            let reference = &mut tables.reference_sequence;
+            rv = rv.min(ll_bindings::tsk_reference_sequence_set_metadata(
+                reference,
+                std::ptr::null(),
+                0,
+            ));
+            rv = rv.min(ll_bindings::tsk_reference_sequence_set_metadata_schema(
+                reference,
+                std::ptr::null(),
+                0,
+            ));
+        }
+    }
+    handle_tsk_return_value!(rv)
+}
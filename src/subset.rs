@@ -0,0 +1,197 @@
+//! Subsetting and canonicalising table collections.
+
+use crate::sys::bindings as ll_bindings;
+use crate::IndividualId;
+use crate::NodeId;
+use crate::PopulationId;
+use crate::SubsetOptions;
+use crate::TableCollection;
+use crate::TskitError;
+
+/// Maps from the row ids of the input tables to the row ids
+/// of the output tables of
+/// [`TableCollection::subset`] and [`TableCollection::canonicalise`].
+///
+/// Each map has one value per row of the input table, which is
+/// the id of the row in the output or `NULL` if the row was removed.
+/// Use these maps to update ids stored outside of the tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubsetIdMaps {
+    /// The new node ids
+    pub nodes: Vec<NodeId>,
+    /// The new individual ids
+    pub individuals: Vec<IndividualId>,
+    /// The new population ids
+    pub populations: Vec<PopulationId>,
+}
+
+// Run `subset`, which calls `tsk_table_collection_subset` or
+// `tsk_table_collection_canonicalise` keeping `nodes`, in order,
+// followed by the nodes passed to `subset`.
+//
+// tskit does not return the maps of individuals and populations,
+// so they are read from the node table that it writes: kept nodes
+// refer to the new ids of their individuals and populations.
+// If unreferenced rows are kept, a node is temporarily added for each
+// individual and population, which tskit keeps after `nodes`, in order,
+// as it does for unreferenced rows. These nodes are then removed.
+fn subset_with_maps<F>(
+    tables: &mut TableCollection,
+    nodes: &[NodeId],
+    options: SubsetOptions,
+    subset: F,
+) -> Result<SubsetIdMaps, TskitError>
+where
+    F: FnOnce(&mut TableCollection, &[NodeId]) -> i32,
+{
+    let num_nodes = tables.nodes().num_rows().as_usize();
+    let num_individuals = tables.individuals().num_rows().as_usize();
+    let num_populations = tables.populations().num_rows().as_usize();
+    let old_individuals = nodes
+        .iter()
+        .map(|&u| tables.nodes().individual_slice()[u.as_usize()])
+        .collect::<Vec<_>>();
+    let old_populations = nodes
+        .iter()
+        .map(|&u| tables.nodes().population_slice()[u.as_usize()])
+        .collect::<Vec<_>>();
+
+    let num_extra = match options.contains(SubsetOptions::KEEP_UNREFERENCED) {
+        true => num_individuals.max(num_populations),
+        false => 0,
+    };
+    let id = |k: usize, n: usize| match k < n {
+        true => k as ll_bindings::tsk_id_t,
+        false => crate::sys::TSK_NULL,
+    };
+    let mut rv = 0;
+    for k in 0..num_extra {
+        // SAFETY: the table collection is initialized
+        rv = unsafe {
+            ll_bindings::tsk_node_table_add_row(
+                &mut (*tables.as_mut_ptr()).nodes,
+                0,
+                0.0,
+                id(k, num_populations),
+                id(k, num_individuals),
+                std::ptr::null(),
+                0,
+            )
+        };
+        if rv < 0 {
+            break;
+        }
+    }
+    let mut maps = SubsetIdMaps {
+        nodes: vec![NodeId::NULL; num_nodes],
+        individuals: vec![IndividualId::NULL; num_individuals],
+        populations: vec![PopulationId::NULL; num_populations],
+    };
+    if rv >= 0 {
+        let extra = (num_nodes..num_nodes + num_extra)
+            .map(|u| NodeId::from(u as ll_bindings::tsk_id_t))
+            .collect::<Vec<_>>();
+        rv = subset(tables, &extra);
+    }
+    if rv >= 0 {
+        let individuals = tables.nodes().individual_slice();
+        let populations = tables.nodes().population_slice();
+        for (new, &u) in nodes.iter().enumerate() {
+            maps.nodes[u.as_usize()] = NodeId::from(new as ll_bindings::tsk_id_t);
+            if let Some(i) = old_individuals[new].to_usize() {
+                maps.individuals[i] = individuals[new];
+            }
+            if let Some(p) = old_populations[new].to_usize() {
+                maps.populations[p] = populations[new];
+            }
+        }
+        for k in 0..num_extra {
+            if k < num_individuals {
+                maps.individuals[k] = individuals[nodes.len() + k];
+            }
+            if k < num_populations {
+                maps.populations[k] = populations[nodes.len() + k];
+            }
+        }
+        if options.contains(SubsetOptions::NO_CHANGE_POPULATIONS) {
+            maps.populations = (0..num_populations)
+                .map(|p| PopulationId::from(p as ll_bindings::tsk_id_t))
+                .collect();
+        }
+    }
+    if num_extra > 0 {
+        let num_rows = match rv >= 0 {
+            true => nodes.len(),
+            false => num_nodes,
+        };
+        // SAFETY: the table collection is initialized
+        let code = unsafe {
+            ll_bindings::tsk_node_table_truncate(
+                &mut (*tables.as_mut_ptr()).nodes,
+                num_rows as ll_bindings::tsk_size_t,
+            )
+        };
+        if rv >= 0 {
+            rv = code;
+        }
+    }
+    if rv < 0 {
+        return Err(TskitError::ErrorCode { code: rv });
+    }
+    Ok(maps)
+}
+
+pub(crate) fn subset(
+    tables: &mut TableCollection,
+    nodes: &[NodeId],
+    options: SubsetOptions,
+) -> Result<SubsetIdMaps, TskitError> {
+    let num_nodes = tables.nodes().num_rows().as_usize();
+    let mut seen = vec![false; num_nodes];
+    for u in nodes {
+        match u.to_usize().filter(|&i| i < num_nodes) {
+            None => return Err(TskitError::IndexError),
+            Some(i) if seen[i] => {
+                return Err(TskitError::ValueError {
+                    got: format!("node {} more than once", u),
+                    expected: "distinct nodes".to_string(),
+                })
+            }
+            Some(i) => seen[i] = true,
+        }
+    }
+    subset_with_maps(tables, nodes, options, |tables, extra| {
+        let nodes = nodes.iter().chain(extra).copied().collect::<Vec<_>>();
+        // SAFETY: the table collection is initialized and NodeId is repr(transparent)
+        unsafe {
+            ll_bindings::tsk_table_collection_subset(
+                tables.as_mut_ptr(),
+                nodes.as_ptr().cast::<ll_bindings::tsk_id_t>(),
+                nodes.len() as ll_bindings::tsk_size_t,
+                options.bits(),
+            )
+        }
+    })
+}
+
+pub(crate) fn canonicalise(
+    tables: &mut TableCollection,
+    options: SubsetOptions,
+) -> Result<SubsetIdMaps, TskitError> {
+    if !SubsetOptions::KEEP_UNREFERENCED.contains(options) {
+        return Err(TskitError::ValueError {
+            got: format!("{:?}", options),
+            expected: "no options other than KEEP_UNREFERENCED".to_string(),
+        });
+    }
+    // Nodes keep their ids
+    let all_nodes = (0..tables.nodes().num_rows().as_usize())
+        .map(|u| NodeId::from(u as ll_bindings::tsk_id_t))
+        .collect::<Vec<_>>();
+    subset_with_maps(tables, &all_nodes, options, |tables, _| {
+        // SAFETY: the table collection is initialized
+        unsafe {
+            ll_bindings::tsk_table_collection_canonicalise(tables.as_mut_ptr(), options.bits())
+        }
+    })
+}
@@ -69,6 +69,7 @@ impl EdgeTable {
 
 impl Default for EdgeTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
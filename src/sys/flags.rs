@@ -39,6 +39,61 @@ macro_rules! flag_builder_api {
     };
 }
 
+macro_rules! impl_user_bits {
+    ($flagstype: ident) => {
+        impl $flagstype {
+            /// Index of the lowest bit available for user-defined values.
+            ///
+            /// Bits below this value are reserved by `tskit`.
+            pub const FIRST_USER_BIT: u32 = 16;
+            /// Mask of all bits available for user-defined values.
+            pub const USER_BITS_MASK: RawFlags = !0 << Self::FIRST_USER_BIT;
+
+            /// Set user-defined bit `bit`.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("Will panic if `bit` is less than [`", stringify!($flagstype), "::FIRST_USER_BIT`] or is not less than 32.")]
+            pub fn mark_user_bit(self, bit: u32) -> Self {
+                Self::from_bits_retain(self.bits() | Self::user_bit(bit))
+            }
+
+            /// Unset user-defined bit `bit`.
+            ///
+            /// # Panics
+            ///
+            /// See [`Self::mark_user_bit`].
+            pub fn clear_user_bit(self, bit: u32) -> Self {
+                Self::from_bits_retain(self.bits() & !Self::user_bit(bit))
+            }
+
+            /// Returns `true` if user-defined bit `bit` is set.
+            ///
+            /// # Panics
+            ///
+            /// See [`Self::mark_user_bit`].
+            pub fn contains_user_bit(&self, bit: u32) -> bool {
+                self.bits() & Self::user_bit(bit) != 0
+            }
+
+            /// Return the user-defined bits, with all
+            /// bits reserved by `tskit` unset.
+            pub fn user_bits(&self) -> RawFlags {
+                self.bits() & Self::USER_BITS_MASK
+            }
+
+            fn user_bit(bit: u32) -> RawFlags {
+                assert!(
+                    (Self::FIRST_USER_BIT..RawFlags::BITS).contains(&bit),
+                    "bit {} is not a user-defined bit",
+                    bit
+                );
+                1 << bit
+            }
+        }
+    };
+}
+
 bitflags! {
     /// Control the behavior of table simplification.
     ///
@@ -92,12 +147,37 @@ bitflags! {
         /// that exist on the path from samples to root.
         const KEEP_UNARY  = ll_bindings::TSK_SIMPLIFY_KEEP_UNARY;
         /// Whether to retain history ancestral to the MRCA of the samples.
+        /// If True, the roots of the input trees are kept, with unary
+        /// edges from the MRCAs of the samples up to those roots, so
+        /// that the roots of the output trees are those of the input.
+        /// Other ancestral nodes are only kept as required by
+        /// [`KEEP_UNARY`](crate::SimplificationOptions::KEEP_UNARY).
+        ///
+        /// This is required to later add history to the roots,
+        /// for example by recapitation or by taking the union of
+        /// tree sequences that share their roots.
         const KEEP_INPUT_ROOTS = ll_bindings::TSK_SIMPLIFY_KEEP_INPUT_ROOTS;
         ///  If True, preserve unary nodes that exist on the path from samples
         ///  to root, but only if they are associated with an individual
         ///  in the individuals table.
         ///  Cannot be specified at the same time as `KEEP_UNARY`.
         const KEEP_UNARY_IN_INDIVIDUALS  = ll_bindings::TSK_SIMPLIFY_KEEP_UNARY_IN_INDIVIDUALS;
+        /// If True, do not remove nodes that are not referenced by edges
+        /// and do not reorder nodes so that samples come first.
+        /// Node ids are then unchanged by simplification.
+        /// This flag is negated compared to the other filtering options,
+        /// because nodes are filtered by default.
+        ///
+        /// Sites and individuals are only filtered if
+        /// [`FILTER_SITES`](crate::SimplificationOptions::FILTER_SITES) and
+        /// [`FILTER_INDIVIDUALS`](crate::SimplificationOptions::FILTER_INDIVIDUALS)
+        /// are set, so leaving them unset along with this flag keeps
+        /// the ids of nodes, sites, and individuals.
+        const NO_FILTER_NODES = ll_bindings::TSK_SIMPLIFY_NO_FILTER_NODES;
+        /// If True, do not update the sample status of nodes.
+        /// By default, the nodes passed to simplification become the
+        /// only samples in the output.
+        const NO_UPDATE_SAMPLE_FLAGS = ll_bindings::TSK_SIMPLIFY_NO_UPDATE_SAMPLE_FLAGS;
     }
 }
 
@@ -185,6 +265,30 @@ impl SimplificationOptions {
     /// assert!(f.contains(SimplificationOptions::FILTER_INDIVIDUALS));
     /// ```
     => filter_individuals, FILTER_INDIVIDUALS);
+
+    flag_builder_api!(
+    /// Update to set [`NO_FILTER_NODES`](crate::SimplificationOptions::NO_FILTER_NODES).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tskit::SimplificationOptions;
+    /// let f = SimplificationOptions::default().no_filter_nodes();
+    /// assert!(f.contains(SimplificationOptions::NO_FILTER_NODES));
+    /// ```
+    => no_filter_nodes, NO_FILTER_NODES);
+
+    flag_builder_api!(
+    /// Update to set [`NO_UPDATE_SAMPLE_FLAGS`](crate::SimplificationOptions::NO_UPDATE_SAMPLE_FLAGS).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tskit::SimplificationOptions;
+    /// let f = SimplificationOptions::default().no_update_sample_flags();
+    /// assert!(f.contains(SimplificationOptions::NO_UPDATE_SAMPLE_FLAGS));
+    /// ```
+    => no_update_sample_flags, NO_UPDATE_SAMPLE_FLAGS);
 }
 
 bitflags! {
@@ -252,6 +356,104 @@ impl TableClearOptions {
         => clear_provenance, CLEAR_PROVENANCE);
 }
 
+bitflags! {
+    /// Select the metadata removed by [`crate::TableCollection::strip_metadata`].
+    ///
+    /// These options are implemented by this crate rather than by `tskit`.
+    ///
+    /// # Examples
+    ///
+    /// ## Default (empty) flags
+    ///
+    /// ```
+    /// # use tskit::StripMetadataOptions;
+    /// let f = StripMetadataOptions::default();
+    /// assert_eq!(f, StripMetadataOptions::NONE);
+    /// ```
+    ///
+    /// ## Builder API
+    ///
+    /// These methods can all be chained.
+    ///
+    /// ```
+    /// # use tskit::StripMetadataOptions;
+    /// let f = StripMetadataOptions::default().individuals().populations();
+    /// assert!(f.contains(StripMetadataOptions::INDIVIDUALS));
+    /// assert!(f.contains(StripMetadataOptions::POPULATIONS));
+    /// assert!(!f.contains(StripMetadataOptions::NODES));
+    /// ```
+    ///
+    /// ## All metadata
+    ///
+    /// ```
+    /// # use tskit::StripMetadataOptions;
+    /// let f = StripMetadataOptions::all();
+    /// assert!(f.contains(StripMetadataOptions::ALL_TABLES));
+    /// assert!(f.contains(StripMetadataOptions::TOP_LEVEL));
+    /// ```
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct StripMetadataOptions : RawFlags {
+        /// Default behavior: no metadata is removed.
+        const NONE = 0;
+        /// Remove the metadata of the individual table.
+        const INDIVIDUALS = 1 << 0;
+        /// Remove the metadata of the node table.
+        const NODES = 1 << 1;
+        /// Remove the metadata of the edge table.
+        const EDGES = 1 << 2;
+        /// Remove the metadata of the migration table.
+        const MIGRATIONS = 1 << 3;
+        /// Remove the metadata of the site table.
+        const SITES = 1 << 4;
+        /// Remove the metadata of the mutation table.
+        const MUTATIONS = 1 << 5;
+        /// Remove the metadata of the population table.
+        const POPULATIONS = 1 << 6;
+        /// Remove the metadata of the table collection and
+        /// of its reference sequence.
+        const TOP_LEVEL = 1 << 7;
+        /// Remove the metadata of all tables, but not the top-level metadata.
+        const ALL_TABLES = Self::INDIVIDUALS.bits()
+            | Self::NODES.bits()
+            | Self::EDGES.bits()
+            | Self::MIGRATIONS.bits()
+            | Self::SITES.bits()
+            | Self::MUTATIONS.bits()
+            | Self::POPULATIONS.bits();
+    }
+}
+
+impl StripMetadataOptions {
+    flag_builder_api!(
+        /// Set [`INDIVIDUALS`](crate::StripMetadataOptions::INDIVIDUALS)
+        => individuals, INDIVIDUALS);
+    flag_builder_api!(
+        /// Set [`NODES`](crate::StripMetadataOptions::NODES)
+        => nodes, NODES);
+    flag_builder_api!(
+        /// Set [`EDGES`](crate::StripMetadataOptions::EDGES)
+        => edges, EDGES);
+    flag_builder_api!(
+        /// Set [`MIGRATIONS`](crate::StripMetadataOptions::MIGRATIONS)
+        => migrations, MIGRATIONS);
+    flag_builder_api!(
+        /// Set [`SITES`](crate::StripMetadataOptions::SITES)
+        => sites, SITES);
+    flag_builder_api!(
+        /// Set [`MUTATIONS`](crate::StripMetadataOptions::MUTATIONS)
+        => mutations, MUTATIONS);
+    flag_builder_api!(
+        /// Set [`POPULATIONS`](crate::StripMetadataOptions::POPULATIONS)
+        => populations, POPULATIONS);
+    flag_builder_api!(
+        /// Set [`TOP_LEVEL`](crate::StripMetadataOptions::TOP_LEVEL)
+        => top_level, TOP_LEVEL);
+    flag_builder_api!(
+        /// Set [`ALL_TABLES`](crate::StripMetadataOptions::ALL_TABLES)
+        => all_tables, ALL_TABLES);
+}
+
 bitflags! {
     /// Modify behavior of [`crate::TableCollection::equals`].
     ///
@@ -367,6 +569,142 @@ impl TableSortOptions {
         => no_check_integrity, NO_CHECK_INTEGRITY);
 }
 
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::subset`]
+    /// and [`crate::TableCollection::canonicalise`].
+    ///
+    /// # Examples
+    ///
+    /// ## Default (empty) flags
+    ///
+    /// ```
+    /// # use tskit::SubsetOptions;
+    /// let f = SubsetOptions::default();
+    /// assert_eq!(f, SubsetOptions::NONE);
+    /// ```
+    ///
+    /// ## Builder API
+    ///
+    /// These methods can all be chained.
+    ///
+    /// ```
+    /// # use tskit::SubsetOptions;
+    /// let f = SubsetOptions::default().keep_unreferenced().no_change_populations();
+    /// assert!(f.contains(SubsetOptions::KEEP_UNREFERENCED));
+    /// assert!(f.contains(SubsetOptions::NO_CHANGE_POPULATIONS));
+    /// ```
+    #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct SubsetOptions : RawFlags {
+        /// Default behavior.
+        const NONE = 0;
+        /// Do not change the population table.
+        /// By default, populations are kept if referenced by a kept node,
+        /// in the order first seen in the list of nodes.
+        const NO_CHANGE_POPULATIONS = ll_bindings::TSK_SUBSET_NO_CHANGE_POPULATIONS;
+        /// Keep individuals and populations that are not referenced
+        /// by kept nodes, after those that are.
+        const KEEP_UNREFERENCED = ll_bindings::TSK_SUBSET_KEEP_UNREFERENCED;
+    }
+}
+
+impl SubsetOptions {
+    flag_builder_api!(
+        /// Set [`NO_CHANGE_POPULATIONS`](crate::SubsetOptions::NO_CHANGE_POPULATIONS)
+        => no_change_populations, NO_CHANGE_POPULATIONS);
+
+    flag_builder_api!(
+        /// Set [`KEEP_UNREFERENCED`](crate::SubsetOptions::KEEP_UNREFERENCED)
+        => keep_unreferenced, KEEP_UNREFERENCED);
+}
+
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::union`].
+    ///
+    /// # Examples
+    ///
+    /// ## Default (empty) flags
+    ///
+    /// ```
+    /// # use tskit::TableUnionOptions;
+    /// let f = TableUnionOptions::default();
+    /// assert_eq!(f, TableUnionOptions::NONE);
+    /// ```
+    ///
+    /// ## Builder API
+    ///
+    /// These methods can all be chained.
+    ///
+    /// ```
+    /// # use tskit::TableUnionOptions;
+    /// let f = TableUnionOptions::default().no_check_shared().no_add_pop();
+    /// assert!(f.contains(TableUnionOptions::NO_CHECK_SHARED));
+    /// assert!(f.contains(TableUnionOptions::NO_ADD_POP));
+    /// ```
+    #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct TableUnionOptions : RawFlags {
+        /// Default behavior.
+        const NONE = 0;
+        /// Do not check that the shared nodes, and the edges and
+        /// mutations among them, are the same in both table collections.
+        const NO_CHECK_SHARED = ll_bindings::TSK_UNION_NO_CHECK_SHARED;
+        /// Do not add the populations of new nodes, which keep
+        /// their population ids.
+        /// By default, the populations of new nodes are added
+        /// to the population table.
+        const NO_ADD_POP = ll_bindings::TSK_UNION_NO_ADD_POP;
+    }
+}
+
+impl TableUnionOptions {
+    flag_builder_api!(
+        /// Set [`NO_CHECK_SHARED`](crate::TableUnionOptions::NO_CHECK_SHARED)
+        => no_check_shared, NO_CHECK_SHARED);
+
+    flag_builder_api!(
+        /// Set [`NO_ADD_POP`](crate::TableUnionOptions::NO_ADD_POP)
+        => no_add_pop, NO_ADD_POP);
+}
+
+bitflags! {
+    /// Modify the behavior of [`crate::TreeInterface::newick`].
+    ///
+    /// # Examples
+    ///
+    /// ## Default (empty) flags
+    ///
+    /// ```
+    /// # use tskit::NewickFlags;
+    /// let f = NewickFlags::default();
+    /// assert_eq!(f, NewickFlags::NONE);
+    /// ```
+    ///
+    /// ## Builder API
+    ///
+    /// ```
+    /// # use tskit::NewickFlags;
+    /// let f = NewickFlags::default().legacy_ms_labels();
+    /// assert!(f.contains(NewickFlags::LEGACY_MS_LABELS));
+    /// ```
+    #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct NewickFlags : RawFlags {
+        /// Default behavior.
+        /// Sample nodes are labelled `n<node id>`.
+        const NONE = 0;
+        /// Label leaves with their node id plus one,
+        /// as done by the `ms` simulator.
+        const LEGACY_MS_LABELS = ll_bindings::TSK_NEWICK_LEGACY_MS_LABELS;
+    }
+}
+
+impl NewickFlags {
+    flag_builder_api!(
+        /// Set [`LEGACY_MS_LABELS`](crate::NewickFlags::LEGACY_MS_LABELS)
+        => legacy_ms_labels, LEGACY_MS_LABELS);
+}
+
 bitflags! {
     /// Modify behavior of [`crate::TableCollection::sort_individuals`].
     ///
@@ -387,6 +725,75 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Modify the behavior of tree sequence statistics,
+    /// such as [`crate::TreeSequence::diversity`].
+    ///
+    /// If none of `SITE`, `BRANCH`, or `NODE` are set,
+    /// statistics are calculated in `SITE` mode.
+    ///
+    /// # Examples
+    ///
+    /// ## Default (empty) flags
+    ///
+    /// ```
+    /// # use tskit::StatisticsOptions;
+    /// let f = StatisticsOptions::default();
+    /// assert_eq!(f, StatisticsOptions::NONE);
+    /// ```
+    ///
+    /// ## Builder API
+    ///
+    /// These methods can be chained.
+    ///
+    /// ```
+    /// # use tskit::StatisticsOptions;
+    /// let f = StatisticsOptions::default().branch().span_normalise();
+    /// assert!(f.contains(StatisticsOptions::BRANCH));
+    /// assert!(f.contains(StatisticsOptions::SPAN_NORMALISE));
+    /// ```
+    #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct StatisticsOptions : RawFlags {
+        /// Default behavior.
+        const NONE = 0;
+        /// Calculate statistics from the sites and mutations.
+        const SITE = ll_bindings::TSK_STAT_SITE;
+        /// Calculate statistics from the branch lengths of the trees.
+        const BRANCH = ll_bindings::TSK_STAT_BRANCH;
+        /// Calculate statistics separately for each node.
+        const NODE = ll_bindings::TSK_STAT_NODE;
+        /// Do not sum over alleles, treating the ancestral state as known.
+        const POLARISED = ll_bindings::TSK_STAT_POLARISED;
+        /// Divide the statistic by the span of the genome considered.
+        const SPAN_NORMALISE = ll_bindings::TSK_STAT_SPAN_NORMALISE;
+        /// Allow statistics to be calculated on tree sequences
+        /// with uncalibrated node times.
+        const ALLOW_TIME_UNCALIBRATED = ll_bindings::TSK_STAT_ALLOW_TIME_UNCALIBRATED;
+    }
+}
+
+impl StatisticsOptions {
+    flag_builder_api!(
+        /// Set [`SITE`](crate::StatisticsOptions::SITE)
+        => site, SITE);
+    flag_builder_api!(
+        /// Set [`BRANCH`](crate::StatisticsOptions::BRANCH)
+        => branch, BRANCH);
+    flag_builder_api!(
+        /// Set [`NODE`](crate::StatisticsOptions::NODE)
+        => node, NODE);
+    flag_builder_api!(
+        /// Set [`POLARISED`](crate::StatisticsOptions::POLARISED)
+        => polarised, POLARISED);
+    flag_builder_api!(
+        /// Set [`SPAN_NORMALISE`](crate::StatisticsOptions::SPAN_NORMALISE)
+        => span_normalise, SPAN_NORMALISE);
+    flag_builder_api!(
+        /// Set [`ALLOW_TIME_UNCALIBRATED`](crate::StatisticsOptions::ALLOW_TIME_UNCALIBRATED)
+        => allow_time_uncalibrated, ALLOW_TIME_UNCALIBRATED);
+}
+
 bitflags! {
     /// Specify the behavior of iterating over [`Tree`] objects.
     /// See [`TreeSequence::tree_iterator`].
@@ -439,6 +846,47 @@ impl TreeFlags {
         => no_sample_counts, NO_SAMPLE_COUNTS);
 }
 
+bitflags! {
+    /// Modify how genotypes are decoded by [`crate::Variant`].
+    /// See [`crate::TreeSequence::variants`].
+    ///
+    /// # Examples
+    ///
+    /// ## Default (empty) flags
+    ///
+    /// ```
+    /// # use tskit::VariantOptions;
+    /// let f = VariantOptions::default();
+    /// assert_eq!(f, VariantOptions::NONE);
+    /// ```
+    ///
+    /// ## Builder API
+    ///
+    /// ```
+    /// # use tskit::VariantOptions;
+    /// let f = VariantOptions::default().isolated_not_missing();
+    /// assert_eq!(f, VariantOptions::ISOLATED_NOT_MISSING);
+    /// ```
+    #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct VariantOptions: RawFlags {
+        /// Default behavior.
+        /// Samples that are isolated in the tree at a site
+        /// (no parent and no children) have missing genotypes
+        /// unless a mutation lies above them.
+        const NONE = 0;
+        /// Isolated samples are assigned the ancestral state
+        /// rather than being marked as missing.
+        const ISOLATED_NOT_MISSING = ll_bindings::TSK_ISOLATED_NOT_MISSING;
+    }
+}
+
+impl VariantOptions {
+    flag_builder_api!(
+        /// Set [`ISOLATED_NOT_MISSING`](crate::VariantOptions::ISOLATED_NOT_MISSING)
+        => isolated_not_missing, ISOLATED_NOT_MISSING);
+}
+
 bitflags! {
     /// Modify behavior of [`crate::TableCollection::dump`].
     ///
@@ -656,6 +1104,25 @@ bitflags! {
     /// let f = NodeFlags::default().mark_sample();
     /// assert_eq!(f, NodeFlags::IS_SAMPLE);
     /// ```
+    ///
+    /// ## User-defined bits
+    ///
+    /// Bits [`FIRST_USER_BIT`](NodeFlags::FIRST_USER_BIT) through 31 are
+    /// not used by `tskit` and are available for client code,
+    /// for example to annotate nodes during a simulation.
+    ///
+    /// ```
+    /// # use tskit::NodeFlags;
+    /// let f = NodeFlags::new_sample().mark_user_bit(17).mark_user_bit(20);
+    /// assert!(f.is_sample());
+    /// assert!(f.contains_user_bit(17));
+    /// assert!(!f.contains_user_bit(18));
+    /// assert_eq!(f.user_bits(), (1 << 17) | (1 << 20));
+    /// let f = f.clear_user_bit(17);
+    /// assert_eq!(f.user_bits(), 1 << 20);
+    /// // Raw values retain user-defined bits
+    /// assert_eq!(NodeFlags::from_bits_retain(f.bits()), f);
+    /// ```
     #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     #[repr(transparent)]
     pub struct NodeFlags : RawFlags {
@@ -699,6 +1166,20 @@ bitflags! {
     #[derive(Default,Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     #[repr(transparent)]
     /// Individual flags
+    ///
+    /// # Examples
+    ///
+    /// ## User-defined bits
+    ///
+    /// Bits [`FIRST_USER_BIT`](IndividualFlags::FIRST_USER_BIT) through 31 are
+    /// not used by `tskit` and are available for client code.
+    ///
+    /// ```
+    /// # use tskit::IndividualFlags;
+    /// let f = IndividualFlags::default().mark_user_bit(16);
+    /// assert!(f.contains_user_bit(16));
+    /// assert_eq!(f.user_bits(), 1 << 16);
+    /// ```
     pub struct IndividualFlags : RawFlags {
         /// Default (empty)
         const NONE = 0;
@@ -714,25 +1195,40 @@ impl IndividualFlags {
     }
 }
 
+impl_user_bits!(NodeFlags);
+impl_user_bits!(IndividualFlags);
+
 impl_flags!(SimplificationOptions);
 impl_flags!(TableClearOptions);
+impl_flags!(StripMetadataOptions);
 impl_flags!(TableEqualityOptions);
 impl_flags!(TreeSequenceFlags);
 impl_flags!(TableSortOptions);
 impl_flags!(TreeFlags);
 impl_flags!(IndividualTableSortOptions);
+impl_flags!(SubsetOptions);
+impl_flags!(TableUnionOptions);
+impl_flags!(NewickFlags);
 impl_flags!(TableIntegrityCheckFlags);
 impl_flags!(TableOutputOptions);
+impl_flags!(StatisticsOptions);
+impl_flags!(VariantOptions);
 
 impl_from_for_flag_types!(SimplificationOptions);
 impl_from_for_flag_types!(TableClearOptions);
+impl_from_for_flag_types!(StripMetadataOptions);
 impl_from_for_flag_types!(TableEqualityOptions);
 impl_from_for_flag_types!(TreeSequenceFlags);
 impl_from_for_flag_types!(TableSortOptions);
 impl_from_for_flag_types!(TreeFlags);
 impl_from_for_flag_types!(IndividualTableSortOptions);
+impl_from_for_flag_types!(SubsetOptions);
+impl_from_for_flag_types!(TableUnionOptions);
+impl_from_for_flag_types!(NewickFlags);
 impl_from_for_flag_types!(TableIntegrityCheckFlags);
 impl_from_for_flag_types!(TableOutputOptions);
+impl_from_for_flag_types!(StatisticsOptions);
+impl_from_for_flag_types!(VariantOptions);
 
 impl From<RawFlags> for NodeFlags {
     fn from(flags: RawFlags) -> Self {
@@ -771,4 +1267,26 @@ mod tests {
         let n = NodeFlags::new_sample();
         assert!(n.is_sample());
     }
+
+    #[test]
+    fn user_bits_do_not_overlap_tskit_bits() {
+        assert_eq!(
+            NodeFlags::USER_BITS_MASK & ll_bindings::TSK_NODE_IS_SAMPLE,
+            0
+        );
+        assert_eq!(NodeFlags::USER_BITS_MASK, 0xFFFF_0000);
+        assert_eq!(IndividualFlags::USER_BITS_MASK, 0xFFFF_0000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reserved_bit_is_not_a_user_bit() {
+        let _ = NodeFlags::default().mark_user_bit(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range_user_bit() {
+        let _ = IndividualFlags::default().mark_user_bit(32);
+    }
 }
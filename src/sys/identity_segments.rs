@@ -0,0 +1,147 @@
+use super::bindings;
+use super::bindings::tsk_identity_segment_list_t;
+use super::bindings::tsk_identity_segment_t;
+use super::bindings::tsk_identity_segments_t;
+use super::newtypes::NodeId;
+use super::tskbox::TskBox;
+use super::TreeSequence;
+use super::TskitError;
+
+pub struct LLIdentitySegments {
+    inner: TskBox<tsk_identity_segments_t>,
+    // The pairs of nodes, in the order of the lists.
+    pairs: Vec<(NodeId, NodeId)>,
+    // NOTE: the lists are owned by inner and are not
+    // moved or freed until inner is dropped.
+    lists: Vec<*mut tsk_identity_segment_list_t>,
+}
+
+// SAFETY: the segments are only accessed via shared references
+// and the pointers are owned by inner.
+unsafe impl Send for LLIdentitySegments {}
+unsafe impl Sync for LLIdentitySegments {}
+
+impl LLIdentitySegments {
+    pub fn within(
+        treeseq: &TreeSequence,
+        samples: Option<&[NodeId]>,
+        min_span: f64,
+        max_time: f64,
+        options: bindings::tsk_flags_t,
+    ) -> Result<Self, TskitError> {
+        let (samples_ptr, num_samples) = match samples {
+            // The cast is safe/sound b/c NodeId is repr(transparent)
+            Some(s) => (s.as_ptr().cast::<bindings::tsk_id_t>(), s.len()),
+            None => (std::ptr::null(), 0),
+        };
+        // SAFETY: treeseq is initialized, so its tables are not null
+        // and are sorted and indexed. samples may be NULL.
+        let inner = TskBox::new(|x: *mut tsk_identity_segments_t| unsafe {
+            bindings::tsk_table_collection_ibd_within(
+                treeseq.as_ref().tables,
+                x,
+                samples_ptr,
+                num_samples as bindings::tsk_size_t,
+                min_span,
+                max_time,
+                options,
+            )
+        })?;
+        Self::new(inner)
+    }
+
+    pub fn between(
+        treeseq: &TreeSequence,
+        sample_sets: &[&[NodeId]],
+        min_span: f64,
+        max_time: f64,
+        options: bindings::tsk_flags_t,
+    ) -> Result<Self, TskitError> {
+        let sample_set_sizes = sample_sets
+            .iter()
+            .map(|s| s.len() as bindings::tsk_size_t)
+            .collect::<Vec<_>>();
+        let samples = sample_sets.concat();
+        // SAFETY: treeseq is initialized, so its tables are not null
+        // and are sorted and indexed.
+        // The cast of the samples is sound b/c NodeId is repr(transparent).
+        let inner = TskBox::new(|x: *mut tsk_identity_segments_t| unsafe {
+            bindings::tsk_table_collection_ibd_between(
+                treeseq.as_ref().tables,
+                x,
+                sample_sets.len() as bindings::tsk_size_t,
+                sample_set_sizes.as_ptr(),
+                samples.as_ptr().cast::<bindings::tsk_id_t>(),
+                min_span,
+                max_time,
+                options,
+            )
+        })?;
+        Self::new(inner)
+    }
+
+    fn new(inner: TskBox<tsk_identity_segments_t>) -> Result<Self, TskitError> {
+        let mut rv = Self {
+            inner,
+            pairs: vec![],
+            lists: vec![],
+        };
+        if rv.inner.as_ref().store_pairs {
+            let num_pairs = rv.num_pairs() as usize;
+            let mut pairs = vec![super::TSK_NULL; 2 * num_pairs];
+            rv.lists = vec![std::ptr::null_mut(); num_pairs];
+            // SAFETY: inner is initialized and the outputs have
+            // the lengths required by tskit.
+            match unsafe {
+                bindings::tsk_identity_segments_get_items(
+                    rv.inner.as_ref(),
+                    pairs.as_mut_ptr(),
+                    rv.lists.as_mut_ptr(),
+                )
+            } {
+                code if code < 0 => return Err(TskitError::ErrorCode { code }),
+                _ => (),
+            }
+            rv.pairs = pairs
+                .chunks_exact(2)
+                .map(|p| (p[0].into(), p[1].into()))
+                .collect();
+        }
+        Ok(rv)
+    }
+
+    pub fn num_segments(&self) -> bindings::tsk_size_t {
+        // SAFETY: inner is initialized
+        unsafe { bindings::tsk_identity_segments_get_num_segments(self.inner.as_ref()) }
+    }
+
+    pub fn total_span(&self) -> f64 {
+        // SAFETY: inner is initialized
+        unsafe { bindings::tsk_identity_segments_get_total_span(self.inner.as_ref()) }
+    }
+
+    pub fn num_pairs(&self) -> bindings::tsk_size_t {
+        // SAFETY: inner is initialized
+        unsafe { bindings::tsk_identity_segments_get_num_pairs(self.inner.as_ref()) }
+    }
+
+    pub fn pairs(&self) -> &[(NodeId, NodeId)] {
+        &self.pairs
+    }
+
+    pub fn list(&self, index: usize) -> Option<&tsk_identity_segment_list_t> {
+        // SAFETY: the non-null lists are owned by inner
+        self.lists.get(index).and_then(|&l| unsafe { l.as_ref() })
+    }
+
+    pub fn segments<'a>(
+        &'a self,
+        list: &'a tsk_identity_segment_list_t,
+    ) -> impl Iterator<Item = &'a tsk_identity_segment_t> + 'a {
+        // SAFETY: the segments of a list are owned by inner
+        // and the last segment has a null next pointer.
+        std::iter::successors(unsafe { list.head.as_ref() }, |s| unsafe {
+            s.next.as_ref()
+        })
+    }
+}
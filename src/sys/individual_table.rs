@@ -4,8 +4,13 @@ use super::bindings::tsk_flags_t;
 use super::bindings::tsk_id_t;
 use super::bindings::tsk_individual_table_add_row;
 use super::bindings::tsk_individual_table_clear;
+use super::bindings::tsk_individual_table_copy;
 use super::bindings::tsk_individual_table_init;
 use super::bindings::tsk_individual_table_t;
+use super::bindings::tsk_node_table_add_row;
+use super::bindings::tsk_table_collection_individual_topological_sort;
+use super::bindings::TSK_NO_INIT;
+use super::table_collection::TableCollection;
 use super::tskbox::TskBox;
 use super::TskitError;
 
@@ -66,10 +71,68 @@ impl IndividualTable {
             ))
         }
     }
+
+    // Sort the rows so that parents come before their children,
+    // returning the new id of each input row.
+    //
+    // The C API only sorts the individuals of a table collection,
+    // so we sort a copy in a temporary one, adding one node per
+    // individual to record where each row ends up.
+    pub fn topological_sort(&mut self, options: tsk_flags_t) -> Result<Vec<tsk_id_t>, TskitError> {
+        let mut tables = TableCollection::new(1.0)?;
+        // SAFETY: both tables are initialized
+        let rv = unsafe {
+            tsk_individual_table_copy(self.as_ref(), tables.individuals_mut(), TSK_NO_INIT)
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        for i in 0..self.as_ref().num_rows as tsk_id_t {
+            // SAFETY: the node table is initialized
+            let rv = unsafe {
+                tsk_node_table_add_row(
+                    tables.nodes_mut(),
+                    0,
+                    0.0,
+                    super::TSK_NULL,
+                    i,
+                    std::ptr::null(),
+                    0,
+                )
+            };
+            if rv < 0 {
+                return Err(TskitError::ErrorCode { code: rv });
+            }
+        }
+        // SAFETY: the table collection is initialized
+        let rv = unsafe {
+            tsk_table_collection_individual_topological_sort(tables.as_mut_ptr(), options)
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        // SAFETY: both tables are initialized
+        let rv = unsafe {
+            tsk_individual_table_copy(tables.individuals_mut(), self.as_mut(), TSK_NO_INIT)
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        let nodes = tables.nodes_mut();
+        let new_ids = if nodes.num_rows == 0 {
+            vec![]
+        } else {
+            // SAFETY: the node table has num_rows individuals
+            unsafe { std::slice::from_raw_parts(nodes.individual, nodes.num_rows as usize) }
+                .to_vec()
+        };
+        Ok(new_ids)
+    }
 }
 
 impl Default for IndividualTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
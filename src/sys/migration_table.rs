@@ -74,6 +74,7 @@ impl MigrationTable {
 
 impl Default for MigrationTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
@@ -9,6 +9,7 @@ pub mod bindings;
 
 mod edge_table;
 pub mod flags;
+mod identity_segments;
 mod individual_table;
 mod migration_table;
 mod mutation_table;
@@ -19,11 +20,13 @@ mod population_table;
 mod provenance_table;
 mod site_table;
 mod table_collection;
+mod text;
 mod trait_impls;
 mod traits;
 mod tree;
 mod treeseq;
 mod tskbox;
+mod variant;
 
 // tskit defines this via a type cast
 // in a macro. bindgen thus misses it.
@@ -32,6 +35,7 @@ mod tskbox;
 pub(crate) const TSK_NULL: bindings::tsk_id_t = -1;
 
 pub use edge_table::EdgeTable;
+pub use identity_segments::LLIdentitySegments;
 pub use individual_table::IndividualTable;
 pub use migration_table::MigrationTable;
 pub use mutation_table::MutationTable;
@@ -41,8 +45,10 @@ pub use population_table::PopulationTable;
 pub use provenance_table::ProvenanceTable;
 pub use site_table::SiteTable;
 pub use table_collection::*;
+pub use text::dump_text;
 pub use tree::LLTree;
-pub use treeseq::TreeSequence;
+pub use treeseq::{OneWayStatFn, TreeSequence, TwoWayStatFn};
+pub use variant::LLVariant;
 
 use traits::TskTeardown;
 
@@ -65,6 +71,11 @@ pub enum TskitError {
     /// not being updated.
     #[error("Not tracking samples in Trees")]
     NotTrackingSamples,
+    /// Returned when the edge table indexes no longer match
+    /// the tables, because edges or nodes were edited
+    /// after the indexes were built.
+    #[error("edge table indexes are stale: the tables were edited after building the indexes")]
+    StaleIndexes,
     /// Wrapper around tskit C API error codes.
     #[error("{}", get_tskit_error_message(*code))]
     ErrorCode { code: i32 },
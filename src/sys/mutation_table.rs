@@ -80,6 +80,7 @@ impl MutationTable {
 
 impl Default for MutationTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
@@ -102,6 +102,7 @@ impl NodeTable {
 
 impl Default for NodeTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
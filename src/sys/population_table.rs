@@ -53,6 +53,7 @@ impl PopulationTable {
 
 impl Default for PopulationTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
@@ -61,6 +61,7 @@ impl ProvenanceTable {
 
 impl Default for ProvenanceTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
@@ -70,6 +70,7 @@ impl SiteTable {
 
 impl Default for SiteTable {
     fn default() -> Self {
-        Self::new(0).unwrap()
+        // Only fails if allocation fails
+        Self::new(0).expect("failed to allocate an empty table")
     }
 }
@@ -57,6 +57,14 @@ impl TableCollection {
         self.0.as_ref().sequence_length
     }
 
+    pub fn set_sequence_length(&mut self, sequence_length: f64) {
+        self.0.as_mut().sequence_length = sequence_length
+    }
+
+    pub fn as_ref(&self) -> &tsk_table_collection_t {
+        self.0.as_ref()
+    }
+
     pub fn as_ptr(&self) -> *const tsk_table_collection_t {
         self.0.as_ptr()
     }
@@ -106,6 +114,50 @@ impl TableCollection {
         unsafe { &mut (*self.as_mut_ptr()).sites }
     }
 
+    pub fn subset(
+        &mut self,
+        nodes: &[super::newtypes::NodeId],
+        options: super::bindings::tsk_flags_t,
+    ) -> Result<(), TskitError> {
+        // SAFETY: self pointer is not null.
+        // The cast is sound b/c NodeId is repr(transparent)
+        let rv = unsafe {
+            super::bindings::tsk_table_collection_subset(
+                self.as_mut_ptr(),
+                nodes.as_ptr().cast::<super::bindings::tsk_id_t>(),
+                nodes.len() as super::bindings::tsk_size_t,
+                options,
+            )
+        };
+        match rv {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(()),
+        }
+    }
+
+    // Subtract `offset` from all genomic coordinates
+    // and set the sequence length to `sequence_length`.
+    pub fn shift_coordinates(&mut self, offset: f64, sequence_length: f64) {
+        self.map_coordinates(|x| x - offset, sequence_length)
+    }
+
+    pub fn map_coordinates<F: FnMut(f64) -> f64>(&mut self, mut f: F, sequence_length: f64) {
+        self.set_sequence_length(sequence_length);
+        let tables = self.0.as_mut();
+        let columns = [
+            (tables.edges.left, tables.edges.num_rows),
+            (tables.edges.right, tables.edges.num_rows),
+            (tables.migrations.left, tables.migrations.num_rows),
+            (tables.migrations.right, tables.migrations.num_rows),
+            (tables.sites.position, tables.sites.num_rows),
+        ];
+        for (column, num_rows) in columns {
+            super::generate_slice_mut::<_, _, f64>(column, num_rows)
+                .iter_mut()
+                .for_each(|x| *x = f(*x));
+        }
+    }
+
     pub fn into_raw(self) -> *mut tsk_table_collection_t {
         self.0.into_raw()
     }
@@ -0,0 +1,64 @@
+use std::io::Write;
+
+use super::bindings;
+use super::TskitError;
+
+fn io_error(e: std::io::Error) -> TskitError {
+    TskitError::LibraryError(e.to_string())
+}
+
+/// Call `dump`, which writes to a `C` stream, and copy
+/// what it wrote to `writer`.
+///
+/// The `C` stream is a temporary file, which is removed
+/// when it is closed.
+pub fn dump_text<W: Write, F: FnOnce(*mut bindings::FILE) -> i32>(
+    writer: &mut W,
+    dump: F,
+) -> Result<(), TskitError> {
+    // SAFETY: tmpfile returns either NULL or an open stream,
+    // which is closed below.
+    let file = unsafe { libc::tmpfile() };
+    if file.is_null() {
+        return Err(io_error(std::io::Error::last_os_error()));
+    }
+    let rv = copy_dumped_text(file, writer, dump);
+    // SAFETY: file is an open stream that is not used again
+    unsafe { libc::fclose(file) };
+    rv
+}
+
+fn copy_dumped_text<W: Write, F: FnOnce(*mut bindings::FILE) -> i32>(
+    file: *mut libc::FILE,
+    writer: &mut W,
+    dump: F,
+) -> Result<(), TskitError> {
+    let code = dump(file as *mut bindings::FILE);
+    if code < 0 {
+        return Err(TskitError::ErrorCode { code });
+    }
+    // SAFETY: file is an open stream
+    unsafe { libc::rewind(file) };
+    let mut buffer = vec![0_u8; 1 << 16];
+    loop {
+        // SAFETY: buffer has room for buffer.len() bytes
+        let n = unsafe {
+            libc::fread(
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                1,
+                buffer.len(),
+                file,
+            )
+        };
+        writer.write_all(&buffer[..n]).map_err(io_error)?;
+        if n < buffer.len() {
+            // SAFETY: file is an open stream
+            if unsafe { libc::ferror(file) } != 0 {
+                return Err(TskitError::LibraryError(
+                    "unable to read dumped text".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+    }
+}
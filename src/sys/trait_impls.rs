@@ -3,6 +3,14 @@ impl_tskteardown!(
     super::bindings::tsk_table_collection_free
 );
 impl_tskteardown!(super::bindings::tsk_tree_t, super::bindings::tsk_tree_free);
+impl_tskteardown!(
+    super::bindings::tsk_variant_t,
+    super::bindings::tsk_variant_free
+);
+impl_tskteardown!(
+    super::bindings::tsk_identity_segments_t,
+    super::bindings::tsk_identity_segments_free
+);
 
 impl_tskteardown!(
     super::bindings::tsk_edge_table_t,
@@ -10,7 +10,6 @@ pub struct LLTree<'treeseq> {
     // contains a NON-OWNING pointer to tsk_treeseq_t.
     // Thus, we could theoretically cause UB without
     // tying the rust-side object liftimes together.
-    #[allow(dead_code)]
     treeseq: &'treeseq TreeSequence,
 }
 
@@ -39,4 +38,8 @@ impl<'treeseq> LLTree<'treeseq> {
     pub fn as_mut_ptr(&mut self) -> *mut tsk_tree_t {
         self.inner.as_mut()
     }
+
+    pub fn treeseq(&self) -> &'treeseq TreeSequence {
+        self.treeseq
+    }
 }
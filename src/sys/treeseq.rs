@@ -6,6 +6,34 @@ use super::bindings;
 use super::tskbox::TskBox;
 use super::TskitError;
 
+/// Signature shared by the "one way" statistics of the C API,
+/// such as `tsk_treeseq_diversity`.
+pub type OneWayStatFn = unsafe extern "C" fn(
+    *const bindings::tsk_treeseq_t,
+    bindings::tsk_size_t,
+    *const bindings::tsk_size_t,
+    *const bindings::tsk_id_t,
+    bindings::tsk_size_t,
+    *const f64,
+    bindings::tsk_flags_t,
+    *mut f64,
+) -> std::os::raw::c_int;
+
+/// Signature shared by the "two way" statistics of the C API,
+/// such as `tsk_treeseq_divergence`.
+pub type TwoWayStatFn = unsafe extern "C" fn(
+    *const bindings::tsk_treeseq_t,
+    bindings::tsk_size_t,
+    *const bindings::tsk_size_t,
+    *const bindings::tsk_id_t,
+    bindings::tsk_size_t,
+    *const bindings::tsk_id_t,
+    bindings::tsk_size_t,
+    *const f64,
+    bindings::tsk_flags_t,
+    *mut f64,
+) -> std::os::raw::c_int;
+
 #[repr(transparent)]
 pub struct TreeSequence(TskBox<bindings::tsk_treeseq_t>);
 
@@ -82,6 +110,39 @@ impl TreeSequence {
         unsafe { bindings::tsk_treeseq_get_num_trees(self.as_ref()) }.into()
     }
 
+    pub fn copy_tables(&self) -> Result<super::TableCollection, TskitError> {
+        // SAFETY: the C API requires that the destination of a copy be uninitalized.
+        // Copying into it will initialize the object.
+        let mut tables = unsafe { super::TableCollection::new_uninit() };
+        // SAFETY: self pointer is not null and tables
+        // matches the input expectations of the C API.
+        let rv =
+            unsafe { bindings::tsk_treeseq_copy_tables(self.as_ref(), tables.as_mut_ptr(), 0) };
+        match rv {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(tables),
+        }
+    }
+
+    pub fn num_sites(&self) -> super::newtypes::SizeType {
+        // SAFETY: self pointer is not null
+        unsafe { bindings::tsk_treeseq_get_num_sites(self.as_ref()) }.into()
+    }
+
+    pub fn sequence_length(&self) -> f64 {
+        // SAFETY: self pointer is not null
+        unsafe { bindings::tsk_treeseq_get_sequence_length(self.as_ref()) }
+    }
+
+    pub fn breakpoints(&self) -> &[super::newtypes::Position] {
+        // SAFETY: self pointer is not null
+        let breakpoints = unsafe { bindings::tsk_treeseq_get_breakpoints(self.as_ref()) };
+        // SAFETY: self pointer is not null
+        let num_trees = unsafe { bindings::tsk_treeseq_get_num_trees(self.as_ref()) };
+        // There is one more breakpoint than there are trees
+        super::generate_slice(breakpoints, num_trees + 1)
+    }
+
     pub fn num_nodes_raw(&self) -> bindings::tsk_size_t {
         assert!(!self.as_ref().tables.is_null());
         // SAFETY: none of the pointers are null
@@ -100,6 +161,196 @@ impl TreeSequence {
         }
     }
 
+    /// Calculate a "one way" statistic over `sample_sets`
+    /// and `windows`.
+    ///
+    /// The output has one row per window.
+    /// In node mode, each row has `num_nodes * sample_sets.len()`
+    /// values and `sample_sets.len()` values otherwise.
+    pub fn one_way_stat(
+        &self,
+        stat: OneWayStatFn,
+        sample_sets: &[&[super::newtypes::NodeId]],
+        windows: &[f64],
+        options: super::flags::StatisticsOptions,
+    ) -> Result<Vec<f64>, TskitError> {
+        if windows.len() < 2 {
+            return Err(TskitError::ValueError {
+                got: format!("{} window breakpoints", windows.len()),
+                expected: "at least 2 window breakpoints".to_string(),
+            });
+        }
+        let sample_set_sizes = sample_sets
+            .iter()
+            .map(|s| s.len() as bindings::tsk_size_t)
+            .collect::<Vec<_>>();
+        let samples = sample_sets.concat();
+        let num_windows = windows.len() - 1;
+        let row_len = if options.contains(super::flags::StatisticsOptions::NODE) {
+            self.num_nodes_raw() as usize * sample_sets.len()
+        } else {
+            sample_sets.len()
+        };
+        let mut result = vec![f64::NAN; num_windows * row_len];
+        // SAFETY: self pointer is not null.
+        // The input and output buffers are sized as required by tskit.
+        // The cast of the samples is sound b/c NodeId is repr(transparent).
+        let rv = unsafe {
+            stat(
+                self.as_ref(),
+                sample_sets.len() as bindings::tsk_size_t,
+                sample_set_sizes.as_ptr(),
+                samples.as_ptr().cast::<bindings::tsk_id_t>(),
+                num_windows as bindings::tsk_size_t,
+                windows.as_ptr(),
+                options.bits(),
+                result.as_mut_ptr(),
+            )
+        };
+        match rv {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(result),
+        }
+    }
+
+    /// Calculate a "two way" statistic for pairs of `sample_sets`
+    /// over `windows`.
+    ///
+    /// Each pair in `indexes` refers to two elements of `sample_sets`.
+    /// The output has one row per window.
+    /// In node mode, each row has `num_nodes * indexes.len()`
+    /// values and `indexes.len()` values otherwise.
+    pub fn two_way_stat(
+        &self,
+        stat: TwoWayStatFn,
+        sample_sets: &[&[super::newtypes::NodeId]],
+        indexes: &[(usize, usize)],
+        windows: &[f64],
+        options: super::flags::StatisticsOptions,
+    ) -> Result<Vec<f64>, TskitError> {
+        if windows.len() < 2 {
+            return Err(TskitError::ValueError {
+                got: format!("{} window breakpoints", windows.len()),
+                expected: "at least 2 window breakpoints".to_string(),
+            });
+        }
+        let mut index_tuples = Vec::with_capacity(2 * indexes.len());
+        for &(i, j) in indexes {
+            if i >= sample_sets.len() || j >= sample_sets.len() {
+                return Err(TskitError::IndexError);
+            }
+            index_tuples.extend([i as bindings::tsk_id_t, j as bindings::tsk_id_t]);
+        }
+        let sample_set_sizes = sample_sets
+            .iter()
+            .map(|s| s.len() as bindings::tsk_size_t)
+            .collect::<Vec<_>>();
+        let samples = sample_sets.concat();
+        let num_windows = windows.len() - 1;
+        let row_len = if options.contains(super::flags::StatisticsOptions::NODE) {
+            self.num_nodes_raw() as usize * indexes.len()
+        } else {
+            indexes.len()
+        };
+        let mut result = vec![f64::NAN; num_windows * row_len];
+        // SAFETY: self pointer is not null.
+        // The input and output buffers are sized as required by tskit.
+        // The cast of the samples is sound b/c NodeId is repr(transparent).
+        let rv = unsafe {
+            stat(
+                self.as_ref(),
+                sample_sets.len() as bindings::tsk_size_t,
+                sample_set_sizes.as_ptr(),
+                samples.as_ptr().cast::<bindings::tsk_id_t>(),
+                indexes.len() as bindings::tsk_size_t,
+                index_tuples.as_ptr(),
+                num_windows as bindings::tsk_size_t,
+                windows.as_ptr(),
+                options.bits(),
+                result.as_mut_ptr(),
+            )
+        };
+        match rv {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(result),
+        }
+    }
+
+    /// Calculate the allele frequency spectrum of `sample_sets`
+    /// over `windows`.
+    ///
+    /// The output has one row per window, and each row is an array
+    /// with dimensions `sample_sets[i].len() + 1`, stored row-major.
+    pub fn allele_frequency_spectrum(
+        &self,
+        sample_sets: &[&[super::newtypes::NodeId]],
+        windows: &[f64],
+        options: super::flags::StatisticsOptions,
+    ) -> Result<Vec<f64>, TskitError> {
+        if windows.len() < 2 {
+            return Err(TskitError::ValueError {
+                got: format!("{} window breakpoints", windows.len()),
+                expected: "at least 2 window breakpoints".to_string(),
+            });
+        }
+        let sample_set_sizes = sample_sets
+            .iter()
+            .map(|s| s.len() as bindings::tsk_size_t)
+            .collect::<Vec<_>>();
+        let samples = sample_sets.concat();
+        let num_windows = windows.len() - 1;
+        let row_len = sample_sets.iter().map(|s| s.len() + 1).product::<usize>();
+        let mut result = vec![f64::NAN; num_windows * row_len];
+        // SAFETY: self pointer is not null.
+        // The input and output buffers are sized as required by tskit.
+        // The cast of the samples is sound b/c NodeId is repr(transparent).
+        let rv = unsafe {
+            bindings::tsk_treeseq_allele_frequency_spectrum(
+                self.as_ref(),
+                sample_sets.len() as bindings::tsk_size_t,
+                sample_set_sizes.as_ptr(),
+                samples.as_ptr().cast::<bindings::tsk_id_t>(),
+                num_windows as bindings::tsk_size_t,
+                windows.as_ptr(),
+                options.bits(),
+                result.as_mut_ptr(),
+            )
+        };
+        match rv {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(result),
+        }
+    }
+
+    fn num_individuals_raw(&self) -> usize {
+        // SAFETY: self pointer is not null and the tables are initialized
+        unsafe { (*(self.as_ref()).tables).individuals.num_rows as usize }
+    }
+
+    pub fn individuals_population(&self) -> Result<Vec<super::newtypes::PopulationId>, TskitError> {
+        let mut output = vec![super::TSK_NULL; self.num_individuals_raw()];
+        // SAFETY: self pointer is not null and output has one
+        // element per individual
+        match unsafe {
+            bindings::tsk_treeseq_get_individuals_population(self.as_ref(), output.as_mut_ptr())
+        } {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(output.into_iter().map(|p| p.into()).collect()),
+        }
+    }
+
+    pub fn individuals_time(&self) -> Result<Vec<super::newtypes::Time>, TskitError> {
+        let mut output = vec![f64::NAN; self.num_individuals_raw()];
+        // SAFETY: self pointer is not null and output has one
+        // element per individual
+        match unsafe {
+            bindings::tsk_treeseq_get_individuals_time(self.as_ref(), output.as_mut_ptr())
+        } {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(output.into_iter().map(|t| t.into()).collect()),
+        }
+    }
+
     pub fn num_samples(&self) -> super::newtypes::SizeType {
         unsafe { bindings::tsk_treeseq_get_num_samples(self.as_ref()) }.into()
     }
@@ -0,0 +1,81 @@
+use super::bindings::tsk_variant_t;
+use super::flags::VariantOptions;
+use super::newtypes::NodeId;
+use super::tskbox::TskBox;
+use super::TreeSequence;
+use super::TskitError;
+
+pub struct LLVariant<'treeseq> {
+    inner: TskBox<tsk_variant_t>,
+    // NOTE: tsk_variant_t contains a NON-OWNING
+    // pointer to tsk_treeseq_t, so we tie the
+    // lifetimes together as we do for LLTree.
+    #[allow(dead_code)]
+    treeseq: &'treeseq TreeSequence,
+}
+
+impl<'treeseq> LLVariant<'treeseq> {
+    pub fn new(
+        treeseq: &'treeseq TreeSequence,
+        samples: Option<&[NodeId]>,
+        options: VariantOptions,
+    ) -> Result<Self, TskitError> {
+        let (samples_ptr, num_samples) = match samples {
+            // The cast is safe/sound b/c NodeId is repr(transparent)
+            Some(s) => (s.as_ptr().cast::<super::bindings::tsk_id_t>(), s.len()),
+            None => (std::ptr::null(), 0),
+        };
+        // SAFETY: treeseq is initialized, samples may be NULL,
+        // and tskit takes a copy of them if they are not.
+        let inner = TskBox::new(|x: *mut tsk_variant_t| unsafe {
+            super::bindings::tsk_variant_init(
+                x,
+                treeseq.as_ref(),
+                samples_ptr,
+                num_samples as super::bindings::tsk_size_t,
+                std::ptr::null_mut(),
+                options.bits(),
+            )
+        })?;
+        Ok(Self { inner, treeseq })
+    }
+
+    pub fn decode(&mut self, site: super::bindings::tsk_id_t) -> Result<(), TskitError> {
+        // SAFETY: the variant is initialized and
+        // tskit validates the site id.
+        match unsafe { super::bindings::tsk_variant_decode(self.inner.as_mut(), site, 0) } {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn as_ref(&self) -> &tsk_variant_t {
+        self.inner.as_ref()
+    }
+
+    pub fn genotypes(&self) -> &[i32] {
+        super::generate_slice(self.as_ref().genotypes, self.as_ref().num_samples)
+    }
+
+    pub fn samples(&self) -> &[NodeId] {
+        super::generate_slice(self.as_ref().samples, self.as_ref().num_samples)
+    }
+
+    pub fn allele(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.as_ref().num_alleles as usize {
+            return None;
+        }
+        // SAFETY: index is in range for both arrays
+        let (allele, length) = unsafe {
+            (
+                *self.as_ref().alleles.add(index),
+                *self.as_ref().allele_lengths.add(index),
+            )
+        };
+        if length == 0 {
+            Some(&[])
+        } else {
+            Some(super::generate_slice(allele.cast::<u8>(), length))
+        }
+    }
+}
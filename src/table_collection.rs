@@ -7,6 +7,7 @@ use crate::metadata::MigrationMetadata;
 use crate::metadata::MutationMetadata;
 use crate::metadata::PopulationMetadata;
 use crate::metadata::SiteMetadata;
+use crate::metadata::TableCollectionMetadata;
 use crate::sys::bindings as ll_bindings;
 use crate::sys::TableCollection as LLTableCollection;
 use crate::types::Bookmark;
@@ -103,6 +104,79 @@ impl TableCollection {
         })
     }
 
+    /// Create tables from a tree in Newick format.
+    ///
+    /// The tree covers the interval `[0, sequence_length)`.
+    /// Every node must have a branch length, except the root.
+    /// Node times are measured back from the leaf furthest from
+    /// the root, which has time 0, and leaves are samples.
+    /// Nodes are added in preorder and the edge table is sorted
+    /// and indexed.
+    ///
+    /// Labelled nodes have their original label stored as
+    /// [`NewickLabel`](crate::NewickLabel) metadata.
+    /// If a label is a key of `samples`, the node is assigned to the
+    /// individual and population named by the value.
+    /// Individuals and populations are added on first use, also with
+    /// [`NewickLabel`](crate::NewickLabel) metadata recording their names.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `newick` is not valid Newick,
+    ///   if a branch length is missing, or if leaf labels are not unique.
+    /// * [`TskitError`] if adding rows to the tables fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use tskit::{NewickLabel, NewickSample};
+    ///
+    /// let samples = HashMap::from([
+    ///     ("A".to_string(), NewickSample {
+    ///         individual: Some("ind0".to_string()),
+    ///         population: Some("pop0".to_string()),
+    ///     }),
+    ///     ("B".to_string(), NewickSample {
+    ///         individual: Some("ind0".to_string()),
+    ///         population: Some("pop0".to_string()),
+    ///     }),
+    /// ]);
+    /// let tables = tskit::TableCollection::from_newick(
+    ///     "((A:1,B:1):2,C:3)root;",
+    ///     100.,
+    ///     &samples,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 5);
+    /// assert_eq!(tables.individuals().num_rows(), 1);
+    /// assert_eq!(tables.populations().num_rows(), 1);
+    ///
+    /// // The root is node 0
+    /// assert_eq!(tables.nodes().time(0), Some(3.0.into()));
+    /// assert_eq!(
+    ///     tables.nodes().metadata::<NewickLabel>(0).unwrap().unwrap(),
+    ///     NewickLabel::from("root")
+    /// );
+    ///
+    /// let treeseq = tables
+    ///     .tree_sequence(tskit::TreeSequenceFlags::default())
+    ///     .unwrap();
+    /// assert_eq!(treeseq.num_samples(), 3);
+    /// let names = treeseq.sample_names::<NewickLabel>().unwrap();
+    /// assert_eq!(
+    ///     names,
+    ///     vec![Some("ind0".to_string()), Some("ind0".to_string()), None]
+    /// );
+    /// ```
+    pub fn from_newick<P: Into<Position>>(
+        newick: &str,
+        sequence_length: P,
+        samples: &std::collections::HashMap<String, crate::NewickSample>,
+    ) -> Result<Self, TskitError> {
+        crate::newick::tables_from_newick(newick, sequence_length, samples)
+    }
+
     pub(crate) fn new_from_ll(lltables: LLTableCollection) -> Result<Self, TskitError> {
         let mut inner = lltables;
         let views = crate::table_views::TableViews::new_from_ll_table_collection(&mut inner)?;
@@ -113,6 +187,11 @@ impl TableCollection {
         })
     }
 
+    pub(crate) fn shift_coordinates(&mut self, offset: Position, sequence_length: Position) {
+        self.inner
+            .shift_coordinates(offset.into(), sequence_length.into())
+    }
+
     pub(crate) fn into_inner(self) -> crate::sys::TableCollection {
         self.inner
     }
@@ -361,6 +440,68 @@ impl TableCollection {
             .add_row_with_metadata(flags, location, parents, metadata)
     }
 
+    /// Add one individual for each sample node, and assign
+    /// each sample node to its individual.
+    ///
+    /// This is a convenient way to attach external information,
+    /// such as sample names, to the sample nodes.
+    ///
+    /// # Parameters
+    ///
+    /// * `metadata`: the metadata for each individual.
+    ///   The k-th value is used for the k-th sample node
+    ///   in the order of [`TableCollection::samples_as_vector`].
+    ///
+    /// # Returns
+    ///
+    /// The ids of the new individuals, in the same order
+    /// as `metadata`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the length of `metadata` differs from
+    ///   the number of samples or if any sample node already
+    ///   has an individual.
+    /// * [`TskitError`] if adding a row fails.
+    ///
+    /// # Examples
+    ///
+    /// See [`TreeSequence::sample_names`](crate::TreeSequence::sample_names).
+    pub fn add_sample_individuals<M: crate::metadata::IndividualMetadata>(
+        &mut self,
+        metadata: &[M],
+    ) -> Result<Vec<crate::IndividualId>, TskitError> {
+        let samples = self.samples_as_vector();
+        if samples.len() != metadata.len() {
+            return Err(TskitError::ValueError {
+                got: format!("{} metadata values", metadata.len()),
+                expected: format!("{} values, one per sample", samples.len()),
+            });
+        }
+        if samples
+            .iter()
+            .any(|s| self.nodes().individual(*s).is_some_and(|i| !i.is_null()))
+        {
+            return Err(TskitError::ValueError {
+                got: "sample node with an individual".to_string(),
+                expected: "sample nodes without individuals".to_string(),
+            });
+        }
+        let mut rv = vec![];
+        for m in metadata {
+            rv.push(self.add_individual_with_metadata(0, None, None, m)?);
+        }
+        let num_nodes = self.nodes().num_rows();
+        let individual = crate::sys::generate_slice_mut::<_, _, crate::IndividualId>(
+            self.inner.nodes_mut().individual,
+            num_nodes,
+        );
+        for (s, i) in samples.iter().zip(rv.iter()) {
+            individual[s.as_usize()] = *i;
+        }
+        Ok(rv)
+    }
+
     /// Add a row to the migration table
     ///
     /// # Warnings
@@ -396,6 +537,79 @@ impl TableCollection {
             .add_row(span, node, source_dest, time)
     }
 
+    /// Add migration events to the migration table.
+    ///
+    /// Unlike [`TableCollection::add_migration`], the events are
+    /// checked against the other tables.
+    /// All events are checked before any row is added, and rows
+    /// added before a failure are removed, so that the migration
+    /// table is unchanged if an error is returned.
+    ///
+    /// # Returns
+    ///
+    /// The ids of the new rows, in the order of `migrations`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if the span of an event is
+    ///   not within the sequence.
+    /// * [`TskitError::IndexError`] if the node, source, or destination
+    ///   of an event is not a row of the node or population table.
+    /// * [`TskitError::ValueError`] if the source and destination of an
+    ///   event are the same, or if its time is not finite or is less
+    ///   than the time of its node.
+    /// * [`TskitError`] if adding a row fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::MigrationEvent;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop0 = tables.add_population().unwrap();
+    /// let pop1 = tables.add_population().unwrap();
+    /// let node = tables.add_node(0, 1.0, pop0, -1).unwrap();
+    ///
+    /// let event = MigrationEvent::new((0., 50.), node, (pop1, pop0), 2.0);
+    /// let later = MigrationEvent { left: 50.0.into(), right: 100.0.into(), time: 3.0.into(), ..event };
+    /// let ids = tables.add_migrations(&[event, later]).unwrap();
+    /// assert_eq!(ids, [0, 1]);
+    ///
+    /// // The migration is more recent than the birth of the node
+    /// let early = MigrationEvent { time: 0.5.into(), ..event };
+    /// assert!(tables.add_migrations(&[event, early]).is_err());
+    /// assert_eq!(tables.migrations().num_rows(), 2);
+    /// ```
+    pub fn add_migrations(
+        &mut self,
+        migrations: &[crate::MigrationEvent],
+    ) -> Result<Vec<MigrationId>, TskitError> {
+        let node_times = self.nodes().time_slice();
+        let num_populations = self.populations().num_rows().as_usize();
+        for m in migrations {
+            m.validate(self.sequence_length(), node_times, num_populations)?;
+        }
+        let num_rows = self.migrations().num_rows();
+        let rv = migrations
+            .iter()
+            .map(|m| self.add_migration((m.left, m.right), m.node, (m.source, m.dest), m.time))
+            .collect::<Result<Vec<_>, _>>();
+        if rv.is_err() {
+            // SAFETY: the table collection is initialized and
+            // num_rows is not greater than the number of rows
+            let code = unsafe {
+                ll_bindings::tsk_migration_table_truncate(
+                    &mut (*self.as_mut_ptr()).migrations,
+                    num_rows.into(),
+                )
+            };
+            if code < 0 {
+                return Err(TskitError::ErrorCode { code });
+            }
+        }
+        rv
+    }
+
     /// Add a row with optional metadata to the migration table
     ///
     /// # Examples
@@ -674,6 +888,9 @@ impl TableCollection {
     /// Build the "input" and "output"
     /// indexes for the edge table.
     ///
+    /// The indexes are not updated when the tables are edited.
+    /// See [`TableCollection::index_is_valid`].
+    ///
     /// # Note
     ///
     /// The `C API` call behind this takes a `flags` argument
@@ -684,11 +901,210 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Set the "input" and "output" indexes for the edge table
+    /// from precomputed values.
+    ///
+    /// This avoids the cost of [`TableCollection::build_index`]
+    /// when valid indexes are already known, for example
+    /// when they are carried over from a copy of the tables
+    /// whose edge table has not changed.
+    /// Any existing indexes are replaced.
+    ///
+    /// # Parameters
+    ///
+    /// * `edge_insertion_order`: the edge insertion order.
+    /// * `edge_removal_order`: the edge removal order.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if either index is not a permutation
+    ///   of the ids of the edge table.
+    ///
+    /// # Note
+    ///
+    /// Only the above is validated.
+    /// Indexes that do not match the edge table are detected
+    /// when creating a [`TreeSequence`](crate::TreeSequence) or
+    /// by [`TableCollection::check_integrity`] with
+    /// [`TableIntegrityCheckFlags::CHECK_TREES`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for left in [0., 50.] {
+    ///     let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(left, left + 50., parent, child).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let insertion = tables.edge_insertion_order().unwrap().to_vec();
+    /// let removal = tables.edge_removal_order().unwrap().to_vec();
+    ///
+    /// // Editing node times does not change the edge indexes
+    /// let mut edited = tables.deepcopy().unwrap();
+    /// edited.rescale_time(2.0).unwrap();
+    /// edited.set_indexes(&insertion, &removal).unwrap();
+    /// assert!(edited.is_indexed());
+    /// assert_eq!(edited.edge_insertion_order(), Some(&insertion[..]));
+    ///
+    /// // Each edge must appear exactly once
+    /// assert!(edited.set_indexes(&insertion[..1], &removal).is_err());
+    /// assert!(edited.set_indexes(&[insertion[0], insertion[0]], &removal).is_err());
+    /// ```
+    pub fn set_indexes(
+        &mut self,
+        edge_insertion_order: &[EdgeId],
+        edge_removal_order: &[EdgeId],
+    ) -> TskReturnValue {
+        let num_edges = self.edges().num_rows().as_usize();
+        for order in [edge_insertion_order, edge_removal_order] {
+            if order.len() != num_edges {
+                return Err(TskitError::ValueError {
+                    got: format!("an index of length {}", order.len()),
+                    expected: format!("an index of length {num_edges}"),
+                });
+            }
+            let mut seen = vec![false; num_edges];
+            for &edge in order {
+                match edge.to_usize() {
+                    Some(e) if e < num_edges && !seen[e] => seen[e] = true,
+                    _ => {
+                        return Err(TskitError::ValueError {
+                            got: format!("edge {edge} in an index"),
+                            expected: "each edge id to appear once".to_string(),
+                        })
+                    }
+                }
+            }
+        }
+        // SAFETY: self pointer is not null and both
+        // inputs have one value per edge.
+        // The C API only reads from the inputs and
+        // the cast is sound b/c EdgeId is repr(transparent).
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_indexes(
+                self.as_mut_ptr(),
+                edge_insertion_order.as_ptr() as *mut ll_bindings::tsk_id_t,
+                edge_removal_order.as_ptr() as *mut ll_bindings::tsk_id_t,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
     /// Return `true` if tables are indexed.
+    ///
+    /// This only checks that indexes exist for the current number
+    /// of edges. Use [`TableCollection::index_is_valid`] to
+    /// also check that they match the tables.
     pub fn is_indexed(&self) -> bool {
         unsafe { ll_bindings::tsk_table_collection_has_index(self.as_ptr(), 0) }
     }
 
+    /// Return `true` if the tables are indexed and the indexes
+    /// still match the edge and node tables.
+    ///
+    /// Indexes become stale when edges or node times are edited
+    /// after [`TableCollection::build_index`].
+    /// Adding or removing edges is detected by
+    /// [`TableCollection::is_indexed`], but editing rows in place is not.
+    /// This function checks that the edge insertion and removal orders
+    /// sort the edges as [`TableCollection::build_index`] does,
+    /// which takes time linear in the number of edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// for time in [1.0, 2.0] {
+    ///     let parent = tables.add_node(0, time, -1, -1).unwrap();
+    ///     let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 100., parent, child).unwrap();
+    /// }
+    /// assert!(!tables.index_is_valid());
+    /// tables.build_index().unwrap();
+    /// assert!(tables.index_is_valid());
+    ///
+    /// // Editing node times reorders the edges...
+    /// tables.nodes_mut().time_slice_mut()[0] = 3.0.into();
+    /// assert!(tables.is_indexed());
+    /// assert!(!tables.index_is_valid());
+    /// let error = tables
+    ///     .deepcopy()
+    ///     .unwrap()
+    ///     .tree_sequence(tskit::TreeSequenceFlags::default());
+    /// assert!(matches!(error, Err(tskit::TskitError::StaleIndexes)));
+    ///
+    /// // ...so the tables must be sorted and indexed again
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// tables.build_index().unwrap();
+    /// assert!(tables.index_is_valid());
+    /// ```
+    pub fn index_is_valid(&self) -> bool {
+        let (insertion, removal) = match (self.edge_insertion_order(), self.edge_removal_order()) {
+            (Some(insertion), Some(removal)) => (insertion, removal),
+            _ => return false,
+        };
+        let edges = self.edges();
+        let (left, right) = (edges.left_slice(), edges.right_slice());
+        let (parent, child) = (edges.parent_slice(), edges.child_slice());
+        let time = self.nodes().time_slice();
+        if parent
+            .iter()
+            .any(|p| p.to_usize().map_or(true, |p| p >= time.len()))
+        {
+            return false;
+        }
+        let is_permutation = |order: &[EdgeId]| {
+            let mut seen = vec![false; left.len()];
+            order.iter().all(|e| match e.to_usize() {
+                Some(e) if e < seen.len() && !seen[e] => {
+                    seen[e] = true;
+                    true
+                }
+                _ => false,
+            })
+        };
+        if !is_permutation(insertion) || !is_permutation(removal) {
+            return false;
+        }
+        // The keys by which build_index sorts the edges
+        let insertion_key = |e: &EdgeId| {
+            let e = e.as_usize();
+            let p = parent[e];
+            (
+                f64::from(left[e]),
+                f64::from(time[p.as_usize()]),
+                p,
+                child[e],
+            )
+        };
+        let removal_key = |e: &EdgeId| {
+            let e = e.as_usize();
+            let p = parent[e];
+            (
+                f64::from(right[e]),
+                -f64::from(time[p.as_usize()]),
+                -i32::from(p),
+                -i32::from(child[e]),
+            )
+        };
+        insertion
+            .windows(2)
+            .all(|w| insertion_key(&w[0]) <= insertion_key(&w[1]))
+            && removal
+                .windows(2)
+                .all(|w| removal_key(&w[0]) <= removal_key(&w[1]))
+    }
+
+    // `true` if indexes were built but no longer match the tables.
+    // Linear in the number of edges, as it calls index_is_valid.
+    pub(crate) fn has_stale_index(&self) -> bool {
+        // SAFETY: self pointer is not null
+        let built = unsafe { !(*self.as_ptr()).indexes.edge_insertion_order.is_null() };
+        built && !self.index_is_valid()
+    }
+
     /// If `self.is_indexed()` is `true`, return a non-owning
     /// slice containing the edge insertion order.
     /// Otherwise, return `None`.
@@ -775,6 +1191,8 @@ impl TableCollection {
     /// This function is needed because neither [``sort``](crate::TableCollection::sort) nor
     /// [``full_sort``](crate::TableCollection::full_sort) sorts
     /// the individual table!
+    /// To sort a standalone table, see
+    /// [`IndividualTable::topological_sort`](crate::IndividualTable::topological_sort).
     ///
     /// # Examples
     ///
@@ -824,6 +1242,324 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Subset the tables in place, keeping the nodes in `nodes`.
+    ///
+    /// Nodes are kept in the order of `nodes`.
+    /// See [`TableCollection::subset_keep_node_order`] to keep
+    /// them in the order of their ids.
+    /// Individuals and populations referred to by kept nodes are kept,
+    /// as are edges between kept nodes and the mutations of kept nodes,
+    /// with their sites.
+    /// Individuals keep their order and populations are ordered as first
+    /// seen in the kept nodes.
+    /// Unlike simplification, ancestors of the kept nodes are not kept.
+    /// See [`SubsetOptions`](crate::SubsetOptions) for other options.
+    ///
+    /// The tables need not be sorted, and the result is not
+    /// sorted unless the nodes keep their order.
+    ///
+    /// # Returns
+    ///
+    /// The maps from input ids to output ids of nodes,
+    /// individuals, and populations.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if a node is out of range.
+    /// * [`TskitError::ValueError`] if `nodes` contains duplicates.
+    /// * [`TskitError::ErrorCode`] if the tables have migrations or
+    ///   do not pass integrity checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop0 = tables.add_population().unwrap();
+    /// let pop1 = tables.add_population().unwrap();
+    /// let i0 = tables.add_individual(0, None, None).unwrap();
+    /// let i1 = tables.add_individual(0, None, None).unwrap();
+    /// let parent = tables.add_node(0, 1.0, pop0, i0).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop1, i1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop1, i1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    ///
+    /// let maps = tables
+    ///     .subset(&[c1, parent], tskit::SubsetOptions::default())
+    ///     .unwrap();
+    /// assert_eq!(maps.nodes, [1.into(), tskit::NodeId::NULL, 0.into()]);
+    /// assert_eq!(maps.individuals, [0, 1].map(tskit::IndividualId::from));
+    /// // pop1 is first seen
+    /// assert_eq!(maps.populations, [1, 0].map(tskit::PopulationId::from));
+    /// assert_eq!(tables.edges().num_rows(), 1);
+    /// assert_eq!(tables.edges().parent(0), Some(maps.nodes[parent.as_usize()]));
+    /// ```
+    pub fn subset<O: Into<crate::SubsetOptions>>(
+        &mut self,
+        nodes: &[NodeId],
+        options: O,
+    ) -> Result<crate::SubsetIdMaps, TskitError> {
+        crate::subset::subset(self, nodes, options.into())
+    }
+
+    /// Subset the tables in place, keeping the nodes in `nodes`
+    /// in the order of their ids in the input tables.
+    ///
+    /// Otherwise the same as [`TableCollection::subset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    ///
+    /// let maps = tables
+    ///     .subset_keep_node_order(&[c1, parent], tskit::SubsetOptions::default())
+    ///     .unwrap();
+    /// assert_eq!(maps.nodes, [0.into(), tskit::NodeId::NULL, 1.into()]);
+    /// ```
+    pub fn subset_keep_node_order<O: Into<crate::SubsetOptions>>(
+        &mut self,
+        nodes: &[NodeId],
+        options: O,
+    ) -> Result<crate::SubsetIdMaps, TskitError> {
+        let mut nodes = nodes.to_vec();
+        nodes.sort();
+        crate::subset::subset(self, &nodes, options.into())
+    }
+
+    /// Put the tables into canonical form.
+    ///
+    /// Tables in canonical form are sorted, with individuals and
+    /// mutations in canonical order, so that tables that differ only
+    /// in the order of their rows are equal after canonicalising.
+    /// Node ids do not change.
+    /// Individuals and populations that are not referred to by nodes
+    /// are removed, unless `options` contains
+    /// [`KEEP_UNREFERENCED`](crate::SubsetOptions::KEEP_UNREFERENCED).
+    ///
+    /// # Returns
+    ///
+    /// The maps from input ids to output ids of nodes,
+    /// individuals, and populations.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `options` contains
+    ///   options other than `KEEP_UNREFERENCED`.
+    /// * [`TskitError::ErrorCode`] if the tables have migrations or
+    ///   do not pass integrity checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let unreferenced = tables.add_population().unwrap();
+    /// let pop = tables.add_population().unwrap();
+    /// let parent = tables.add_node(0, 1.0, pop, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, child).unwrap();
+    ///
+    /// let mut other = tables.deepcopy().unwrap();
+    /// let maps = tables.canonicalise(tskit::SubsetOptions::default()).unwrap();
+    /// assert_eq!(maps.nodes, [parent, child]);
+    /// assert!(maps.populations[unreferenced.as_usize()].is_null());
+    /// assert_eq!(maps.populations[pop.as_usize()], 0);
+    ///
+    /// let maps = other
+    ///     .canonicalise(tskit::SubsetOptions::default().keep_unreferenced())
+    ///     .unwrap();
+    /// assert_eq!(maps.populations, [1, 0].map(tskit::PopulationId::from));
+    /// ```
+    pub fn canonicalise<O: Into<crate::SubsetOptions>>(
+        &mut self,
+        options: O,
+    ) -> Result<crate::SubsetIdMaps, TskitError> {
+        crate::subset::canonicalise(self, options.into())
+    }
+
+    /// Add the nodes of `other` that are not shared with `self`,
+    /// along with the edges, mutations, and sites that refer to them.
+    ///
+    /// `other_node_mapping` has one entry per node of `other`,
+    /// which is the id of the same node in `self`, or
+    /// [`NodeId::NULL`] if the node is new.
+    /// Individuals and populations of new nodes are added.
+    /// If `options` contains
+    /// [`NO_ADD_POP`](crate::TableUnionOptions::NO_ADD_POP),
+    /// new nodes instead keep their population ids, which must
+    /// refer to populations of `self`.
+    /// Provenance records are not added to `self`; see
+    /// [`TableCollection::add_provenance`].
+    ///
+    /// Afterwards, the tables are sorted and indexed, sites
+    /// at the same position are merged, and the parents of
+    /// mutations are computed.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the length of `other_node_mapping`
+    ///   is not the number of nodes of `other`.
+    /// * [`TskitError::ErrorCode`] if either table collection fails
+    ///   integrity checks or has migrations, if a node id is out of range,
+    ///   or if the shared nodes differ between the table collections, unless
+    ///   `options` contains
+    ///   [`NO_CHECK_SHARED`](crate::TableUnionOptions::NO_CHECK_SHARED).
+    ///
+    /// If sorting the new tables fails, for example because new nodes
+    /// refer to populations that do not exist, `self` is left with
+    /// the new rows added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    ///
+    /// // other has a second child of the same parent
+    /// let mut other = tables.deepcopy().unwrap();
+    /// let c1 = other.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// other.add_edge(0., 100., parent, c1).unwrap();
+    ///
+    /// let mapping = [parent, c0, tskit::NodeId::NULL];
+    /// tables.union(&other, &mapping, tskit::TableUnionOptions::default()).unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 3);
+    /// assert_eq!(tables.edges().num_rows(), 2);
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// assert_eq!(ts.num_samples(), 2);
+    /// ```
+    pub fn union<O: Into<crate::TableUnionOptions>>(
+        &mut self,
+        other: &TableCollection,
+        other_node_mapping: &[NodeId],
+        options: O,
+    ) -> TskReturnValue {
+        if other_node_mapping.len() != other.nodes().num_rows().as_usize() {
+            return Err(TskitError::ValueError {
+                got: format!("{} node mappings", other_node_mapping.len()),
+                expected: format!("{} node mappings", other.nodes().num_rows()),
+            });
+        }
+        // SAFETY: neither table collection can be created with
+        // null pointers, and the mapping has one entry per node of other.
+        // The cast is sound b/c NodeId is repr(transparent).
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_union(
+                self.as_mut_ptr(),
+                other.as_ptr(),
+                other_node_mapping.as_ptr().cast::<ll_bindings::tsk_id_t>(),
+                options.into().bits(),
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Remove metadata and metadata schemas.
+    ///
+    /// Use this function to share tables without the information
+    /// stored in their metadata, such as the names of samples.
+    /// `options` selects the tables whose metadata are removed,
+    /// and whether the metadata of the table collection itself
+    /// are removed.
+    /// Rows are kept, with empty metadata.
+    /// Provenance records are not changed.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the C library fails to clear a schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::StripMetadataOptions;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// # #[cfg(feature = "derive")] {
+    /// # #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::PopulationMetadata)]
+    /// # #[serializer("serde_json")]
+    /// # struct Name(String);
+    /// let pop = tables.add_population_with_metadata(&Name("Villabajo".to_string())).unwrap();
+    /// assert!(tables.populations().metadata::<Name>(pop).is_some());
+    /// tables.strip_metadata(StripMetadataOptions::default().populations()).unwrap();
+    /// assert_eq!(tables.populations().num_rows(), 1);
+    /// assert!(tables.populations().metadata::<Name>(pop).is_none());
+    /// # }
+    ///
+    /// // Remove all metadata
+    /// tables.strip_metadata(StripMetadataOptions::all()).unwrap();
+    /// ```
+    pub fn strip_metadata<O: Into<crate::StripMetadataOptions>>(
+        &mut self,
+        options: O,
+    ) -> TskReturnValue {
+        // SAFETY: the table collection is initialized
+        crate::strip_metadata::strip_metadata(unsafe { &mut *self.as_mut_ptr() }, options.into())
+    }
+
+    /// Increase the times of nodes and mutations that are slightly
+    /// out of order.
+    ///
+    /// Times written with limited precision, for example to text files,
+    /// may leave a parent no older than its child.
+    /// This function makes the smallest increases to the times of nodes
+    /// so that each parent is older than its children and the mutations
+    /// above them, and increases the times of mutations so that they are
+    /// no younger than their nodes and child mutations.
+    /// Unknown mutation times are not changed.
+    ///
+    /// Changing the times of nodes may change the sorted order of edges,
+    /// so the tables may need to be sorted and indexed again.
+    ///
+    /// # Parameters
+    ///
+    /// * `tolerance`: the largest allowed increase of a time.
+    ///
+    /// # Returns
+    ///
+    /// Each change made, in the order made.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if a change larger than `tolerance`
+    ///   is needed, if `tolerance` is negative, or if a node is its own
+    ///   ancestor. The tables are not changed.
+    /// * [`TskitError::IndexError`] if an edge or mutation refers to an
+    ///   invalid node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let sample = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, child).unwrap();
+    /// tables.add_edge(0., 100., child, sample).unwrap();
+    /// let site = tables.add_site(50., None).unwrap();
+    /// tables.add_mutation(site, child, -1, 1.0 - 1e-12, None).unwrap();
+    ///
+    /// assert!(tables.repair_times(0.0).is_err());
+    /// let adjustments = tables.repair_times(1e-9).unwrap();
+    /// assert_eq!(adjustments.len(), 2);
+    /// assert!(tables.nodes().time(parent).unwrap() > 1.0);
+    /// assert_eq!(tables.mutations().time(0).unwrap(), 1.0);
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// tables.build_index().unwrap();
+    /// assert!(tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_TREES).is_ok());
+    /// ```
+    pub fn repair_times<T: Into<Time>>(
+        &mut self,
+        tolerance: T,
+    ) -> Result<Vec<crate::TimeAdjustment>, TskitError> {
+        crate::repair::repair_times(self, tolerance.into().into())
+    }
+
     /// Dump the table collection to file.
     ///
     /// # Panics
@@ -845,6 +1581,72 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Dump the table collection to a file whose bytes only
+    /// depend on the contents of the tables, so that files may
+    /// be compared or stored by their hashes.
+    ///
+    /// The file uuid, which is otherwise random, is set to
+    /// `00000000-0000-0000-0000-000000000000`, and floating-point
+    /// `NaN` values are written as [`f64::NAN`], except for
+    /// unknown mutation times, which keep their own value.
+    /// The tables themselves are not changed.
+    ///
+    /// The file is first written next to `filename` and then
+    /// renamed, so that `filename` is not left partially written.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the file cannot be written, or if
+    ///   rows are sorted by [`CanonicalDumpOptions::sort_rows`](crate::CanonicalDumpOptions::sort_rows)
+    ///   and the tables cannot be canonicalised.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// let dir = std::env::temp_dir();
+    /// let file1 = dir.join("tskit_dump_canonical1.trees");
+    /// let file2 = dir.join("tskit_dump_canonical2.trees");
+    /// let options = tskit::CanonicalDumpOptions::default();
+    /// tables.dump_canonical(file1.to_str().unwrap(), options).unwrap();
+    /// tables.dump_canonical(file2.to_str().unwrap(), options).unwrap();
+    /// assert_eq!(std::fs::read(&file1).unwrap(), std::fs::read(&file2).unwrap());
+    /// # std::fs::remove_file(file1).unwrap();
+    /// # std::fs::remove_file(file2).unwrap();
+    /// ```
+    pub fn dump_canonical(
+        &self,
+        filename: &str,
+        options: crate::CanonicalDumpOptions,
+    ) -> TskReturnValue {
+        crate::canonical::dump(self, filename, options)
+    }
+
+    /// Dump the table collection to file, adding extra kastore arrays.
+    ///
+    /// Use this function to preserve the arrays that other tools
+    /// add to the files they write.
+    /// See [`KastoreExtras`](crate::KastoreExtras) for an example.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the tables or the arrays cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// This function allocates a `CString` to pass the file name to the C API.
+    /// A panic will occur if the system runs out of memory.
+    pub fn dump_with_extras<O: Into<TableOutputOptions>>(
+        &self,
+        filename: &str,
+        options: O,
+        extras: &crate::KastoreExtras,
+    ) -> TskReturnValue {
+        self.dump(filename, options)?;
+        extras.append_to_file(filename)
+    }
+
     /// Clear the contents of all tables.
     /// Does not release memory.
     /// Memory will be released when the object goes out
@@ -882,12 +1684,76 @@ impl TableCollection {
         }
     }
 
-    /// Return a "deep" copy of the tables.
-    pub fn deepcopy(&self) -> Result<TableCollection, TskitError> {
-        let (rv, inner) = self.inner.copy();
-        let tables = TableCollection::new_from_ll(inner)?;
-        handle_tsk_return_value!(rv, tables)
-    }
+    /// Return a SHA-256 digest of the contents of the tables.
+    ///
+    /// Tables that are [equal](TableCollection::equals) under `options`
+    /// have the same digest, so `options` chooses whether metadata
+    /// and provenance contribute to the digest.
+    /// Table indexes do not contribute to the digest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableEqualityOptions;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// let copy = tables.deepcopy().unwrap();
+    /// let options = TableEqualityOptions::default();
+    /// assert_eq!(tables.digest(options), copy.digest(options));
+    /// // Digests are written in hexadecimal
+    /// assert_eq!(tables.digest(options).to_string().len(), 64);
+    ///
+    /// # #[cfg(feature = "provenance")] {
+    /// tables.add_provenance("a provenance record").unwrap();
+    /// assert_ne!(tables.digest(options), copy.digest(options));
+    /// let options = options.ignore_provenance();
+    /// assert_eq!(tables.digest(options), copy.digest(options));
+    /// # }
+    /// ```
+    pub fn digest<O: Into<TableEqualityOptions>>(
+        &self,
+        options: O,
+    ) -> crate::TableCollectionDigest {
+        // SAFETY: self.as_ptr() is not null and the tables are initialized
+        crate::digest::digest(unsafe { &*self.as_ptr() }, options.into())
+    }
+
+    /// Return the number of bytes used by each table.
+    ///
+    /// See [`TableMemoryUsage`](crate::TableMemoryUsage) for how
+    /// the sizes are calculated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for _ in 0..10 {
+    ///     let child = tables.add_node(0, 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 100., parent, child).unwrap();
+    /// }
+    /// let usage = tables.memory_usage();
+    /// // 11 rows of flags, time, population, and individual,
+    /// // and 12 metadata offsets
+    /// assert_eq!(usage.nodes, 11 * 20 + 12 * 8);
+    /// assert_eq!(usage.largest().0, "edges");
+    /// assert_eq!(usage.indexes, 0);
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// tables.build_index().unwrap();
+    /// assert_eq!(tables.memory_usage().indexes, 10 * 8);
+    /// ```
+    pub fn memory_usage(&self) -> crate::TableMemoryUsage {
+        // SAFETY: self pointer is not null
+        crate::memory_usage::memory_usage(unsafe { &*self.as_ptr() })
+    }
+
+    /// Return a "deep" copy of the tables.
+    pub fn deepcopy(&self) -> Result<TableCollection, TskitError> {
+        let (rv, inner) = self.inner.copy();
+        let tables = TableCollection::new_from_ll(inner)?;
+        handle_tsk_return_value!(rv, tables)
+    }
 
     /// Return a [`crate::TreeSequence`] based on the tables.
     /// This function will raise errors if tables are not sorted,
@@ -912,13 +1778,116 @@ impl TableCollection {
     ///   in length to the input node table.  For each input node,
     ///   this vector either contains the node's new index or [`NodeId::NULL`]
     ///   if the input node is not part of the simplified history.
+    ///
+    /// # Migrations
+    ///
+    /// `tskit` cannot simplify tables with migrations, so this
+    /// function removes the migrations before simplifying.
+    /// The migrations of retained nodes are then restored,
+    /// in their original order, with their node ids updated.
+    /// Populations referenced by restored migrations are not removed by
+    /// [`FILTER_POPULATIONS`](crate::SimplificationOptions::FILTER_POPULATIONS).
+    /// If simplification fails, all of the migrations are restored.
+    ///
+    /// See [`TableCollection::simplify_clear_migrations`] to remove
+    /// all migrations instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop0 = tables.add_population().unwrap();
+    /// let pop1 = tables.add_population().unwrap();
+    /// let parent = tables.add_node(0, 2.0, pop1, -1).unwrap();
+    /// let dead_end = tables.add_node(0, 1.0, pop1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop0, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop0, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// tables.add_migration((0., 100.), c0, (pop0, pop1), 0.5).unwrap();
+    /// tables.add_migration((0., 100.), dead_end, (pop0, pop1), 1.5).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    ///
+    /// let options = tskit::SimplificationOptions::default().filter_populations();
+    /// let mut simplified = tables.deepcopy().unwrap();
+    /// let idmap = simplified.simplify(&[c0, c1], options, true).unwrap().unwrap().to_vec();
+    /// assert!(idmap[dead_end.to_usize().unwrap()].is_null());
+    /// assert_eq!(simplified.migrations().num_rows(), 1);
+    /// assert_eq!(simplified.migrations().node(0), Some(idmap[c0.to_usize().unwrap()]));
+    /// // Population 1 is referenced by the migration
+    /// assert_eq!(simplified.populations().num_rows(), 2);
+    /// ```
     pub fn simplify<O: Into<SimplificationOptions>>(
         &mut self,
         samples: &[NodeId],
         options: O,
         idmap: bool,
     ) -> Result<Option<&[NodeId]>, TskitError> {
-        if idmap {
+        self.simplify_impl(samples, options.into(), idmap, false)
+    }
+
+    /// Simplify tables in place, removing all migrations.
+    ///
+    /// The parameters, return value, and errors are the same as for
+    /// [`TableCollection::simplify`].
+    /// If simplification fails, the migrations are restored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop0 = tables.add_population().unwrap();
+    /// let pop1 = tables.add_population().unwrap();
+    /// let parent = tables.add_node(0, 2.0, pop1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop0, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop0, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// tables.add_migration((0., 100.), c0, (pop0, pop1), 0.5).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    ///
+    /// let options = tskit::SimplificationOptions::default();
+    /// tables.simplify_clear_migrations(&[c0, c1], options, false).unwrap();
+    /// assert_eq!(tables.migrations().num_rows(), 0);
+    /// ```
+    pub fn simplify_clear_migrations<O: Into<SimplificationOptions>>(
+        &mut self,
+        samples: &[NodeId],
+        options: O,
+        idmap: bool,
+    ) -> Result<Option<&[NodeId]>, TskitError> {
+        self.simplify_impl(samples, options.into(), idmap, true)
+    }
+
+    fn simplify_impl(
+        &mut self,
+        samples: &[NodeId],
+        options: SimplificationOptions,
+        idmap: bool,
+        clear_migrations: bool,
+    ) -> Result<Option<&[NodeId]>, TskitError> {
+        // Kept until simplification succeeds, so that they can be
+        // restored if it fails.
+        let migrations = if self.views.migrations().num_rows() > 0 {
+            let rows = self.views.migrations().iter().collect::<Vec<_>>();
+            // SAFETY: the table collection is initialized
+            let rv = unsafe { ll_bindings::tsk_migration_table_clear(self.inner.migrations_mut()) };
+            if rv < 0 {
+                return Err(TskitError::ErrorCode { code: rv });
+            }
+            Some(rows)
+        } else {
+            None
+        };
+        let restore = migrations.is_some() && !clear_migrations;
+        let mut tskit_options = options;
+        if restore {
+            // Populations are filtered below, so that those
+            // referenced by migrations are kept.
+            tskit_options.remove(SimplificationOptions::FILTER_POPULATIONS);
+        }
+        let need_idmap = idmap || restore;
+        if need_idmap {
             self.idmap.resize(
                 usize::try_from(self.views.nodes().num_rows())?,
                 NodeId::NULL,
@@ -929,20 +1898,182 @@ impl TableCollection {
                 self.as_mut_ptr(),
                 samples.as_ptr().cast::<tsk_id_t>(),
                 samples.len() as tsk_size_t,
-                options.into().bits(),
-                match idmap {
+                tskit_options.bits(),
+                match need_idmap {
                     true => self.idmap.as_mut_ptr().cast::<tsk_id_t>(),
                     false => std::ptr::null_mut(),
                 },
             )
         };
-        handle_tsk_return_value!(
-            rv,
-            match idmap {
-                true => Some(&self.idmap),
-                false => None,
+        if rv < 0 {
+            if let Some(rows) = migrations {
+                let mut table = migration_table_from_rows(rows, |p| p)?;
+                self.set_migrations(&MigrationTable::new_from_table(table.as_mut())?)?;
             }
-        )
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        if let Some(rows) = migrations.filter(|_| restore) {
+            self.restore_migrations(rows, options)?;
+        }
+        Ok(match idmap {
+            true => Some(&self.idmap),
+            false => None,
+        })
+    }
+
+    /// Simplify tables in place, keeping the nodes chosen by a predicate.
+    ///
+    /// The tables are simplified with respect to `samples` and to every
+    /// node for which `keep` returns `true`, such as nodes whose metadata
+    /// marks them as "remembered".
+    /// Kept nodes that are not in `samples` follow them in order of
+    /// node id, and, like all nodes passed to simplification, are
+    /// samples in the output.
+    ///
+    /// Unlike [`TableCollection::simplify`], the node id map is always
+    /// returned, so that ids stored outside of the tables can be updated.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples` and `options`: see [`TableCollection::simplify`].
+    /// * `keep`: a predicate over the rows of the node table.
+    ///
+    /// # Errors
+    ///
+    /// See [`TableCollection::simplify`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let remembered = b"remembered";
+    /// let grandparent = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let parent = tables
+    ///     .add_node_with_metadata(0, 1.0, -1, -1, &Remembered)
+    ///     .unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, child).unwrap();
+    /// tables.add_edge(0., 100., grandparent, parent).unwrap();
+    ///
+    /// // Node ids stored outside of the tables
+    /// let mut labels = vec![(parent, "parent"), (child, "child")];
+    /// let idmap = tables
+    ///     .simplify_keeping(&[child], tskit::SimplificationOptions::default(), |row| {
+    ///         row.metadata.as_deref() == Some(&remembered[..])
+    ///     })
+    ///     .unwrap();
+    /// for (node, _) in labels.iter_mut() {
+    ///     *node = idmap[node.as_usize()];
+    /// }
+    /// assert!(idmap[grandparent.as_usize()].is_null());
+    /// assert_eq!(tables.nodes().num_rows(), 2);
+    /// assert!(tables.nodes().flags(labels[0].0).unwrap().is_sample());
+    ///
+    /// # struct Remembered;
+    /// # impl tskit::metadata::MetadataRoundtrip for Remembered {
+    /// #     fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+    /// #         Ok(b"remembered".to_vec())
+    /// #     }
+    /// #     fn decode(_: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+    /// #         Ok(Self)
+    /// #     }
+    /// # }
+    /// # impl tskit::metadata::NodeMetadata for Remembered {}
+    /// ```
+    pub fn simplify_keeping<O, F>(
+        &mut self,
+        samples: &[NodeId],
+        options: O,
+        mut keep: F,
+    ) -> Result<&[NodeId], TskitError>
+    where
+        O: Into<SimplificationOptions>,
+        F: FnMut(&crate::NodeTableRow) -> bool,
+    {
+        let mut is_sample = vec![false; usize::try_from(self.nodes().num_rows())?];
+        for s in samples {
+            if let Some(flag) = s.to_usize().and_then(|u| is_sample.get_mut(u)) {
+                *flag = true;
+            }
+        }
+        let mut nodes = samples.to_vec();
+        nodes.extend(
+            self.nodes()
+                .iter()
+                .filter(|row| !is_sample[row.id.as_usize()] && keep(row))
+                .map(|row| row.id),
+        );
+        let idmap = self.simplify(&nodes, options, true)?;
+        Ok(idmap.unwrap_or_default())
+    }
+
+    // Add back the migrations of the nodes retained by simplification,
+    // and then filter populations if requested.
+    fn restore_migrations(
+        &mut self,
+        rows: Vec<crate::MigrationTableRow>,
+        options: SimplificationOptions,
+    ) -> Result<(), TskitError> {
+        let rows = rows
+            .into_iter()
+            .filter_map(|mut row| {
+                row.node = self.idmap[row.node.to_usize()?];
+                (!row.node.is_null()).then_some(row)
+            })
+            .collect::<Vec<_>>();
+
+        let num_populations = usize::try_from(self.views.populations().num_rows())?;
+        let mut population_map = (0..num_populations)
+            .map(|p| PopulationId::from(p as tsk_id_t))
+            .collect::<Vec<_>>();
+        if options.contains(SimplificationOptions::FILTER_POPULATIONS) {
+            let mut referenced = vec![false; num_populations];
+            for p in self
+                .views
+                .nodes()
+                .population_slice()
+                .iter()
+                .chain(rows.iter().flat_map(|row| [&row.source, &row.dest]))
+            {
+                if let Some(p) = p.to_usize().filter(|&p| p < num_populations) {
+                    referenced[p] = true;
+                }
+            }
+            let mut new_populations = crate::sys::PopulationTable::new(0)?;
+            let mut next = 0;
+            for (p, population) in self.views.populations().iter().enumerate() {
+                if referenced[p] {
+                    new_populations
+                        .add_row_with_metadata(population.metadata.as_deref().unwrap_or(&[]))?;
+                    population_map[p] = next.into();
+                    next += 1;
+                } else {
+                    population_map[p] = PopulationId::NULL;
+                }
+            }
+            let remap = |p: PopulationId| p.to_usize().map_or(p, |p| population_map[p]);
+
+            let mut new_nodes = crate::sys::NodeTable::new(0)?;
+            for node in self.views.nodes().iter() {
+                new_nodes.add_row_with_metadata(
+                    node.flags,
+                    node.time,
+                    remap(node.population),
+                    node.individual,
+                    node.metadata.as_deref().unwrap_or(&[]),
+                )?;
+            }
+            let new_populations = crate::PopulationTable::new_from_table(new_populations.as_mut())?;
+            let new_nodes = crate::NodeTable::new_from_table(new_nodes.as_mut())?;
+            self.set_populations(&new_populations)?;
+            self.set_nodes(&new_nodes)?;
+        }
+
+        let remap = |p: PopulationId| p.to_usize().map_or(p, |p| population_map[p]);
+        let mut new_migrations = migration_table_from_rows(rows, remap)?;
+        let new_migrations = MigrationTable::new_from_table(new_migrations.as_mut())?;
+        self.set_migrations(&new_migrations)?;
+        Ok(())
     }
 
     /// Validate the contents of the table collection
@@ -996,6 +2127,342 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Multiply all node, mutation, and migration times by `factor`.
+    ///
+    /// Unknown mutation times are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `factor` is not finite and positive.
+    /// * [`TskitError`] if the rescaled tables fail
+    ///   [`TableCollection::check_integrity`], for example because
+    ///   rounding made a parent node no older than its child.
+    ///   In this case, the times are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let c = tables.add_node(0, 0.5, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c).unwrap();
+    /// let site = tables.add_site(50., None).unwrap();
+    /// tables.add_mutation(site, c, -1, 1.0, None).unwrap();
+    /// tables.rescale_time(4.0).unwrap();
+    /// assert_eq!(tables.nodes().time(p).unwrap(), 8.0);
+    /// assert_eq!(tables.nodes().time(c).unwrap(), 2.0);
+    /// assert_eq!(tables.mutations().time(0).unwrap(), 4.0);
+    /// assert!(tables.rescale_time(0.0).is_err());
+    /// ```
+    pub fn rescale_time(&mut self, factor: f64) -> Result<(), TskitError> {
+        if !(factor.is_finite() && factor > 0.0) {
+            return Err(TskitError::ValueError {
+                got: format!("{factor}"),
+                expected: "a finite and positive scaling factor".to_string(),
+            });
+        }
+        let original = self.time_columns_mut().map(|c| c.to_vec());
+        for column in self.time_columns_mut() {
+            for t in column.iter_mut() {
+                // SAFETY: a pure function of its input
+                if !unsafe { ll_bindings::tsk_is_unknown_time(*t) } {
+                    *t *= factor;
+                }
+            }
+        }
+        if let Err(e) = self.check_integrity(TableIntegrityCheckFlags::default()) {
+            for (column, original) in self.time_columns_mut().into_iter().zip(original) {
+                column.copy_from_slice(&original);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Convert times from generations to years.
+    ///
+    /// See [`TableCollection::rescale_time`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `generation_time`: the number of years per generation.
+    ///
+    /// # Errors
+    ///
+    /// See [`TableCollection::rescale_time`].
+    pub fn generations_to_years(&mut self, generation_time: f64) -> Result<(), TskitError> {
+        self.rescale_time(generation_time)
+    }
+
+    /// Convert times from years to generations.
+    ///
+    /// See [`TableCollection::rescale_time`] for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `generation_time`: the number of years per generation.
+    ///
+    /// # Errors
+    ///
+    /// See [`TableCollection::rescale_time`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 50.0, -1, -1).unwrap();
+    /// tables.years_to_generations(25.0).unwrap();
+    /// assert_eq!(tables.nodes().time(0).unwrap(), 2.0);
+    /// tables.generations_to_years(25.0).unwrap();
+    /// assert_eq!(tables.nodes().time(0).unwrap(), 50.0);
+    /// ```
+    pub fn years_to_generations(&mut self, generation_time: f64) -> Result<(), TskitError> {
+        if !(generation_time.is_finite() && generation_time > 0.0) {
+            return Err(TskitError::ValueError {
+                got: format!("{generation_time}"),
+                expected: "a finite and positive generation time".to_string(),
+            });
+        }
+        self.rescale_time(1.0 / generation_time)
+    }
+
+    /// Snap node, mutation, and migration times to the nearest
+    /// time of a grid, such as a grid of whole generations.
+    ///
+    /// Times halfway between two grid times are snapped to the
+    /// younger one.
+    /// Snapping may make a parent node no older than its child.
+    /// Such parents are moved to the youngest grid time that is
+    /// older than all of their children.
+    /// Mutation times are then kept no younger than the time of their
+    /// node and no older than the time of their parent mutation.
+    /// Unknown mutation times are left unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `grid`: the grid times, which must be finite and
+    ///   strictly increasing.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `grid` is empty or not finite
+    ///   and strictly increasing, or if a parent node cannot be made
+    ///   older than its children because the grid has no older time.
+    /// * [`TskitError`] if the snapped tables fail
+    ///   [`TableCollection::check_integrity`].
+    ///
+    /// In case of error, the times are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.4, -1, -1).unwrap();
+    /// let c = tables.add_node(0, 0.6, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c).unwrap();
+    /// let site = tables.add_site(50., None).unwrap();
+    /// tables.add_mutation(site, c, -1, 0.7, None).unwrap();
+    ///
+    /// tables.discretize_times([0.0, 1.0, 2.0, 3.0]).unwrap();
+    /// // Both nodes snap to 1.0, so the parent moves to 2.0
+    /// assert_eq!(tables.nodes().time(p).unwrap(), 2.0);
+    /// assert_eq!(tables.nodes().time(c).unwrap(), 1.0);
+    /// assert_eq!(tables.mutations().time(0).unwrap(), 1.0);
+    ///
+    /// assert!(tables.discretize_times([0.0, 1.0]).is_err());
+    /// assert!(tables.discretize_times([1.0, 0.0]).is_err());
+    /// ```
+    pub fn discretize_times<G>(&mut self, grid: G) -> Result<(), TskitError>
+    where
+        G: IntoIterator,
+        G::Item: Into<Time>,
+    {
+        let grid = grid
+            .into_iter()
+            .map(|t| f64::from(t.into()))
+            .collect::<Vec<_>>();
+        if grid.is_empty()
+            || grid.iter().any(|t| !t.is_finite())
+            || grid.windows(2).any(|w| w[1] <= w[0])
+        {
+            return Err(TskitError::ValueError {
+                got: format!("{grid:?}"),
+                expected: "a non-empty grid of finite and strictly increasing times".to_string(),
+            });
+        }
+        let nearest = |t: f64| {
+            let i = grid.partition_point(|&g| g < t);
+            if i == 0 {
+                grid[0]
+            } else if i == grid.len() || t - grid[i - 1] <= grid[i] - t {
+                grid[i - 1]
+            } else {
+                grid[i]
+            }
+        };
+
+        // Nodes are visited in order of time, so that children
+        // are snapped before their parents.
+        let node_time = self.nodes().time_slice_raw();
+        let mut order = (0..node_time.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| node_time[a].total_cmp(&node_time[b]));
+        let mut parent_edges = (0..self.edges().num_rows().as_usize()).collect::<Vec<_>>();
+        let edge_child = self.edges().child_slice();
+        let edge_parent = self.edges().parent_slice();
+        parent_edges.sort_by_key(|&e| edge_child[e]);
+        let mut snapped_nodes = vec![0.0; node_time.len()];
+        // The oldest snapped time of the children of each node
+        let mut oldest_child = vec![f64::NEG_INFINITY; node_time.len()];
+        for u in order {
+            let mut t = nearest(node_time[u]);
+            if t <= oldest_child[u] {
+                let i = grid.partition_point(|&g| g <= oldest_child[u]);
+                t = *grid.get(i).ok_or_else(|| TskitError::ValueError {
+                    got: format!("node {u} with a child at the oldest grid time"),
+                    expected: "a grid time older than the children of every node".to_string(),
+                })?;
+            }
+            snapped_nodes[u] = t;
+            let node = NodeId::from(u as tsk_id_t);
+            let start = parent_edges.partition_point(|&e| edge_child[e] < node);
+            for &e in parent_edges[start..]
+                .iter()
+                .take_while(|&&e| edge_child[e] == node)
+            {
+                if let Some(p) = edge_parent[e].to_usize() {
+                    oldest_child[p] = oldest_child[p].max(t);
+                }
+            }
+        }
+
+        // Parent mutations precede their children
+        let mutation_node = self.mutations().node_slice();
+        let mutation_parent = self.mutations().parent_slice();
+        let mut snapped_mutations = self.mutations().time_slice_raw().to_vec();
+        for m in 0..snapped_mutations.len() {
+            let t = snapped_mutations[m];
+            // SAFETY: a pure function of its input
+            if unsafe { ll_bindings::tsk_is_unknown_time(t) } {
+                continue;
+            }
+            let mut t = nearest(t);
+            if let Some(u) = mutation_node[m].to_usize() {
+                t = t.max(snapped_nodes[u]);
+            }
+            if let Some(p) = mutation_parent[m].to_usize() {
+                if p < m && !unsafe { ll_bindings::tsk_is_unknown_time(snapped_mutations[p]) } {
+                    t = t.min(snapped_mutations[p]);
+                }
+            }
+            snapped_mutations[m] = t;
+        }
+
+        let original = self.time_columns_mut().map(|c| c.to_vec());
+        let [nodes, mutations, migrations] = self.time_columns_mut();
+        nodes.copy_from_slice(&snapped_nodes);
+        mutations.copy_from_slice(&snapped_mutations);
+        for t in migrations.iter_mut() {
+            *t = nearest(*t);
+        }
+        if let Err(e) = self.check_integrity(TableIntegrityCheckFlags::default()) {
+            for (column, original) in self.time_columns_mut().into_iter().zip(original) {
+                column.copy_from_slice(&original);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Apply a monotonic transformation to all genomic coordinates.
+    ///
+    /// The transformation is applied to the left and right
+    /// coordinates of edges and migrations, to site positions,
+    /// and to the sequence length.
+    /// It must be strictly increasing over the coordinates
+    /// present in the tables and must map zero to a
+    /// non-negative value, so that the sorting order of all
+    /// tables and any edge indexes remain valid.
+    ///
+    /// A transformation can be undone by applying its inverse.
+    /// See [`CoordinateMap`](crate::CoordinateMap).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if the transformed coordinates are
+    ///   not finite, non-negative, and strictly increasing.
+    ///   In this case, the tables are not modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(200.).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// tables.add_edge(100., 200., 0, 1).unwrap();
+    /// tables.add_site(150., None).unwrap();
+    ///
+    /// let map = tskit::CoordinateMap::new([(0., 0.), (100., 1.), (200., 3.)]).unwrap();
+    /// tables.transform_coordinates(|x| map.forward(x)).unwrap();
+    /// assert_eq!(tables.sequence_length(), 3.);
+    /// assert_eq!(tables.edges().right(0).unwrap(), 1.);
+    /// assert_eq!(tables.sites().position(0).unwrap(), 2.);
+    ///
+    /// let inverse = map.inverse();
+    /// tables.transform_coordinates(|x| inverse.forward(x)).unwrap();
+    /// assert_eq!(tables.sequence_length(), 200.);
+    /// assert_eq!(tables.sites().position(0).unwrap(), 150.);
+    ///
+    /// // Reversing the coordinates is not monotonic
+    /// assert!(tables
+    ///     .transform_coordinates(|x| (200. - f64::from(x)).into())
+    ///     .is_err());
+    /// ```
+    pub fn transform_coordinates<F: Fn(Position) -> Position>(
+        &mut self,
+        f: F,
+    ) -> Result<(), TskitError> {
+        let mut coordinates = vec![Position::from(0.0), self.sequence_length()];
+        coordinates.extend_from_slice(self.edges().left_slice());
+        coordinates.extend_from_slice(self.edges().right_slice());
+        coordinates.extend_from_slice(self.migrations().left_slice());
+        coordinates.extend_from_slice(self.migrations().right_slice());
+        coordinates.extend_from_slice(self.sites().position_slice());
+        coordinates.sort_by(|a, b| f64::from(*a).total_cmp(&f64::from(*b)));
+        coordinates.dedup();
+        let transformed = coordinates
+            .iter()
+            .map(|x| f64::from(f(*x)))
+            .collect::<Vec<_>>();
+        if !(transformed[0].is_finite() && transformed[0] >= 0.0)
+            || transformed
+                .windows(2)
+                .any(|w| !(w[1].is_finite() && w[1] > w[0]))
+        {
+            return Err(TskitError::RangeError(
+                "transformed coordinates must be finite, non-negative, and strictly increasing"
+                    .to_string(),
+            ));
+        }
+        let lookup = |x: f64| {
+            let i = coordinates.partition_point(|c| f64::from(*c) < x);
+            transformed[i]
+        };
+        let sequence_length = lookup(self.sequence_length().into());
+        self.inner.map_coordinates(lookup, sequence_length);
+        Ok(())
+    }
+
+    // The node, mutation, and migration time columns
+    fn time_columns_mut(&mut self) -> [&mut [f64]; 3] {
+        let num_nodes = self.inner.nodes_mut().num_rows;
+        let num_mutations = self.inner.mutations_mut().num_rows;
+        let num_migrations = self.inner.migrations_mut().num_rows;
+        [
+            crate::sys::generate_slice_mut(self.inner.nodes_mut().time, num_nodes),
+            crate::sys::generate_slice_mut(self.inner.mutations_mut().time, num_mutations),
+            crate::sys::generate_slice_mut(self.inner.migrations_mut().time, num_migrations),
+        ]
+    }
+
     #[cfg(feature = "provenance")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "provenance")))]
     /// Add provenance record with a time stamp.
@@ -1205,6 +2672,148 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Delete sites and their mutations.
+    ///
+    /// The remaining sites and mutations keep their order
+    /// and are renumbered.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if any site id is out of range,
+    ///   or if a mutation refers to a site that does not exist.
+    /// * [`TskitError`] if the `C` back end returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    /// let s1 = tables.add_site(20., Some(b"A")).unwrap();
+    /// tables.add_mutation(s0, 0, -1, 1.0, Some(b"G")).unwrap();
+    /// tables.add_mutation(s1, 0, -1, 1.0, Some(b"C")).unwrap();
+    /// tables.delete_sites(&[s0]).unwrap();
+    /// assert_eq!(tables.sites().num_rows(), 1);
+    /// assert_eq!(tables.sites().position(0).unwrap(), 20.);
+    /// assert_eq!(tables.mutations().num_rows(), 1);
+    /// assert_eq!(tables.mutations().site(0).unwrap(), 0);
+    /// assert_eq!(tables.mutations().derived_state(0).unwrap(), b"C");
+    /// ```
+    pub fn delete_sites(&mut self, sites: &[SiteId]) -> Result<(), TskitError> {
+        let mut keep_sites = vec![true; self.sites().num_rows().as_usize()];
+        for s in sites {
+            match s.to_usize() {
+                Some(i) if i < keep_sites.len() => keep_sites[i] = false,
+                _ => return Err(TskitError::IndexError),
+            }
+        }
+        let keep_mutations = vec![true; self.mutations().num_rows().as_usize()];
+        self.retain_sites_and_mutations(&keep_sites, &keep_mutations)
+    }
+
+    /// Delete mutations.
+    ///
+    /// The remaining mutations keep their order
+    /// and are renumbered.
+    /// If the parent of a remaining mutation is deleted, its
+    /// parent becomes the nearest remaining ancestral mutation,
+    /// or [`MutationId::NULL`] if there is none.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if any mutation id is out of range,
+    ///   or if a mutation refers to a site that does not exist.
+    /// * [`TskitError`] if the `C` back end returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    /// let m0 = tables.add_mutation(s0, 0, -1, 3.0, Some(b"G")).unwrap();
+    /// let m1 = tables.add_mutation(s0, 0, m0, 2.0, Some(b"C")).unwrap();
+    /// let m2 = tables.add_mutation(s0, 0, m1, 1.0, Some(b"T")).unwrap();
+    /// tables.delete_mutations(&[m1]).unwrap();
+    /// assert_eq!(tables.mutations().num_rows(), 2);
+    /// // The former m2 is now the child of m0
+    /// assert_eq!(tables.mutations().parent(1).unwrap(), m0);
+    /// ```
+    pub fn delete_mutations(&mut self, mutations: &[MutationId]) -> Result<(), TskitError> {
+        let mut keep_mutations = vec![true; self.mutations().num_rows().as_usize()];
+        for m in mutations {
+            match m.to_usize() {
+                Some(i) if i < keep_mutations.len() => keep_mutations[i] = false,
+                _ => return Err(TskitError::IndexError),
+            }
+        }
+        let keep_sites = vec![true; self.sites().num_rows().as_usize()];
+        self.retain_sites_and_mutations(&keep_sites, &keep_mutations)
+    }
+
+    pub(crate) fn retain_sites_and_mutations(
+        &mut self,
+        keep_sites: &[bool],
+        keep_mutations: &[bool],
+    ) -> Result<(), TskitError> {
+        use streaming_iterator::StreamingIterator;
+        let mut new_sites = crate::sys::SiteTable::new(0)?;
+        let mut new_mutations = crate::sys::MutationTable::new(0)?;
+
+        let mut site_map = vec![SiteId::NULL; keep_sites.len()];
+        let mut running_site_id = 0;
+        let mut site_iter = self.sites().lending_iter();
+        while let Some(row) = site_iter.next() {
+            let i = row.id.as_usize();
+            if keep_sites[i] {
+                new_sites.add_row_with_metadata(
+                    row.position.into(),
+                    row.ancestral_state,
+                    row.metadata.unwrap_or(&[0u8; 0]),
+                )?;
+                site_map[i] = running_site_id.into();
+                running_site_id += 1;
+            }
+        }
+
+        // For each mutation, the nearest retained mutation,
+        // starting from and including itself, in the new id space.
+        let mut mutation_map = vec![MutationId::NULL; keep_mutations.len()];
+        let mut running_mutation_id = 0;
+        let mut mutation_iter = self.mutations().lending_iter();
+        while let Some(row) = mutation_iter.next() {
+            let i = row.id.as_usize();
+            // Valid tables require that parents precede children.
+            let parent = match row.parent.to_usize() {
+                Some(p) if row.parent < row.id => mutation_map[p],
+                _ => MutationId::NULL,
+            };
+            let site = row
+                .site
+                .to_usize()
+                .filter(|&s| s < keep_sites.len())
+                .ok_or(TskitError::IndexError)?;
+            if keep_mutations[i] && keep_sites[site] {
+                new_mutations.add_row_with_metadata(
+                    site_map[site].into(),
+                    row.node.into(),
+                    parent.into(),
+                    row.time.into(),
+                    row.derived_state,
+                    row.metadata.unwrap_or(&[0u8; 0]),
+                )?;
+                mutation_map[i] = running_mutation_id.into();
+                running_mutation_id += 1;
+            } else {
+                mutation_map[i] = parent;
+            }
+        }
+
+        let new_sites = SiteTable::new_from_table(new_sites.as_mut())?;
+        let new_mutations = MutationTable::new_from_table(new_mutations.as_mut())?;
+        self.set_sites(&new_sites)?;
+        self.set_mutations(&new_mutations)?;
+        Ok(())
+    }
+
     /// Set the individual table from an [`IndividualTable`](`crate::IndividualTable`)
     ///
     /// # Errors
@@ -1358,6 +2967,148 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Get the top-level metadata of the table collection.
+    ///
+    /// Returns `None` if there is no metadata.
+    pub fn raw_metadata(&self) -> Option<&[u8]> {
+        let tables = self.inner.as_ref();
+        if tables.metadata_length == 0 {
+            return None;
+        }
+        Some(crate::sys::generate_slice(
+            tables.metadata,
+            tables.metadata_length,
+        ))
+    }
+
+    /// Decode the top-level metadata of the table collection.
+    ///
+    /// Returns `None` if there is no metadata.
+    ///
+    /// See [`TableCollection::set_metadata`] for an example.
+    pub fn metadata<T: TableCollectionMetadata>(&self) -> Option<Result<T, TskitError>> {
+        let buffer = self.raw_metadata()?;
+        Some(decode_metadata_row!(T, buffer).map_err(|e| e.into()))
+    }
+
+    /// Set the top-level metadata of the table collection.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::MetadataError`] if encoding `metadata` fails.
+    /// * [`TskitError`] if the `C` back end is unable to copy the metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::metadata::{MetadataError, MetadataRoundtrip, TableCollectionMetadata};
+    ///
+    /// struct Description(String);
+    ///
+    /// impl MetadataRoundtrip for Description {
+    ///     fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+    ///         Ok(self.0.as_bytes().to_vec())
+    ///     }
+    ///     fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+    ///         match String::from_utf8(md.to_vec()) {
+    ///             Ok(s) => Ok(Self(s)),
+    ///             Err(e) => Err(MetadataError::RoundtripError { value: Box::new(e) }),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl TableCollectionMetadata for Description {}
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// assert!(tables.metadata::<Description>().is_none());
+    /// tables.set_metadata(&Description("simulated".to_string())).unwrap();
+    /// let description = tables.metadata::<Description>().unwrap().unwrap();
+    /// assert_eq!(description.0, "simulated");
+    /// assert_eq!(tables.raw_metadata(), Some(b"simulated".as_slice()));
+    /// ```
+    pub fn set_metadata<T: TableCollectionMetadata>(&mut self, metadata: &T) -> TskReturnValue {
+        let md = crate::metadata::EncodedMetadata::new(metadata)?;
+        // SAFETY: the tables are initialized and the metadata
+        // has md.as_slice().len() bytes, which are copied.
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_metadata(
+                self.as_mut_ptr(),
+                md.as_slice().as_ptr().cast(),
+                md.as_slice().len() as tsk_size_t,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Get the schema of the top-level metadata of the table collection.
+    ///
+    /// Returns `None` if there is no schema, or if the
+    /// schema is not valid UTF-8.
+    ///
+    /// The schemas of the tables are returned by the tables,
+    /// such as [`NodeTable::metadata_schema`](crate::NodeTable::metadata_schema).
+    pub fn metadata_schema(&self) -> Option<&str> {
+        let tables = self.inner.as_ref();
+        crate::metadata::schema_str(tables.metadata_schema, tables.metadata_schema_length)
+    }
+
+    /// Set the schema of the top-level metadata of the table collection.
+    ///
+    /// An empty `schema` removes the schema.
+    /// See [`MetadataSchema`](crate::metadata::MetadataSchema)
+    /// for schemas that are checked when they are created.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the `C` back end is unable to copy the schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let schema = tskit::metadata::MetadataSchema::permissive_json();
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.set_metadata_schema(&schema).unwrap();
+    /// tables.set_nodes_metadata_schema(&schema).unwrap();
+    /// assert_eq!(tables.metadata_schema(), Some(r#"{"codec":"json"}"#));
+    /// assert_eq!(tables.nodes().metadata_schema(), Some(r#"{"codec":"json"}"#));
+    /// assert!(tables.edges().metadata_schema().is_none());
+    /// ```
+    pub fn set_metadata_schema<S: AsRef<str>>(&mut self, schema: S) -> TskReturnValue {
+        let schema = schema.as_ref();
+        // SAFETY: the tables are initialized and schema has
+        // schema.len() bytes, which are copied.
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_metadata_schema(
+                self.as_mut_ptr(),
+                schema.as_ptr().cast(),
+                schema.len() as tsk_size_t,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`EdgeTable`](crate::EdgeTable).
+        => set_edges_metadata_schema, edges_mut);
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`IndividualTable`](crate::IndividualTable).
+        => set_individuals_metadata_schema, individuals_mut);
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`MigrationTable`](crate::MigrationTable).
+        => set_migrations_metadata_schema, migrations_mut);
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`MutationTable`](crate::MutationTable).
+        => set_mutations_metadata_schema, mutations_mut);
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`NodeTable`](crate::NodeTable).
+        => set_nodes_metadata_schema, nodes_mut);
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`PopulationTable`](crate::PopulationTable).
+        => set_populations_metadata_schema, populations_mut);
+    build_table_collection_metadata_schema_setter!(
+        /// Set the metadata schema of the [`SiteTable`](crate::SiteTable).
+        => set_sites_metadata_schema, sites_mut);
+
     delegate! {
         to self.views {
             /// Get mutable reference to the [``NodeTable``](crate::NodeTable).
@@ -1385,10 +3136,10 @@ impl TableCollection {
     /// and results in non-empty edge table.
     /// - `Error(TskitError)`: Any errors from the C API propagate. An
     /// [TskitError::RangeError] will occur when `intervals` are not
-    /// sorted. Note that as `tskit` currently does not support `simplify`
-    /// on [TableCollection] with a non-empty migration table, calling
-    /// `keep_intervals` on those [TableCollection] with `simplify` set to
-    /// `true` will return an error.
+    /// sorted, and an [TskitError::IndexError] when a mutation refers
+    /// to a site or parent mutation that does not exist.
+    /// See [`TableCollection::simplify`] for the handling
+    /// of migrations when `simplify` is `true`.
     ///
     /// # Example
     /// ```rust
@@ -1485,7 +3236,20 @@ impl TableCollection {
 
             while let Some(migration_row) = migration_iter.next() {
                 new_migrations.add_row_with_metadata(
-                    (migration_row.left.into(), migration_row.right.into()),
+                    (
+                        if migration_row.left < s {
+                            s
+                        } else {
+                            migration_row.left
+                        }
+                        .into(),
+                        if migration_row.right > e {
+                            e
+                        } else {
+                            migration_row.right
+                        }
+                        .into(),
+                    ),
                     migration_row.node.into(),
                     migration_row.source.into(),
                     migration_row.dest.into(),
@@ -1499,7 +3263,7 @@ impl TableCollection {
         let mut running_site_id = 0;
         let mut site_iter = tables.sites().lending_iter();
         while let Some(site_row) = site_iter.next() {
-            let old_id = site_row.id.to_usize().unwrap();
+            let old_id = site_row.id.as_usize();
             if keep_sites[old_id] {
                 new_sites.add_row_with_metadata(
                     site_row.position.into(),
@@ -1511,6 +3275,13 @@ impl TableCollection {
             }
         }
 
+        // the index of a site referred to by a mutation
+        let site_index = |site: SiteId| {
+            site.to_usize()
+                .filter(|&i| i < keep_sites.len())
+                .ok_or(TskitError::IndexError)
+        };
+
         // build mutation_map
         let mutation_map: Vec<_> = {
             let mut n = 0;
@@ -1518,25 +3289,29 @@ impl TableCollection {
                 .mutations()
                 .site_slice()
                 .iter()
-                .map(|site| {
-                    if keep_sites[site.as_usize()] {
+                .map(|&site| {
+                    if keep_sites[site_index(site)?] {
                         n += 1
                     };
-                    n - 1
+                    Ok(n - 1)
                 })
-                .collect()
+                .collect::<Result<_, TskitError>>()?
         };
 
         let mut mutations_iter = tables.mutations().lending_iter();
         while let Some(mutation_row) = mutations_iter.next() {
-            let old_id = mutation_row.site.to_usize().unwrap();
+            let old_id = site_index(mutation_row.site)?;
             if keep_sites[old_id] {
                 let new_site = site_map[old_id];
                 let new_parent = {
                     if mutation_row.parent.is_null() {
                         mutation_row.parent.into()
                     } else {
-                        mutation_map[mutation_row.parent.as_usize()]
+                        *mutation_row
+                            .parent
+                            .to_usize()
+                            .and_then(|p| mutation_map.get(p))
+                            .ok_or(TskitError::IndexError)?
                     }
                 };
                 new_mutations.add_row_with_metadata(
@@ -1578,4 +3353,162 @@ impl TableCollection {
             Ok(Some(tables))
         }
     }
+
+    /// Remove the specified genome intervals from the [TableCollection].
+    ///
+    /// This is the complement of [`TableCollection::keep_intervals`]:
+    /// the parts of the genome between `0` and the sequence length
+    /// that are not in `intervals` are passed to `keep_intervals`,
+    /// which does the work.
+    /// The sequence length does not change.
+    ///
+    /// No provenance is recorded.
+    /// See [`TableCollection::delete_intervals_with_provenance`].
+    ///
+    /// # Return
+    /// - `Ok(None)`: when deletion leads to empty edge table.
+    /// - `Ok(Some(TableCollection))`: when deletion is successfully performed
+    ///   and results in non-empty edge table.
+    /// - `Error(TskitError)`: A [TskitError::RangeError] will occur when
+    ///   `intervals` are not sorted or extend beyond the sequence length.
+    ///   Otherwise, errors are those of [`TableCollection::keep_intervals`].
+    ///
+    /// # Example
+    /// ```rust
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, child).unwrap();
+    /// for position in [5., 50., 95.] {
+    ///     tables.add_site(position, Some(b"A")).unwrap();
+    /// }
+    ///
+    /// let intervals = [(0.0, 10.0), (90.0, 100.0)].into_iter();
+    /// let tables = tables.delete_intervals(intervals, false).unwrap().unwrap();
+    /// assert_eq!(tables.edges().left(0), Some(10.0.into()));
+    /// assert_eq!(tables.edges().right(0), Some(90.0.into()));
+    /// assert_eq!(tables.sites().num_rows(), 1);
+    /// ```
+    pub fn delete_intervals<P>(
+        self,
+        intervals: impl Iterator<Item = (P, P)>,
+        simplify: bool,
+    ) -> Result<Option<Self>, TskitError>
+    where
+        P: Into<Position>,
+    {
+        let sequence_length = self.sequence_length();
+        let mut keep = vec![];
+        let mut last_right = Position::from(0.0);
+        for (s, e) in intervals {
+            let (s, e) = (s.into(), e.into());
+            if (s > e) || (s < last_right) || (e > sequence_length) {
+                return Err(TskitError::RangeError(
+                    "intervals not valid or sorted".into(),
+                ));
+            }
+            if s > last_right {
+                keep.push((last_right, s));
+            }
+            last_right = e;
+        }
+        if sequence_length > last_right {
+            keep.push((last_right, sequence_length));
+        }
+        self.keep_intervals(keep.into_iter(), simplify)
+    }
+
+    /// Remove the specified genome intervals, as for
+    /// [`TableCollection::delete_intervals`], and record the
+    /// deletion in the provenance table.
+    ///
+    /// The record is a `JSON` document following the
+    /// [tskit provenance schema](https://tskit.dev/tskit/docs/stable/provenance.html),
+    /// whose parameters are the command, `"delete_intervals"`,
+    /// the intervals, and `simplify`.
+    /// It is added after the intervals are deleted, so nothing is
+    /// recorded if deletion fails or leaves an empty edge table.
+    ///
+    /// # Errors
+    ///
+    /// As for [`TableCollection::delete_intervals`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "provenance")] {
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, child).unwrap();
+    ///
+    /// let intervals = [(0.0, 10.0)].into_iter();
+    /// let tables = tables
+    ///     .delete_intervals_with_provenance(intervals, false)
+    ///     .unwrap()
+    ///     .unwrap();
+    /// let history = tables.provenances().history();
+    /// assert_eq!(history.len(), 1);
+    /// assert!(history[0].parameters().unwrap().contains("delete_intervals"));
+    /// # }
+    /// ```
+    #[cfg(feature = "provenance")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "provenance")))]
+    pub fn delete_intervals_with_provenance<P>(
+        self,
+        intervals: impl Iterator<Item = (P, P)>,
+        simplify: bool,
+    ) -> Result<Option<Self>, TskitError>
+    where
+        P: Into<Position>,
+    {
+        let intervals = intervals
+            .map(|(s, e)| (s.into(), e.into()))
+            .collect::<Vec<(Position, Position)>>();
+        let record = serde_json::json!({
+            "schema_version": "1.0.0",
+            "software": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "parameters": {
+                "command": "delete_intervals",
+                "intervals": intervals
+                    .iter()
+                    .map(|&(s, e)| [f64::from(s), f64::from(e)])
+                    .collect::<Vec<_>>(),
+                "simplify": simplify,
+            },
+            "environment": {
+                "os": {
+                    "system": std::env::consts::OS,
+                    "machine": std::env::consts::ARCH,
+                },
+            },
+        });
+        let Some(mut tables) = self.delete_intervals(intervals.into_iter(), simplify)? else {
+            return Ok(None);
+        };
+        tables.add_provenance(&record.to_string())?;
+        Ok(Some(tables))
+    }
+}
+
+// A migration table with the rows `rows`, with their populations
+// updated by `remap`.
+fn migration_table_from_rows<F: Fn(PopulationId) -> PopulationId>(
+    rows: Vec<crate::MigrationTableRow>,
+    remap: F,
+) -> Result<crate::sys::MigrationTable, TskitError> {
+    let mut migrations = crate::sys::MigrationTable::new(0)?;
+    for row in rows {
+        migrations.add_row_with_metadata(
+            (row.left.into(), row.right.into()),
+            row.node.into(),
+            remap(row.source).into(),
+            remap(row.dest).into(),
+            row.time.into(),
+            row.metadata.as_deref().unwrap_or(&[]),
+        )?;
+    }
+    Ok(migrations)
 }
@@ -12,6 +12,18 @@ use crate::PopulationTable;
 use crate::SiteTable;
 use crate::TskitError;
 
+// Write a table between "#name#" and "#end#name" lines
+fn dump_table_text<W: std::io::Write, F: FnOnce(&mut W) -> Result<(), TskitError>>(
+    writer: &mut W,
+    name: &str,
+    dump: F,
+) -> Result<(), TskitError> {
+    let io_error = |e: std::io::Error| TskitError::LibraryError(e.to_string());
+    writeln!(writer, "#{}#", name).map_err(io_error)?;
+    dump(writer)?;
+    writeln!(writer, "#end#{}", name).map_err(io_error)
+}
+
 pub struct TableViews {
     edges: EdgeTable,
     nodes: NodeTable,
@@ -207,6 +219,44 @@ impl TableViews {
         self.provenances.iter()
     }
 
+    /// Call the methods of `visitor` for each table.
+    ///
+    /// See [`crate::TableVisitor`].
+    pub fn visit_tables<V: crate::TableVisitor>(&self, visitor: &mut V) -> Result<(), TskitError> {
+        visitor.visit_individuals(&self.individuals)?;
+        visitor.visit_nodes(&self.nodes)?;
+        visitor.visit_edges(&self.edges)?;
+        visitor.visit_migrations(&self.migrations)?;
+        visitor.visit_sites(&self.sites)?;
+        visitor.visit_mutations(&self.mutations)?;
+        visitor.visit_populations(&self.populations)?;
+        #[cfg(feature = "provenance")]
+        visitor.visit_provenances(&self.provenances)?;
+        Ok(())
+    }
+
+    /// Write each table in the text format of `tskit`.
+    ///
+    /// See [`crate::TableCollection::dump_text`].
+    pub fn dump_text<W: std::io::Write>(&self, mut writer: W) -> Result<(), TskitError> {
+        dump_table_text(&mut writer, "individuals", |w| {
+            self.individuals.dump_text(w)
+        })?;
+        dump_table_text(&mut writer, "nodes", |w| self.nodes.dump_text(w))?;
+        dump_table_text(&mut writer, "edges", |w| self.edges.dump_text(w))?;
+        dump_table_text(&mut writer, "migrations", |w| self.migrations.dump_text(w))?;
+        dump_table_text(&mut writer, "sites", |w| self.sites.dump_text(w))?;
+        dump_table_text(&mut writer, "mutations", |w| self.mutations.dump_text(w))?;
+        dump_table_text(&mut writer, "populations", |w| {
+            self.populations.dump_text(w)
+        })?;
+        #[cfg(feature = "provenance")]
+        dump_table_text(&mut writer, "provenances", |w| {
+            self.provenances.dump_text(w)
+        })?;
+        Ok(())
+    }
+
     /// Obtain a vector containing the indexes ("ids")
     /// of all nodes for which [`crate::NodeFlags::is_sample`]
     /// is `true`.
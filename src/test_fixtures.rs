@@ -91,7 +91,9 @@ pub mod simulation {
     use core::panic;
 
     use crate::{
-        metadata::{MetadataError, MetadataRoundtrip, PopulationMetadata},
+        metadata::{
+            MetadataError, MetadataRoundtrip, MutationMetadata, PopulationMetadata, SiteMetadata,
+        },
         EdgeId, IndividualId, MutationId, NodeFlags, NodeId, PopulationId, Position,
         SimplificationOptions, SiteId, TableCollection, TableSortOptions, TreeSequence,
         TreeSequenceFlags, TskitError,
@@ -132,6 +134,46 @@ pub mod simulation {
 
     impl PopulationMetadata for MyMeta {}
 
+    /// Mutation metadata recorded by [`simulate_two_treesequences_with_metadata`]
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Copy, Clone)]
+    pub struct MutationMeta {
+        pub selection_coefficient: f64,
+        pub origin_population: i32,
+        pub origin_time: f64,
+    }
+
+    impl MetadataRoundtrip for MutationMeta {
+        fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+            handle_metadata_return!(bincode::serialize(&self))
+        }
+
+        fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+            handle_metadata_return!(bincode::deserialize(md))
+        }
+    }
+
+    impl MutationMetadata for MutationMeta {}
+
+    /// Site metadata recorded by [`simulate_two_treesequences_with_metadata`].
+    /// The origin is that of the first mutation at the site.
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Copy, Clone)]
+    pub struct SiteMeta {
+        pub origin_population: i32,
+        pub origin_time: f64,
+    }
+
+    impl MetadataRoundtrip for SiteMeta {
+        fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+            handle_metadata_return!(bincode::serialize(&self))
+        }
+
+        fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+            handle_metadata_return!(bincode::deserialize(md))
+        }
+    }
+
+    impl SiteMetadata for SiteMeta {}
+
     fn add_pop(tables: &mut TableCollection, name: &str) -> PopulationId {
         tables
             .add_population_with_metadata(&MyMeta::from(name))
@@ -261,6 +303,31 @@ pub mod simulation {
             }]
     }
 
+    fn add_site(tables: &mut TableCollection, pos: usize, metadata: Option<SiteMeta>) -> SiteId {
+        let ancestral_state = Some(&[b'a'][..]);
+        match metadata {
+            Some(md) => tables.add_site_with_metadata(pos as f64, ancestral_state, &md),
+            None => tables.add_site(pos as f64, ancestral_state),
+        }
+        .unwrap()
+    }
+
+    fn add_mutation(
+        tables: &mut TableCollection,
+        (site, node, parent, time): (SiteId, NodeId, MutationId, f64),
+        derived_state: &[u8],
+        metadata: Option<MutationMeta>,
+    ) -> MutationId {
+        let derived_state = Some(derived_state);
+        match metadata {
+            Some(md) => {
+                tables.add_mutation_with_metadata(site, node, parent, time, derived_state, &md)
+            }
+            None => tables.add_mutation(site, node, parent, time, derived_state),
+        }
+        .unwrap()
+    }
+
     /// simulate diplid individual with migration between two subpopulations
     ///
     /// Both full_trees and trucated_trees will be generated
@@ -272,10 +339,104 @@ pub mod simulation {
         intervals: &[(P, P)],
         seed: u64,
     ) -> Result<(TreeSequence, TreeSequence), TskitError>
+    where
+        P: Into<Position> + Copy + PartialOrd,
+    {
+        simulate(
+            seqlen,
+            pop_size,
+            start_time,
+            split_time,
+            intervals,
+            seed,
+            Records {
+                metadata: false,
+                migrations: false,
+            },
+        )
+    }
+
+    /// Same as [`simulate_two_treesequences`], but mutations and sites
+    /// have [`MutationMeta`] and [`SiteMeta`] metadata, respectively.
+    ///
+    /// The metadata are generated from a separate random number stream,
+    /// so that the output only differs from that of
+    /// [`simulate_two_treesequences`] in its metadata.
+    pub fn simulate_two_treesequences_with_metadata<P>(
+        seqlen: P,
+        pop_size: usize,
+        start_time: usize,
+        split_time: usize,
+        intervals: &[(P, P)],
+        seed: u64,
+    ) -> Result<(TreeSequence, TreeSequence), TskitError>
+    where
+        P: Into<Position> + Copy + PartialOrd,
+    {
+        simulate(
+            seqlen,
+            pop_size,
+            start_time,
+            split_time,
+            intervals,
+            seed,
+            Records {
+                metadata: true,
+                migrations: false,
+            },
+        )
+    }
+
+    /// Same as [`simulate_two_treesequences`], but each node whose
+    /// parent is in another population has a migration record.
+    ///
+    /// The output only differs from that of
+    /// [`simulate_two_treesequences`] in its migration tables.
+    pub fn simulate_two_treesequences_with_migrations<P>(
+        seqlen: P,
+        pop_size: usize,
+        start_time: usize,
+        split_time: usize,
+        intervals: &[(P, P)],
+        seed: u64,
+    ) -> Result<(TreeSequence, TreeSequence), TskitError>
+    where
+        P: Into<Position> + Copy + PartialOrd,
+    {
+        simulate(
+            seqlen,
+            pop_size,
+            start_time,
+            split_time,
+            intervals,
+            seed,
+            Records {
+                metadata: false,
+                migrations: true,
+            },
+        )
+    }
+
+    // Optional contents of the simulated tables
+    struct Records {
+        metadata: bool,
+        migrations: bool,
+    }
+
+    fn simulate<P>(
+        seqlen: P,
+        pop_size: usize,
+        start_time: usize,
+        split_time: usize,
+        intervals: &[(P, P)],
+        seed: u64,
+        records: Records,
+    ) -> Result<(TreeSequence, TreeSequence), TskitError>
     where
         P: Into<Position> + Copy + PartialOrd,
     {
         let rng = &mut StdRng::seed_from_u64(seed);
+        let metadata_rng = &mut StdRng::seed_from_u64(seed.wrapping_add(1));
         let intervals: Vec<(Position, Position)> = intervals
             .iter()
             .map(|(a, b)| ((*a).into(), (*b).into()))
@@ -295,7 +456,7 @@ pub mod simulation {
         let pop_1 = add_pop(&mut tables, "pop1");
         let pop_2 = add_pop(&mut tables, "pop2");
 
-        add_pop(&mut tr_tbls, "ancestral");
+        add_pop(&mut tr_tbls, "ancestor");
         add_pop(&mut tr_tbls, "pop1");
         add_pop(&mut tr_tbls, "pop2");
 
@@ -308,6 +469,7 @@ pub mod simulation {
 
         let mut site_id_map_tables = vec![SiteId::NULL; num_sites];
         let mut site_id_map_tr_tbls = vec![SiteId::NULL; num_sites];
+        let mut site_metadata = vec![None; num_sites];
 
         // base population
         let mut parents = Vec::<(NodeId, NodeId)>::with_capacity(pop_size);
@@ -343,8 +505,8 @@ pub mod simulation {
                 }
 
                 // find parents
-                let (parent1, _parent1_pop) = find_parent(rng, &parents, child_pop);
-                let (parent2, _parent2_pop) = find_parent(rng, &parents, child_pop);
+                let (parent1, parent1_pop) = find_parent(rng, &parents, child_pop);
+                let (parent2, parent2_pop) = find_parent(rng, &parents, child_pop);
 
                 // add individual
                 let child_ind = add_ind(&mut tables, parent1, parent2);
@@ -360,6 +522,23 @@ pub mod simulation {
                 add_node(&mut tr_tbls, is_sample, t, child_pop, child_ind);
                 add_node(&mut tr_tbls, is_sample, t, child_pop, child_ind);
 
+                // add migrations of lineages whose parent is in another population
+                for (c, parent_pop) in [(child_id.0, parent1_pop), (child_id.1, parent2_pop)] {
+                    if records.migrations && parent_pop != child_pop {
+                        let time = t as f64 + 0.5;
+                        let (s, e) = (Position::from(0.0), seqlen.into());
+                        tables
+                            .add_migration((s, e), c, (child_pop, parent_pop), time)
+                            .unwrap();
+                        find_overlaps(s, e, &intervals, &mut buffer);
+                        for (s_, e_) in buffer.iter() {
+                            tr_tbls
+                                .add_migration((*s_, *e_), c, (child_pop, parent_pop), time)
+                                .unwrap();
+                        }
+                    }
+                }
+
                 // add edges, sites & mutations to both tables and tr_tabls
                 let mu = 0.01f64;
                 for (s, e, p, c) in [
@@ -378,20 +557,33 @@ pub mod simulation {
                     let to_add_mut: bool = rng.gen_bool(mut_prob);
                     let derived_state = &calc_derived_state(&site_last_mutation_order, mut_pos);
                     let t = t as f64;
+                    let mutation_metadata =
+                        (records.metadata && to_add_mut).then(|| MutationMeta {
+                            selection_coefficient: metadata_rng.gen_range(-0.1..0.1),
+                            origin_population: child_pop.into(),
+                            origin_time: t,
+                        });
 
                     if to_add_mut {
                         // add site
                         let site_not_exist = site_id_map_tables[mut_pos] == SiteId::NULL;
                         if site_not_exist {
+                            site_metadata[mut_pos] = mutation_metadata.map(|md| SiteMeta {
+                                origin_population: md.origin_population,
+                                origin_time: md.origin_time,
+                            });
                             site_id_map_tables[mut_pos] =
-                                tables.add_site(mut_pos as f64, Some(&[b'a'])).unwrap();
+                                add_site(&mut tables, mut_pos, site_metadata[mut_pos]);
                         }
                         // add mutation
                         let parent_mut = site_last_mutation_tables[mut_pos];
                         let site = site_id_map_tables[mut_pos];
-                        let new_mutation = tables
-                            .add_mutation(site, c, parent_mut, t, Some(derived_state))
-                            .unwrap();
+                        let new_mutation = add_mutation(
+                            &mut tables,
+                            (site, c, parent_mut, t),
+                            derived_state,
+                            mutation_metadata,
+                        );
 
                         site_last_mutation_tables[mut_pos] = new_mutation;
                         site_last_mutation_order[mut_pos] += 1;
@@ -407,14 +599,17 @@ pub mod simulation {
                             let site_not_exist = site_id_map_tr_tbls[mut_pos] == SiteId::NULL;
                             if site_not_exist {
                                 site_id_map_tr_tbls[mut_pos] =
-                                    tr_tbls.add_site(mut_pos as f64, Some(&[b'a'])).unwrap();
+                                    add_site(&mut tr_tbls, mut_pos, site_metadata[mut_pos]);
                             }
                             // add mutation
                             let parent_mut = site_last_mutation_tr_tbls[mut_pos];
                             let site = site_id_map_tr_tbls[mut_pos];
-                            let new_mutation = tr_tbls
-                                .add_mutation(site, c, parent_mut, t, Some(derived_state))
-                                .unwrap();
+                            let new_mutation = add_mutation(
+                                &mut tr_tbls,
+                                (site, c, parent_mut, t),
+                                derived_state,
+                                mutation_metadata,
+                            );
                             site_last_mutation_tr_tbls[mut_pos] = new_mutation;
                         }
                     }
@@ -508,7 +703,11 @@ pub mod simulation {
 mod keep_intervals {
     use crate::*;
 
-    use super::simulation::{generate_simple_treesequence, simulate_two_treesequences};
+    use super::simulation::{
+        generate_simple_treesequence, simulate_two_treesequences,
+        simulate_two_treesequences_with_metadata, simulate_two_treesequences_with_migrations,
+        MutationMeta, SiteMeta,
+    };
 
     #[test]
     fn test_keep_intervals_invalid_input() {
@@ -524,6 +723,18 @@ mod keep_intervals {
         }
     }
 
+    #[test]
+    fn test_keep_intervals_invalid_mutation_site() {
+        let mut tables = TableCollection::new(100.).unwrap();
+        let node = tables
+            .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+            .unwrap();
+        tables.add_site(10., Some(b"A")).unwrap();
+        tables.add_mutation(1, node, -1, 0.0, Some(b"C")).unwrap();
+        let res = tables.keep_intervals([(0.0, 50.0)].into_iter(), false);
+        assert!(matches!(res, Err(TskitError::IndexError)));
+    }
+
     #[test]
     fn test_keep_intervals_nonempty_migration_table() {
         let intervals = [(10.0, 20.0)];
@@ -532,7 +743,9 @@ mod keep_intervals {
         let to_simplify = true;
         let trees = generate_simple_treesequence(add_migration_table);
         let res = trees.keep_intervals(intervals.iter().copied(), to_simplify);
-        assert!(res.is_err());
+        let tables = res.unwrap().unwrap();
+        assert_eq!(tables.migrations().num_rows(), 2);
+        assert_eq!(tables.migrations().left(0), Some(10.0.into()));
 
         let add_migration_table = true;
         let to_simply = false;
@@ -593,4 +806,234 @@ mod keep_intervals {
             }
         }
     }
+
+    #[test]
+    fn test_delete_intervals() {
+        let seqlen = 1000.0;
+        let intervals = [(10.0, 20.0), (700.0, 850.0)];
+        let deleted = [(0.0, 10.0), (20.0, 700.0), (850.0, seqlen)];
+        let (full_trees, _) =
+            simulate_two_treesequences(seqlen, 50, 300, 20, &intervals, 123).unwrap();
+        let tables = full_trees.dump_tables().unwrap();
+
+        let kept = tables
+            .deepcopy()
+            .unwrap()
+            .keep_intervals(intervals.iter().copied(), true)
+            .unwrap()
+            .unwrap();
+        let truncated = tables
+            .deepcopy()
+            .unwrap()
+            .delete_intervals(deleted.iter().copied(), true)
+            .unwrap()
+            .unwrap();
+        assert!(truncated.equals(&kept, TableEqualityOptions::all()));
+
+        // Deleting intervals keeps their complement
+        let deleted_tables = tables
+            .deepcopy()
+            .unwrap()
+            .delete_intervals(intervals.iter().copied(), true)
+            .unwrap()
+            .unwrap();
+        let kept_tables = tables
+            .deepcopy()
+            .unwrap()
+            .keep_intervals(deleted.iter().copied(), true)
+            .unwrap()
+            .unwrap();
+        assert!(deleted_tables.equals(&kept_tables, TableEqualityOptions::all()));
+        #[cfg(feature = "provenance")]
+        {
+            assert_eq!(
+                deleted_tables.provenances().num_rows(),
+                tables.provenances().num_rows()
+            );
+            let recorded = tables
+                .deepcopy()
+                .unwrap()
+                .delete_intervals_with_provenance(intervals.iter().copied(), true)
+                .unwrap()
+                .unwrap();
+            assert!(recorded.equals(&deleted_tables, TableEqualityOptions::IGNORE_PROVENANCE));
+            let history = recorded.provenances().history();
+            assert_eq!(
+                history.len(),
+                tables.provenances().num_rows().as_usize() + 1
+            );
+            let entry = history.last().unwrap();
+            assert_eq!(entry.software_name.as_deref(), Some("tskit"));
+            let parameters =
+                serde_json::from_str::<serde_json::Value>(entry.parameters().unwrap()).unwrap();
+            assert_eq!(parameters["command"], "delete_intervals");
+            assert_eq!(parameters["intervals"][1][1], 850.0);
+            assert_eq!(parameters["simplify"], true);
+        }
+
+        // Deleting nothing keeps everything
+        let unchanged = tables
+            .deepcopy()
+            .unwrap()
+            .delete_intervals(std::iter::empty::<(f64, f64)>(), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged.edges().num_rows(), tables.edges().num_rows());
+
+        // Deleting everything leaves no edges
+        assert!(tables
+            .deepcopy()
+            .unwrap()
+            .delete_intervals([(0.0, seqlen)].into_iter(), false)
+            .unwrap()
+            .is_none());
+
+        for invalid in [vec![(20.0, 10.0)], vec![(990.0, seqlen + 1.0)]] {
+            assert!(matches!(
+                tables
+                    .deepcopy()
+                    .unwrap()
+                    .delete_intervals(invalid.into_iter(), true),
+                Err(TskitError::RangeError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_keep_intervals_with_metadata() {
+        let seqlen = 1000.0;
+        let intervals = [(10.0, 20.0), (700.0, 850.0)];
+        let (full_trees, expected) =
+            simulate_two_treesequences_with_metadata(seqlen, 50, 300, 20, &intervals, 123).unwrap();
+
+        let with_metadata = full_trees.dump_tables().unwrap();
+        let truncated = TreeSequence::from_tables_ref(&with_metadata, TreeSequenceFlags::default())
+            .unwrap()
+            .keep_intervals(intervals.iter().copied(), true)
+            .unwrap()
+            .unwrap()
+            .dump_tables()
+            .unwrap();
+
+        // Only the metadata differ from the simulation without metadata
+        let (no_metadata, _) =
+            simulate_two_treesequences(seqlen, 50, 300, 20, &intervals, 123).unwrap();
+        let no_metadata = no_metadata.dump_tables().unwrap();
+        assert!(with_metadata.equals(&no_metadata, TableEqualityOptions::IGNORE_METADATA));
+        assert!(!with_metadata.equals(&no_metadata, TableEqualityOptions::default()));
+
+        let mutations = with_metadata.mutations();
+        assert!(mutations.num_rows() > 0);
+        for row in 0..mutations.num_rows().as_usize() {
+            let row = MutationId::from(row as i32);
+            let md = mutations.metadata::<MutationMeta>(row).unwrap().unwrap();
+            assert!(md.selection_coefficient.abs() < 0.1);
+            assert_eq!(md.origin_time, f64::from(mutations.time(row).unwrap()));
+            let site = mutations.site(row).unwrap();
+            let site_md = with_metadata
+                .sites()
+                .metadata::<SiteMeta>(site)
+                .unwrap()
+                .unwrap();
+            assert!(site_md.origin_time >= md.origin_time);
+        }
+
+        let expected = expected.dump_tables().unwrap();
+        assert!(truncated.equals(&expected, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_keep_intervals_with_migrations() {
+        let seqlen = 1000.0;
+        let intervals = [(10.0, 20.0), (700.0, 850.0)];
+        let (full_trees, expected) =
+            simulate_two_treesequences_with_migrations(seqlen, 50, 300, 20, &intervals, 123)
+                .unwrap();
+        let with_migrations = full_trees.dump_tables().unwrap();
+        assert!(with_migrations.migrations().num_rows() > 0);
+
+        // Only the migrations differ from the simulation without migrations
+        let (no_migrations, _) =
+            simulate_two_treesequences(seqlen, 50, 300, 20, &intervals, 123).unwrap();
+        let mut no_migrations = no_migrations.dump_tables().unwrap();
+        assert_eq!(no_migrations.migrations().num_rows(), 0);
+        let mut cleared = with_migrations.deepcopy().unwrap();
+        let samples = cleared.samples_as_vector();
+        cleared
+            .simplify_clear_migrations(&samples, SimplificationOptions::default(), false)
+            .unwrap();
+        no_migrations
+            .simplify(&samples, SimplificationOptions::default(), false)
+            .unwrap();
+        assert!(cleared.equals(&no_migrations, TableEqualityOptions::default()));
+
+        let truncated =
+            TreeSequence::from_tables_ref(&with_migrations, TreeSequenceFlags::default())
+                .unwrap()
+                .keep_intervals(intervals.iter().copied(), true)
+                .unwrap()
+                .unwrap()
+                .dump_tables()
+                .unwrap();
+        let expected = expected.dump_tables().unwrap();
+        assert!(expected.migrations().num_rows() > 0);
+        assert!(truncated.equals(&expected, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_simplify_with_migrations() {
+        let (full_trees, _) =
+            simulate_two_treesequences_with_migrations(1000.0, 50, 300, 20, &[(0., 1000.)], 42)
+                .unwrap();
+        let tables = full_trees.dump_tables().unwrap();
+        let num_migrations = tables.migrations().num_rows();
+        assert!(num_migrations > 0);
+
+        // Simplifying to a subset of the samples keeps only the
+        // migrations of lineages that are still ancestral
+        let samples = tables.samples_as_vector();
+        let (simplified, idmap) =
+            TreeSequence::from_tables_ref(&tables, TreeSequenceFlags::default())
+                .unwrap()
+                .simplify(&samples[..10], SimplificationOptions::default(), true)
+                .unwrap();
+        let idmap = idmap.unwrap();
+        let migrations = simplified.migrations();
+        let mut kept = 0;
+        for row in tables.migrations().iter() {
+            let node = idmap[row.node.to_usize().unwrap()];
+            if !node.is_null() {
+                let new_row = migrations.row(MigrationId::from(kept)).unwrap();
+                assert_eq!(new_row.node, node);
+                assert_eq!(new_row.time, row.time);
+                assert_eq!((new_row.source, new_row.dest), (row.source, row.dest));
+                kept += 1;
+            }
+        }
+        assert_eq!(migrations.num_rows(), kept as u64);
+
+        let (simplified, _) = TreeSequence::from_tables_ref(&tables, TreeSequenceFlags::default())
+            .unwrap()
+            .simplify_clear_migrations(&samples[..10], SimplificationOptions::default(), false)
+            .unwrap();
+        assert_eq!(simplified.migrations().num_rows(), 0);
+
+        // Migrations are unchanged if simplification fails
+        for clear_migrations in [false, true] {
+            let mut copy = tables.deepcopy().unwrap();
+            let bad = [NodeId::from(-1)];
+            let options = SimplificationOptions::default();
+            let rv = match clear_migrations {
+                true => copy.simplify_clear_migrations(&bad, options, false),
+                false => copy.simplify(&bad, options, false),
+            };
+            assert!(rv.is_err());
+            assert_eq!(copy.migrations().num_rows(), num_migrations);
+            assert!(copy
+                .migrations()
+                .iter()
+                .zip(tables.migrations().iter())
+                .all(|(a, b)| a == b));
+        }
+    }
 }
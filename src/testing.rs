@@ -0,0 +1,209 @@
+//! Deterministic tree sequences for testing.
+//!
+//! The functions of this module build tree sequences of a single
+//! tree of `num_samples` samples spanning the whole genome.
+//! Their topologies are fixed, or drawn from a seeded random number
+//! generator, so that the same arguments always give the same
+//! tree sequence.
+//! Use them to test algorithms on trees without shipping
+//! files of tree sequences.
+//!
+//! Samples are the nodes `0` to `num_samples - 1`, at time zero.
+//!
+//! # Examples
+//!
+//! ```
+//! use streaming_iterator::StreamingIterator;
+//!
+//! for seed in 0..10 {
+//!     let ts = tskit::testing::random_binary(8, 100., seed).unwrap();
+//!     assert_eq!(ts.num_samples(), 8);
+//!     // A binary tree of 8 samples has 7 ancestors
+//!     assert_eq!(ts.nodes().num_rows(), 15);
+//!     let mut trees = ts.tree_iterator(tskit::TreeFlags::default()).unwrap();
+//!     let tree = trees.next().unwrap();
+//!     assert_eq!(tree.roots_to_vec().len(), 1);
+//! }
+//! ```
+
+use crate::NodeFlags;
+use crate::NodeId;
+use crate::Position;
+use crate::TableCollection;
+use crate::TableSortOptions;
+use crate::TreeSequence;
+use crate::TreeSequenceFlags;
+use crate::TskitError;
+
+// The splitmix64 generator, which is good enough
+// for topologies and does not add a dependency.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform on (0, 1)
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    // Uniform on 0..n, for n > 0
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+// Adds the samples and returns a table collection and their ids
+fn new_tables<P: Into<Position>>(
+    num_samples: usize,
+    sequence_length: P,
+) -> Result<(TableCollection, Vec<NodeId>), TskitError> {
+    if num_samples == 0 {
+        return Err(TskitError::ValueError {
+            got: "0 samples".to_string(),
+            expected: "at least one sample".to_string(),
+        });
+    }
+    let mut tables = TableCollection::new(sequence_length)?;
+    let samples = (0..num_samples)
+        .map(|_| tables.add_node(NodeFlags::new_sample(), 0.0, -1, -1))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((tables, samples))
+}
+
+// Adds a parent of two children at time
+fn add_parent(
+    tables: &mut TableCollection,
+    left: NodeId,
+    right: NodeId,
+    time: f64,
+) -> Result<NodeId, TskitError> {
+    let parent = tables.add_node(0, time, -1, -1)?;
+    let sequence_length = tables.sequence_length();
+    for child in [left, right] {
+        tables.add_edge(0., sequence_length, parent, child)?;
+    }
+    Ok(parent)
+}
+
+fn tree_sequence(mut tables: TableCollection) -> Result<TreeSequence, TskitError> {
+    tables.full_sort(TableSortOptions::default())?;
+    tables.build_index()?;
+    tables.tree_sequence(TreeSequenceFlags::default())
+}
+
+/// A balanced binary tree.
+///
+/// Each ancestor has two children whose numbers of descendant samples
+/// differ by at most one.
+/// The time of an ancestor is the number of edges on the longest path
+/// to its samples.
+///
+/// # Errors
+///
+/// * [`TskitError::ValueError`] if `num_samples` is zero.
+/// * [`TskitError`] if `sequence_length` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// let ts = tskit::testing::balanced(4, 10.).unwrap();
+/// // ((0,1),(2,3))
+/// let times = ts.nodes().time_slice();
+/// assert_eq!(times[6], 2.0);
+/// assert_eq!(ts.edges().num_rows(), 6);
+/// ```
+pub fn balanced<P: Into<Position>>(
+    num_samples: usize,
+    sequence_length: P,
+) -> Result<TreeSequence, TskitError> {
+    let (mut tables, samples) = new_tables(num_samples, sequence_length)?;
+    // Split the nodes in halves, and return the root and its time
+    fn build(tables: &mut TableCollection, nodes: &[NodeId]) -> Result<(NodeId, f64), TskitError> {
+        if nodes.len() == 1 {
+            return Ok((nodes[0], 0.0));
+        }
+        let (left, right) = nodes.split_at(nodes.len() / 2);
+        let (left, left_time) = build(tables, left)?;
+        let (right, right_time) = build(tables, right)?;
+        let time = left_time.max(right_time) + 1.0;
+        Ok((add_parent(tables, left, right, time)?, time))
+    }
+    build(&mut tables, &samples)?;
+    tree_sequence(tables)
+}
+
+/// A caterpillar tree.
+///
+/// The first two samples coalesce at time one, and each other
+/// sample joins their ancestor in turn, one unit of time later.
+///
+/// # Errors
+///
+/// * [`TskitError::ValueError`] if `num_samples` is zero.
+/// * [`TskitError`] if `sequence_length` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// let ts = tskit::testing::caterpillar(4, 10.).unwrap();
+/// // (((0,1),2),3)
+/// let root = tskit::NodeId::from(6);
+/// assert_eq!(ts.nodes().time(root).unwrap(), 3.0);
+/// ```
+pub fn caterpillar<P: Into<Position>>(
+    num_samples: usize,
+    sequence_length: P,
+) -> Result<TreeSequence, TskitError> {
+    let (mut tables, samples) = new_tables(num_samples, sequence_length)?;
+    let mut ancestor = samples[0];
+    for (i, &sample) in samples.iter().enumerate().skip(1) {
+        ancestor = add_parent(&mut tables, ancestor, sample, i as f64)?;
+    }
+    tree_sequence(tables)
+}
+
+/// A random binary tree of the coalescent.
+///
+/// Lineages coalesce in random pairs, and the time while there
+/// are `k` lineages is exponential with rate `k * (k - 1) / 2`,
+/// as in Kingman's coalescent with time in units of `2N` generations.
+/// The tree only depends on the arguments.
+///
+/// # Errors
+///
+/// * [`TskitError::ValueError`] if `num_samples` is zero.
+/// * [`TskitError`] if `sequence_length` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// let ts = tskit::testing::random_binary(10, 1e6, 42).unwrap();
+/// let again = tskit::testing::random_binary(10, 1e6, 42).unwrap();
+/// assert_eq!(ts.nodes().time_slice(), again.nodes().time_slice());
+/// assert_eq!(ts.edges().child_slice(), again.edges().child_slice());
+/// ```
+pub fn random_binary<P: Into<Position>>(
+    num_samples: usize,
+    sequence_length: P,
+    seed: u64,
+) -> Result<TreeSequence, TskitError> {
+    let (mut tables, mut lineages) = new_tables(num_samples, sequence_length)?;
+    let mut rng = SplitMix64(seed);
+    let mut time = 0.0;
+    while lineages.len() > 1 {
+        let k = lineages.len() as f64;
+        time += -rng.next_f64().ln() / (k * (k - 1.0) / 2.0);
+        let left = lineages.swap_remove(rng.below(lineages.len()));
+        let right = lineages.swap_remove(rng.below(lineages.len()));
+        let parent = add_parent(&mut tables, left, right, time)?;
+        lineages.push(parent);
+    }
+    tree_sequence(tables)
+}
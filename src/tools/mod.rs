@@ -0,0 +1,20 @@
+//! Practical entry points to the crate, as library functions.
+//!
+//! These functions are used by the `tskit-tools` program,
+//! which is built with the `tools` feature:
+//!
+//! ```text
+//! tskit-tools summary file.trees
+//! tskit-tools traverse file.trees
+//! tskit-tools vcf file.trees --contig chr1 > file.vcf
+//! ```
+//!
+//! Requires the `tools` feature.
+
+mod summary;
+mod traversal;
+mod vcf;
+
+pub use summary::{summary, TreeSequenceSummary};
+pub use traversal::{traversal_report, TreeReport};
+pub use vcf::write_vcf;
@@ -0,0 +1,91 @@
+use crate::Position;
+use crate::SizeType;
+use crate::Time;
+use crate::TreeSequence;
+
+/// The sizes of the tables of a tree sequence.
+///
+/// The [`Display`](std::fmt::Display) implementation writes
+/// one `name value` line per field.
+///
+/// See [`summary`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreeSequenceSummary {
+    /// The sequence length
+    pub sequence_length: Position,
+    /// The number of trees
+    pub num_trees: SizeType,
+    /// The number of samples
+    pub num_samples: SizeType,
+    /// The number of nodes
+    pub num_nodes: SizeType,
+    /// The number of edges
+    pub num_edges: SizeType,
+    /// The number of sites
+    pub num_sites: SizeType,
+    /// The number of mutations
+    pub num_mutations: SizeType,
+    /// The number of individuals
+    pub num_individuals: SizeType,
+    /// The number of populations
+    pub num_populations: SizeType,
+    /// The number of migrations
+    pub num_migrations: SizeType,
+    /// The number of provenances
+    pub num_provenances: SizeType,
+    /// The youngest and oldest node times, or `None`
+    /// if there are no nodes
+    pub time_range: Option<(Time, Time)>,
+}
+
+impl std::fmt::Display for TreeSequenceSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "sequence_length {}", self.sequence_length)?;
+        for (name, value) in [
+            ("trees", self.num_trees),
+            ("samples", self.num_samples),
+            ("nodes", self.num_nodes),
+            ("edges", self.num_edges),
+            ("sites", self.num_sites),
+            ("mutations", self.num_mutations),
+            ("individuals", self.num_individuals),
+            ("populations", self.num_populations),
+            ("migrations", self.num_migrations),
+            ("provenances", self.num_provenances),
+        ] {
+            writeln!(f, "{} {}", name, value)?;
+        }
+        match self.time_range {
+            Some((min, max)) => writeln!(f, "time_range {} {}", min, max),
+            None => writeln!(f, "time_range NA NA"),
+        }
+    }
+}
+
+/// Summarize the tables of a tree sequence.
+///
+/// # Examples
+///
+/// ```
+/// let ts = tskit::testing::caterpillar(3, 10.).unwrap();
+/// let summary = tskit::tools::summary(&ts);
+/// assert_eq!(summary.num_nodes, 5);
+/// assert_eq!(summary.time_range, Some((0.0.into(), 2.0.into())));
+/// assert!(summary.to_string().contains("edges 4\n"));
+/// ```
+pub fn summary(treeseq: &TreeSequence) -> TreeSequenceSummary {
+    TreeSequenceSummary {
+        sequence_length: treeseq.inner.sequence_length().into(),
+        num_trees: treeseq.num_trees(),
+        num_samples: treeseq.num_samples(),
+        num_nodes: treeseq.num_nodes(),
+        num_edges: treeseq.num_edges(),
+        num_sites: treeseq.num_sites(),
+        num_mutations: treeseq.num_mutations(),
+        num_individuals: treeseq.num_individuals(),
+        num_populations: treeseq.num_populations(),
+        num_migrations: treeseq.num_migrations(),
+        num_provenances: treeseq.num_provenances(),
+        time_range: treeseq.nodes().time_minmax(),
+    }
+}
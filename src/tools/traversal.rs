@@ -0,0 +1,94 @@
+use streaming_iterator::StreamingIterator;
+
+use crate::NodeTraversalOrder;
+use crate::Position;
+use crate::Time;
+use crate::TreeFlags;
+use crate::TreeSequence;
+use crate::TskitError;
+
+/// Properties of a tree found by traversing it.
+///
+/// See [`traversal_report`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreeReport {
+    /// The index of the tree
+    pub index: usize,
+    /// The left coordinate of the tree
+    pub left: Position,
+    /// The right coordinate of the tree
+    pub right: Position,
+    /// The number of roots
+    pub num_roots: usize,
+    /// The number of nodes reached from the roots
+    pub num_nodes: usize,
+    /// The largest number of edges from a sample to its root
+    pub max_depth: usize,
+    /// The sum of the branch lengths
+    pub total_branch_length: Time,
+}
+
+impl TreeReport {
+    /// The names of the fields, separated by tabs, as written by
+    /// the [`Display`](std::fmt::Display) implementation.
+    pub const HEADER: &'static str =
+        "index\tleft\tright\tnum_roots\tnum_nodes\tmax_depth\ttotal_branch_length";
+}
+
+impl std::fmt::Display for TreeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.index,
+            self.left,
+            self.right,
+            self.num_roots,
+            self.num_nodes,
+            self.max_depth,
+            self.total_branch_length
+        )
+    }
+}
+
+/// Traverse each tree of a tree sequence, from the samples
+/// up to the roots and from the roots down to the leaves.
+///
+/// # Errors
+///
+/// * [`TskitError`] if the `C` back end is unable to iterate over
+///   the trees.
+///
+/// # Examples
+///
+/// ```
+/// let ts = tskit::testing::caterpillar(4, 10.).unwrap();
+/// let report = tskit::tools::traversal_report(&ts).unwrap();
+/// assert_eq!(report.len(), 1);
+/// assert_eq!(report[0].num_nodes, 7);
+/// assert_eq!(report[0].max_depth, 3);
+/// assert_eq!(report[0].total_branch_length, 9.0);
+/// ```
+pub fn traversal_report(treeseq: &TreeSequence) -> Result<Vec<TreeReport>, TskitError> {
+    let mut rv = vec![];
+    let mut trees = treeseq.tree_iterator(TreeFlags::default())?;
+    while let Some(tree) = trees.next() {
+        let (left, right) = tree.interval();
+        rv.push(TreeReport {
+            index: rv.len(),
+            left,
+            right,
+            num_roots: tree.roots().count(),
+            num_nodes: tree.traverse_nodes(NodeTraversalOrder::Preorder).count(),
+            // parents includes the sample
+            max_depth: tree
+                .sample_nodes()
+                .iter()
+                .map(|&s| tree.parents(s).count() - 1)
+                .max()
+                .unwrap_or(0),
+            total_branch_length: tree.total_branch_length(false)?,
+        });
+    }
+    Ok(rv)
+}
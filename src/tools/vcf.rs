@@ -0,0 +1,202 @@
+use std::io::Write;
+
+use streaming_iterator::StreamingIterator;
+
+use crate::sys::bindings as ll_bindings;
+use crate::TreeSequence;
+use crate::TskitError;
+use crate::VariantOptions;
+
+fn io_error(e: std::io::Error) -> TskitError {
+    TskitError::LibraryError(e.to_string())
+}
+
+fn write_header<W: Write>(
+    writer: &mut W,
+    contig_id: &str,
+    sequence_length: f64,
+    names: &[String],
+) -> std::io::Result<()> {
+    writeln!(writer, "##fileformat=VCFv4.2")?;
+    writeln!(writer, "##source=tskit-rust {}", crate::version())?;
+    writeln!(
+        writer,
+        "##FILTER=<ID=PASS,Description=\"All filters passed\">"
+    )?;
+    writeln!(
+        writer,
+        "##contig=<ID={},length={}>",
+        contig_id,
+        sequence_length.ceil()
+    )?;
+    writeln!(
+        writer,
+        "##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">"
+    )?;
+    write!(
+        writer,
+        "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT"
+    )?;
+    for name in names {
+        write!(writer, "\ttsk_{}", name)?;
+    }
+    writeln!(writer)
+}
+
+// Write the genotypes of each column, separated by '|'
+fn write_genotypes<'a, W: Write>(
+    writer: &mut W,
+    columns: impl Iterator<Item = &'a [i32]>,
+) -> std::io::Result<()> {
+    for genotypes in columns {
+        for (i, &g) in genotypes.iter().enumerate() {
+            let sep = if i == 0 { "\t" } else { "|" };
+            match g {
+                ll_bindings::TSK_MISSING_DATA => write!(writer, "{}.", sep)?,
+                g => write!(writer, "{}{}", sep, g)?,
+            }
+        }
+    }
+    writeln!(writer)
+}
+
+/// Write the sites of a tree sequence in VCF format.
+///
+/// If every sample node belongs to an individual, there is one
+/// column per individual, named `tsk_<individual id>`, with phased
+/// genotypes of the sample nodes of the individual.
+/// If no sample node belongs to an individual, there is one haploid
+/// column per sample node, named `tsk_<node id>`.
+///
+/// The VCF position of a site is its position rounded to the
+/// nearest integer.
+/// Sites whose positions round to the same integer are an error,
+/// because VCF positions of a contig must be distinct.
+/// The reference allele is the ancestral state of the site.
+///
+/// # Parameters
+///
+/// * `contig_id`: the name of the chromosome.
+/// * `writer`: where to write the VCF.
+///
+/// # Errors
+///
+/// * [`TskitError::ValueError`] if a site rounds to position zero,
+///   which is not a valid VCF position.
+/// * [`TskitError::ValueError`] if a site rounds to the same position
+///   as the previous site.
+/// * [`TskitError::ValueError`] if some, but not all, sample nodes
+///   belong to an individual.
+/// * [`TskitError::LibraryError`] if writing fails.
+/// * [`TskitError`] if decoding genotypes fails.
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+/// for _ in 0..2 {
+///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+///     tables.add_edge(0., 100., p, c).unwrap();
+/// }
+/// let site = tables.add_site(10.2, Some(b"A")).unwrap();
+/// tables.add_mutation(site, 2, -1, 0.5, Some(b"T")).unwrap();
+/// tables.build_index().unwrap();
+/// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+///
+/// let mut vcf = vec![];
+/// tskit::tools::write_vcf(&ts, "chr1", &mut vcf).unwrap();
+/// let vcf = String::from_utf8(vcf).unwrap();
+/// let lines = vcf.lines().collect::<Vec<_>>();
+/// assert_eq!(lines[0], "##fileformat=VCFv4.2");
+/// assert_eq!(lines[lines.len() - 2].split('\t').last(), Some("tsk_2"));
+/// assert_eq!(lines[lines.len() - 1], "chr1\t10\t0\tA\tT\t.\tPASS\t.\tGT\t0\t1");
+/// ```
+pub fn write_vcf<W: Write>(
+    treeseq: &TreeSequence,
+    contig_id: &str,
+    mut writer: W,
+) -> Result<(), TskitError> {
+    let nodes = treeseq.nodes();
+    let individuals = if treeseq
+        .sample_nodes()
+        .iter()
+        .any(|&u| nodes.individual(u).is_some_and(|i| !i.is_null()))
+    {
+        Some(treeseq.individual_samples(None)?)
+    } else {
+        None
+    };
+    let (samples, names) = match &individuals {
+        Some(individuals) => (
+            individuals.samples().to_vec(),
+            individuals
+                .individuals()
+                .iter()
+                .map(|i| i.to_string())
+                .collect(),
+        ),
+        None => (
+            treeseq.sample_nodes().to_vec(),
+            treeseq
+                .sample_nodes()
+                .iter()
+                .map(|u| u.to_string())
+                .collect::<Vec<_>>(),
+        ),
+    };
+
+    write_header(
+        &mut writer,
+        contig_id,
+        treeseq.inner.sequence_length(),
+        &names,
+    )
+    .map_err(io_error)?;
+
+    let mut variants = treeseq.variants(Some(&samples), VariantOptions::default())?;
+    let mut previous = 0.0;
+    while let Some(variant) = variants.next() {
+        let position = f64::from(variant.position().unwrap()).round();
+        if position < 1.0 {
+            return Err(TskitError::ValueError {
+                got: format!("site {} at position {}", variant.site(), position),
+                expected: "VCF positions of at least 1".to_string(),
+            });
+        }
+        if position == previous {
+            return Err(TskitError::ValueError {
+                got: format!("site {} at duplicate position {}", variant.site(), position),
+                expected: "distinct VCF positions".to_string(),
+            });
+        }
+        previous = position;
+        let alleles = variant
+            .alleles()
+            .map(|a| String::from_utf8_lossy(a).into_owned())
+            .collect::<Vec<_>>();
+        let alt = match alleles.len() {
+            1 => ".".to_string(),
+            _ => alleles[1..].join(","),
+        };
+        write!(
+            writer,
+            "{}\t{}\t{}\t{}\t{}\t.\tPASS\t.\tGT",
+            contig_id,
+            position,
+            variant.site(),
+            alleles[0],
+            alt
+        )
+        .map_err(io_error)?;
+        let genotypes = variant.genotypes();
+        match &individuals {
+            Some(individuals) => {
+                write_genotypes(&mut writer, individuals.split(genotypes).unwrap())
+            }
+            None => write_genotypes(&mut writer, genotypes.chunks(1)),
+        }
+        .map_err(io_error)?;
+    }
+    Ok(())
+}
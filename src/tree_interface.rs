@@ -1,4 +1,5 @@
 use crate::sys;
+use crate::EdgeId;
 use crate::NodeId;
 use crate::Position;
 use crate::SizeType;
@@ -38,6 +39,18 @@ impl TreeInterface {
         unsafe { self.non_owned_pointer.as_ref() }
     }
 
+    // Node times of the tree sequence that this tree belongs to.
+    fn node_times(&self) -> &[Time] {
+        // SAFETY: tsk_tree_init set tree_sequence to the tree sequence
+        // that this tree borrows, so it and its tables outlive &self.
+        // Those tables cannot be modified while the tree sequence exists,
+        // and num_nodes is that tree sequence's number of nodes,
+        // which is the length of the node time column.
+        // generate_slice panics if the column pointer is null.
+        let tables = unsafe { &*(*self.as_ref().tree_sequence).tables };
+        sys::generate_slice(tables.nodes.time, self.num_nodes)
+    }
+
     /// Pointer to the low-level C type.
     pub fn as_ptr(&self) -> *const ll_bindings::tsk_tree_t {
         self.non_owned_pointer.as_ptr()
@@ -72,6 +85,13 @@ impl TreeInterface {
         sys::generate_slice(self.as_ref().parent, self.array_len)
     }
 
+    /// The id of the edge from each node to its parent, indexed by node.
+    ///
+    /// The value is [`EdgeId::NULL`] for nodes without a parent.
+    pub fn edge_array(&self) -> &[EdgeId] {
+        sys::generate_slice(self.as_ref().edge, self.array_len)
+    }
+
     /// # Failing examples
     ///
     /// An error will be returned if ['crate::TreeFlags::SAMPLE_LISTS`] is not used:
@@ -337,6 +357,17 @@ impl TreeInterface {
         i.1 - i.0
     }
 
+    /// Return the fraction of the genome for which this
+    /// tree is the ancestry.
+    ///
+    /// This value is [`TreeInterface::span`] divided by the sequence length.
+    pub fn span_as_fraction(&self) -> f64 {
+        // SAFETY: a tsk_tree_t always points to its (non-NULL) tree sequence
+        let sequence_length =
+            unsafe { ll_bindings::tsk_treeseq_get_sequence_length(self.as_ref().tree_sequence) };
+        f64::from(self.span()) / sequence_length
+    }
+
     /// Get the parent of node `u`.
     ///
     /// Returns `None` if `u` is out of range.
@@ -452,6 +483,11 @@ impl TreeInterface {
     ///
     /// * `order`: A value from [`NodeTraversalOrder`] specifying the
     ///   iteration order.
+    ///
+    /// # Panics
+    ///
+    /// [`NodeTraversalOrder::Postorder`] panics if the `C` back end
+    /// fails to allocate the node list.
     // Return value is dyn for later addition of other traversal orders
     pub fn traverse_nodes(
         &self,
@@ -481,14 +517,7 @@ impl TreeInterface {
     ///
     /// [`TskitError`] may be returned if a node index is out of range.
     pub fn total_branch_length(&self, by_span: bool) -> Result<Time, TskitError> {
-        let time: &[Time] = sys::generate_slice(
-            unsafe {
-                (*(*(*self.non_owned_pointer.as_ptr()).tree_sequence).tables)
-                    .nodes
-                    .time
-            },
-            self.num_nodes,
-        );
+        let time = self.node_times();
         let mut b = Time::from(0.);
         for n in self.traverse_nodes(NodeTraversalOrder::Preorder) {
             let p = self.parent(n).ok_or(TskitError::IndexError {})?;
@@ -503,6 +532,172 @@ impl TreeInterface {
         }
     }
 
+    /// Write the tree in Newick format.
+    ///
+    /// Children are written in the order of
+    /// [`TreeInterface::children`], and branch lengths are
+    /// differences of node times.
+    /// See [`NewickOptions`](crate::NewickOptions) for labels,
+    /// precision, and the choice of root.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the tree does not have a single
+    ///   root and no root is given by `options`.
+    /// * [`TskitError::IndexError`] if the root given by `options`
+    ///   is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    /// use tskit::NewickLabel;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let root = tables.add_node(0, 1.5, -1, -1).unwrap();
+    /// for name in ["A", "B C"] {
+    ///     let node = tables
+    ///         .add_node_with_metadata(
+    ///             tskit::NodeFlags::new_sample(), 0.0, -1, -1, &NewickLabel::from(name),
+    ///         )
+    ///         .unwrap();
+    ///     tables.add_edge(0., 100., root, node).unwrap();
+    /// }
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    /// let mut trees = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// let tree = trees.next().unwrap();
+    ///
+    /// let options = tskit::NewickOptions::default().precision(2);
+    /// assert_eq!(tree.as_newick(&options).unwrap(), "(1:1.50,2:1.50);");
+    ///
+    /// let nodes = treeseq.nodes();
+    /// let options = options.node_labels(|u| {
+    ///     nodes.metadata::<NewickLabel>(u).and_then(Result::ok).map(|label| label.0)
+    /// });
+    /// assert_eq!(tree.as_newick(&options).unwrap(), "(A:1.50,'B C':1.50);");
+    /// ```
+    pub fn as_newick(&self, options: &crate::NewickOptions) -> Result<String, TskitError> {
+        let time = self.node_times();
+        crate::newick::tree_to_newick(self, time, options)
+    }
+
+    /// Return the subtree of `root` in Newick format, as written
+    /// by the `C` API of `tskit`.
+    ///
+    /// Branch lengths are written with `precision` digits after
+    /// the decimal point.
+    /// By default, sample nodes are labelled `n<node id>`.
+    /// See [`NewickFlags`](crate::NewickFlags) for other labels.
+    /// For custom labels, see [`TreeInterface::as_newick`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `root` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let root = tables.add_node(0, 1.5, -1, -1).unwrap();
+    /// for _ in 0..2 {
+    ///     let node = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 100., root, node).unwrap();
+    /// }
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    /// let mut trees = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// let tree = trees.next().unwrap();
+    ///
+    /// let newick = tree.newick(root, 2, tskit::NewickFlags::default()).unwrap();
+    /// assert_eq!(newick, "(n1:1.50,n2:1.50);");
+    /// let newick = tree.newick(root, 0, tskit::NewickFlags::LEGACY_MS_LABELS).unwrap();
+    /// assert_eq!(newick, "(2:2,3:2);");
+    /// ```
+    pub fn newick<N: Into<NodeId>, O: Into<crate::NewickFlags>>(
+        &self,
+        root: N,
+        precision: u32,
+        options: O,
+    ) -> Result<String, TskitError> {
+        let root = root.into();
+        let options = options.into();
+        // The buffer is enlarged until the tree fits
+        let mut buffer = vec![0_u8; 1024];
+        loop {
+            // SAFETY: the tree is initialized and buffer has buffer.len() bytes
+            let code = unsafe {
+                ll_bindings::tsk_convert_newick(
+                    self.as_ptr(),
+                    root.into(),
+                    precision,
+                    options.bits(),
+                    buffer.len(),
+                    buffer.as_mut_ptr() as *mut std::os::raw::c_char,
+                )
+            };
+            match code {
+                ll_bindings::TSK_ERR_BUFFER_OVERFLOW => buffer.resize(2 * buffer.len(), 0),
+                code if code < 0 => return Err(TskitError::ErrorCode { code }),
+                _ => break,
+            }
+        }
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        buffer.truncate(len);
+        String::from_utf8(buffer).map_err(|e| TskitError::LibraryError(e.to_string()))
+    }
+
+    /// Map a value on `[0, 1)` to a point on the branches of the tree.
+    ///
+    /// The branches are laid end to end, in preorder, and `u`
+    /// gives the fraction of the total branch length at which
+    /// the point lies.
+    /// Thus, passing a uniform random deviate samples a point
+    /// uniformly with respect to branch length, and the
+    /// [`BranchPoint::node`] of the result is a lineage sampled
+    /// with probability proportional to its branch length.
+    ///
+    /// Returns `None` if the total branch length is zero.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `u` is not on `[0, 1)`.
+    /// * [`TskitError::IndexError`] if a node index is out of range.
+    pub fn branch_point(&self, u: f64) -> Result<Option<BranchPoint>, TskitError> {
+        if !(0.0..1.0).contains(&u) {
+            return Err(TskitError::ValueError {
+                got: format!("{u}"),
+                expected: "a value on [0, 1)".to_string(),
+            });
+        }
+        let time = self.node_times();
+        let mut target = f64::from(self.total_branch_length(false)?) * u;
+        let mut last = None;
+        for n in self.traverse_nodes(NodeTraversalOrder::Preorder) {
+            let p = self.parent(n).ok_or(TskitError::IndexError {})?;
+            if p == NodeId::NULL {
+                continue;
+            }
+            let length = f64::from(time[p.as_usize()] - time[n.as_usize()]);
+            if length <= 0.0 {
+                continue;
+            }
+            if target < length {
+                return Ok(Some(BranchPoint {
+                    node: n,
+                    time: time[n.as_usize()] + target.into(),
+                }));
+            }
+            target -= length;
+            last = Some((n, p));
+        }
+        // Rounding error may leave us just past the last branch
+        Ok(last.map(|(n, p)| BranchPoint {
+            node: n,
+            time: time[p.as_usize()],
+        }))
+    }
+
     /// Get the number of samples below node `u`.
     ///
     /// # Errors
@@ -520,6 +715,27 @@ impl TreeInterface {
         handle_tsk_return_value!(code, n.into())
     }
 
+    /// Get the number of samples in the tree.
+    ///
+    /// This is the number of samples below [`TreeInterface::virtual_root`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if [`TreeFlags::NO_SAMPLE_COUNTS`].
+    pub fn num_samples(&self) -> Result<SizeType, TskitError> {
+        let mut n = tsk_size_t::MAX;
+        let np: *mut tsk_size_t = &mut n;
+        let code = unsafe {
+            ll_bindings::tsk_tree_get_num_samples(self.as_ptr(), self.virtual_root().into(), np)
+        };
+        handle_tsk_return_value!(code, n.into())
+    }
+
+    /// Get the number of edges in the tree.
+    pub fn num_edges(&self) -> SizeType {
+        self.as_ref().num_edges.into()
+    }
+
     /// Calculate the average Kendall-Colijn (`K-C`) distance between
     /// pairs of trees whose intervals overlap.
     ///
@@ -547,6 +763,18 @@ impl TreeInterface {
     }
 }
 
+/// A point on a branch of a tree.
+///
+/// See [`TreeInterface::branch_point`].
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct BranchPoint {
+    /// The node below the branch.
+    pub node: NodeId,
+    /// The time of the point, which lies between
+    /// the times of `node` and of its parent.
+    pub time: Time,
+}
+
 /// Specify the traversal order used by
 /// [`TreeInterface::traverse_nodes`].
 #[non_exhaustive]
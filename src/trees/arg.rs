@@ -0,0 +1,62 @@
+use crate::NodeId;
+use crate::Position;
+use crate::Time;
+
+use super::TreeSequence;
+
+/// A record of the ancestral recombination graph (ARG).
+///
+/// Each record states that `child` inherits the genomic
+/// interval `[left, right)` from `parent`.
+///
+/// See [`TreeSequence::arg_edges`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArgEdge {
+    pub child: NodeId,
+    pub parent: NodeId,
+    pub left: Position,
+    pub right: Position,
+    pub child_time: Time,
+    pub parent_time: Time,
+}
+
+pub(crate) fn arg_edges(treeseq: &TreeSequence, squash: bool) -> Vec<ArgEdge> {
+    let edges = treeseq.edges();
+    let time = treeseq.nodes().time_slice();
+    let mut rv = edges
+        .left_slice()
+        .iter()
+        .zip(edges.right_slice())
+        .zip(edges.parent_slice().iter().zip(edges.child_slice()))
+        .map(|((&left, &right), (&parent, &child))| ArgEdge {
+            child,
+            parent,
+            left,
+            right,
+            child_time: time[child.as_usize()],
+            parent_time: time[parent.as_usize()],
+        })
+        .collect::<Vec<_>>();
+
+    if squash {
+        rv.sort_by(|a, b| {
+            a.child
+                .cmp(&b.child)
+                .then(a.parent.cmp(&b.parent))
+                .then(f64::from(a.left).total_cmp(&f64::from(b.left)))
+        });
+        let mut squashed: Vec<ArgEdge> = Vec::with_capacity(rv.len());
+        for e in rv {
+            match squashed.last_mut() {
+                Some(last)
+                    if last.child == e.child && last.parent == e.parent && last.right == e.left =>
+                {
+                    last.right = e.right
+                }
+                _ => squashed.push(e),
+            }
+        }
+        rv = squashed;
+    }
+    rv
+}
@@ -0,0 +1,171 @@
+use std::cell::OnceCell;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::EdgeIntervalIndex;
+use crate::NodeId;
+use crate::PopulationId;
+use crate::Position;
+use crate::SampleSets;
+use crate::StatisticsOptions;
+use crate::StatsMode;
+use crate::TskitError;
+use crate::Windows;
+
+use super::TreeSequence;
+
+// The bits of the window and mask intervals, as positions
+// are not hashable
+type IntervalBits = Vec<(u64, u64)>;
+type DivergenceKey = (SampleSets, IntervalBits, IntervalBits, StatisticsOptions);
+
+fn interval_bits(intervals: &[(Position, Position)]) -> IntervalBits {
+    intervals
+        .iter()
+        .map(|&(left, right)| (f64::from(left).to_bits(), f64::from(right).to_bits()))
+        .collect()
+}
+
+/// Memoized values derived from a [`TreeSequence`].
+///
+/// Each value is calculated on first request and shared
+/// by later requests with the same parameters.
+/// A tree sequence cannot be modified while it is borrowed
+/// by a cache, so cached values never become stale.
+///
+/// This type is not thread-safe.
+/// Create one cache per thread when needed.
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// let pop = tables.add_population().unwrap();
+/// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+/// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop, -1).unwrap();
+/// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+/// tables.add_edge(0., 100., parent, c0).unwrap();
+/// tables.add_edge(0., 100., parent, c1).unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+///
+/// let cache = treeseq.cache();
+/// let samples = cache.population_samples(pop);
+/// assert_eq!(&samples[..], &[c0]);
+/// // The second request shares the first result
+/// assert!(std::rc::Rc::ptr_eq(&samples, &cache.population_samples(pop)));
+///
+/// let sample_sets = tskit::SampleSets::new().add_set([c0]).add_set([c1]);
+/// let genome = tskit::Windows::genome();
+/// let branch = tskit::StatsMode::Branch;
+/// let options = tskit::StatisticsOptions::default().span_normalise();
+/// let d = cache.divergence_matrix(&sample_sets, &genome, branch, options).unwrap();
+/// assert_eq!(d[1], 2.0);
+/// ```
+pub struct TreeSequenceCache<'treeseq> {
+    treeseq: &'treeseq TreeSequence,
+    population_samples: RefCell<HashMap<PopulationId, Rc<[NodeId]>>>,
+    tree_spans: OnceCell<Rc<[Position]>>,
+    edge_interval_index: OnceCell<Rc<EdgeIntervalIndex>>,
+    divergence_matrices: RefCell<HashMap<DivergenceKey, Rc<[f64]>>>,
+}
+
+impl<'treeseq> TreeSequenceCache<'treeseq> {
+    pub(crate) fn new(treeseq: &'treeseq TreeSequence) -> Self {
+        Self {
+            treeseq,
+            population_samples: RefCell::new(HashMap::new()),
+            tree_spans: OnceCell::new(),
+            edge_interval_index: OnceCell::new(),
+            divergence_matrices: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// The cached tree sequence.
+    pub fn treeseq(&self) -> &'treeseq TreeSequence {
+        self.treeseq
+    }
+
+    /// The sample nodes assigned to `population`, in the
+    /// order of [`TreeSequence::sample_nodes`].
+    ///
+    /// Use [`PopulationId::NULL`] for samples without a population.
+    pub fn population_samples<P: Into<PopulationId>>(&self, population: P) -> Rc<[NodeId]> {
+        let population = population.into();
+        self.population_samples
+            .borrow_mut()
+            .entry(population)
+            .or_insert_with(|| {
+                let nodes = self.treeseq.nodes();
+                self.treeseq
+                    .sample_nodes()
+                    .iter()
+                    .copied()
+                    .filter(|&s| nodes.population(s) == Some(population))
+                    .collect()
+            })
+            .clone()
+    }
+
+    /// The span of each tree.
+    ///
+    /// See [`TreeSequence::tree_spans`].
+    pub fn tree_spans(&self) -> Rc<[Position]> {
+        self.tree_spans
+            .get_or_init(|| self.treeseq.tree_spans().into())
+            .clone()
+    }
+
+    /// An index of the genomic intervals of the edges.
+    ///
+    /// See [`EdgeTable::interval_index`](crate::EdgeTable::interval_index).
+    pub fn edge_interval_index(&self) -> Rc<EdgeIntervalIndex> {
+        self.edge_interval_index
+            .get_or_init(|| Rc::new(self.treeseq.edges().interval_index()))
+            .clone()
+    }
+
+    /// The divergence matrices of `sample_sets`.
+    ///
+    /// Results are cached for each combination of
+    /// `sample_sets`, `windows`, `mode`, and `options`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TreeSequence::divergence_matrix`].
+    /// Errors are not cached.
+    pub fn divergence_matrix<O: Into<StatisticsOptions>>(
+        &self,
+        sample_sets: &SampleSets,
+        windows: &Windows,
+        mode: StatsMode,
+        options: O,
+    ) -> Result<Rc<[f64]>, TskitError> {
+        let options = crate::stats::with_mode(options.into(), mode);
+        let key = (
+            sample_sets.clone(),
+            interval_bits(&windows.resolve(self.treeseq.inner.sequence_length())),
+            interval_bits(windows.genome_mask().map_or(&[], |m| m.intervals())),
+            options,
+        );
+        if let Some(matrix) = self.divergence_matrices.borrow().get(&key) {
+            return Ok(matrix.clone());
+        }
+        let matrix: Rc<[f64]> = self
+            .treeseq
+            .divergence_matrix(sample_sets, windows, mode, options)?
+            .into();
+        self.divergence_matrices
+            .borrow_mut()
+            .insert(key, matrix.clone());
+        Ok(matrix)
+    }
+
+    /// Remove all cached values.
+    pub fn clear(&mut self) {
+        self.population_samples.get_mut().clear();
+        self.tree_spans.take();
+        self.edge_interval_index.take();
+        self.divergence_matrices.get_mut().clear();
+    }
+}
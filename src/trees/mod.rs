@@ -1,5 +1,15 @@
+mod arg;
+mod cache;
+mod populations;
+mod sites;
+mod spans;
 mod tree;
 mod treeseq;
 
+pub use arg::ArgEdge;
+pub use cache::TreeSequenceCache;
+pub use populations::{MutationPopulationFrequencies, PopulationSummary};
+pub use sites::{TreeSite, TreeSiteIterator};
+pub use spans::{EdgeSpanSummary, NodeSpanSummary};
 pub use tree::Tree;
 pub use treeseq::TreeSequence;
@@ -0,0 +1,168 @@
+use crate::IndividualId;
+use crate::MutationId;
+use crate::NodeId;
+use crate::PopulationId;
+use crate::SiteId;
+use crate::StatisticsOptions;
+use crate::StatsMode;
+use crate::TskitError;
+use crate::Windows;
+
+use super::TreeSequence;
+
+/// Summary of the samples of a population.
+///
+/// See [`TreeSequence::population_summaries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationSummary {
+    /// The population.
+    pub population: PopulationId,
+    /// The number of sample nodes in the population.
+    pub num_samples: usize,
+    /// The number of individuals with sample nodes
+    /// in the population.
+    pub num_individuals: usize,
+    /// The mean pairwise diversity of the sample nodes
+    /// in each window.
+    /// `NaN` if there are fewer than two sample nodes.
+    pub diversity: Vec<f64>,
+}
+
+/// The frequency of the derived state of a mutation
+/// within the samples of each population.
+///
+/// See [`TreeSequence::mutation_population_frequencies`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutationPopulationFrequencies {
+    /// The mutation.
+    pub mutation: MutationId,
+    /// The site of the mutation.
+    pub site: SiteId,
+    /// The number of sample nodes of each population that
+    /// carry the derived state, indexed by population id.
+    pub counts: Vec<usize>,
+    /// The counts divided by the number of sample nodes of
+    /// each population.
+    /// `NaN` for populations without sample nodes.
+    pub frequencies: Vec<f64>,
+}
+
+pub(crate) fn population_summaries(
+    treeseq: &TreeSequence,
+    windows: &Windows,
+    mode: StatsMode,
+    options: StatisticsOptions,
+) -> Result<Vec<PopulationSummary>, TskitError> {
+    crate::stats::reject_node_mode(mode)?;
+    let num_populations = treeseq.populations().num_rows().as_usize();
+    let node_populations = treeseq.nodes().population_slice();
+    let node_individuals = treeseq.nodes().individual_slice();
+
+    let mut samples: Vec<Vec<NodeId>> = vec![vec![]; num_populations];
+    let mut individuals: Vec<Vec<IndividualId>> = vec![vec![]; num_populations];
+    for &u in treeseq.sample_nodes() {
+        let Some(p) = node_populations[u.as_usize()].to_usize() else {
+            continue;
+        };
+        if p >= num_populations {
+            return Err(TskitError::IndexError);
+        }
+        samples[p].push(u);
+        let individual = node_individuals[u.as_usize()];
+        if !individual.is_null() {
+            individuals[p].push(individual);
+        }
+    }
+
+    // Diversity is undefined for fewer than two samples
+    let sample_sets = samples
+        .iter()
+        .filter(|s| s.len() > 1)
+        .map(|s| s.as_slice())
+        .collect::<Vec<_>>();
+    let sequence_length = treeseq.inner.sequence_length();
+    let intervals = windows.resolve(sequence_length);
+    crate::stats::check_windows(sequence_length, &intervals, windows.genome_mask())?;
+    let diversity = if sample_sets.is_empty() {
+        vec![]
+    } else {
+        crate::stats::one_way_stat(
+            treeseq,
+            crate::sys::bindings::tsk_treeseq_diversity,
+            &sample_sets,
+            windows,
+            crate::stats::with_mode(options, mode),
+        )?
+    };
+    let mut column = 0;
+
+    Ok(samples
+        .iter()
+        .zip(individuals.iter_mut())
+        .enumerate()
+        .map(|(p, (samples, individuals))| {
+            individuals.sort();
+            individuals.dedup();
+            PopulationSummary {
+                population: PopulationId::from(p as i32),
+                num_samples: samples.len(),
+                num_individuals: individuals.len(),
+                diversity: if samples.len() > 1 {
+                    column += 1;
+                    diversity
+                        .iter()
+                        .skip(column - 1)
+                        .step_by(sample_sets.len())
+                        .copied()
+                        .collect()
+                } else {
+                    vec![f64::NAN; intervals.len()]
+                },
+            }
+        })
+        .collect())
+}
+
+pub(crate) fn mutation_population_frequencies(
+    treeseq: &TreeSequence,
+) -> Result<Vec<MutationPopulationFrequencies>, TskitError> {
+    let num_populations = treeseq.populations().num_rows().as_usize();
+    let node_populations = treeseq.nodes().population_slice();
+    let mutation_sites = treeseq.mutations().site_slice();
+
+    let mut samples: Vec<Vec<NodeId>> = vec![vec![]; num_populations];
+    for &u in treeseq.sample_nodes() {
+        let Some(p) = node_populations[u.as_usize()].to_usize() else {
+            continue;
+        };
+        if p >= num_populations {
+            return Err(TskitError::IndexError);
+        }
+        samples[p].push(u);
+    }
+    let num_samples = samples.iter().map(|s| s.len()).collect::<Vec<_>>();
+    let sample_sets = samples.iter().map(|s| s.as_slice()).collect::<Vec<_>>();
+    let carriers = crate::allele_counts::mutation_carrier_counts(treeseq, &sample_sets)?;
+
+    Ok(mutation_sites
+        .iter()
+        .enumerate()
+        .map(|(m, &site)| {
+            let counts = carriers[m * num_populations..(m + 1) * num_populations]
+                .iter()
+                .map(|&c| c as usize)
+                .collect::<Vec<_>>();
+            let frequencies = counts
+                .iter()
+                .zip(num_samples.iter())
+                .map(|(&c, &n)| if n > 0 { c as f64 / n as f64 } else { f64::NAN })
+                .collect();
+            MutationPopulationFrequencies {
+                mutation: MutationId::from(m as i32),
+                site,
+                counts,
+                frequencies,
+            }
+        })
+        .collect())
+}
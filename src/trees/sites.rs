@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use streaming_iterator::StreamingIterator;
+
+use crate::MutationId;
+use crate::Position;
+use crate::SiteId;
+
+use super::Tree;
+
+/// A site of a tree and the range of its mutations.
+///
+/// See [`TreeSiteIterator::sites`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeSite {
+    /// The site id.
+    pub id: SiteId,
+    /// The position of the site.
+    pub position: Position,
+    mutations: Range<usize>,
+}
+
+impl TreeSite {
+    /// The ids of the mutations at the site.
+    pub fn mutations(&self) -> impl Iterator<Item = MutationId> {
+        self.mutations.clone().map(|m| MutationId::from(m as i32))
+    }
+
+    /// The number of mutations at the site.
+    pub fn num_mutations(&self) -> usize {
+        self.mutations.len()
+    }
+}
+
+/// A [`StreamingIterator`] over trees and the sites
+/// in their genomic intervals.
+///
+/// See [`TreeSequence::trees_with_sites`](crate::TreeSequence::trees_with_sites).
+pub struct TreeSiteIterator<'treeseq> {
+    tree: Tree<'treeseq>,
+    positions: &'treeseq [Position],
+    mutation_sites: &'treeseq [SiteId],
+    sites: Range<usize>,
+    mutations: Range<usize>,
+}
+
+impl<'treeseq> TreeSiteIterator<'treeseq> {
+    pub(crate) fn new(
+        tree: Tree<'treeseq>,
+        positions: &'treeseq [Position],
+        mutation_sites: &'treeseq [SiteId],
+    ) -> Self {
+        Self {
+            tree,
+            positions,
+            mutation_sites,
+            sites: 0..0,
+            mutations: 0..0,
+        }
+    }
+
+    /// The current tree.
+    pub fn tree(&self) -> &Tree<'treeseq> {
+        &self.tree
+    }
+
+    /// The genomic interval of the current tree.
+    pub fn interval(&self) -> (Position, Position) {
+        self.tree.interval()
+    }
+
+    /// The number of sites in the interval of the current tree.
+    pub fn num_sites(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// The number of mutations at the sites of the current tree.
+    pub fn num_mutations(&self) -> usize {
+        self.mutations.len()
+    }
+
+    /// The sites in the interval of the current tree,
+    /// in order of position.
+    pub fn sites(&self) -> impl Iterator<Item = TreeSite> + '_ {
+        let mut next_mutation = self.mutations.start;
+        self.sites.clone().map(move |s| {
+            let start = next_mutation;
+            while next_mutation < self.mutations.end
+                && self.mutation_sites[next_mutation].as_usize() == s
+            {
+                next_mutation += 1;
+            }
+            TreeSite {
+                id: SiteId::from(s as i32),
+                position: self.positions[s],
+                mutations: start..next_mutation,
+            }
+        })
+    }
+}
+
+impl<'treeseq> StreamingIterator for TreeSiteIterator<'treeseq> {
+    type Item = Self;
+
+    fn advance(&mut self) {
+        self.tree.advance();
+        if self.tree.get().is_none() {
+            self.sites = self.sites.end..self.sites.end;
+            self.mutations = self.mutations.end..self.mutations.end;
+            return;
+        }
+        let right = self.tree.interval().1;
+        let start = self.sites.end;
+        let end = start + self.positions[start..].partition_point(|&p| p < right);
+        self.sites = start..end;
+        let start = self.mutations.end;
+        let num_mutations = self.mutation_sites[start..].partition_point(|s| s.as_usize() < end);
+        self.mutations = start..start + num_mutations;
+    }
+
+    fn get(&self) -> Option<&Self::Item> {
+        self.tree.get().map(|_| self)
+    }
+}
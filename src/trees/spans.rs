@@ -0,0 +1,117 @@
+use crate::EdgeId;
+use crate::NodeId;
+use crate::NodeTraversalOrder;
+use crate::Position;
+use crate::TreeFlags;
+use crate::TskitError;
+
+use super::TreeSequence;
+
+/// Summary of the genomic span over which a node is an ancestor.
+///
+/// See [`TreeSequence::node_span_summaries`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeSpanSummary {
+    /// The sum of the spans of the edges of which the node
+    /// is the parent.
+    /// Edges to different children that overlap are each counted.
+    pub ancestral_span: Position,
+    /// The number of samples descending from the node, averaged
+    /// over the genome where the node has at least one child.
+    /// `NaN` if the node has no children.
+    pub mean_descendant_samples: f64,
+}
+
+/// Summary of the samples descending through an edge.
+///
+/// See [`TreeSequence::edge_span_summaries`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeSpanSummary {
+    /// The edge.
+    pub edge: EdgeId,
+    /// The length of the genomic interval of the edge.
+    pub span: Position,
+    /// The number of samples descending from the child of the
+    /// edge, averaged over `span`.
+    pub mean_descendant_samples: f64,
+}
+
+pub(crate) fn node_span_summaries(
+    treeseq: &TreeSequence,
+) -> Result<Vec<NodeSpanSummary>, TskitError> {
+    use streaming_iterator::StreamingIterator;
+    let num_nodes = treeseq.nodes().num_rows().as_usize();
+    let mut span = vec![0.0; num_nodes];
+    let mut weighted_samples = vec![0.0; num_nodes];
+    let mut tree = treeseq.tree_iterator(TreeFlags::default())?;
+    while let Some(tree) = tree.next() {
+        let tree_span = f64::from(tree.span());
+        for node in tree.traverse_nodes(NodeTraversalOrder::Preorder) {
+            if tree
+                .left_child(node)
+                .ok_or(TskitError::IndexError)?
+                .is_null()
+            {
+                continue;
+            }
+            let samples = u64::from(tree.num_tracked_samples(node)?) as f64;
+            span[node.as_usize()] += tree_span;
+            weighted_samples[node.as_usize()] += samples * tree_span;
+        }
+    }
+
+    let edges = treeseq.edges();
+    let mut ancestral_span = vec![0.0; num_nodes];
+    for ((&parent, &left), &right) in edges
+        .parent_slice()
+        .iter()
+        .zip(edges.left_slice())
+        .zip(edges.right_slice())
+    {
+        ancestral_span[parent.as_usize()] += f64::from(right - left);
+    }
+
+    Ok(span
+        .into_iter()
+        .zip(weighted_samples)
+        .zip(ancestral_span)
+        .map(|((s, w), a)| NodeSpanSummary {
+            ancestral_span: a.into(),
+            mean_descendant_samples: if s > 0.0 { w / s } else { f64::NAN },
+        })
+        .collect())
+}
+
+pub(crate) fn edge_span_summaries(
+    treeseq: &TreeSequence,
+) -> Result<Vec<EdgeSpanSummary>, TskitError> {
+    use streaming_iterator::StreamingIterator;
+    let edges = treeseq.edges();
+    let mut weighted_samples = vec![0.0; edges.num_rows().as_usize()];
+    let mut tree = treeseq.tree_iterator(TreeFlags::default())?;
+    while let Some(tree) = tree.next() {
+        let tree_span = f64::from(tree.span());
+        for (node, edge) in tree.edge_array().iter().enumerate() {
+            let Some(e) = edge.to_usize() else {
+                continue;
+            };
+            let samples = u64::from(tree.num_tracked_samples(NodeId::from(node as i32))?) as f64;
+            weighted_samples[e] += samples * tree_span;
+        }
+    }
+    Ok(edges
+        .left_slice()
+        .iter()
+        .zip(edges.right_slice())
+        .zip(weighted_samples)
+        .enumerate()
+        .map(|(e, ((&left, &right), w))| {
+            let span = right - left;
+            EdgeSpanSummary {
+                edge: EdgeId::from(e as i32),
+                span,
+                mean_descendant_samples: w / f64::from(span),
+            }
+        })
+        .collect())
+}
@@ -3,7 +3,12 @@ use std::ops::DerefMut;
 
 use crate::sys::bindings as ll_bindings;
 use crate::sys::{LLTree, TreeSequence};
+use crate::EdgeTable;
+use crate::MigrationTable;
+use crate::NodeId;
 use crate::Position;
+use crate::TableCollection;
+use crate::TableSortOptions;
 use crate::TreeFlags;
 use crate::TreeInterface;
 use crate::TskitError;
@@ -37,7 +42,8 @@ impl<'treeseq> Tree<'treeseq> {
     ) -> Result<Self, TskitError> {
         let flags = flags.into();
         let mut inner = LLTree::new(ts, flags)?;
-        let nonnull = std::ptr::NonNull::new(inner.as_mut_ptr()).unwrap();
+        let nonnull = std::ptr::NonNull::new(inner.as_mut_ptr())
+            .ok_or_else(|| TskitError::LibraryError("null tree pointer".to_string()))?;
         let num_nodes = ts.num_nodes_raw();
         let api = TreeInterface::new(nonnull, num_nodes, num_nodes + 1, flags);
         Ok(Self {
@@ -58,7 +64,7 @@ impl<'treeseq> Tree<'treeseq> {
         // SAFETY: tree is initialized and the pointer is not NULL
         match unsafe { ll_bindings::tsk_tree_seek(tree.as_mut_ptr(), at.into().into(), 0) } {
             code if code < 0 => return Err(TskitError::ErrorCode { code }),
-            _ => (),
+            _ => tree.advanced = ll_bindings::TSK_TREE_OK as i32,
         };
         Ok(tree)
     }
@@ -74,10 +80,203 @@ impl<'treeseq> Tree<'treeseq> {
         // SAFETY: tree is initialized and the pointer is not NULL
         match unsafe { ll_bindings::tsk_tree_seek_index(tree.as_mut_ptr(), at, 0) } {
             code if code < 0 => return Err(TskitError::ErrorCode { code }),
-            _ => (),
+            _ => tree.advanced = ll_bindings::TSK_TREE_OK as i32,
         };
         Ok(tree)
     }
+    pub(crate) fn new_at_first<F: Into<TreeFlags>>(
+        ts: &'treeseq TreeSequence,
+        flags: F,
+    ) -> Result<Self, TskitError> {
+        let mut tree = Self::new(ts, flags)?;
+        assert!(!tree.as_ptr().is_null());
+        // SAFETY: tree is initialized and the pointer is not NULL
+        match unsafe { ll_bindings::tsk_tree_first(tree.as_mut_ptr()) } {
+            code if code < 0 => return Err(TskitError::ErrorCode { code }),
+            _ => tree.advanced = ll_bindings::TSK_TREE_OK as i32,
+        };
+        Ok(tree)
+    }
+
+    pub(crate) fn new_at_last<F: Into<TreeFlags>>(
+        ts: &'treeseq TreeSequence,
+        flags: F,
+    ) -> Result<Self, TskitError> {
+        let mut tree = Self::new(ts, flags)?;
+        assert!(!tree.as_ptr().is_null());
+        // SAFETY: tree is initialized and the pointer is not NULL
+        match unsafe { ll_bindings::tsk_tree_last(tree.as_mut_ptr()) } {
+            code if code < 0 => return Err(TskitError::ErrorCode { code }),
+            _ => tree.advanced = ll_bindings::TSK_TREE_OK as i32,
+        };
+        Ok(tree)
+    }
+
+    /// Move the tree to the one covering `position`.
+    ///
+    /// The tree moves from its current position, so nearby
+    /// positions are reached without iterating from the first tree.
+    /// Iteration continues from the new position.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `position` is negative or not less than the
+    ///   sequence length. The tree is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 50., parent, child).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let mut tree = treeseq.first_tree(tskit::TreeFlags::default()).unwrap();
+    /// tree.seek(75.).unwrap();
+    /// assert_eq!(tree.interval(), (50.0.into(), 100.0.into()));
+    /// assert!(tree.seek(100.).is_err());
+    /// tree.seek(10.).unwrap();
+    /// assert_eq!(tree.interval(), (0.0.into(), 50.0.into()));
+    /// assert!(tree.next().is_some());
+    /// assert!(tree.next().is_none());
+    /// ```
+    pub fn seek<P: Into<Position>>(&mut self, position: P) -> Result<(), TskitError> {
+        assert!(!self.as_ptr().is_null());
+        // SAFETY: tree is initialized and the pointer is not NULL
+        match unsafe { ll_bindings::tsk_tree_seek(self.as_mut_ptr(), position.into().into(), 0) } {
+            code if code < 0 => Err(TskitError::ErrorCode { code }),
+            _ => {
+                self.advanced = ll_bindings::TSK_TREE_OK as i32;
+                Ok(())
+            }
+        }
+    }
+
+    /// Extract the clade below `node` as a standalone table collection.
+    ///
+    /// The returned tables describe a single tree: the subtree
+    /// of `node` over the interval of this tree.
+    ///
+    /// # Details
+    ///
+    /// * `node` becomes node 0 and the other nodes of the
+    ///   clade follow in preorder.
+    /// * Genomic coordinates are shifted so that the tree covers
+    ///   `[0, span)`, where `span` is the span of this tree.
+    /// * Mutations on nodes of the clade, including `node` itself,
+    ///   are kept if their site is within the interval of this tree.
+    ///   Sites without such mutations are removed.
+    /// * Populations and individuals not referenced by the retained
+    ///   nodes are removed.
+    /// * The migration table is cleared.
+    ///
+    /// The tables are sorted and indexed.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `node` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let root = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let internal = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for _ in 0..3 {
+    ///     tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// }
+    /// tables.add_edge(0., 100., root, internal).unwrap();
+    /// tables.add_edge(0., 100., root, 2).unwrap();
+    /// tables.add_edge(0., 100., internal, 3).unwrap();
+    /// tables.add_edge(0., 100., internal, 4).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let mut trees = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// let tree = trees.next().unwrap();
+    /// let clade = tree.extract_subtree(internal).unwrap();
+    /// assert_eq!(clade.nodes().num_rows(), 3);
+    /// assert_eq!(clade.edges().num_rows(), 2);
+    /// assert_eq!(clade.edges().parent(0), Some(0.into()));
+    /// ```
+    pub fn extract_subtree<N: Into<NodeId>>(&self, node: N) -> Result<TableCollection, TskitError> {
+        use streaming_iterator::StreamingIterator;
+
+        let node = node.into();
+        let treeseq = self.inner.treeseq();
+        let num_nodes = treeseq.num_nodes_raw() as usize;
+        match node.to_usize() {
+            Some(u) if u < num_nodes => (),
+            _ => return Err(TskitError::IndexError),
+        }
+
+        // Nodes of the clade in preorder
+        let mut clade = vec![];
+        let mut in_clade = vec![false; num_nodes];
+        let mut stack = vec![node];
+        while let Some(u) = stack.pop() {
+            clade.push(u);
+            in_clade[u.as_usize()] = true;
+            let first = stack.len();
+            stack.extend(self.children(u));
+            stack[first..].reverse();
+        }
+
+        let (left, right) = self.interval();
+        let mut tables = TableCollection::new_from_ll(treeseq.copy_tables()?)?;
+
+        // Edges overlapping the interval of a tree span all of it.
+        let mut new_edges = crate::sys::EdgeTable::new(0)?;
+        let mut edge_iter = tables.edges().lending_iter();
+        while let Some(row) = edge_iter.next() {
+            if in_clade[row.parent.as_usize()]
+                && in_clade[row.child.as_usize()]
+                && row.left < right
+                && row.right > left
+            {
+                new_edges.add_row_with_metadata(
+                    left.into(),
+                    right.into(),
+                    row.parent.into(),
+                    row.child.into(),
+                    row.metadata.unwrap_or(&[0u8; 0]),
+                )?;
+            }
+        }
+        let new_edges = EdgeTable::new_from_table(new_edges.as_mut())?;
+        let mut new_migrations = crate::sys::MigrationTable::new(0)?;
+        let new_migrations = MigrationTable::new_from_table(new_migrations.as_mut())?;
+        tables.set_edges(&new_edges)?;
+        tables.set_migrations(&new_migrations)?;
+
+        let keep_sites = tables
+            .sites()
+            .position_slice()
+            .iter()
+            .map(|&p| p >= left && p < right)
+            .collect::<Vec<_>>();
+        let keep_mutations = tables
+            .mutations()
+            .node_slice()
+            .iter()
+            .map(|n| in_clade[n.as_usize()])
+            .collect::<Vec<_>>();
+        tables.retain_sites_and_mutations(&keep_sites, &keep_mutations)?;
+
+        let mut inner = tables.into_inner();
+        inner.subset(&clade, 0)?;
+        inner.shift_coordinates(left.into(), f64::from(right - left));
+        let mut tables = TableCollection::new_from_ll(inner)?;
+        tables.full_sort(TableSortOptions::default())?;
+        tables.build_index()?;
+        Ok(tables)
+    }
 }
 
 impl<'ts> streaming_iterator::StreamingIterator for Tree<'ts> {
@@ -1,14 +1,21 @@
 use crate::error::TskitError;
 use crate::sys;
+use crate::BranchPoint;
+use crate::GenotypeChunks;
+use crate::MutationId;
 use crate::NodeId;
 use crate::Position;
+use crate::RateMap;
 use crate::SimplificationOptions;
+use crate::SiteId;
 use crate::SizeType;
 use crate::TableCollection;
 use crate::TableOutputOptions;
 use crate::TreeFlags;
 use crate::TreeSequenceFlags;
 use crate::TskReturnValue;
+use crate::Variant;
+use crate::VariantOptions;
 use sys::bindings as ll_bindings;
 
 use super::Tree;
@@ -76,6 +83,8 @@ impl TreeSequence {
     /// # Errors
     ///
     /// * [`TskitError`] if the tables are not indexed.
+    /// * [`TskitError::StaleIndexes`] if the tables were edited after
+    ///   being indexed. See [`TableCollection::index_is_valid`].
     /// * [`TskitError`] if the tables are not properly sorted.
     ///   See [`TableCollection::full_sort`](crate::TableCollection::full_sort).
     ///
@@ -108,16 +117,67 @@ impl TreeSequence {
     /// This function makes *no extra copies* of the tables.
     /// There is, however, a temporary allocation of an empty table collection
     /// in order to convince rust that we are safely handling all memory.
+    ///
+    /// Unless [`TreeSequenceFlags::BUILD_INDEXES`] is set, existing indexes
+    /// are checked with [`TableCollection::index_is_valid`] before the tree
+    /// sequence is created.
+    /// This check takes time linear in the number of edges and allocates
+    /// two vectors of that length.
+    /// Setting `BUILD_INDEXES` skips it because the indexes are rebuilt.
     pub fn new<F: Into<TreeSequenceFlags>>(
         tables: TableCollection,
         flags: F,
     ) -> Result<Self, TskitError> {
+        let flags = flags.into();
+        // O(num_edges): see the note on stale indexes above
+        if !flags.contains(TreeSequenceFlags::BUILD_INDEXES) && tables.has_stale_index() {
+            return Err(TskitError::StaleIndexes);
+        }
         let raw_tables_ptr = tables.into_inner();
-        let mut inner = sys::TreeSequence::new(raw_tables_ptr, flags.into())?;
+        let mut inner = sys::TreeSequence::new(raw_tables_ptr, flags)?;
         let views = crate::table_views::TableViews::new_from_tree_sequence(inner.as_mut())?;
         Ok(Self { inner, views })
     }
 
+    /// Create a tree sequence from a copy of a [`TableCollection`].
+    ///
+    /// Unlike [`TreeSequence::new`], the input tables are not consumed,
+    /// so they may be edited further after creating the tree sequence.
+    ///
+    /// # Parameters
+    ///
+    /// * `tables`, a [`TableCollection`]
+    ///
+    /// # Errors
+    ///
+    /// See [`TreeSequence::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let snapshot = tskit::TreeSequence::from_tables_ref(
+    ///     &tables,
+    ///     tskit::TreeSequenceFlags::default(),
+    /// )
+    /// .unwrap();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// assert_eq!(snapshot.nodes().num_rows(), 0);
+    /// assert_eq!(tables.nodes().num_rows(), 1);
+    /// ```
+    ///
+    /// ## Note
+    ///
+    /// This function copies the tables.
+    /// Prefer [`TreeSequence::new`] when the tables are no longer needed.
+    pub fn from_tables_ref<F: Into<TreeSequenceFlags>>(
+        tables: &TableCollection,
+        flags: F,
+    ) -> Result<Self, TskitError> {
+        Self::new(tables.deepcopy()?, flags)
+    }
+
     fn as_ref(&self) -> &ll_bindings::tsk_treeseq_t {
         self.inner.as_ref()
     }
@@ -151,6 +211,34 @@ impl TreeSequence {
         self.inner.dump(c_str, options.into().bits())
     }
 
+    /// Dump the tree sequence to a file whose bytes only depend
+    /// on the contents of its tables.
+    ///
+    /// The file is the same as that written by
+    /// [`TableCollection::dump_canonical`] for the tables
+    /// of the tree sequence.
+    pub fn dump_canonical(
+        &self,
+        filename: &str,
+        options: crate::CanonicalDumpOptions,
+    ) -> TskReturnValue {
+        let tables = TableCollection::new_from_ll(self.inner.copy_tables()?)?;
+        crate::canonical::dump(&tables, filename, options)
+    }
+
+    /// Dump the tree sequence to file, adding extra kastore arrays.
+    ///
+    /// See [`TableCollection::dump_with_extras`].
+    pub fn dump_with_extras<O: Into<TableOutputOptions>>(
+        &self,
+        filename: &str,
+        options: O,
+        extras: &crate::KastoreExtras,
+    ) -> TskReturnValue {
+        self.dump(filename, options)?;
+        extras.append_to_file(filename)
+    }
+
     /// Load from a file.
     ///
     /// This function calls [`TableCollection::new_from_file`] with
@@ -161,144 +249,2043 @@ impl TreeSequence {
         Self::new(tables, TreeSequenceFlags::default())
     }
 
-    /// Obtain the underlying [`TableCollection`].
+    /// Obtain the underlying [`TableCollection`].
+    ///
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] will be raised if the underlying C library returns an error code.
+    pub fn dump_tables(self) -> Result<TableCollection, TskitError> {
+        assert!(!self.as_ptr().is_null());
+        let mut treeseq = self;
+        // SAFETY: the above assert passed
+        let tables = std::ptr::NonNull::new(unsafe { (*treeseq.as_ptr()).tables })
+            .ok_or_else(|| TskitError::LibraryError("null table collection pointer".to_string()))?;
+        // SAFETY: the above assert passed
+        unsafe { (*treeseq.as_mut_ptr()).tables = std::ptr::null_mut() };
+        // SAFETY: the table collection points to data that has passed
+        // tsk_table_collection_check_integrity, meaning that it must be initialized.
+        let tables = unsafe { crate::sys::TableCollection::new_owning_from_nonnull(tables) };
+        crate::TableCollection::new_from_ll(tables)
+    }
+
+    /// Return a SHA-256 digest of the contents of the tables.
+    ///
+    /// The digest is that of the tables used to create
+    /// the tree sequence.
+    /// See [`TableCollection::digest`].
+    pub fn digest<O: Into<crate::TableEqualityOptions>>(
+        &self,
+        options: O,
+    ) -> crate::TableCollectionDigest {
+        // SAFETY: the tables of an initialized tree sequence are not null
+        crate::digest::digest(unsafe { &*self.as_ref().tables }, options.into())
+    }
+
+    /// Create an iterator over trees.
+    ///
+    /// # Parameters
+    ///
+    /// * `flags` A [`TreeFlags`] bit field.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the `C` back end is unable to allocate the tree.
+    ///
+    /// # Panics
+    ///
+    /// Advancing the iterator panics if the `C` back end returns an error.
+    /// Once the tree is allocated, this only happens if the tree sequence
+    /// has been corrupted, for example through its raw pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // You must include streaming_iterator as a dependency
+    /// // and import this type.
+    /// use streaming_iterator::StreamingIterator;
+    /// // Import this to allow .next_back() for reverse
+    /// // iteration over trees.
+    /// use streaming_iterator::DoubleEndedStreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let mut tree_iterator = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// while let Some(tree) = tree_iterator.next() {
+    /// }
+    /// ```
+    ///
+    /// ## Coupled liftimes
+    ///
+    /// A `Tree`'s lifetime is tied to that of its tree sequence:
+    ///
+    /// ```{compile_fail}
+    /// # use streaming_iterator::StreamingIterator;
+    /// # use streaming_iterator::DoubleEndedStreamingIterator;
+    /// # let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// # tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let mut tree_iterator = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// drop(tree_sequence);
+    /// while let Some(tree) = tree_iterator.next() { // compile fail.
+    /// }
+    /// ```
+    /// # Warning
+    ///
+    /// The following code results in an infinite loop.
+    /// Be sure to note the difference from the previous example.
+    ///
+    /// ```no_run
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// while let Some(tree) = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap().next() {
+    /// }
+    /// ```
+    pub fn tree_iterator<F: Into<TreeFlags>>(&self, flags: F) -> Result<Tree, TskitError> {
+        let tree = Tree::new(&self.inner, flags)?;
+
+        Ok(tree)
+    }
+
+    /// Create an iterator over trees starting at a specific position.
+    ///
+    /// See [`TreeSequence::tree_iterator`] for details
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `at` is not valid
+    pub fn tree_iterator_at_position<F: Into<TreeFlags>, P: Into<Position>>(
+        &self,
+        flags: F,
+        at: P,
+    ) -> Result<Tree, TskitError> {
+        Tree::new_at_position(&self.inner, flags, at)
+    }
+
+    /// Create an iterator over trees starting at a specific tree index.
+    ///
+    /// See [`TreeSequence::tree_iterator`] for details
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `at` is not valid
+    pub fn tree_iterator_at_index<F: Into<TreeFlags>>(
+        &self,
+        flags: F,
+        at: i32,
+    ) -> Result<Tree, TskitError> {
+        Tree::new_at_index(&self.inner, flags, at)
+    }
+
+    /// Create an iterator over trees and the sites in their intervals.
+    ///
+    /// Each step advances to the next tree, as for
+    /// [`TreeSequence::tree_iterator`], and gives the sites
+    /// in the genomic interval of that tree, with their mutations.
+    /// See [`TreeSiteIterator`](crate::TreeSiteIterator).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the `C` back end is unable to allocate the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c0).unwrap();
+    /// tables.add_edge(0., 50., p, c1).unwrap();
+    /// for (position, node) in [(10., c0), (20., c1), (60., c0)] {
+    ///     let site = tables.add_site(position, Some(b"A")).unwrap();
+    ///     tables.add_mutation(site, node, -1, 0.5, Some(b"T")).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let mut trees = treeseq.trees_with_sites(tskit::TreeFlags::default()).unwrap();
+    /// let mut positions = vec![];
+    /// while let Some(bundle) = trees.next() {
+    ///     assert_eq!(bundle.num_sites(), bundle.num_mutations());
+    ///     positions.push(bundle.sites().map(|s| f64::from(s.position)).collect::<Vec<_>>());
+    /// }
+    /// assert_eq!(positions, [vec![10., 20.], vec![60.]]);
+    /// ```
+    pub fn trees_with_sites<F: Into<TreeFlags>>(
+        &self,
+        flags: F,
+    ) -> Result<crate::TreeSiteIterator<'_>, TskitError> {
+        Ok(crate::TreeSiteIterator::new(
+            self.tree_iterator(flags)?,
+            self.sites().position_slice(),
+            self.mutations().site_slice(),
+        ))
+    }
+
+    /// Get the site at `position`.
+    ///
+    /// Sites of a tree sequence are sorted by position and have
+    /// distinct positions, so the lookup is a binary search over
+    /// the site table.
+    ///
+    /// # Returns
+    ///
+    /// The site id, or `None` if there is no site at `position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c).unwrap();
+    /// let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    /// let s1 = tables.add_site(20., Some(b"A")).unwrap();
+    /// tables.add_mutation(s1, c, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.add_mutation(s1, c, -1, 0.25, Some(b"G")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// assert_eq!(treeseq.site_at_position(10.), Some(s0));
+    /// assert_eq!(treeseq.site_at_position(15.), None);
+    /// assert_eq!(treeseq.mutations_at_position(20.).collect::<Vec<_>>(), [0, 1]);
+    /// assert_eq!(treeseq.mutations_at_position(10.).count(), 0);
+    /// assert_eq!(treeseq.mutations_at_position(15.).count(), 0);
+    /// ```
+    pub fn site_at_position<P: Into<Position>>(&self, position: P) -> Option<SiteId> {
+        let position = position.into();
+        let positions = self.sites().position_slice();
+        let index = positions.partition_point(|&p| p < position);
+        match positions.get(index) {
+            Some(&p) if p == position => Some(SiteId::from(index as ll_bindings::tsk_id_t)),
+            _ => None,
+        }
+    }
+
+    /// Get the mutations at the site at `position`.
+    ///
+    /// Mutations of a tree sequence are sorted by site, so the
+    /// mutations of a site are found by binary search over the
+    /// mutation table, without scanning.
+    /// The iterator is empty if there is no site at `position`.
+    ///
+    /// See [`TreeSequence::site_at_position`] for an example.
+    pub fn mutations_at_position<P: Into<Position>>(
+        &self,
+        position: P,
+    ) -> impl Iterator<Item = MutationId> {
+        let sites = self.mutations().site_slice();
+        let range = match self.site_at_position(position) {
+            Some(site) => {
+                sites.partition_point(|&s| s < site)..sites.partition_point(|&s| s <= site)
+            }
+            None => 0..0,
+        };
+        range.map(|m| MutationId::from(m as ll_bindings::tsk_id_t))
+    }
+
+    /// Get the genetic position of each site, in order of site id.
+    ///
+    /// The genetic position is the cumulative mass of `map` up to the
+    /// position of the site. See [`RateMap::cumulative_mass`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::RangeError`] if a site is beyond the sequence
+    /// length of `map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_site(10., Some(b"A")).unwrap();
+    /// tables.add_site(75., Some(b"A")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let map = tskit::RateMap::new([0., 50., 100.], [0.0, 0.5]).unwrap();
+    /// assert_eq!(treeseq.site_genetic_positions(&map).unwrap(), [0.0, 12.5]);
+    /// let short = tskit::RateMap::new([0., 50.], [0.5]).unwrap();
+    /// assert!(treeseq.site_genetic_positions(&short).is_err());
+    /// ```
+    pub fn site_genetic_positions(&self, map: &RateMap) -> Result<Vec<f64>, TskitError> {
+        self.sites()
+            .position_slice()
+            .iter()
+            .map(|&p| map.cumulative_mass(p))
+            .collect()
+    }
+
+    /// Create an iterator over the variants (sites with decoded
+    /// genotypes) of the tree sequence.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: the nodes whose genotypes are decoded.
+    ///   If `None`, all samples are used.
+    /// * `options`: how genotypes are decoded, including the
+    ///   treatment of missing data. See [`VariantOptions`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if any of `samples` is not a valid node id
+    ///   or if `samples` contains duplicates.
+    ///
+    /// # Panics
+    ///
+    /// Advancing the iterator panics if decoding a site fails.
+    /// Use [`Variant::decode`] to handle such errors.
+    ///
+    /// # Examples
+    ///
+    /// Node 2 is isolated, so its genotype is missing
+    /// unless [`VariantOptions::ISOLATED_NOT_MISSING`] is set.
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    /// use tskit::Genotype;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c).unwrap();
+    /// let site = tables.add_site(50., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let mut variants = ts.variants(None, tskit::VariantOptions::default()).unwrap();
+    /// let variant = variants.next().unwrap();
+    /// assert_eq!(variant.num_alleles(), 2);
+    /// assert_eq!(variant.allele(1), Some(&b"T"[..]));
+    /// assert_eq!(variant.genotype(0).unwrap(), Genotype::Allele(1));
+    /// assert_eq!(variant.genotype(1).unwrap(), Genotype::Missing);
+    /// assert_eq!(variant.allele_frequencies(), vec![0.0, 1.0]);
+    /// assert!(variants.next().is_none());
+    ///
+    /// let options = tskit::VariantOptions::default().isolated_not_missing();
+    /// let mut variants = ts.variants(None, options).unwrap();
+    /// let variant = variants.next().unwrap();
+    /// assert_eq!(variant.genotypes(), &[1, 0]);
+    /// assert!(!variant.has_missing_data());
+    /// ```
+    pub fn variants<O: Into<VariantOptions>>(
+        &self,
+        samples: Option<&[NodeId]>,
+        options: O,
+    ) -> Result<Variant<'_>, TskitError> {
+        Variant::new(&self.inner, samples, options.into())
+    }
+
+    /// Create an iterator over the genotype matrix in chunks
+    /// of `chunk_size` sites.
+    ///
+    /// Only one chunk is held in memory at a time, so that
+    /// very large numbers of sites may be streamed.
+    /// The last chunk may contain fewer sites.
+    ///
+    /// # Parameters
+    ///
+    /// * `chunk_size`: the number of sites per chunk.
+    /// * `samples` and `options`: see [`TreeSequence::variants`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `chunk_size` is zero.
+    /// * See [`TreeSequence::variants`] for other errors.
+    ///
+    /// Errors decoding a chunk are returned by the iterator,
+    /// after which iteration ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for _ in 0..2 {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 100., p, c).unwrap();
+    /// }
+    /// for (i, position) in [10., 20., 30.].into_iter().enumerate() {
+    ///     let site = tables.add_site(position, Some(b"A")).unwrap();
+    ///     tables.add_mutation(site, 1 + (i as i32 % 2), -1, 0.5, Some(b"T")).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let chunks = ts
+    ///     .genotype_chunks(2, None, tskit::VariantOptions::default())
+    ///     .unwrap()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[0].positions, [tskit::Position::from(10.), 20.0.into()]);
+    /// assert_eq!(chunks[0].genotypes, vec![1, 0, 0, 1]);
+    /// assert_eq!(chunks[1].num_sites(), 1);
+    /// assert_eq!(chunks[1].site_genotypes(0), Some(&[1, 0][..]));
+    /// assert_eq!(chunks[1].alleles[0], vec![b"A".to_vec(), b"T".to_vec()]);
+    /// ```
+    pub fn genotype_chunks<O: Into<VariantOptions>>(
+        &self,
+        chunk_size: usize,
+        samples: Option<&[NodeId]>,
+        options: O,
+    ) -> Result<GenotypeChunks<'_>, TskitError> {
+        GenotypeChunks::new(self.variants(samples, options)?, chunk_size)
+    }
+
+    /// Pair sample nodes with their individuals.
+    ///
+    /// Use the result to decode genotypes grouped by individual,
+    /// for example to write diploid genotypes.
+    /// See [`IndividualSamples`](crate::IndividualSamples) for the ordering.
+    ///
+    /// # Parameters
+    ///
+    /// * `ploidy`: if `Some`, the number of sample nodes that
+    ///   every individual must have.
+    ///   If `None`, the ploidy may vary among individuals.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if a sample node has no individual
+    ///   or if an individual does not have `ploidy` sample nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let i0 = tables.add_individual(0, None, None).unwrap();
+    /// let i1 = tables.add_individual(0, None, None).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// // The nodes of the individuals are interleaved
+    /// for individual in [i0, i1, i1, i0] {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, individual).unwrap();
+    ///     tables.add_edge(0., 100., p, c).unwrap();
+    /// }
+    /// let site = tables.add_site(50., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, 2, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let individuals = ts.individual_samples(Some(2)).unwrap();
+    /// assert_eq!(individuals.samples(), [1, 4, 2, 3].map(tskit::NodeId::from));
+    /// assert_eq!(individuals.ploidy(1), Some(2));
+    /// let mut variants = ts
+    ///     .variants(Some(individuals.samples()), tskit::VariantOptions::default())
+    ///     .unwrap();
+    /// let variant = variants.next().unwrap();
+    /// let genotypes = variant
+    ///     .individual_genotypes(&individuals)
+    ///     .unwrap()
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(genotypes, vec![(i0, &[0, 0][..]), (i1, &[1, 0][..])]);
+    ///
+    /// assert!(ts.individual_samples(Some(1)).is_err());
+    /// ```
+    pub fn individual_samples(
+        &self,
+        ploidy: Option<usize>,
+    ) -> Result<crate::IndividualSamples, TskitError> {
+        crate::IndividualSamples::new(self.sample_nodes(), self.nodes().individual_slice(), ploidy)
+    }
+
+    /// Export alternate-allele dosages as a matrix of
+    /// sites by individuals.
+    ///
+    /// The dosage of an individual at a site is the number of
+    /// its sample nodes that do not carry the ancestral state,
+    /// which is 0, 1, or 2 for diploids.
+    /// See [`Variant::individual_dosages`] for missing data.
+    ///
+    /// # Parameters
+    ///
+    /// * `individuals`: the individuals, from
+    ///   [`TreeSequence::individual_samples`].
+    /// * `options`: see [`TreeSequence::variants`].
+    ///
+    /// # Errors
+    ///
+    /// * See [`TreeSequence::variants`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let i0 = tables.add_individual(0, None, None).unwrap();
+    /// let i1 = tables.add_individual(0, None, None).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for individual in [i0, i0, i1, i1] {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, individual).unwrap();
+    ///     tables.add_edge(0., 100., p, c).unwrap();
+    /// }
+    /// let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    /// tables.add_mutation(s0, 1, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.add_mutation(s0, 2, -1, 0.5, Some(b"T")).unwrap();
+    /// let s1 = tables.add_site(20., Some(b"A")).unwrap();
+    /// tables.add_mutation(s1, 4, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let individuals = ts.individual_samples(Some(2)).unwrap();
+    /// let dosages = ts
+    ///     .dosages(&individuals, tskit::VariantOptions::default())
+    ///     .unwrap();
+    /// assert_eq!(dosages.individuals, [i0, i1]);
+    /// assert_eq!(dosages.site_dosages(0), Some(&[2, 0][..]));
+    /// assert_eq!(dosages.site_dosages(1), Some(&[0, 1][..]));
+    /// ```
+    pub fn dosages<O: Into<VariantOptions>>(
+        &self,
+        individuals: &crate::IndividualSamples,
+        options: O,
+    ) -> Result<crate::DosageMatrix, TskitError> {
+        crate::DosageMatrix::new(
+            self.variants(Some(individuals.samples()), options)?,
+            individuals,
+        )
+    }
+
+    /// Count the derived alleles of each site in each sample set.
+    ///
+    /// Counts are calculated from the numbers of samples in the
+    /// subtrees of mutations, without decoding genotypes.
+    /// A node has the state of the nearest mutation of the site on
+    /// the path from the node to the root, or of the last one if
+    /// there are several on the same node; the mutation parent
+    /// column is not used.
+    /// The count of a sample set is the number of its nodes
+    /// whose state differs from the ancestral state of the site.
+    /// For biallelic sites, this is the number of copies of the
+    /// derived allele.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_sets`: the sample sets, which may overlap.
+    /// * `windows`: the genomic windows, which group the rows of the
+    ///   result by window. Sites in regions masked by
+    ///   [`Windows::mask`](crate::Windows::mask) are left out.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if a sample set contains an invalid node.
+    /// * [`TskitError::RangeError`] if a window is empty or extends
+    ///   beyond the sequence length, or if the mask extends beyond
+    ///   the sequence length.
+    /// * [`TskitError::ValueError`] if there are no windows.
+    /// * [`TskitError`] if the `C` back end is unable to iterate over
+    ///   the trees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let q = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c = (0..4)
+    ///     .map(|_| tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap())
+    ///     .collect::<Vec<_>>();
+    /// tables.add_edge(0., 100., p, q).unwrap();
+    /// tables.add_edge(0., 100., p, c[0]).unwrap();
+    /// tables.add_edge(0., 100., p, c[1]).unwrap();
+    /// tables.add_edge(0., 100., q, c[2]).unwrap();
+    /// tables.add_edge(0., 100., q, c[3]).unwrap();
+    /// let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    /// tables.add_mutation(s0, q, -1, 1.5, Some(b"T")).unwrap();
+    /// let s1 = tables.add_site(60., Some(b"A")).unwrap();
+    /// tables.add_mutation(s1, c[0], -1, 0.5, Some(b"T")).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let sample_sets = tskit::SampleSets::new()
+    ///     .add_set(c[..2].iter().copied())
+    ///     .add_set(c[2..].iter().copied());
+    /// let windows = tskit::Windows::breakpoints([0., 50., 100.]);
+    /// let counts = ts.allele_counts(&sample_sets, &windows).unwrap();
+    /// assert_eq!(counts.site_counts(0), Some(&[0, 2][..]));
+    /// assert_eq!(counts.site_counts(1), Some(&[1, 0][..]));
+    /// assert_eq!(counts.window_sites(1), Some(&[s1][..]));
+    /// assert_eq!(counts.window_counts(1), Some(&[1, 0][..]));
+    ///
+    /// // The first site is masked
+    /// let mask = tskit::GenomeMask::new([(0., 50.)]).unwrap();
+    /// let windows = tskit::Windows::genome().mask(mask);
+    /// let counts = ts.allele_counts(&sample_sets, &windows).unwrap();
+    /// assert_eq!(counts.sites, [s1]);
+    /// ```
+    pub fn allele_counts(
+        &self,
+        sample_sets: &crate::SampleSets,
+        windows: &crate::Windows,
+    ) -> Result<crate::AlleleCountMatrix, TskitError> {
+        crate::allele_counts::allele_counts(self, &sample_sets.as_slices(), windows)
+    }
+
+    /// Export haplotypes, with one byte per site.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples` and `options`: see [`TreeSequence::variants`].
+    /// * `haplotype_options`: how alleles and missing data are written.
+    ///
+    /// # Returns
+    ///
+    /// One haplotype per sample, in the order of `samples`,
+    /// or of the sample nodes if `samples` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if an allele has no encoding
+    ///   in `haplotype_options` or is encoded as the missing character.
+    /// * See [`TreeSequence::variants`] for other errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::{HaplotypeOptions, MultiCharacterAlleles};
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c).unwrap();
+    /// // The second sample is isolated, so its genotypes are missing
+    /// tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    /// tables.add_mutation(s0, c, -1, 0.5, Some(b"T")).unwrap();
+    /// let s1 = tables.add_site(20., Some(b"12")).unwrap();
+    /// tables.add_mutation(s1, c, -1, 0.5, Some(b"13")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// // Repeat counts are not single characters...
+    /// let variant_options = tskit::VariantOptions::default();
+    /// assert!(ts
+    ///     .haplotypes(None, variant_options, &HaplotypeOptions::default())
+    ///     .is_err());
+    ///
+    /// // ...so they must be encoded deliberately
+    /// let options = HaplotypeOptions::default().allele_encoding("13", b'+');
+    /// let options = options.multi_character_alleles(MultiCharacterAlleles::Replace(b'.'));
+    /// let haplotypes = ts.haplotypes(None, variant_options, &options).unwrap();
+    /// assert_eq!(haplotypes, [b"T+".to_vec(), b"NN".to_vec()]);
+    ///
+    /// let options = options.missing_character(b'-');
+    /// let haplotypes = ts.haplotypes(None, variant_options, &options).unwrap();
+    /// assert_eq!(haplotypes[1], b"--");
+    /// ```
+    pub fn haplotypes<O: Into<VariantOptions>>(
+        &self,
+        samples: Option<&[NodeId]>,
+        options: O,
+        haplotype_options: &crate::HaplotypeOptions,
+    ) -> Result<Vec<Vec<u8>>, TskitError> {
+        crate::haplotypes::haplotypes(self.variants(samples, options)?, haplotype_options)
+    }
+
+    /// Get the population of each individual.
+    ///
+    /// The population of an individual is that of its nodes.
+    /// Individuals without nodes have population
+    /// [`PopulationId::NULL`](crate::PopulationId::NULL).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ErrorCode`] if the nodes of an individual
+    ///   are in different populations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop = tables.add_population().unwrap();
+    /// let i0 = tables.add_individual(0, None, None).unwrap();
+    /// let i1 = tables.add_individual(0, None, None).unwrap();
+    /// for individual in [i0, i0, i1] {
+    ///     tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop, individual).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// assert_eq!(ts.individuals_population().unwrap(), [pop, pop]);
+    /// assert_eq!(ts.individuals_time().unwrap(), [0.0, 0.0].map(tskit::Time::from));
+    /// assert_eq!(ts.sample_individuals(), [i0, i0, i1]);
+    /// ```
+    pub fn individuals_population(&self) -> Result<Vec<crate::PopulationId>, TskitError> {
+        self.inner.individuals_population()
+    }
+
+    /// Get the birth time of each individual.
+    ///
+    /// The time of an individual is that of its nodes.
+    /// Individuals without nodes have an unknown time, which is `NaN`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ErrorCode`] if the nodes of an individual
+    ///   have different times.
+    ///
+    /// See [`TreeSequence::individuals_population`] for an example.
+    pub fn individuals_time(&self) -> Result<Vec<crate::Time>, TskitError> {
+        self.inner.individuals_time()
+    }
+
+    /// Get the individual of each sample node.
+    ///
+    /// The result is in the order of [`TreeSequence::sample_nodes`],
+    /// with [`IndividualId::NULL`](crate::IndividualId::NULL) for
+    /// samples without an individual.
+    ///
+    /// See [`TreeSequence::individuals_population`] for an example.
+    pub fn sample_individuals(&self) -> Vec<crate::IndividualId> {
+        let individuals = self.nodes().individual_slice();
+        self.sample_nodes()
+            .iter()
+            .map(|&s| individuals[s.to_usize().unwrap()])
+            .collect()
+    }
+
+    /// Get the first tree of the tree sequence.
+    ///
+    /// The returned [`Tree`] is already positioned at the first tree.
+    /// See [`TreeSequence::tree_iterator`] for details.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the `C` back end is unable to allocate
+    ///   or position the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let tree = tree_sequence.first_tree(tskit::TreeFlags::default()).unwrap();
+    /// assert_eq!(tree.interval().0, 0.0);
+    /// ```
+    pub fn first_tree<F: Into<TreeFlags>>(&self, flags: F) -> Result<Tree<'_>, TskitError> {
+        Tree::new_at_first(&self.inner, flags)
+    }
+
+    /// Get the last tree of the tree sequence.
+    ///
+    /// The returned [`Tree`] is already positioned at the last tree.
+    /// See [`TreeSequence::tree_iterator`] for details.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the `C` back end is unable to allocate
+    ///   or position the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let tree = tree_sequence.last_tree(tskit::TreeFlags::default()).unwrap();
+    /// assert_eq!(tree.interval().1, 1000.0);
+    /// ```
+    pub fn last_tree<F: Into<TreeFlags>>(&self, flags: F) -> Result<Tree<'_>, TskitError> {
+        Tree::new_at_last(&self.inner, flags)
+    }
+
+    /// Get the tree covering `position`.
+    ///
+    /// The returned [`Tree`] is already positioned at that tree,
+    /// which is found without iterating from the first tree.
+    /// See [`Tree::seek`] to move an existing tree.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `position` is negative or not less than the
+    ///   sequence length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(250., 500., parent, child).unwrap();
+    /// tables.build_index().unwrap();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let tree = tree_sequence.tree_at_position(300., tskit::TreeFlags::default()).unwrap();
+    /// assert_eq!(tree.interval(), (250.0.into(), 500.0.into()));
+    /// ```
+    pub fn tree_at_position<P: Into<Position>, F: Into<TreeFlags>>(
+        &self,
+        position: P,
+        flags: F,
+    ) -> Result<Tree<'_>, TskitError> {
+        self.tree_iterator_at_position(flags, position)
+    }
+
+    /// Get the tree with index `index`.
+    ///
+    /// The returned [`Tree`] is already positioned at that tree,
+    /// which is found without iterating from the first tree.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `index` is negative or not less than the
+    ///   number of trees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(250., 500., parent, child).unwrap();
+    /// tables.build_index().unwrap();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let tree = tree_sequence.tree_at_index(2, tskit::TreeFlags::default()).unwrap();
+    /// assert_eq!(tree.interval(), (500.0.into(), 1000.0.into()));
+    /// assert!(tree_sequence.tree_at_index(3, tskit::TreeFlags::default()).is_err());
+    /// ```
+    pub fn tree_at_index<F: Into<TreeFlags>>(
+        &self,
+        index: i32,
+        flags: F,
+    ) -> Result<Tree<'_>, TskitError> {
+        self.tree_iterator_at_index(flags, index)
+    }
+
+    /// Get the list of sample nodes as a slice.
+    pub fn sample_nodes(&self) -> &[NodeId] {
+        let num_samples = unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) };
+        sys::generate_slice(self.as_ref().samples, num_samples)
+    }
+
+    /// Get the number of trees.
+    pub fn num_trees(&self) -> SizeType {
+        self.inner.num_trees()
+    }
+
+    /// Get the number of nodes.
+    ///
+    /// The counts of rows of each table are available without
+    /// going through the tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ts = tskit::testing::balanced(4, 10.).unwrap();
+    /// assert_eq!(ts.num_nodes(), 7);
+    /// assert_eq!(ts.num_nodes(), ts.nodes().num_rows());
+    /// assert_eq!(ts.num_edges(), 6);
+    /// assert_eq!(ts.num_sites(), 0);
+    /// ```
+    pub fn num_nodes(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_nodes(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of edges.
+    pub fn num_edges(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_edges(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of sites.
+    pub fn num_sites(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_sites(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of mutations.
+    pub fn num_mutations(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_mutations(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of individuals.
+    pub fn num_individuals(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_individuals(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of populations.
+    pub fn num_populations(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_populations(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of migrations.
+    pub fn num_migrations(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_migrations(self.as_ptr()) }.into()
+    }
+
+    /// Get the number of provenances.
+    pub fn num_provenances(&self) -> SizeType {
+        // SAFETY: the tree sequence is initialized
+        unsafe { ll_bindings::tsk_treeseq_get_num_provenances(self.as_ptr()) }.into()
+    }
+
+    /// Get the span of each tree.
+    ///
+    /// The returned vector has length equal to [`TreeSequence::num_trees`],
+    /// and the values are in the same order as the trees.
+    /// The spans sum to the sequence length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let spans = tree_sequence.tree_spans();
+    /// assert_eq!(spans.len(), 1);
+    /// assert_eq!(spans[0], 1000.0);
+    /// ```
+    pub fn tree_spans(&self) -> Vec<Position> {
+        self.inner
+            .breakpoints()
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .collect()
+    }
+
+    /// Count the tree transitions in each window.
+    ///
+    /// A transition is a breakpoint between adjacent trees,
+    /// and is counted in the window `[left, right)` containing it.
+    /// The number of transitions per unit length is a cheap proxy
+    /// for the intensity of recombination.
+    ///
+    /// # Parameters
+    ///
+    /// * `windows`: the window breakpoints, which must strictly
+    ///   increase from 0 to the sequence length.
+    ///
+    /// # Returns
+    ///
+    /// The number of transitions in each window.
+    /// The counts sum to one less than [`TreeSequence::num_trees`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `windows` are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 10., parent, c1).unwrap();
+    /// tables.add_edge(20., 30., parent, c1).unwrap();
+    /// tables.add_edge(75., 100., parent, c1).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// // Transitions at 10, 20, 30, and 75
+    /// let counts = treeseq.breakpoint_density([0., 50., 100.]).unwrap();
+    /// assert_eq!(counts, [3, 1]);
+    /// assert!(treeseq.breakpoint_density([0., 50.]).is_err());
+    /// ```
+    pub fn breakpoint_density<P: Into<Position>>(
+        &self,
+        windows: impl IntoIterator<Item = P>,
+    ) -> Result<Vec<usize>, TskitError> {
+        let windows = crate::scan::window_breakpoints(windows, self.inner.sequence_length())?;
+        let breakpoints = self.inner.breakpoints();
+        // Skip the start and end of the genome
+        let transitions = &breakpoints[1..breakpoints.len() - 1];
+        Ok(windows
+            .windows(2)
+            .map(|w| {
+                transitions.partition_point(|&b| b < w[1])
+                    - transitions.partition_point(|&b| b < w[0])
+            })
+            .collect())
+    }
+
+    /// Sample `n` points uniformly with respect to the area
+    /// (branch length times span) of the tree sequence.
+    ///
+    /// Each point is a genomic position and a [`BranchPoint`]
+    /// in the tree covering that position.
+    /// The lineages of the points are therefore sampled with
+    /// probability proportional to branch length and span.
+    /// See [`TreeInterface::branch_point`](crate::TreeInterface::branch_point).
+    ///
+    /// # Parameters
+    ///
+    /// * `n`: the number of points
+    /// * `uniform`: a source of uniform random deviates on `[0, 1)`.
+    ///
+    /// # Returns
+    ///
+    /// The points, sorted by position.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `uniform` returns a value not on `[0, 1)`
+    ///   or if the total branch length of the tree sequence is zero.
+    /// * [`TskitError`] if the trees cannot be iterated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::{Rng, SeedableRng};
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for _ in 0..2 {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 100., p, c).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let points = ts.sample_branch_points(10, || rng.gen::<f64>()).unwrap();
+    /// assert_eq!(points.len(), 10);
+    /// for (position, point) in points {
+    ///     assert!(position >= 0.0 && position < 100.0);
+    ///     assert!(point.node == 1 || point.node == 2);
+    ///     assert!(point.time >= 0.0 && point.time <= 1.0);
+    /// }
+    /// ```
+    pub fn sample_branch_points<F: FnMut() -> f64>(
+        &self,
+        n: usize,
+        mut uniform: F,
+    ) -> Result<Vec<(Position, BranchPoint)>, TskitError> {
+        use streaming_iterator::StreamingIterator;
+        let mut draw = || {
+            let u = uniform();
+            if (0.0..1.0).contains(&u) {
+                Ok(u)
+            } else {
+                Err(TskitError::ValueError {
+                    got: format!("{u}"),
+                    expected: "a value on [0, 1)".to_string(),
+                })
+            }
+        };
+
+        let mut areas = vec![];
+        let mut tree = self.tree_iterator(TreeFlags::default())?;
+        while let Some(tree) = tree.next() {
+            areas.push(f64::from(tree.total_branch_length(true)?));
+        }
+        let total = areas.iter().sum::<f64>();
+        if total <= 0.0 {
+            return Err(TskitError::ValueError {
+                got: "a total branch length of zero".to_string(),
+                expected: "a tree sequence with branches".to_string(),
+            });
+        }
+
+        let mut targets = (0..n)
+            .map(|_| draw().map(|u| u * total))
+            .collect::<Result<Vec<_>, _>>()?;
+        targets.sort_by(|a, b| a.total_cmp(b));
+
+        let mut points = Vec::with_capacity(n);
+        let mut targets = targets.into_iter().peekable();
+        let mut left_area = 0.0;
+        let mut tree = self.tree_iterator(TreeFlags::default())?;
+        let mut index = 0;
+        while let Some(tree) = tree.next() {
+            let right_area = left_area + areas[index];
+            let is_last = index + 1 == areas.len();
+            let (left, right) = tree.interval();
+            let length = f64::from(tree.total_branch_length(false)?);
+            while let Some(&x) = targets.peek() {
+                if x >= right_area && !is_last {
+                    break;
+                }
+                targets.next();
+                if let Some(point) = tree.branch_point(draw()?)? {
+                    let position = (f64::from(left) + (x - left_area) / length)
+                        .clamp(f64::from(left), f64::from(right));
+                    points.push((position.into(), point));
+                }
+            }
+            left_area = right_area;
+            index += 1;
+        }
+        Ok(points)
+    }
+
+    /// Calculate the average Kendall-Colijn (`K-C`) distance between
+    /// pairs of trees whose intervals overlap.
+    ///
+    /// # Note
+    ///
+    /// * [Citation](https://doi.org/10.1093/molbev/msw124)
+    ///
+    /// # Parameters
+    ///
+    /// * `lambda` specifies the relative weight of topology and branch length.
+    ///   If `lambda` is 0, we only consider topology.
+    ///   If `lambda` is 1, we only consider branch lengths.
+    pub fn kc_distance(&self, other: &TreeSequence, lambda: f64) -> Result<f64, TskitError> {
+        self.inner.kc_distance(&other.inner, lambda)
+    }
+
+    /// Calculate the Kendall-Colijn (`K-C`) distance between
+    /// the trees of two tree sequences along the genome.
+    ///
+    /// The two tree sequences are traversed together.
+    /// Each interval where a tree of `self` overlaps a tree of `other`
+    /// gives one `(left, right, distance)` value, in order along the genome.
+    /// The intervals are the intersections of the tree intervals
+    /// of `self` and `other`.
+    ///
+    /// See [`TreeSequence::kc_distance`] for the meaning of `lambda`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the sequence lengths differ.
+    /// * [`TskitError::ErrorCode`] if the samples differ or
+    ///   if a tree does not have exactly one root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let root = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let internal = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let samples = (0..3)
+    ///     .map(|_| tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap())
+    ///     .collect::<Vec<_>>();
+    /// tables.add_edge(0., 100., root, internal).unwrap();
+    /// tables.add_edge(0., 100., root, samples[2]).unwrap();
+    /// tables.add_edge(0., 100., internal, samples[0]).unwrap();
+    /// tables.add_edge(0., 100., internal, samples[1]).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let profile = ts.kc_distance_profile(&ts, 0.0).unwrap();
+    /// assert_eq!(profile, vec![(0.0.into(), 100.0.into(), 0.0)]);
+    /// ```
+    pub fn kc_distance_profile(
+        &self,
+        other: &TreeSequence,
+        lambda: f64,
+    ) -> Result<Vec<(Position, Position, f64)>, TskitError> {
+        use streaming_iterator::StreamingIterator;
+
+        if self.inner.sequence_length() != other.inner.sequence_length() {
+            return Err(TskitError::ValueError {
+                got: format!("{}", other.inner.sequence_length()),
+                expected: format!("sequence length {}", self.inner.sequence_length()),
+            });
+        }
+        let mut trees = self.tree_iterator(TreeFlags::SAMPLE_LISTS)?;
+        let mut other_trees = other.tree_iterator(TreeFlags::SAMPLE_LISTS)?;
+        trees.advance();
+        other_trees.advance();
+        let mut profile = vec![];
+        while let (Some(tree), Some(other_tree)) = (trees.get(), other_trees.get()) {
+            let (left, right) = tree.interval();
+            let (other_left, other_right) = other_tree.interval();
+            let distance = tree.kc_distance(other_tree, lambda)?;
+            profile.push((
+                if left > other_left { left } else { other_left },
+                if right < other_right {
+                    right
+                } else {
+                    other_right
+                },
+                distance,
+            ));
+            if right <= other_right {
+                trees.advance();
+            }
+            if other_right <= right {
+                other_trees.advance();
+            }
+        }
+        Ok(profile)
+    }
+
+    /// Export the ancestral recombination graph (ARG)
+    /// as a list of [`ArgEdge`](crate::ArgEdge) records.
+    ///
+    /// # Parameters
+    ///
+    /// * `squash`: if `true`, records for the same parent/child
+    ///   pair whose intervals abut are merged into a single record.
+    ///   Each record then spans a maximal interval of inheritance
+    ///   between recombination events.
+    ///   The records are sorted by child, parent, and left coordinate.
+    ///   If `false`, one record is returned per edge,
+    ///   in edge table order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 50., parent, child).unwrap();
+    /// tables.add_edge(50., 100., parent, child).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let edges = treeseq.arg_edges(false);
+    /// assert_eq!(edges.len(), 2);
+    /// assert_eq!(edges[0].child_time, 0.0);
+    /// assert_eq!(edges[0].parent_time, 1.0);
+    ///
+    /// let edges = treeseq.arg_edges(true);
+    /// assert_eq!(edges.len(), 1);
+    /// assert_eq!(edges[0].left, 0.0);
+    /// assert_eq!(edges[0].right, 100.0);
+    /// ```
+    pub fn arg_edges(&self, squash: bool) -> Vec<super::ArgEdge> {
+        super::arg::arg_edges(self, squash)
+    }
+
+    /// Summarize, for each node, the genomic span over which it
+    /// is an ancestor and the mean number of samples descending
+    /// from it over that span.
+    ///
+    /// The returned vector is indexed by node id.
+    /// See [`NodeSpanSummary`](crate::NodeSpanSummary).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the trees cannot be iterated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c0).unwrap();
+    /// tables.add_edge(0., 25., p, c1).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let summaries = ts.node_span_summaries().unwrap();
+    /// // The sum of the spans of both edges
+    /// assert_eq!(summaries[0].ancestral_span, 125.);
+    /// // 2 samples over 25% of the genome and 1 sample elsewhere
+    /// assert_eq!(summaries[0].mean_descendant_samples, 1.25);
+    /// // Samples are leaves
+    /// assert_eq!(summaries[1].ancestral_span, 0.);
+    /// assert!(summaries[1].mean_descendant_samples.is_nan());
+    /// ```
+    pub fn node_span_summaries(&self) -> Result<Vec<super::NodeSpanSummary>, TskitError> {
+        super::spans::node_span_summaries(self)
+    }
+
+    /// Summarize, for each edge, the number of samples descending
+    /// from its child over its genomic interval.
+    ///
+    /// The returned vector is indexed by edge id.
+    /// See [`EdgeSpanSummary`](crate::EdgeSpanSummary).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the trees cannot be iterated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let q = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., q, c0).unwrap();
+    /// tables.add_edge(0., 40., q, c1).unwrap();
+    /// let e = tables.add_edge(0., 100., p, q).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let summaries = ts.edge_span_summaries().unwrap();
+    /// assert_eq!(summaries[e.as_usize()].span, 100.);
+    /// // 2 samples over 40% of the edge and 1 sample elsewhere
+    /// assert_eq!(summaries[e.as_usize()].mean_descendant_samples, 1.4);
+    /// assert_eq!(summaries[1].mean_descendant_samples, 1.0);
+    /// ```
+    pub fn edge_span_summaries(&self) -> Result<Vec<super::EdgeSpanSummary>, TskitError> {
+        super::spans::edge_span_summaries(self)
+    }
+
+    /// Summarize the samples of each population.
+    ///
+    /// For each population, the summary gives the numbers of
+    /// sample nodes and of individuals with sample nodes,
+    /// and the mean pairwise diversity of the sample nodes
+    /// in each of `windows`, calculated by [`TreeSequence::diversity`]
+    /// with `mode` and `options`.
+    /// Sample nodes without a population are not counted.
+    ///
+    /// The returned vector is indexed by population id.
+    /// See [`PopulationSummary`](crate::PopulationSummary).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `mode` is
+    ///   [`StatsMode::Node`](crate::StatsMode::Node).
+    /// * As for [`TreeSequence::diversity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop0 = tables.add_population().unwrap();
+    /// let pop1 = tables.add_population().unwrap();
+    /// let individual = tables.add_individual(0, None, None).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for (pop, ind) in [(pop0, individual), (pop0, individual), (pop1, tskit::IndividualId::NULL)] {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop, ind).unwrap();
+    ///     tables.add_edge(0., 100., p, c).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let genome = tskit::Windows::genome();
+    /// let branch = tskit::StatsMode::Branch;
+    /// let options = tskit::StatisticsOptions::default().span_normalise();
+    /// let summaries = ts.population_summaries(&genome, branch, options).unwrap();
+    /// assert_eq!(summaries[0].num_samples, 2);
+    /// assert_eq!(summaries[0].num_individuals, 1);
+    /// assert_eq!(summaries[0].diversity, [2.0]);
+    /// assert_eq!(summaries[1].num_individuals, 0);
+    /// assert!(summaries[1].diversity[0].is_nan());
+    /// ```
+    pub fn population_summaries<O: Into<crate::StatisticsOptions>>(
+        &self,
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+        options: O,
+    ) -> Result<Vec<super::PopulationSummary>, TskitError> {
+        super::populations::population_summaries(self, windows, mode, options.into())
+    }
+
+    /// Calculate the frequency of the derived state of each mutation
+    /// within the samples of each population, in one pass over the trees.
+    ///
+    /// A sample node carries the derived state of a mutation if the
+    /// mutation is the nearest one of its site on the path from the
+    /// sample to the root, as for [`TreeSequence::allele_counts`].
+    /// Of several mutations on the same node, only the last one is
+    /// carried.
+    /// The mutation parent column is not used.
+    /// Sample nodes without a population are not counted.
+    ///
+    /// The returned vector is indexed by mutation id.
+    /// See [`MutationPopulationFrequencies`](crate::MutationPopulationFrequencies).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if a sample node has an invalid population.
+    /// * [`TskitError`] if the `C` back end is unable to iterate over
+    ///   the trees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let pop0 = tables.add_population().unwrap();
+    /// let pop1 = tables.add_population().unwrap();
+    /// let p = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let q = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let mut samples = vec![];
+    /// for pop in [pop0, pop0, pop1, pop1] {
+    ///     samples.push(tables.add_node(tskit::NodeFlags::new_sample(), 0.0, pop, -1).unwrap());
+    /// }
+    /// tables.add_edge(0., 100., q, samples[0]).unwrap();
+    /// tables.add_edge(0., 100., q, samples[2]).unwrap();
+    /// tables.add_edge(0., 100., p, q).unwrap();
+    /// tables.add_edge(0., 100., p, samples[1]).unwrap();
+    /// tables.add_edge(0., 100., p, samples[3]).unwrap();
+    /// let site = tables.add_site(50., Some(b"A")).unwrap();
+    /// let m0 = tables.add_mutation(site, q, -1, 1.5, Some(b"C")).unwrap();
+    /// // A back mutation below m0
+    /// tables.add_mutation(site, samples[2], m0, 0.5, Some(b"A")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let frequencies = ts.mutation_population_frequencies().unwrap();
+    /// assert_eq!(frequencies[0].mutation, m0);
+    /// assert_eq!(frequencies[0].counts, [1, 0]);
+    /// assert_eq!(frequencies[0].frequencies, [0.5, 0.0]);
+    /// assert_eq!(frequencies[1].counts, [0, 1]);
+    /// ```
+    pub fn mutation_population_frequencies(
+        &self,
+    ) -> Result<Vec<super::MutationPopulationFrequencies>, TskitError> {
+        super::populations::mutation_population_frequencies(self)
+    }
+
+    /// Calculate nucleotide diversity for each of `sample_sets`
+    /// in each of `windows`.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_sets`: the sets of sample nodes.
+    /// * `windows`: the genomic windows. Regions masked by
+    ///   [`Windows::mask`](crate::Windows::mask) are excluded:
+    ///   sites in masked regions are not counted and,
+    ///   if [`SPAN_NORMALISE`](crate::StatisticsOptions::SPAN_NORMALISE)
+    ///   is set, values are divided by the accessible span
+    ///   of each window rather than its length.
+    /// * `mode`: the [mode](crate::StatsMode) of the statistic.
+    /// * `options`: a [`StatisticsOptions`](crate::StatisticsOptions) bit field.
+    ///   Any mode set in `options` is replaced by `mode`.
+    ///
+    /// # Returns
+    ///
+    /// One row of values per window, stored row-major.
+    /// Each row has one value per sample set or, in
+    /// [`Node`](crate::StatsMode::Node) mode, `sample_sets.num_sets()`
+    /// values for each node, in node order.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if a window is empty or extends
+    ///   beyond the sequence length, or if the mask extends beyond
+    ///   the sequence length.
+    /// * [`TskitError::ValueError`] if there are no windows, or if
+    ///   [`SPAN_NORMALISE`](crate::StatisticsOptions::SPAN_NORMALISE)
+    ///   is set and a window is entirely masked.
+    /// * [`TskitError`] if the `C` back end returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let site = tables.add_site(75., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c0, -1, 0.5, Some(b"G")).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let samples = tskit::SampleSets::new().add_set(treeseq.sample_nodes().iter().copied());
+    /// let options = tskit::StatisticsOptions::default().span_normalise();
+    /// let site = tskit::StatsMode::Site;
+    /// let pi = treeseq
+    ///     .diversity(&samples, &tskit::Windows::genome(), site, options)
+    ///     .unwrap();
+    /// assert_eq!(pi, vec![1.0 / 100.]);
+    ///
+    /// // One row per window
+    /// let windows = tskit::Windows::breakpoints([0., 50., 100.]);
+    /// let pi = treeseq.diversity(&samples, &windows, site, options).unwrap();
+    /// assert_eq!(pi, vec![0.0, 1.0 / 50.]);
+    ///
+    /// // The pairwise branch length
+    /// let branch = tskit::StatsMode::Branch;
+    /// let pi = treeseq.diversity(&samples, &windows, branch, options).unwrap();
+    /// assert_eq!(pi, vec![2.0, 2.0]);
+    ///
+    /// // The site is in the masked region
+    /// let mask = tskit::GenomeMask::new([(50., 100.)]).unwrap();
+    /// let windows = tskit::Windows::genome().mask(mask);
+    /// let pi = treeseq.diversity(&samples, &windows, site, options).unwrap();
+    /// assert_eq!(pi, vec![0.0]);
+    ///
+    /// // The site is not masked, and the accessible span is 50
+    /// let mask = tskit::GenomeMask::new([(0., 50.)]).unwrap();
+    /// let windows = tskit::Windows::genome().mask(mask);
+    /// let pi = treeseq.diversity(&samples, &windows, site, options).unwrap();
+    /// assert_eq!(pi, vec![1.0 / 50.]);
+    /// ```
+    pub fn diversity<O: Into<crate::StatisticsOptions>>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+        options: O,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::one_way_stat(
+            self,
+            ll_bindings::tsk_treeseq_diversity,
+            &sample_sets.as_slices(),
+            windows,
+            crate::stats::with_mode(options.into(), mode),
+        )
+    }
+
+    /// Calculate the number of segregating sites for each of `sample_sets`
+    /// in each of `windows`.
+    ///
+    /// The parameters, return value, and errors are the same as for
+    /// [`TreeSequence::diversity`].
+    pub fn segregating_sites<O: Into<crate::StatisticsOptions>>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+        options: O,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::one_way_stat(
+            self,
+            ll_bindings::tsk_treeseq_segregating_sites,
+            &sample_sets.as_slices(),
+            windows,
+            crate::stats::with_mode(options.into(), mode),
+        )
+    }
+
+    /// Calculate the diversity of each site from the genotypes
+    /// of `samples`.
+    ///
+    /// The diversity of a site is the probability that two distinct
+    /// samples, drawn from those with known genotypes, have
+    /// different alleles.
+    /// It is `NaN` if fewer than two samples have known genotypes.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: the sample nodes. If `None`, all samples are used.
+    /// * `missing`: how missing genotypes are treated.
+    ///   See [`MissingData`](crate::MissingData).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if any of `samples` is not a valid node id,
+    ///   or if decoding the genotypes of a site fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// // An isolated sample, whose genotype is missing
+    /// tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c0).unwrap();
+    /// tables.add_edge(0., 100., p, c1).unwrap();
+    /// let site = tables.add_site(50., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c0, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let pi = ts.site_diversity(None, tskit::MissingData::Exclude).unwrap();
+    /// assert_eq!(pi, [1.0]);
+    /// let pi = ts.site_diversity(None, tskit::MissingData::Ancestral).unwrap();
+    /// assert!((pi[0] - 2.0 / 3.0).abs() < 1e-12);
+    /// ```
+    pub fn site_diversity(
+        &self,
+        samples: Option<&[NodeId]>,
+        missing: crate::MissingData,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::site_diversity(self, samples, missing)
+    }
+
+    /// Calculate the diversity of `sample_sets` in each of a stream
+    /// of `windows`.
+    ///
+    /// Windows are calculated lazily, in batches, as the returned
+    /// [`WindowedStatistic`](crate::WindowedStatistic) is iterated,
+    /// so that very long lists of windows, such as those read by
+    /// [`read_bed_windows`](crate::read_bed_windows), need not be
+    /// held in memory.
+    /// The values for each window are the same as those returned by
+    /// [`TreeSequence::diversity`] for a tree sequence covering
+    /// only that window, with the same `mode` and `options`.
+    /// See [`WindowedStatistic::mask`](crate::WindowedStatistic::mask)
+    /// to exclude masked regions.
+    ///
+    /// # Errors
+    ///
+    /// The iterator returns
+    ///
+    /// * [`TskitError::RangeError`] for windows that are empty or
+    ///   that extend beyond the sequence length, or if the mask
+    ///   extends beyond the sequence length.
+    /// * [`TskitError::ValueError`] if
+    ///   [`SPAN_NORMALISE`](crate::StatisticsOptions::SPAN_NORMALISE)
+    ///   is set and a window is entirely masked.
+    /// * [`TskitError`] if the `C` back end returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let site = tables.add_site(75., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c0, -1, 0.5, Some(b"G")).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let samples = tskit::SampleSets::new().add_set(treeseq.sample_nodes().iter().copied());
+    /// let site = tskit::StatsMode::Site;
+    /// let options = tskit::StatisticsOptions::default();
+    /// let bed = "chr1\t50\t100\nchr1\t0\t50\n";
+    /// let windows = tskit::read_bed_windows(bed.as_bytes());
+    /// let pi = treeseq
+    ///     .windowed_diversity(&samples, site, options, windows)
+    ///     .map(|w| w.unwrap().2)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(pi, [[1.0], [0.0]]);
+    ///
+    /// // The site is in the masked region
+    /// let mask = tskit::GenomeMask::new([(50., 100.)]).unwrap();
+    /// let pi = treeseq
+    ///     .windowed_diversity(&samples, site, options, [(0., 100.)])
+    ///     .mask(mask)
+    ///     .map(|w| w.unwrap().2)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(pi, [[0.0]]);
+    /// ```
+    pub fn windowed_diversity<O, I>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        mode: crate::StatsMode,
+        options: O,
+        windows: I,
+    ) -> crate::WindowedStatistic<'_, I::IntoIter>
+    where
+        O: Into<crate::StatisticsOptions>,
+        I: IntoIterator,
+        I::Item: crate::StatWindow,
+    {
+        crate::WindowedStatistic::new(
+            self,
+            ll_bindings::tsk_treeseq_diversity,
+            sample_sets,
+            crate::stats::with_mode(options.into(), mode),
+            windows.into_iter(),
+        )
+    }
+
+    /// Calculate the number of segregating sites of `sample_sets`
+    /// in each of a stream of `windows`.
+    ///
+    /// See [`TreeSequence::windowed_diversity`].
+    pub fn windowed_segregating_sites<O, I>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        mode: crate::StatsMode,
+        options: O,
+        windows: I,
+    ) -> crate::WindowedStatistic<'_, I::IntoIter>
+    where
+        O: Into<crate::StatisticsOptions>,
+        I: IntoIterator,
+        I::Item: crate::StatWindow,
+    {
+        crate::WindowedStatistic::new(
+            self,
+            ll_bindings::tsk_treeseq_segregating_sites,
+            sample_sets,
+            crate::stats::with_mode(options.into(), mode),
+            windows.into_iter(),
+        )
+    }
+
+    /// Calculate the divergence between all pairs of `sample_sets`
+    /// in each of `windows`.
+    ///
+    /// The diagonal contains the diversity of each sample set.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_sets`: the sets of sample nodes.
+    /// * `windows`, `mode`, `options`: as for [`TreeSequence::diversity`].
+    ///
+    /// # Returns
+    ///
+    /// One `sample_sets.num_sets()` by `sample_sets.num_sets()`
+    /// symmetric matrix per window, each stored row-major,
+    /// one after the other.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `mode` is
+    ///   [`StatsMode::Node`](crate::StatsMode::Node).
+    /// * As for [`TreeSequence::diversity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let site = tables.add_site(75., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c0, -1, 0.5, Some(b"G")).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let sample_sets = tskit::SampleSets::new()
+    ///     .add_set([c0])
+    ///     .add_set([c1])
+    ///     .add_set([c0, c1]);
+    /// let genome = tskit::Windows::genome();
+    /// let site = tskit::StatsMode::Site;
+    /// let options = tskit::StatisticsOptions::default();
+    /// let d = treeseq.divergence_matrix(&sample_sets, &genome, site, options).unwrap();
+    /// assert_eq!(d[1], 1.0);
+    /// assert_eq!(d[3], 1.0);
+    /// // The diversity of a single sample is undefined
+    /// assert!(d[0].is_nan());
+    /// assert_eq!(d[8], 1.0);
+    ///
+    /// // One matrix per window
+    /// let windows = tskit::Windows::breakpoints([0., 50., 100.]);
+    /// let d = treeseq.divergence_matrix(&sample_sets, &windows, site, options).unwrap();
+    /// assert_eq!(d.len(), 18);
+    /// assert_eq!((d[1], d[10]), (0.0, 1.0));
+    /// ```
+    pub fn divergence_matrix<O: Into<crate::StatisticsOptions>>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+        options: O,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::reject_node_mode(mode)?;
+        let k = sample_sets.num_sets();
+        let indexes = (0..k)
+            .flat_map(|i| (i..k).map(move |j| (i, j)))
+            .collect::<Vec<_>>();
+        let values = crate::stats::two_way_stat(
+            self,
+            ll_bindings::tsk_treeseq_divergence,
+            &sample_sets.as_slices(),
+            &indexes,
+            windows,
+            crate::stats::with_mode(options.into(), mode),
+        )?;
+        let mut rv = vec![];
+        for window in values.chunks_exact(indexes.len().max(1)) {
+            let mut matrix = vec![0.0; k * k];
+            for (&(i, j), &value) in indexes.iter().zip(window) {
+                matrix[i * k + j] = value;
+                matrix[j * k + i] = value;
+            }
+            rv.extend(matrix);
+        }
+        Ok(rv)
+    }
+
+    /// Calculate the divergence between pairs of `sample_sets`
+    /// in each of `windows`.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_sets`: the sets of sample nodes.
+    /// * `indexes`: pairs of indexes into `sample_sets`.
+    /// * `windows`, `mode`, `options`: as for [`TreeSequence::diversity`].
+    ///
+    /// # Returns
+    ///
+    /// One row of values per window, stored row-major.
+    /// Each row has one value per pair in `indexes` or, in
+    /// [`Node`](crate::StatsMode::Node) mode, `indexes.len()`
+    /// values for each node, in node order.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `indexes` refers to a sample set
+    ///   that does not exist.
+    /// * As for [`TreeSequence::diversity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let sample_sets = tskit::SampleSets::new().add_set([c0]).add_set([c1]);
+    /// let d = treeseq
+    ///     .divergence(
+    ///         &sample_sets,
+    ///         &[(0, 1)],
+    ///         &tskit::Windows::breakpoints([0., 25., 100.]),
+    ///         tskit::StatsMode::Branch,
+    ///         tskit::StatisticsOptions::default(),
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(d, vec![50.0, 150.0]);
+    /// ```
+    pub fn divergence<O: Into<crate::StatisticsOptions>>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        indexes: &[(usize, usize)],
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+        options: O,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::two_way_stat(
+            self,
+            ll_bindings::tsk_treeseq_divergence,
+            &sample_sets.as_slices(),
+            indexes,
+            windows,
+            crate::stats::with_mode(options.into(), mode),
+        )
+    }
+
+    /// Calculate Tajima's D for each of `sample_sets` in each
+    /// of `windows`.
+    ///
+    /// Calculated from the [diversity](TreeSequence::diversity)
+    /// and the number of [segregating sites](TreeSequence::segregating_sites),
+    /// so that in [`Branch`](crate::StatsMode::Branch) mode the
+    /// result is the branch length analogue of Tajima's D.
+    /// The value is `NaN` for sample sets with fewer than two
+    /// samples or with no segregating sites.
     ///
+    /// # Returns
+    ///
+    /// One row of values per window, stored row-major,
+    /// with one value per sample set.
     ///
     /// # Errors
     ///
-    /// [`TskitError`] will be raised if the underlying C library returns an error code.
-    pub fn dump_tables(self) -> Result<TableCollection, TskitError> {
-        assert!(!self.as_ptr().is_null());
-        let mut treeseq = self;
-        // SAFETY: the above assert passed
-        let tables = std::ptr::NonNull::new(unsafe { (*treeseq.as_ptr()).tables }).unwrap();
-        // SAFETY: the above assert passed
-        unsafe { (*treeseq.as_mut_ptr()).tables = std::ptr::null_mut() };
-        // SAFETY: the table collection points to data that has passed
-        // tsk_table_collection_check_integrity, meaning that it must be initialized.
-        let tables = unsafe { crate::sys::TableCollection::new_owning_from_nonnull(tables) };
-        crate::TableCollection::new_from_ll(tables)
+    /// * [`TskitError::ValueError`] if `mode` is
+    ///   [`StatsMode::Node`](crate::StatsMode::Node).
+    /// * As for [`TreeSequence::diversity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ts = tskit::testing::random_binary(10, 100., 1).unwrap();
+    /// let samples = tskit::SampleSets::new().add_set(ts.sample_nodes().iter().copied());
+    /// let windows = tskit::Windows::breakpoints([0., 50., 100.]);
+    /// let d = ts.tajimas_d(&samples, &windows, tskit::StatsMode::Branch).unwrap();
+    /// assert_eq!(d.len(), 2);
+    /// assert!(d.iter().all(|d| d.is_finite()));
+    /// ```
+    pub fn tajimas_d(
+        &self,
+        sample_sets: &crate::SampleSets,
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::tajimas_d(self, &sample_sets.as_slices(), windows, mode)
     }
 
-    /// Create an iterator over trees.
+    /// Calculate Fst between pairs of `sample_sets` in each of `windows`.
     ///
-    /// # Parameters
+    /// Fst is one minus the ratio of the mean diversity within the
+    /// two sample sets to the mean diversity of the combined sets,
+    /// calculated from the [diversity](TreeSequence::diversity) and
+    /// [divergence](TreeSequence::divergence).
     ///
-    /// * `flags` A [`TreeFlags`] bit field.
+    /// # Returns
+    ///
+    /// One row of values per window, stored row-major,
+    /// with one value per pair in `indexes`.
     ///
     /// # Errors
     ///
+    /// * [`TskitError::ValueError`] if `mode` is
+    ///   [`StatsMode::Node`](crate::StatsMode::Node).
+    /// * [`TskitError::IndexError`] if `indexes` refers to a sample set
+    ///   that does not exist.
+    /// * As for [`TreeSequence::diversity`].
+    ///
     /// # Examples
     ///
     /// ```
-    /// // You must include streaming_iterator as a dependency
-    /// // and import this type.
-    /// use streaming_iterator::StreamingIterator;
-    /// // Import this to allow .next_back() for reverse
-    /// // iteration over trees.
-    /// use streaming_iterator::DoubleEndedStreamingIterator;
-    ///
-    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
-    /// tables.build_index();
-    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
-    /// let mut tree_iterator = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap();
-    /// while let Some(tree) = tree_iterator.next() {
-    /// }
+    /// let ts = tskit::testing::balanced(4, 100.).unwrap();
+    /// let sample_sets = tskit::SampleSets::new().add_set([0, 1]).add_set([2, 3]);
+    /// let fst = ts
+    ///     .fst(&sample_sets, &[(0, 1)], &tskit::Windows::genome(), tskit::StatsMode::Branch)
+    ///     .unwrap();
+    /// // Within sets, pairs coalesce at time 1, and between sets, at time 2.
+    /// assert_eq!(fst, vec![1.0 - 2.0 * 4.0 / (4.0 + 2.0 * 4.0)]);
     /// ```
+    pub fn fst(
+        &self,
+        sample_sets: &crate::SampleSets,
+        indexes: &[(usize, usize)],
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::fst(self, &sample_sets.as_slices(), indexes, windows, mode)
+    }
+
+    /// Calculate the joint allele frequency spectrum of `sample_sets`
+    /// in each of `windows`.
     ///
-    /// ## Coupled liftimes
+    /// Unless [`POLARISED`](crate::StatisticsOptions::POLARISED) is set,
+    /// the spectrum is folded.
     ///
-    /// A `Tree`'s lifetime is tied to that of its tree sequence:
+    /// # Returns
+    ///
+    /// One spectrum per window, stored one after the other.
+    /// Each spectrum is an array with dimensions `len + 1` for
+    /// each sample set, stored row-major.
+    /// Element `[k_0, k_1, ...]` is the number of alleles
+    /// (or, in [`Branch`](crate::StatsMode::Branch) mode,
+    /// the branch length) found in `k_i` samples of each set `i`.
+    ///
+    /// # Errors
+    ///
+    /// * As for [`TreeSequence::diversity`].
+    /// * [`TskitError`] if the `C` back end returns an error,
+    ///   including for [`Node`](crate::StatsMode::Node) mode.
+    ///
+    /// # Examples
     ///
-    /// ```{compile_fail}
-    /// # use streaming_iterator::StreamingIterator;
-    /// # use streaming_iterator::DoubleEndedStreamingIterator;
-    /// # let mut tables = tskit::TableCollection::new(1000.).unwrap();
-    /// # tables.build_index();
-    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
-    /// let mut tree_iterator = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap();
-    /// drop(tree_sequence);
-    /// while let Some(tree) = tree_iterator.next() { // compile fail.
-    /// }
     /// ```
-    /// # Warning
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let site = tables.add_site(75., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c0, -1, 0.5, Some(b"G")).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
     ///
-    /// The following code results in an infinite loop.
-    /// Be sure to note the difference from the previous example.
+    /// let samples = tskit::SampleSets::new().add_set([c0, c1]);
+    /// let genome = tskit::Windows::genome();
+    /// let site = tskit::StatsMode::Site;
+    /// let options = tskit::StatisticsOptions::default().polarised();
+    /// let afs = treeseq.allele_frequency_spectrum(&samples, &genome, site, options).unwrap();
+    /// assert_eq!(afs, vec![0.0, 1.0, 0.0]);
     ///
-    /// ```no_run
-    /// use streaming_iterator::StreamingIterator;
+    /// // One sample from each of two sets
+    /// let sample_sets = tskit::SampleSets::new().add_set([c0]).add_set([c1]);
+    /// let afs = treeseq
+    ///     .allele_frequency_spectrum(&sample_sets, &genome, site, options)
+    ///     .unwrap();
+    /// assert_eq!(afs, vec![0.0, 0.0, 1.0, 0.0]);
     ///
-    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
-    /// tables.build_index();
-    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
-    /// while let Some(tree) = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap().next() {
-    /// }
+    /// // One spectrum per window
+    /// let windows = tskit::Windows::breakpoints([0., 50., 100.]);
+    /// let afs = treeseq.allele_frequency_spectrum(&samples, &windows, site, options).unwrap();
+    /// assert_eq!(afs, vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
     /// ```
-    pub fn tree_iterator<F: Into<TreeFlags>>(&self, flags: F) -> Result<Tree, TskitError> {
-        let tree = Tree::new(&self.inner, flags)?;
-
-        Ok(tree)
+    pub fn allele_frequency_spectrum<O: Into<crate::StatisticsOptions>>(
+        &self,
+        sample_sets: &crate::SampleSets,
+        windows: &crate::Windows,
+        mode: crate::StatsMode,
+        options: O,
+    ) -> Result<Vec<f64>, TskitError> {
+        crate::stats::allele_frequency_spectrum(
+            self,
+            &sample_sets.as_slices(),
+            windows,
+            crate::stats::with_mode(options.into(), mode),
+        )
     }
 
-    /// Create an iterator over trees starting at a specific position.
+    /// Find the IBD segments of all pairs of `samples`.
     ///
-    /// See [`TreeSequence::tree_iterator`] for details
+    /// A segment of the genome is identical by descent (IBD) for
+    /// a pair of samples if they have the same most recent common
+    /// ancestor (MRCA) along the segment.
+    /// Adjacent segments may have the same MRCA.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: the sample nodes, or `None` for all sample nodes.
+    /// * `options`: filters on the segments that are found.
+    ///   See [`IbdOptions`](crate::IbdOptions).
     ///
     /// # Errors
     ///
-    /// * [`TskitError`] if `at` is not valid
-    pub fn tree_iterator_at_position<F: Into<TreeFlags>, P: Into<Position>>(
+    /// * [`TskitError`] if a sample is out of range or duplicated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p0 = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let p1 = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// // c0 and c1 coalesce in p0 on [0, 40) and in p1 on [40, 100)
+    /// tables.add_edge(0., 40., p0, c0).unwrap();
+    /// tables.add_edge(0., 40., p0, c1).unwrap();
+    /// tables.add_edge(40., 100., p1, c0).unwrap();
+    /// tables.add_edge(40., 100., p1, c1).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let ibd = ts.ibd_segments_within(None, tskit::IbdOptions::default()).unwrap();
+    /// assert_eq!(ibd.num_segments(), 2);
+    /// assert_eq!(ibd.total_span(), 100.);
+    /// let mrcas = ibd.get(c1, c0).map(|s| s.node).collect::<Vec<_>>();
+    /// assert_eq!(mrcas.len(), 2);
+    /// assert!(mrcas.contains(&p0) && mrcas.contains(&p1));
+    ///
+    /// // Only the segment with the younger MRCA
+    /// let options = tskit::IbdOptions::default().max_time(1.5);
+    /// let ibd = ts.ibd_segments_within(None, options).unwrap();
+    /// let segments = ibd.iter().collect::<Vec<_>>();
+    /// assert_eq!(segments.len(), 1);
+    /// assert_eq!(segments[0].node, p0);
+    /// assert_eq!(segments[0].span(), 40.);
+    /// ```
+    pub fn ibd_segments_within(
         &self,
-        flags: F,
-        at: P,
-    ) -> Result<Tree, TskitError> {
-        Tree::new_at_position(&self.inner, flags, at)
+        samples: Option<&[NodeId]>,
+        options: crate::IbdOptions,
+    ) -> Result<crate::IdentitySegments, TskitError> {
+        crate::ibd::within(self, samples, options)
     }
 
-    /// Create an iterator over trees starting at a specific tree index.
+    /// Find the IBD segments of all pairs of samples from
+    /// different sets of `sample_sets`.
     ///
-    /// See [`TreeSequence::tree_iterator`] for details
+    /// See [`TreeSequence::ibd_segments_within`].
     ///
     /// # Errors
     ///
-    /// * [`TskitError`] if `at` is not valid
-    pub fn tree_iterator_at_index<F: Into<TreeFlags>>(
+    /// * [`TskitError`] if a sample is out of range or
+    ///   in more than one set.
+    pub fn ibd_segments_between(
         &self,
-        flags: F,
-        at: i32,
-    ) -> Result<Tree, TskitError> {
-        Tree::new_at_index(&self.inner, flags, at)
-    }
-
-    /// Get the list of sample nodes as a slice.
-    pub fn sample_nodes(&self) -> &[NodeId] {
-        let num_samples = unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) };
-        sys::generate_slice(self.as_ref().samples, num_samples)
+        sample_sets: &[&[NodeId]],
+        options: crate::IbdOptions,
+    ) -> Result<crate::IdentitySegments, TskitError> {
+        crate::ibd::between(self, sample_sets, options)
     }
 
-    /// Get the number of trees.
-    pub fn num_trees(&self) -> SizeType {
-        self.inner.num_trees()
+    /// Create a [`GenomeScan`](crate::GenomeScan) over the trees
+    /// of this tree sequence.
+    pub fn genome_scan(&self) -> crate::GenomeScan<'_> {
+        crate::GenomeScan::new(self)
     }
 
-    /// Calculate the average Kendall-Colijn (`K-C`) distance between
-    /// pairs of trees whose intervals overlap.
-    ///
-    /// # Note
-    ///
-    /// * [Citation](https://doi.org/10.1093/molbev/msw124)
-    ///
-    /// # Parameters
-    ///
-    /// * `lambda` specifies the relative weight of topology and branch length.
-    ///   If `lambda` is 0, we only consider topology.
-    ///   If `lambda` is 1, we only consider branch lengths.
-    pub fn kc_distance(&self, other: &TreeSequence, lambda: f64) -> Result<f64, TskitError> {
-        self.inner.kc_distance(&other.inner, lambda)
+    /// Create a [`TreeSequenceCache`](crate::TreeSequenceCache)
+    /// that memoizes values derived from this tree sequence.
+    pub fn cache(&self) -> super::TreeSequenceCache<'_> {
+        super::TreeSequenceCache::new(self)
     }
 
-    // FIXME: document
+    /// Get the number of samples.
     pub fn num_samples(&self) -> SizeType {
         self.inner.num_samples()
     }
@@ -316,19 +2303,56 @@ impl TreeSequence {
     ///   in length to the input node table.  For each input node,
     ///   this vector either contains the node's new index or [`NodeId::NULL`]
     ///   if the input node is not part of the simplified history.
+    ///
+    /// Migrations are handled as by [`TableCollection::simplify`].
     pub fn simplify<O: Into<SimplificationOptions>>(
         &self,
         samples: &[NodeId],
         options: O,
         idmap: bool,
     ) -> Result<(Self, Option<Vec<NodeId>>), TskitError> {
+        self.simplify_impl(samples, options.into(), idmap, false)
+    }
+
+    /// Simplify tables, removing all migrations, and return
+    /// a new tree sequence.
+    ///
+    /// The parameters, return value, and errors are the same as for
+    /// [`TreeSequence::simplify`].
+    /// See [`TableCollection::simplify_clear_migrations`].
+    pub fn simplify_clear_migrations<O: Into<SimplificationOptions>>(
+        &self,
+        samples: &[NodeId],
+        options: O,
+        idmap: bool,
+    ) -> Result<(Self, Option<Vec<NodeId>>), TskitError> {
+        self.simplify_impl(samples, options.into(), idmap, true)
+    }
+
+    fn simplify_impl(
+        &self,
+        samples: &[NodeId],
+        options: SimplificationOptions,
+        idmap: bool,
+        clear_migrations: bool,
+    ) -> Result<(Self, Option<Vec<NodeId>>), TskitError> {
+        if self.migrations().num_rows() > 0 {
+            let mut tables = TableCollection::new_from_ll(self.inner.copy_tables()?)?;
+            let output_node_map = match clear_migrations {
+                true => tables.simplify_clear_migrations(samples, options, idmap)?,
+                false => tables.simplify(samples, options, idmap)?,
+            }
+            .map(|idmap| idmap.to_vec());
+            let treeseq = Self::new(tables, TreeSequenceFlags::default().build_indexes())?;
+            return Ok((treeseq, output_node_map));
+        }
         let mut output_node_map: Vec<NodeId> = vec![];
         if idmap {
             output_node_map.resize(usize::try_from(self.nodes().num_rows())?, NodeId::NULL);
         }
         let mut inner = self.inner.simplify(
             samples,
-            options.into(),
+            options,
             match idmap {
                 true => Some(&mut output_node_map),
                 false => None,
@@ -351,10 +2375,8 @@ impl TreeSequence {
     /// and results in non-empty edge table.
     /// - `Error(TskitError)`: Any errors from the C API propagate. An
     /// [TskitError::RangeError] will occur when `intervals` are not
-    /// sorted. Note that as `tskit` currently does not support `simplify`
-    /// on [TreeSequence] with a non-empty migration table, calling
-    /// `keep_intervals` on those [TreeSequence] with `simplify` set to `true`
-    /// will return an error.
+    /// sorted. See [`TableCollection::simplify`] for the handling
+    /// of migrations when `simplify` is `true`.
     ///
     /// # Example
     /// ```rust
@@ -404,6 +2426,417 @@ impl TreeSequence {
         }
     }
 
+    /// Return a copy of the tree sequence with the sample nodes reordered.
+    ///
+    /// Node ids are reassigned so that the k-th value of `samples`
+    /// becomes node `k`, and thus the k-th sample.
+    /// The remaining nodes follow the samples, in their original order.
+    /// Output that is ordered by sample, such as genotypes
+    /// and per-sample statistics, follows the new order.
+    ///
+    /// Populations are unchanged.
+    /// Individuals are retained, but may be renumbered.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: a permutation of [`TreeSequence::sample_nodes`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `samples` is not a permutation of
+    ///   the sample nodes.
+    /// * [`TskitError`] if the `C` back end returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, 0, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, 1, -1).unwrap();
+    /// tables.add_population().unwrap();
+    /// tables.add_population().unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let reordered = treeseq.reorder_samples(&[c1, c0]).unwrap();
+    /// assert_eq!(reordered.sample_nodes(), &[0, 1]);
+    /// // The first sample is the former node c1
+    /// assert_eq!(reordered.nodes().population(0).unwrap(), 1);
+    /// assert_eq!(reordered.nodes().population(1).unwrap(), 0);
+    ///
+    /// assert!(treeseq.reorder_samples(&[c0]).is_err());
+    /// assert!(treeseq.reorder_samples(&[c0, c0]).is_err());
+    /// assert!(treeseq.reorder_samples(&[c0, parent]).is_err());
+    /// ```
+    pub fn reorder_samples(&self, samples: &[NodeId]) -> Result<Self, TskitError> {
+        let num_nodes = self.nodes().num_rows().as_usize();
+        let mut is_sample = vec![false; num_nodes];
+        self.sample_nodes()
+            .iter()
+            .for_each(|s| is_sample[s.as_usize()] = true);
+        let mut seen = vec![false; num_nodes];
+        let mut is_permutation = samples.len() == self.sample_nodes().len();
+        for s in samples {
+            match s.to_usize() {
+                Some(i) if i < num_nodes && is_sample[i] && !seen[i] => seen[i] = true,
+                _ => is_permutation = false,
+            }
+        }
+        if !is_permutation {
+            return Err(TskitError::ValueError {
+                got: format!("{:?}", samples),
+                expected: "a permutation of the sample nodes".to_string(),
+            });
+        }
+        let nodes = samples
+            .iter()
+            .copied()
+            .chain(
+                (0..num_nodes)
+                    .filter(|i| !is_sample[*i])
+                    .map(|i| NodeId::from(i as ll_bindings::tsk_id_t)),
+            )
+            .collect::<Vec<_>>();
+        let mut tables = self.inner.copy_tables()?;
+        tables.subset(
+            &nodes,
+            ll_bindings::TSK_SUBSET_NO_CHANGE_POPULATIONS
+                | ll_bindings::TSK_SUBSET_KEEP_UNREFERENCED,
+        )?;
+        let mut tables = TableCollection::new_from_ll(tables)?;
+        tables.full_sort(crate::TableSortOptions::default())?;
+        Self::new(tables, TreeSequenceFlags::default().build_indexes())
+    }
+
+    /// Get the name of each sample, in the order of [`TreeSequence::sample_nodes`].
+    ///
+    /// The names are decoded from the metadata of each sample's individual.
+    /// The value for a sample is `None` if the sample has no individual
+    /// or the individual has no metadata.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::MetadataError`] if decoding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// struct Name(String);
+    ///
+    /// impl tskit::metadata::MetadataRoundtrip for Name {
+    ///     fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+    ///         Ok(self.0.as_bytes().to_vec())
+    ///     }
+    ///     fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+    ///         Ok(Self(String::from_utf8_lossy(md).to_string()))
+    ///     }
+    /// }
+    /// impl tskit::metadata::IndividualMetadata for Name {}
+    /// impl tskit::metadata::NamedIndividualMetadata for Name {
+    ///     fn individual_name(&self) -> &str {
+    ///         &self.0
+    ///     }
+    /// }
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// tables
+    ///     .add_sample_individuals(&[Name("NA0001".to_string()), Name("NA0002".to_string())])
+    ///     .unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let names = treeseq.sample_names::<Name>().unwrap();
+    /// assert_eq!(names, vec![Some("NA0001".to_string()), Some("NA0002".to_string())]);
+    ///
+    /// // Names follow the samples when they are reordered
+    /// let reordered = treeseq.reorder_samples(&[c1, c0]).unwrap();
+    /// let names = reordered.sample_names::<Name>().unwrap();
+    /// assert_eq!(names, vec![Some("NA0002".to_string()), Some("NA0001".to_string())]);
+    /// ```
+    pub fn sample_names<M: crate::metadata::NamedIndividualMetadata>(
+        &self,
+    ) -> Result<Vec<Option<String>>, TskitError> {
+        let mut rv = vec![];
+        for s in self.sample_nodes() {
+            let individual = self.nodes().individual(*s).ok_or(TskitError::IndexError)?;
+            let name = match self.individuals().metadata::<M>(individual) {
+                Some(md) => Some(md?.individual_name().to_string()),
+                None => None,
+            };
+            rv.push(name);
+        }
+        Ok(rv)
+    }
+
+    /// Split the [TreeSequence] into one tree sequence per genome interval.
+    ///
+    /// For each interval `(left, right)`, the returned tree sequence
+    /// contains only the edges, sites, mutations, and migrations
+    /// overlapping `[left, right)`, and is simplified with respect
+    /// to the sample nodes.
+    /// If no edges overlap an interval, the corresponding tree
+    /// sequence has no edges, sites, mutations, or migrations.
+    ///
+    /// # Parameters
+    ///
+    /// * `intervals`: the `(left, right)` genome intervals.
+    ///   Intervals need not be sorted and may overlap.
+    /// * `shift`: if `true`, the coordinates of each output are
+    ///   shifted to start at zero and the sequence length becomes
+    ///   `right - left`.
+    ///   If `false`, the coordinates and sequence length are
+    ///   those of the input.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if any interval has `left >= right`,
+    ///   `left < 0`, or `right` greater than the sequence length.
+    /// * [`TskitError`] if copying, simplifying, or indexing the tables fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., parent, c0).unwrap();
+    /// tables.add_edge(0., 100., parent, c1).unwrap();
+    /// tables.add_site(75., None).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    ///
+    /// let chunks = treeseq.split(&[(0., 50.), (50., 100.)], true).unwrap();
+    /// assert_eq!(chunks.len(), 2);
+    /// assert_eq!(chunks[1].edges().num_rows(), 2);
+    /// assert_eq!(chunks[1].sites().position(0).unwrap(), 25.0);
+    /// assert_eq!(chunks[0].sites().num_rows(), 0);
+    ///
+    /// let chunks = treeseq.split(&[(50., 100.)], false).unwrap();
+    /// assert_eq!(chunks[0].sites().position(0).unwrap(), 75.0);
+    /// ```
+    pub fn split<P: Into<Position> + Copy>(
+        &self,
+        intervals: &[(P, P)],
+        shift: bool,
+    ) -> Result<Vec<Self>, TskitError> {
+        let sequence_length = self.inner.sequence_length();
+        let mut rv = vec![];
+        for &(left, right) in intervals {
+            let (left, right) = (left.into(), right.into());
+            if !(left >= 0.0 && left < right && right <= sequence_length) {
+                return Err(TskitError::RangeError(format!(
+                    "invalid interval [{}, {})",
+                    left, right
+                )));
+            }
+            let tables = TableCollection::new_from_ll(self.inner.copy_tables()?)?;
+            let mut tables = match tables.keep_intervals(std::iter::once((left, right)), true)? {
+                Some(tables) => tables,
+                None => {
+                    let mut lltables = self.inner.copy_tables()?;
+                    // SAFETY: the tables are initialized and not null
+                    unsafe {
+                        ll_bindings::tsk_edge_table_clear(lltables.edges_mut());
+                        ll_bindings::tsk_migration_table_clear(lltables.migrations_mut());
+                        ll_bindings::tsk_mutation_table_clear(lltables.mutations_mut());
+                        ll_bindings::tsk_site_table_clear(lltables.sites_mut());
+                    }
+                    let mut tables = TableCollection::new_from_ll(lltables)?;
+                    let samples = tables.samples_as_vector();
+                    tables.simplify(&samples, SimplificationOptions::default(), false)?;
+                    tables
+                }
+            };
+            if shift {
+                tables.shift_coordinates(left, right - left);
+            }
+            rv.push(Self::new(
+                tables,
+                TreeSequenceFlags::default().build_indexes(),
+            )?);
+        }
+        Ok(rv)
+    }
+
+    /// Concatenate tree sequences end-to-end.
+    ///
+    /// The genome of the output is the genomes of the inputs
+    /// laid end-to-end, so that its sequence length is the sum
+    /// of the input sequence lengths.
+    /// The coordinates of each input are shifted by the summed
+    /// sequence lengths of the preceding inputs.
+    ///
+    /// The output starts from a copy of the tables of the first
+    /// input.
+    /// For each following input:
+    ///
+    /// * The sample nodes are identified, in order, with the sample nodes
+    ///   of the first input.
+    /// * All other nodes are appended to the node table.
+    ///   Appended nodes keep their population, but their individual
+    ///   is set to [`IndividualId::NULL`](crate::IndividualId::NULL).
+    /// * Population ids are assumed to refer to the same populations
+    ///   as in the first input.
+    ///   Any populations beyond those of the output tables are appended.
+    /// * Edges, sites, mutations, and migrations are appended,
+    ///   with node, site, and mutation ids remapped.
+    ///
+    /// The output tables are sorted and indexed.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `treeseqs` is empty or if
+    ///   the inputs differ in their number of samples.
+    /// * [`TskitError`] if the `C` back end returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn make_treeseq(sequence_length: f64) -> tskit::TreeSequence {
+    /// #    let mut tables = tskit::TableCollection::new(sequence_length).unwrap();
+    /// #    let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// #    let c0 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// #    let c1 = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// #    tables.add_edge(0., sequence_length, parent, c0).unwrap();
+    /// #    tables.add_edge(0., sequence_length, parent, c1).unwrap();
+    /// #    tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap()
+    /// # }
+    /// let chunks = [make_treeseq(100.), make_treeseq(50.)];
+    /// let treeseq = tskit::TreeSequence::concat(&chunks).unwrap();
+    /// assert_eq!(treeseq.num_trees(), 2);
+    /// assert_eq!(treeseq.num_samples(), 2);
+    /// // The root of each chunk is a distinct node.
+    /// assert_eq!(treeseq.nodes().num_rows(), 4);
+    /// assert_eq!(treeseq.tree_spans(), [100.0, 50.0].map(tskit::Position::from));
+    /// ```
+    pub fn concat(treeseqs: &[TreeSequence]) -> Result<Self, TskitError> {
+        use streaming_iterator::StreamingIterator;
+        let (first, rest) = treeseqs
+            .split_first()
+            .ok_or_else(|| TskitError::ValueError {
+                got: "no tree sequences".to_string(),
+                expected: "at least one tree sequence".to_string(),
+            })?;
+        let samples = first.sample_nodes();
+        let mut tables = first.inner.copy_tables()?;
+        let mut offset = first.inner.sequence_length();
+
+        for treeseq in rest {
+            if treeseq.sample_nodes().len() != samples.len() {
+                return Err(TskitError::ValueError {
+                    got: format!("{} samples", treeseq.sample_nodes().len()),
+                    expected: format!("{} samples", samples.len()),
+                });
+            }
+            // SAFETY: the pointers to the tables are not null
+            // and point to initialized tables.
+            let (mut populations, mut nodes, mut edges, mut sites, mut mutations, mut migrations) = unsafe {
+                (
+                    sys::PopulationTable::new_borrowed(tables.populations_mut().into()),
+                    sys::NodeTable::new_borrowed(tables.nodes_mut().into()),
+                    sys::EdgeTable::new_borrowed(tables.edges_mut().into()),
+                    sys::SiteTable::new_borrowed(tables.sites_mut().into()),
+                    sys::MutationTable::new_borrowed(tables.mutations_mut().into()),
+                    sys::MigrationTable::new_borrowed(tables.migrations_mut().into()),
+                )
+            };
+
+            let mut rows = treeseq
+                .populations()
+                .lending_iter()
+                .skip(populations.as_ref().num_rows as usize);
+            while let Some(row) = rows.next() {
+                let id = populations.add_row_with_metadata(row.metadata.unwrap_or(&[]))?;
+                check_row_id(id)?;
+            }
+
+            let mut node_map = vec![NodeId::NULL; treeseq.nodes().num_rows().as_usize()];
+            for (o, s) in treeseq.sample_nodes().iter().zip(samples) {
+                node_map[o.as_usize()] = *s;
+            }
+            let mut rows = treeseq.nodes().lending_iter();
+            while let Some(row) = rows.next() {
+                if node_map[row.id.as_usize()].is_null() {
+                    node_map[row.id.as_usize()] = nodes.add_row_with_metadata(
+                        row.flags,
+                        row.time,
+                        row.population,
+                        crate::IndividualId::NULL,
+                        row.metadata.unwrap_or(&[]),
+                    )?;
+                }
+            }
+
+            let mut rows = treeseq.edges().lending_iter();
+            while let Some(row) = rows.next() {
+                let id = edges.add_row_with_metadata(
+                    f64::from(row.left) + offset,
+                    f64::from(row.right) + offset,
+                    node_map[row.parent.as_usize()].into(),
+                    node_map[row.child.as_usize()].into(),
+                    row.metadata.unwrap_or(&[]),
+                )?;
+                check_row_id(id)?;
+            }
+
+            let site_offset = sites.as_ref().num_rows as ll_bindings::tsk_id_t;
+            let mut rows = treeseq.sites().lending_iter();
+            while let Some(row) = rows.next() {
+                let id = sites.add_row_with_metadata(
+                    f64::from(row.position) + offset,
+                    row.ancestral_state,
+                    row.metadata.unwrap_or(&[]),
+                )?;
+                check_row_id(id)?;
+            }
+
+            let mutation_offset = mutations.as_ref().num_rows as ll_bindings::tsk_id_t;
+            let mut rows = treeseq.mutations().lending_iter();
+            while let Some(row) = rows.next() {
+                let parent = match row.parent {
+                    p if p.is_null() => p.into(),
+                    p => ll_bindings::tsk_id_t::from(p) + mutation_offset,
+                };
+                let id = mutations.add_row_with_metadata(
+                    ll_bindings::tsk_id_t::from(row.site) + site_offset,
+                    node_map[row.node.as_usize()].into(),
+                    parent,
+                    row.time.into(),
+                    row.derived_state,
+                    row.metadata.unwrap_or(&[]),
+                )?;
+                check_row_id(id)?;
+            }
+
+            let mut rows = treeseq.migrations().lending_iter();
+            while let Some(row) = rows.next() {
+                let id = migrations.add_row_with_metadata(
+                    (f64::from(row.left) + offset, f64::from(row.right) + offset),
+                    node_map[row.node.as_usize()].into(),
+                    row.source.into(),
+                    row.dest.into(),
+                    row.time.into(),
+                    row.metadata.unwrap_or(&[]),
+                )?;
+                check_row_id(id)?;
+            }
+
+            offset += treeseq.inner.sequence_length();
+        }
+
+        tables.set_sequence_length(offset);
+        let mut tables = TableCollection::new_from_ll(tables)?;
+        tables.full_sort(crate::TableSortOptions::default())?;
+        Self::new(tables, TreeSequenceFlags::default().build_indexes())
+    }
+
     #[cfg(feature = "provenance")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "provenance")))]
     /// Add provenance record with a time stamp.
@@ -473,6 +2906,58 @@ impl TreeSequence {
     ) -> Result<crate::edge_differences::EdgeDifferencesIterator, TskitError> {
         crate::edge_differences::EdgeDifferencesIterator::new_from_treeseq(self, 0)
     }
+
+    /// Summarize the changes between adjacent trees.
+    ///
+    /// The result has one [`TreeTransition`](crate::TreeTransition)
+    /// per tree.
+    /// The first is the transition from an empty tree to the first tree,
+    /// so that all of its edges are insertions.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if the `C` back end is unable to allocate
+    ///   needed memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p0 = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let p1 = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for _ in 0..2 {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 50., p0, c).unwrap();
+    ///     tables.add_edge(50., 100., p1, c).unwrap();
+    /// }
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let transitions = ts.transition_sizes().unwrap();
+    /// assert_eq!(transitions.len(), 2);
+    /// assert_eq!(transitions[0].num_edge_insertions, 2);
+    /// assert_eq!(transitions[1].num_edge_removals, 2);
+    /// assert_eq!(transitions[1].num_parent_changes, 2);
+    /// assert_eq!(transitions[1].num_edges, 2);
+    /// ```
+    pub fn transition_sizes(&self) -> Result<Vec<crate::TreeTransition>, TskitError> {
+        use streaming_iterator::StreamingIterator;
+
+        let mut diffs = self.edge_differences_iter()?;
+        let mut rv = Vec::with_capacity(self.num_trees().as_usize());
+        let mut num_edges = 0;
+        while let Some(diff) = diffs.next() {
+            let transition = diff.transition(num_edges);
+            num_edges = transition.num_edges;
+            rv.push(transition);
+        }
+        Ok(rv)
+    }
+}
+
+fn check_row_id(id: ll_bindings::tsk_id_t) -> Result<ll_bindings::tsk_id_t, TskitError> {
+    handle_tsk_return_value!(id)
 }
 
 impl TryFrom<TableCollection> for TreeSequence {
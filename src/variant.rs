@@ -0,0 +1,696 @@
+use crate::sys::bindings as ll_bindings;
+use crate::sys::LLVariant;
+use crate::IndividualId;
+use crate::NodeId;
+use crate::Position;
+use crate::SiteId;
+use crate::SizeType;
+use crate::TskitError;
+use crate::VariantOptions;
+
+/// The genotype of a single sample at a site.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::Genotype;
+/// assert_eq!(Genotype::try_from(1).unwrap(), Genotype::Allele(1));
+/// assert_eq!(Genotype::try_from(-1).unwrap(), Genotype::Missing);
+/// assert!(Genotype::try_from(256).is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Genotype {
+    /// An index into the alleles of the [`Variant`].
+    Allele(u8),
+    /// The genotype is missing.
+    Missing,
+}
+
+impl TryFrom<i32> for Genotype {
+    type Error = TskitError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        if value == ll_bindings::TSK_MISSING_DATA {
+            Ok(Self::Missing)
+        } else {
+            match u8::try_from(value) {
+                Ok(allele) => Ok(Self::Allele(allele)),
+                Err(_) => Err(TskitError::RangeError(format!(
+                    "invalid genotype value: {value}"
+                ))),
+            }
+        }
+    }
+}
+
+/// The genotypes of a set of samples at a site.
+///
+/// Wrapper around `tsk_variant_t`.
+///
+/// This type is also a streaming iterator over all sites
+/// of a tree sequence.
+/// See [`TreeSequence::variants`](crate::TreeSequence::variants).
+///
+/// # Missing data
+///
+/// A sample that is isolated at a site (it has no parent
+/// and no children in the tree) and that has no mutation
+/// above it has a missing genotype, [`Genotype::Missing`].
+/// Use [`VariantOptions::ISOLATED_NOT_MISSING`] to assign
+/// the ancestral state to such samples instead.
+pub struct Variant<'treeseq> {
+    inner: LLVariant<'treeseq>,
+    site: SiteId,
+    num_sites: SizeType,
+    advanced: bool,
+}
+
+impl<'treeseq> Variant<'treeseq> {
+    pub(crate) fn new(
+        ts: &'treeseq crate::sys::TreeSequence,
+        samples: Option<&[NodeId]>,
+        options: VariantOptions,
+    ) -> Result<Self, TskitError> {
+        let inner = LLVariant::new(ts, samples, options)?;
+        Ok(Self {
+            inner,
+            site: SiteId::NULL,
+            num_sites: ts.num_sites(),
+            advanced: false,
+        })
+    }
+
+    /// Decode the genotypes at `site`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError`] if `site` is out of range.
+    pub fn decode<S: Into<SiteId>>(&mut self, site: S) -> Result<(), TskitError> {
+        let site = site.into();
+        self.inner.decode(site.into())?;
+        self.site = site;
+        Ok(())
+    }
+
+    // The number of sites in the tree sequence
+    pub(crate) fn num_sites(&self) -> usize {
+        self.num_sites.as_usize()
+    }
+
+    /// The id of the currently-decoded site.
+    ///
+    /// Returns [`SiteId::NULL`] if no site has been decoded.
+    pub fn site(&self) -> SiteId {
+        self.site
+    }
+
+    /// The position of the currently-decoded site.
+    ///
+    /// Returns `None` if no site has been decoded.
+    pub fn position(&self) -> Option<Position> {
+        if self.site.is_null() {
+            None
+        } else {
+            Some(self.inner.as_ref().site.position.into())
+        }
+    }
+
+    /// The samples whose genotypes are decoded.
+    pub fn samples(&self) -> &[NodeId] {
+        self.inner.samples()
+    }
+
+    /// The number of alleles at the current site.
+    ///
+    /// The missing state is not an allele.
+    pub fn num_alleles(&self) -> usize {
+        self.inner.as_ref().num_alleles as usize
+    }
+
+    /// Get the allele with index `index`.
+    ///
+    /// The ancestral state is the allele with index 0.
+    /// Returns `None` if `index` is out of range.
+    pub fn allele(&self, index: usize) -> Option<&[u8]> {
+        self.inner.allele(index)
+    }
+
+    /// Iterate over the alleles at the current site.
+    pub fn alleles(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        (0..self.num_alleles()).filter_map(|i| self.allele(i))
+    }
+
+    /// The raw genotypes, one per sample.
+    ///
+    /// Each value is an index into the alleles, or
+    /// [`TSK_MISSING_DATA`](crate::bindings::TSK_MISSING_DATA)
+    /// if the genotype is missing.
+    pub fn genotypes(&self) -> &[i32] {
+        self.inner.genotypes()
+    }
+
+    /// Copy the genotypes, stored as `G`.
+    ///
+    /// See [`GenotypeChunks::genotypes_as`] for the storage of
+    /// missing data and the limits on the number of alleles.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if a genotype cannot be stored as `G`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use streaming_iterator::StreamingIterator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., p, c).unwrap();
+    /// let site = tables.add_site(50., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, c, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let mut variants = ts.variants(None, tskit::VariantOptions::default()).unwrap();
+    /// let variant = variants.next().unwrap();
+    /// assert_eq!(variant.genotypes_as::<i16>().unwrap(), vec![1, -1]);
+    /// // Missing data cannot be stored as an unsigned type
+    /// assert!(variant.genotypes_as::<u16>().is_err());
+    /// ```
+    pub fn genotypes_as<G: TryFrom<i32>>(&self) -> Result<Vec<G>, TskitError> {
+        let mut genotypes = Vec::with_capacity(self.genotypes().len());
+        self.extend_genotypes(&mut genotypes)?;
+        Ok(genotypes)
+    }
+
+    // Append the genotypes to `genotypes`, stored as `G`
+    fn extend_genotypes<G: TryFrom<i32>>(&self, genotypes: &mut Vec<G>) -> Result<(), TskitError> {
+        for &g in self.genotypes() {
+            genotypes.push(G::try_from(g).map_err(|_| TskitError::ValueError {
+                got: format!("genotype {} at site {}", g, self.site()),
+                expected: format!(
+                    "a genotype that can be stored as {}",
+                    std::any::type_name::<G>()
+                ),
+            })?);
+        }
+        Ok(())
+    }
+
+    /// Get the genotype of the sample at index `index`
+    /// of [`Variant::samples`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `index` is out of range.
+    /// * [`TskitError::RangeError`] if the allele index does not fit in a [`u8`].
+    pub fn genotype(&self, index: usize) -> Result<Genotype, TskitError> {
+        match self.genotypes().get(index) {
+            Some(&g) => Genotype::try_from(g),
+            None => Err(TskitError::IndexError),
+        }
+    }
+
+    /// Iterate over the genotypes of all samples.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::RangeError`] if an allele index does not fit in a [`u8`].
+    pub fn typed_genotypes(&self) -> impl Iterator<Item = Result<Genotype, TskitError>> + '_ {
+        self.genotypes().iter().map(|&g| Genotype::try_from(g))
+    }
+
+    /// `true` if any sample has a missing genotype
+    /// at the current site.
+    pub fn has_missing_data(&self) -> bool {
+        self.inner.as_ref().has_missing_data
+    }
+
+    /// The number of samples with a missing genotype
+    /// at the current site.
+    pub fn num_missing(&self) -> usize {
+        self.genotypes()
+            .iter()
+            .filter(|&&g| g == ll_bindings::TSK_MISSING_DATA)
+            .count()
+    }
+
+    /// The number of samples carrying each allele.
+    ///
+    /// Missing genotypes are not counted.
+    pub fn allele_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.num_alleles()];
+        for &g in self.genotypes() {
+            if let Ok(i) = usize::try_from(g) {
+                counts[i] += 1;
+            }
+        }
+        counts
+    }
+
+    /// The frequency of each allele.
+    ///
+    /// Missing genotypes are excluded from the denominator,
+    /// so that frequencies are relative to the samples with
+    /// known genotypes.
+    /// All values are `NaN` if every genotype is missing.
+    pub fn allele_frequencies(&self) -> Vec<f64> {
+        let counts = self.allele_counts();
+        let total = counts.iter().sum::<usize>() as f64;
+        counts.into_iter().map(|c| c as f64 / total).collect()
+    }
+
+    /// Iterate over the genotypes of each individual.
+    ///
+    /// The genotypes must have been decoded for
+    /// [`IndividualSamples::samples`], which is done by passing
+    /// them to [`TreeSequence::variants`](crate::TreeSequence::variants).
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the samples of `self` differ
+    ///   from those of `individuals`.
+    pub fn individual_genotypes<'a>(
+        &'a self,
+        individuals: &'a IndividualSamples,
+    ) -> Result<impl Iterator<Item = (IndividualId, &'a [i32])> + 'a, TskitError> {
+        if self.samples() != individuals.samples() {
+            return Err(TskitError::ValueError {
+                got: "genotypes decoded for other samples".to_string(),
+                expected: "genotypes decoded for the samples of the individuals".to_string(),
+            });
+        }
+        Ok(individuals
+            .individuals()
+            .iter()
+            .copied()
+            .zip(individuals.split(self.genotypes()).unwrap()))
+    }
+
+    /// The number of alternate alleles carried by each individual.
+    ///
+    /// The dosage of an individual is the number of its sample nodes
+    /// whose genotype is not the ancestral state, so that dosages of
+    /// diploids are 0, 1, or 2.
+    /// The dosage is [`TSK_MISSING_DATA`](crate::bindings::TSK_MISSING_DATA)
+    /// if any genotype of the individual is missing.
+    ///
+    /// # Errors
+    ///
+    /// See [`Variant::individual_genotypes`].
+    pub fn individual_dosages(
+        &self,
+        individuals: &IndividualSamples,
+    ) -> Result<Vec<i32>, TskitError> {
+        Ok(self
+            .individual_genotypes(individuals)?
+            .map(|(_, genotypes)| dosage(genotypes))
+            .collect())
+    }
+}
+
+fn dosage(genotypes: &[i32]) -> i32 {
+    let mut rv = 0;
+    for &g in genotypes {
+        match g {
+            ll_bindings::TSK_MISSING_DATA => return ll_bindings::TSK_MISSING_DATA,
+            0 => (),
+            _ => rv += 1,
+        }
+    }
+    rv
+}
+
+/// The sample nodes of each individual.
+///
+/// Pairs sample nodes with the individuals of the individual table,
+/// so that genotypes can be grouped by individual without relying
+/// on the order of the sample nodes.
+/// Individuals are ordered by id and the nodes of an
+/// individual are ordered by node id.
+/// Individuals without sample nodes are not included.
+///
+/// See [`TreeSequence::individual_samples`](crate::TreeSequence::individual_samples).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndividualSamples {
+    individuals: Vec<IndividualId>,
+    samples: Vec<NodeId>,
+    // offsets[i]..offsets[i + 1] are the samples of individual i
+    offsets: Vec<usize>,
+}
+
+impl IndividualSamples {
+    pub(crate) fn new(
+        sample_nodes: &[NodeId],
+        node_individuals: &[IndividualId],
+        ploidy: Option<usize>,
+    ) -> Result<Self, TskitError> {
+        let mut pairs = Vec::with_capacity(sample_nodes.len());
+        for &node in sample_nodes {
+            let individual = node_individuals[node.as_usize()];
+            if individual.is_null() {
+                return Err(TskitError::ValueError {
+                    got: format!("sample node {node} without an individual"),
+                    expected: "all sample nodes to have an individual".to_string(),
+                });
+            }
+            pairs.push((individual, node));
+        }
+        pairs.sort_unstable();
+
+        let mut rv = Self {
+            individuals: vec![],
+            samples: Vec::with_capacity(pairs.len()),
+            offsets: vec![0],
+        };
+        for (individual, node) in pairs {
+            if rv.individuals.last() != Some(&individual) {
+                if !rv.individuals.is_empty() {
+                    rv.offsets.push(rv.samples.len());
+                }
+                rv.individuals.push(individual);
+            }
+            rv.samples.push(node);
+        }
+        if !rv.individuals.is_empty() {
+            rv.offsets.push(rv.samples.len());
+        }
+
+        if let Some(ploidy) = ploidy {
+            for (i, individual) in rv.individuals.iter().enumerate() {
+                let n = rv.offsets[i + 1] - rv.offsets[i];
+                if n != ploidy {
+                    return Err(TskitError::ValueError {
+                        got: format!("individual {individual} with {n} sample nodes"),
+                        expected: format!("{ploidy} sample nodes per individual"),
+                    });
+                }
+            }
+        }
+        Ok(rv)
+    }
+
+    /// The number of individuals
+    pub fn len(&self) -> usize {
+        self.individuals.len()
+    }
+
+    /// `true` if there are no individuals
+    pub fn is_empty(&self) -> bool {
+        self.individuals.is_empty()
+    }
+
+    /// The individual ids
+    pub fn individuals(&self) -> &[IndividualId] {
+        &self.individuals
+    }
+
+    /// The sample nodes, grouped by individual.
+    ///
+    /// Pass these to [`TreeSequence::variants`](crate::TreeSequence::variants)
+    /// or [`TreeSequence::genotype_chunks`](crate::TreeSequence::genotype_chunks)
+    /// to decode genotypes in this order.
+    pub fn samples(&self) -> &[NodeId] {
+        &self.samples
+    }
+
+    /// The sample nodes of the individual at index `index`.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn individual_samples(&self, index: usize) -> Option<&[NodeId]> {
+        let start = *self.offsets.get(index)?;
+        let stop = *self.offsets.get(index + 1)?;
+        Some(&self.samples[start..stop])
+    }
+
+    /// The ploidy of the individual at index `index`.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn ploidy(&self, index: usize) -> Option<usize> {
+        self.individual_samples(index).map(|s| s.len())
+    }
+
+    /// Split per-sample values, such as the genotypes of a site,
+    /// into one slice per individual.
+    ///
+    /// Returns `None` if the length of `values` is not the
+    /// number of samples.
+    pub fn split<'a, T>(&'a self, values: &'a [T]) -> Option<impl Iterator<Item = &'a [T]> + 'a> {
+        if values.len() != self.samples.len() {
+            return None;
+        }
+        Some(self.offsets.windows(2).map(|w| &values[w[0]..w[1]]))
+    }
+}
+
+/// The genotypes of a contiguous block of sites.
+///
+/// Genotypes are stored as `i32` by default.
+/// See [`GenotypeChunks::genotypes_as`] to store them
+/// as a smaller type, such as `i8`.
+///
+/// See [`TreeSequence::genotype_chunks`](crate::TreeSequence::genotype_chunks).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenotypeChunk<G = i32> {
+    /// The site ids
+    pub sites: Vec<SiteId>,
+    /// The site positions
+    pub positions: Vec<Position>,
+    /// The alleles of each site.
+    /// Genotypes are indexes into these values.
+    pub alleles: Vec<Vec<Vec<u8>>>,
+    /// The genotypes, stored with one row of
+    /// `num_samples` values per site.
+    pub genotypes: Vec<G>,
+    /// The number of samples
+    pub num_samples: usize,
+}
+
+impl<G> GenotypeChunk<G> {
+    /// The number of sites in the chunk
+    pub fn num_sites(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// The genotypes of the site with index `index` in the chunk.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn site_genotypes(&self, index: usize) -> Option<&[G]> {
+        let start = index.checked_mul(self.num_samples)?;
+        self.genotypes.get(start..start + self.num_samples)
+    }
+}
+
+/// Alternate-allele dosages, with one row per site
+/// and one column per individual.
+///
+/// See [`TreeSequence::dosages`](crate::TreeSequence::dosages)
+/// and [`Variant::individual_dosages`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DosageMatrix {
+    /// The site ids
+    pub sites: Vec<SiteId>,
+    /// The individual ids
+    pub individuals: Vec<IndividualId>,
+    /// The dosages, stored with one row of
+    /// `individuals.len()` values per site.
+    pub dosages: Vec<i32>,
+}
+
+impl DosageMatrix {
+    pub(crate) fn new(
+        mut variant: Variant<'_>,
+        individuals: &IndividualSamples,
+    ) -> Result<Self, TskitError> {
+        let num_sites = variant.num_sites.as_usize();
+        let mut rv = Self {
+            sites: Vec::with_capacity(num_sites),
+            individuals: individuals.individuals().to_vec(),
+            dosages: Vec::with_capacity(num_sites * individuals.len()),
+        };
+        for site in 0..num_sites {
+            variant.decode(site as ll_bindings::tsk_id_t)?;
+            rv.sites.push(variant.site());
+            rv.dosages.extend(
+                variant
+                    .individual_genotypes(individuals)?
+                    .map(|(_, g)| dosage(g)),
+            );
+        }
+        Ok(rv)
+    }
+
+    /// The number of sites
+    pub fn num_sites(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// The number of individuals
+    pub fn num_individuals(&self) -> usize {
+        self.individuals.len()
+    }
+
+    /// The dosages of the site with index `index`.
+    ///
+    /// Returns `None` if `index` is out of range.
+    pub fn site_dosages(&self, index: usize) -> Option<&[i32]> {
+        if index >= self.num_sites() {
+            return None;
+        }
+        let n = self.num_individuals();
+        self.dosages.get(index * n..(index + 1) * n)
+    }
+
+    /// The dosage of the individual with index `individual`
+    /// at the site with index `site`.
+    ///
+    /// Returns `None` if either index is out of range.
+    pub fn get(&self, site: usize, individual: usize) -> Option<i32> {
+        if individual >= self.num_individuals() {
+            return None;
+        }
+        self.site_dosages(site).map(|d| d[individual])
+    }
+}
+
+/// An [`Iterator`] over [`GenotypeChunk`].
+///
+/// See [`TreeSequence::genotype_chunks`](crate::TreeSequence::genotype_chunks).
+pub struct GenotypeChunks<'treeseq, G = i32> {
+    variant: Variant<'treeseq>,
+    chunk_size: usize,
+    next_site: usize,
+    genotype_type: std::marker::PhantomData<G>,
+}
+
+impl<'treeseq> GenotypeChunks<'treeseq> {
+    pub(crate) fn new(variant: Variant<'treeseq>, chunk_size: usize) -> Result<Self, TskitError> {
+        if chunk_size == 0 {
+            return Err(TskitError::ValueError {
+                got: "0".to_string(),
+                expected: "a positive chunk size".to_string(),
+            });
+        }
+        Ok(Self {
+            variant,
+            chunk_size,
+            next_site: 0,
+            genotype_type: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<'treeseq, G: TryFrom<i32>> GenotypeChunks<'treeseq, G> {
+    /// Store the genotypes of the remaining chunks as `H`.
+    ///
+    /// Storing genotypes as `i8` takes a quarter of the memory of `i32`.
+    /// No precision is lost, but sites with more alleles than `H` can
+    /// index are an error. For `i8`, genotypes must be at most 127,
+    /// which holds for all sites with at most 128 alleles.
+    /// Missing data are stored as
+    /// [`TSK_MISSING_DATA`](crate::bindings::TSK_MISSING_DATA), -1,
+    /// so unsigned types cannot store missing data.
+    ///
+    /// # Errors
+    ///
+    /// The iterator returns [`TskitError::ValueError`] for a chunk
+    /// containing a genotype that cannot be stored as `H`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// for _ in 0..2 {
+    ///     let c = tables.add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1).unwrap();
+    ///     tables.add_edge(0., 100., p, c).unwrap();
+    /// }
+    /// let site = tables.add_site(10., Some(b"A")).unwrap();
+    /// tables.add_mutation(site, 1, -1, 0.5, Some(b"T")).unwrap();
+    /// tables.build_index().unwrap();
+    /// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    ///
+    /// let chunks = ts
+    ///     .genotype_chunks(2, None, tskit::VariantOptions::default())
+    ///     .unwrap()
+    ///     .genotypes_as::<i8>()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(chunks[0].genotypes, vec![1_i8, 0]);
+    /// ```
+    pub fn genotypes_as<H: TryFrom<i32>>(self) -> GenotypeChunks<'treeseq, H> {
+        GenotypeChunks {
+            variant: self.variant,
+            chunk_size: self.chunk_size,
+            next_site: self.next_site,
+            genotype_type: std::marker::PhantomData,
+        }
+    }
+
+    fn next_chunk(&mut self) -> Result<GenotypeChunk<G>, TskitError> {
+        let end = (self.next_site + self.chunk_size).min(self.variant.num_sites.as_usize());
+        let num_sites = end - self.next_site;
+        let num_samples = self.variant.samples().len();
+        let mut chunk = GenotypeChunk {
+            sites: Vec::with_capacity(num_sites),
+            positions: Vec::with_capacity(num_sites),
+            alleles: Vec::with_capacity(num_sites),
+            genotypes: Vec::with_capacity(num_sites * num_samples),
+            num_samples,
+        };
+        for site in self.next_site..end {
+            self.variant.decode(site as ll_bindings::tsk_id_t)?;
+            chunk.sites.push(self.variant.site());
+            chunk.positions.extend(self.variant.position());
+            chunk
+                .alleles
+                .push(self.variant.alleles().map(|a| a.to_vec()).collect());
+            self.variant.extend_genotypes(&mut chunk.genotypes)?;
+        }
+        self.next_site = end;
+        Ok(chunk)
+    }
+}
+
+impl<G: TryFrom<i32>> Iterator for GenotypeChunks<'_, G> {
+    type Item = Result<GenotypeChunk<G>, TskitError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_site >= self.variant.num_sites.as_usize() {
+            return None;
+        }
+        let rv = self.next_chunk();
+        if rv.is_err() {
+            // Do not retry a failing chunk
+            self.next_site = self.variant.num_sites.as_usize();
+        }
+        Some(rv)
+    }
+}
+
+impl<'ts> streaming_iterator::StreamingIterator for Variant<'ts> {
+    type Item = Variant<'ts>;
+    fn advance(&mut self) {
+        let next = if self.site.is_null() {
+            0
+        } else {
+            self.site.as_usize() + 1
+        };
+        self.advanced = next < self.num_sites.as_usize();
+        if self.advanced {
+            if let Err(TskitError::ErrorCode { code }) = self.decode(next as ll_bindings::tsk_id_t)
+            {
+                panic_on_tskit_error!(code);
+            }
+        }
+    }
+
+    fn get(&self) -> Option<&Self::Item> {
+        match self.advanced {
+            true => Some(self),
+            false => None,
+        }
+    }
+}
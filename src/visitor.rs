@@ -0,0 +1,161 @@
+//! Visit all tables of a table collection or tree sequence.
+
+use crate::ColumnDescriptor;
+use crate::EdgeTable;
+use crate::IndividualTable;
+use crate::MigrationTable;
+use crate::MutationTable;
+use crate::NodeTable;
+use crate::PopulationTable;
+use crate::SiteTable;
+use crate::SizeType;
+use crate::TableColumns;
+use crate::TskitError;
+
+/// Callbacks for each table of a table collection.
+///
+/// Pass a visitor to
+/// [`TableCollection::visit_tables`](crate::TableCollection::visit_tables) or
+/// [`TreeSequence::visit_tables`](crate::TreeSequence::visit_tables)
+/// to call the method for each table, in the order of the `tskit`
+/// file format.
+///
+/// Each typed method calls [`TableVisitor::visit_table`] by default,
+/// so that code that does not depend on the type of the table,
+/// such as counting rows, is written once.
+/// Implement the typed methods to process the rows of particular tables.
+/// Visiting stops at the first error.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::{ColumnDescriptor, SizeType, TableVisitor, TskitError};
+///
+/// // Describe every table, and check the times of nodes
+/// #[derive(Default)]
+/// struct Summary {
+///     lines: Vec<String>,
+/// }
+///
+/// impl TableVisitor for Summary {
+///     fn visit_table(
+///         &mut self,
+///         name: &'static str,
+///         columns: &'static [ColumnDescriptor],
+///         num_rows: SizeType,
+///     ) -> Result<(), TskitError> {
+///         self.lines
+///             .push(format!("{name}: {num_rows} rows, {} columns", columns.len()));
+///         Ok(())
+///     }
+///
+///     fn visit_nodes(&mut self, nodes: &tskit::NodeTable) -> Result<(), TskitError> {
+///         if nodes.time_slice().iter().any(|t| f64::from(*t) < 0.0) {
+///             return Err(TskitError::ValueError {
+///                 got: "negative node time".to_string(),
+///                 expected: "non-negative node times".to_string(),
+///             });
+///         }
+///         // Also describe the table
+///         self.visit_table("nodes", tskit::NodeTable::COLUMNS, nodes.num_rows())
+///     }
+/// }
+///
+/// # use tskit::TableColumns;
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.add_node(0, 0.0, -1, -1).unwrap();
+/// let mut summary = Summary::default();
+/// tables.visit_tables(&mut summary).unwrap();
+/// assert_eq!(summary.lines[0], "individuals: 0 rows, 4 columns");
+/// assert_eq!(summary.lines[1], "nodes: 1 rows, 5 columns");
+///
+/// tables.add_node(0, -1.0, -1, -1).unwrap();
+/// let mut summary = Summary::default();
+/// assert!(tables.visit_tables(&mut summary).is_err());
+/// // Visiting stops at the node table
+/// assert_eq!(summary.lines.len(), 1);
+/// ```
+pub trait TableVisitor {
+    /// Called for each table whose typed method is not implemented.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: the name of the table, as in [`TableColumns::TABLE_NAME`].
+    /// * `columns`: the columns of the table, as in [`TableColumns::COLUMNS`].
+    /// * `num_rows`: the number of rows of the table.
+    fn visit_table(
+        &mut self,
+        name: &'static str,
+        columns: &'static [ColumnDescriptor],
+        num_rows: SizeType,
+    ) -> Result<(), TskitError> {
+        let _ = (name, columns, num_rows);
+        Ok(())
+    }
+
+    /// Visit the individual table.
+    fn visit_individuals(&mut self, table: &IndividualTable) -> Result<(), TskitError> {
+        self.visit_table(
+            IndividualTable::TABLE_NAME,
+            IndividualTable::COLUMNS,
+            table.num_rows(),
+        )
+    }
+
+    /// Visit the node table.
+    fn visit_nodes(&mut self, table: &NodeTable) -> Result<(), TskitError> {
+        self.visit_table(NodeTable::TABLE_NAME, NodeTable::COLUMNS, table.num_rows())
+    }
+
+    /// Visit the edge table.
+    fn visit_edges(&mut self, table: &EdgeTable) -> Result<(), TskitError> {
+        self.visit_table(EdgeTable::TABLE_NAME, EdgeTable::COLUMNS, table.num_rows())
+    }
+
+    /// Visit the migration table.
+    fn visit_migrations(&mut self, table: &MigrationTable) -> Result<(), TskitError> {
+        self.visit_table(
+            MigrationTable::TABLE_NAME,
+            MigrationTable::COLUMNS,
+            table.num_rows(),
+        )
+    }
+
+    /// Visit the site table.
+    fn visit_sites(&mut self, table: &SiteTable) -> Result<(), TskitError> {
+        self.visit_table(SiteTable::TABLE_NAME, SiteTable::COLUMNS, table.num_rows())
+    }
+
+    /// Visit the mutation table.
+    fn visit_mutations(&mut self, table: &MutationTable) -> Result<(), TskitError> {
+        self.visit_table(
+            MutationTable::TABLE_NAME,
+            MutationTable::COLUMNS,
+            table.num_rows(),
+        )
+    }
+
+    /// Visit the population table.
+    fn visit_populations(&mut self, table: &PopulationTable) -> Result<(), TskitError> {
+        self.visit_table(
+            PopulationTable::TABLE_NAME,
+            PopulationTable::COLUMNS,
+            table.num_rows(),
+        )
+    }
+
+    /// Visit the provenance table.
+    #[cfg(feature = "provenance")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "provenance")))]
+    fn visit_provenances(
+        &mut self,
+        table: &crate::provenance::ProvenanceTable,
+    ) -> Result<(), TskitError> {
+        use crate::provenance::ProvenanceTable;
+        self.visit_table(
+            ProvenanceTable::TABLE_NAME,
+            ProvenanceTable::COLUMNS,
+            table.num_rows(),
+        )
+    }
+}
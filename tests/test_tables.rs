@@ -122,6 +122,14 @@ mod test_adding_rows_without_metadata {
             compare_column_to_row!(tables.edges(), right_slice, right);
             compare_column_to_row!(tables.edges(), parent_slice, parent);
             compare_column_to_row!(tables.edges(), child_slice, child);
+
+            let edges = tables.edges();
+            for row in edges.iter() {
+                assert_eq!(edges.interval(row.id), Some((row.left, row.right)));
+                assert_eq!(edges.span(row.id), Some(row.span()));
+                assert_eq!(edges.row_view(row.id).unwrap().span(), row.span());
+            }
+            assert!(edges.span(edges.num_rows().as_usize() as i32).is_none());
         }
         add_row_without_metadata!(edges, add_edge, tskit::Position::from(0.1), 0.5, 0, 1); // left, right, parent, child
         add_row_without_metadata!(edges, add_edge, 0.1, tskit::Position::from(0.5), 0, 1); // left, right, parent, child
@@ -550,3 +558,2336 @@ mod test_metadata_round_trips {
         );
     }
 }
+
+#[test]
+fn test_node_table_query_helpers() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, 0, -1)
+        .unwrap();
+    tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, 1, -1)
+        .unwrap();
+    tables.add_node(0, 1.0, 0, -1).unwrap();
+    tables.add_node(0, 2.0, 1, -1).unwrap();
+
+    let nodes = tables.nodes();
+    assert_eq!(
+        nodes.ids_where(|row| row.flags.is_sample()),
+        nodes.samples_as_vector()
+    );
+    assert_eq!(
+        nodes.nodes_by_population(0),
+        vec![tskit::NodeId::from(0), tskit::NodeId::from(2)]
+    );
+    assert_eq!(
+        nodes.nodes_by_population(tskit::PopulationId::from(1)),
+        vec![tskit::NodeId::from(1), tskit::NodeId::from(3)]
+    );
+    assert!(nodes.nodes_by_population(2).is_empty());
+    assert_eq!(
+        nodes.nodes_in_time_range(0.0..1.0),
+        vec![tskit::NodeId::from(0), tskit::NodeId::from(1)]
+    );
+    assert_eq!(
+        nodes.nodes_in_time_range(tskit::Time::from(1.0)..tskit::Time::from(3.0)),
+        vec![tskit::NodeId::from(2), tskit::NodeId::from(3)]
+    );
+    assert!(nodes.nodes_in_time_range(3.0..4.0).is_empty());
+}
+
+#[test]
+fn test_individual_pedigree_traversal() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    // Two founders, two full sibs, and an inbred grandchild
+    let f0 = tables.add_individual(0, None, None).unwrap();
+    let f1 = tables.add_individual(0, None, None).unwrap();
+    let s0 = tables.add_individual(0, None, [f0, f1]).unwrap();
+    let s1 = tables.add_individual(0, None, [f0, f1]).unwrap();
+    let g = tables.add_individual(0, None, [s0, s1]).unwrap();
+
+    let individuals = tables.individuals();
+    let ancestors = individuals.ancestors(g).unwrap().collect::<Vec<_>>();
+    assert_eq!(ancestors, vec![s0, s1, f0, f1]);
+    let descendants = individuals.descendants(f1).unwrap().collect::<Vec<_>>();
+    assert_eq!(descendants, vec![s0, s1, g]);
+    assert!(individuals.ancestors(tskit::IndividualId::NULL).is_none());
+    assert!(individuals.descendants(5).is_none());
+
+    // A (malformed) pedigree with a cycle must not loop forever
+    let mut individuals = tskit::IndividualTable::default();
+    individuals.add_row(0, None, [1]).unwrap();
+    individuals.add_row(0, None, [0]).unwrap();
+    assert_eq!(
+        individuals.ancestors(0).unwrap().collect::<Vec<_>>(),
+        vec![tskit::IndividualId::from(1)]
+    );
+    assert_eq!(
+        individuals.descendants(0).unwrap().collect::<Vec<_>>(),
+        vec![tskit::IndividualId::from(1)]
+    );
+}
+
+#[test]
+fn test_mutation_chain_traversal() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    let s1 = tables.add_site(20., Some(b"A")).unwrap();
+    let m0 = tables
+        .add_mutation(s0, 0, tskit::MutationId::NULL, 3.0, Some(b"C"))
+        .unwrap();
+    let m1 = tables
+        .add_mutation(s1, 0, tskit::MutationId::NULL, 3.0, Some(b"G"))
+        .unwrap();
+    let m2 = tables.add_mutation(s0, 1, m0, 2.0, Some(b"T")).unwrap();
+    let m3 = tables.add_mutation(s0, 2, m2, 1.0, Some(b"A")).unwrap();
+
+    let mutations = tables.mutations();
+    assert_eq!(
+        mutations.ancestors(m3).unwrap().collect::<Vec<_>>(),
+        vec![m2, m0]
+    );
+    assert_eq!(mutations.ancestors(m1).unwrap().count(), 0);
+    assert!(mutations.ancestors(tskit::MutationId::NULL).is_none());
+    assert_eq!(mutations.site_mutations(s0), vec![m0, m2, m3]);
+    assert_eq!(mutations.site_mutations(s1), vec![m1]);
+
+    // A parent that does not precede its child ends the traversal
+    let mut mutations = tskit::MutationTable::default();
+    mutations.add_row(0, 0, 1, 2.0, None).unwrap();
+    mutations.add_row(0, 0, 0, 1.0, None).unwrap();
+    assert_eq!(
+        mutations.ancestors(1).unwrap().collect::<Vec<_>>(),
+        vec![tskit::MutationId::from(0)]
+    );
+}
+
+#[test]
+fn test_delete_sites_and_mutations() {
+    let make_tables = || {
+        let mut tables = tskit::TableCollection::new(100.).unwrap();
+        let s0 = tables.add_site(10., Some(b"A")).unwrap();
+        let s1 = tables.add_site(20., Some(b"A")).unwrap();
+        let s2 = tables.add_site(30., Some(b"A")).unwrap();
+        let m0 = tables
+            .add_mutation(s0, 0, tskit::MutationId::NULL, 3.0, Some(b"C"))
+            .unwrap();
+        tables
+            .add_mutation(s1, 0, tskit::MutationId::NULL, 3.0, Some(b"G"))
+            .unwrap();
+        let m2 = tables
+            .add_mutation(s2, 0, tskit::MutationId::NULL, 3.0, Some(b"T"))
+            .unwrap();
+        let m3 = tables.add_mutation(s2, 1, m2, 2.0, Some(b"A")).unwrap();
+        tables.add_mutation(s2, 2, m3, 1.0, Some(b"C")).unwrap();
+        tables.add_mutation(s0, 1, m0, 2.0, Some(b"G")).unwrap();
+        tables
+    };
+
+    let mut tables = make_tables();
+    tables.delete_sites(&[1.into()]).unwrap();
+    assert_eq!(tables.sites().num_rows(), 2);
+    assert_eq!(tables.sites().position(1).unwrap(), 30.);
+    let mutations = tables.mutations();
+    assert_eq!(mutations.num_rows(), 5);
+    assert_eq!(
+        mutations.site_slice(),
+        &[0, 1, 1, 1, 0].map(tskit::SiteId::from)
+    );
+    assert_eq!(
+        mutations.parent_slice(),
+        &[-1, -1, 1, 2, 0].map(tskit::MutationId::from)
+    );
+
+    let mut tables = make_tables();
+    tables.delete_mutations(&[0.into(), 3.into()]).unwrap();
+    assert_eq!(tables.sites().num_rows(), 3);
+    let mutations = tables.mutations();
+    assert_eq!(mutations.num_rows(), 4);
+    assert_eq!(
+        mutations.parent_slice(),
+        &[-1, -1, 1, -1].map(tskit::MutationId::from)
+    );
+    assert_eq!(mutations.derived_state(2).unwrap(), b"C");
+
+    let mut tables = make_tables();
+    assert!(tables.delete_sites(&[3.into()]).is_err());
+    assert!(tables.delete_mutations(&[tskit::MutationId::NULL]).is_err());
+    assert_eq!(tables.mutations().num_rows(), 6);
+
+    // A mutation whose site does not exist
+    let mut tables = make_tables();
+    tables.add_mutation(10, 0, -1, 0.0, Some(b"A")).unwrap();
+    assert!(matches!(
+        tables.delete_sites(&[0.into()]),
+        Err(tskit::TskitError::IndexError)
+    ));
+    assert!(matches!(
+        tables.delete_mutations(&[0.into()]),
+        Err(tskit::TskitError::IndexError)
+    ));
+}
+
+#[test]
+fn test_rescale_time() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    tables.add_population().unwrap();
+    tables.add_population().unwrap();
+    let p = tables.add_node(0, 1.25, -1, -1).unwrap();
+    let c = tables.add_node(0, 1.0, -1, -1).unwrap();
+    tables.add_edge(0., 100., p, c).unwrap();
+    let site = tables.add_site(50., None).unwrap();
+    let unknown_time = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    tables
+        .add_mutation(site, c, -1, unknown_time, None)
+        .unwrap();
+    tables.add_migration((0., 100.), c, (0, 1), 1.125).unwrap();
+
+    // Rounding both node times to the smallest positive value
+    // violates parent/child ordering, so nothing changes.
+    assert!(tables.rescale_time(f64::from_bits(1)).is_err());
+    assert_eq!(tables.nodes().time(p).unwrap(), 1.25);
+    assert_eq!(tables.nodes().time(c).unwrap(), 1.0);
+    assert_eq!(tables.migrations().time(0).unwrap(), 1.125);
+
+    tables.rescale_time(4.0).unwrap();
+    assert_eq!(tables.nodes().time(p).unwrap(), 5.0);
+    assert_eq!(tables.nodes().time(c).unwrap(), 4.0);
+    assert_eq!(tables.migrations().time(0).unwrap(), 4.5);
+    let mutation_time = f64::from(tables.mutations().time(0).unwrap());
+    assert_eq!(mutation_time.to_bits(), unknown_time.to_bits());
+
+    tables.generations_to_years(30.0).unwrap();
+    assert_eq!(tables.nodes().time(p).unwrap(), 150.0);
+    assert!(tables.years_to_generations(f64::NAN).is_err());
+    assert!(tables.generations_to_years(-1.0).is_err());
+}
+
+#[test]
+fn test_discretize_times() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    tables.add_population().unwrap();
+    tables.add_population().unwrap();
+    // A chain of nodes that all snap to the same time
+    let n0 = tables.add_node(0, 0.9, -1, -1).unwrap();
+    let n1 = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let n2 = tables.add_node(0, 1.1, -1, -1).unwrap();
+    let n3 = tables.add_node(0, 5.4, -1, -1).unwrap();
+    tables.add_edge(0., 100., n1, n0).unwrap();
+    tables.add_edge(0., 100., n2, n1).unwrap();
+    tables.add_edge(0., 100., n3, n2).unwrap();
+    let site = tables.add_site(50., None).unwrap();
+    let unknown_time = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    // Snaps below its node
+    let m0 = tables.add_mutation(site, n1, -1, 1.05, None).unwrap();
+    // Snaps above its parent mutation
+    tables.add_mutation(site, n0, m0, 1.04, None).unwrap();
+    let site = tables.add_site(60., None).unwrap();
+    tables
+        .add_mutation(site, n0, -1, unknown_time, None)
+        .unwrap();
+    tables.add_migration((0., 100.), n2, (0, 1), 4.4).unwrap();
+    let original = tables.deepcopy().unwrap();
+
+    let grid = (0..10).map(f64::from).collect::<Vec<_>>();
+    // The grid has no time older than the children of n3
+    assert!(tables.discretize_times(grid[..3].to_vec()).is_err());
+    assert!(tables.equals(&original, tskit::TableEqualityOptions::default()));
+    assert!(tables.discretize_times(Vec::<f64>::new()).is_err());
+    assert!(tables.discretize_times([0.0, f64::NAN]).is_err());
+    assert!(tables.discretize_times([0.0, 0.0]).is_err());
+
+    tables.discretize_times(grid.iter().copied()).unwrap();
+    assert_eq!(
+        tables.nodes().time_slice(),
+        [1.0, 2.0, 3.0, 5.0].map(tskit::Time::from)
+    );
+    assert_eq!(tables.mutations().time(0).unwrap(), 2.0);
+    assert_eq!(tables.mutations().time(1).unwrap(), 1.0);
+    let mutation_time = f64::from(tables.mutations().time(2).unwrap());
+    assert_eq!(mutation_time.to_bits(), unknown_time.to_bits());
+    assert_eq!(tables.migrations().time(0).unwrap(), 4.0);
+    tables
+        .check_integrity(tskit::TableIntegrityCheckFlags::CHECK_MUTATION_ORDERING)
+        .unwrap();
+
+    // Already discrete times do not change
+    let discrete = tables.deepcopy().unwrap();
+    tables.discretize_times(grid).unwrap();
+    assert!(tables.equals(&discrete, tskit::TableEqualityOptions::default()));
+}
+
+#[test]
+fn test_transform_coordinates() {
+    let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    tables.add_population().unwrap();
+    let p = tables.add_node(0, 1.0, 0, -1).unwrap();
+    let c0 = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, 0, -1)
+        .unwrap();
+    let c1 = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, 0, -1)
+        .unwrap();
+    tables.add_edge(0., 1000., p, c0).unwrap();
+    tables.add_edge(0., 400., p, c1).unwrap();
+    tables.add_edge(400., 1000., p, c1).unwrap();
+    tables.add_site(250., None).unwrap();
+    tables.add_site(750., None).unwrap();
+    tables.add_migration((100., 600.), c0, (0, 0), 0.5).unwrap();
+    tables.build_index().unwrap();
+    let original = tables.deepcopy().unwrap();
+
+    // Double the density of the first half of the genome
+    let map = tskit::CoordinateMap::new([(0., 0.), (500., 1000.), (1000., 1500.)]).unwrap();
+    tables.transform_coordinates(|x| map.forward(x)).unwrap();
+    assert_eq!(tables.sequence_length(), 1500.);
+    assert_eq!(tables.edges().left_slice(), &[0., 0., 800.]);
+    assert_eq!(tables.edges().right_slice(), &[1500., 800., 1500.]);
+    assert_eq!(tables.sites().position_slice(), &[500., 1250.]);
+    assert_eq!(tables.migrations().left(0).unwrap(), 200.);
+    assert_eq!(tables.migrations().right(0).unwrap(), 1100.);
+    tables
+        .check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDEXES)
+        .unwrap();
+
+    // Decreasing and negative transformations are errors,
+    // and leave the tables unchanged.
+    let transformed = tables.deepcopy().unwrap();
+    assert!(tables
+        .transform_coordinates(|x| (-f64::from(x)).into())
+        .is_err());
+    assert!(tables
+        .transform_coordinates(|x| (f64::from(x) - 1.).into())
+        .is_err());
+    assert!(tables
+        .transform_coordinates(|x| f64::from(x).min(1000.).into())
+        .is_err());
+    assert!(tables.equals(&transformed, tskit::TableEqualityOptions::default()));
+
+    let inverse = map.inverse();
+    tables
+        .transform_coordinates(|x| inverse.forward(x))
+        .unwrap();
+    assert!(tables.equals(&original, tskit::TableEqualityOptions::default()));
+    let treeseq = tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 2);
+}
+
+#[test]
+fn test_hapmap_rate_map() {
+    let hapmap = "\
+Chromosome\tPosition(bp)\tRate(cM/Mb)\tMap(cM)
+chr1\t0\t1.0\t0.0
+
+chr1\t250\t0.0\t0.025
+chr1\t500\t2.0\t0.025
+chr1\t750\t0.0\t0.075
+";
+    let map = tskit::RateMap::read_hapmap(hapmap.as_bytes(), Some(1000.)).unwrap();
+    assert_eq!(map.positions(), &[0., 250., 500., 750., 1000.]);
+    assert_eq!(map.rates(), &[1e-6, 0., 2e-6, 0.]);
+    assert!((map.total_mass() - 7.5e-4).abs() < 1e-15);
+    assert!((map.cumulative_mass(600.).unwrap() - 4.5e-4).abs() < 1e-15);
+    let p = map.position_at_mass(4.5e-4).unwrap();
+    assert!((f64::from(p) - 600.).abs() < 1e-6);
+    assert_eq!(map.position_at_mass(2.5e-4).unwrap(), 250.);
+    assert_eq!(map.position_at_mass(map.total_mass()).unwrap(), 750.);
+    assert!(map.position_at_mass(1.).is_err());
+    assert!(map.cumulative_mass(-1.).is_err());
+    // Zero rates do not give a strictly increasing coordinate map
+    assert!(map.coordinate_map().is_err());
+
+    // Without a sequence length, the map ends at the last position
+    let map = tskit::RateMap::read_hapmap(hapmap.as_bytes(), None::<f64>).unwrap();
+    assert_eq!(map.sequence_length(), 750.);
+    assert!(tskit::RateMap::read_hapmap(hapmap.as_bytes(), Some(500.)).is_err());
+
+    // Malformed lines and decreasing maps are errors
+    for bad in [
+        "header\nchr1\t100\t1.0\n",
+        "header\nchr1\tx\t1.0\t0.0\n",
+        "header\nchr1\t100\t1.0\t0.5\nchr1\t200\t1.0\t0.4\n",
+        "header\nchr1\t200\t1.0\t0.0\nchr1\t100\t1.0\t0.1\n",
+        "header\n",
+    ] {
+        assert!(tskit::RateMap::read_hapmap(bad.as_bytes(), None::<f64>).is_err());
+    }
+
+    // Genetic coordinates of tables, with a positive rate everywhere
+    let map = tskit::RateMap::new([0., 500., 1000.], [1e-3, 3e-3]).unwrap();
+    let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let c = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 1000., p, c).unwrap();
+    tables.add_site(250., None).unwrap();
+    tables.add_site(750., None).unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    let genetic = treeseq.site_genetic_positions(&map).unwrap();
+    assert!((genetic[0] - 0.25).abs() < 1e-12);
+    assert!((genetic[1] - 1.25).abs() < 1e-12);
+
+    let coordinates = map.coordinate_map().unwrap();
+    tables
+        .transform_coordinates(|x| coordinates.forward(x))
+        .unwrap();
+    assert!((f64::from(tables.sequence_length()) - 2.).abs() < 1e-12);
+    for (p, g) in tables.sites().position_slice().iter().zip(genetic) {
+        assert!((f64::from(*p) - g).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_add_migrations() {
+    use tskit::MigrationEvent;
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let pop0 = tables.add_population().unwrap();
+    let pop1 = tables.add_population().unwrap();
+    let n0 = tables.add_node(0, 1.0, pop0, -1).unwrap();
+    let n1 = tables.add_node(0, 2.0, pop0, -1).unwrap();
+
+    let pulse = MigrationEvent::new((0., 100.), n0, (pop0, pop1), 5.0);
+    let events = [n0, n1].map(|node| MigrationEvent { node, ..pulse });
+    let ids = tables.add_migrations(&events).unwrap();
+    assert_eq!(ids, [0, 1]);
+    for (row, event) in tables.migrations().iter().zip(events) {
+        assert_eq!(row.left, event.left);
+        assert_eq!(row.right, event.right);
+        assert_eq!(row.node, event.node);
+        assert_eq!(row.source, pop0);
+        assert_eq!(row.dest, pop1);
+        assert_eq!(row.time, 5.0);
+    }
+    // The migration may be at the time of the node
+    let ids = tables
+        .add_migrations(&[MigrationEvent {
+            time: 2.0.into(),
+            ..events[1]
+        }])
+        .unwrap();
+    assert_eq!(ids, [2]);
+    assert!(tables.add_migrations(&[]).unwrap().is_empty());
+
+    let invalid = [
+        MigrationEvent {
+            right: 101.0.into(),
+            ..pulse
+        },
+        MigrationEvent {
+            left: 50.0.into(),
+            right: 50.0.into(),
+            ..pulse
+        },
+        MigrationEvent {
+            left: (-1.0).into(),
+            ..pulse
+        },
+        MigrationEvent {
+            node: 2.into(),
+            ..pulse
+        },
+        MigrationEvent {
+            node: tskit::NodeId::NULL,
+            ..pulse
+        },
+        MigrationEvent {
+            source: 2.into(),
+            ..pulse
+        },
+        MigrationEvent {
+            dest: tskit::PopulationId::NULL,
+            ..pulse
+        },
+        MigrationEvent {
+            dest: pop0,
+            ..pulse
+        },
+        MigrationEvent {
+            time: 0.5.into(),
+            ..pulse
+        },
+        MigrationEvent {
+            time: f64::INFINITY.into(),
+            ..pulse
+        },
+    ];
+    for event in invalid {
+        // No rows are added when any event is invalid
+        let rv = tables.add_migrations(&[pulse, event]);
+        match rv {
+            Err(tskit::TskitError::RangeError(_))
+            | Err(tskit::TskitError::IndexError)
+            | Err(tskit::TskitError::ValueError { .. }) => (),
+            _ => panic!("unexpected result {:?} for {:?}", rv, event),
+        }
+        assert_eq!(tables.migrations().num_rows(), 3);
+    }
+}
+
+#[test]
+fn test_memory_usage() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let empty = tables.memory_usage();
+    // The offsets of ragged columns
+    assert_eq!(empty.nodes, 8);
+    assert_eq!(empty.sites, 16);
+    assert_eq!(empty.individuals, 24);
+    assert_eq!(empty.total(), 8 * 13);
+
+    tables
+        .add_individual(0, [1.0, 2.0], [tskit::IndividualId::NULL])
+        .unwrap();
+    let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let c = tables.add_node(0, 0.0, -1, -1).unwrap();
+    tables.add_edge(0., 100., p, c).unwrap();
+    let s = tables.add_site(10., Some(b"ACGT")).unwrap();
+    tables.add_mutation(s, c, -1, 0.5, Some(b"T")).unwrap();
+    tables.add_population().unwrap();
+    tables.add_migration((0., 100.), c, (0, 0), 2.0).unwrap();
+
+    let usage = tables.memory_usage();
+    assert_eq!(usage.individuals, 4 + 6 * 8 + 2 * 8 + 4);
+    assert_eq!(usage.nodes, 2 * 20 + 3 * 8);
+    assert_eq!(usage.edges, 24 + 2 * 8);
+    assert_eq!(usage.migrations, 36 + 2 * 8);
+    assert_eq!(usage.sites, 8 + 4 * 8 + 4);
+    assert_eq!(usage.mutations, 20 + 4 * 8 + 1);
+    assert_eq!(usage.populations, 2 * 8);
+    assert_eq!(usage.provenances, 2 * 8);
+    assert_eq!(usage.indexes, 0);
+    assert_eq!(
+        usage.total(),
+        usage.iter().map(|(_, bytes)| bytes).sum::<usize>()
+    );
+    assert_eq!(usage.largest(), ("individuals", 72));
+    let names = usage.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    assert_eq!(names[..3], ["individuals", "nodes", "edges"]);
+    assert_eq!(names.last(), Some(&"indexes"));
+
+    tables.build_index().unwrap();
+    assert_eq!(tables.memory_usage().indexes, 8);
+    // Clearing the tables does not free memory, but rows use none
+    tables.clear(tskit::TableClearOptions::default()).unwrap();
+    assert_eq!(tables.memory_usage(), empty);
+}
+
+#[test]
+fn test_visit_tables() {
+    use tskit::{ColumnDescriptor, SizeType, TableVisitor, TskitError};
+
+    // Counts rows of all tables, and sums the edge spans
+    #[derive(Default)]
+    struct Counter {
+        rows: Vec<(&'static str, usize)>,
+        edge_span: f64,
+    }
+
+    impl TableVisitor for Counter {
+        fn visit_table(
+            &mut self,
+            name: &'static str,
+            _columns: &'static [ColumnDescriptor],
+            num_rows: SizeType,
+        ) -> Result<(), TskitError> {
+            self.rows.push((name, num_rows.as_usize()));
+            Ok(())
+        }
+
+        fn visit_edges(&mut self, edges: &tskit::EdgeTable) -> Result<(), TskitError> {
+            self.edge_span = edges
+                .left_slice()
+                .iter()
+                .zip(edges.right_slice())
+                .map(|(l, r)| f64::from(*r - *l))
+                .sum();
+            Ok(())
+        }
+    }
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    for _ in 0..2 {
+        let c = tables
+            .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+            .unwrap();
+        tables.add_edge(25., 100., p, c).unwrap();
+    }
+    tables.add_site(50., None).unwrap();
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+
+    let mut counter = Counter::default();
+    tables.visit_tables(&mut counter).unwrap();
+    assert_eq!(counter.edge_span, 150.);
+    assert_eq!(
+        counter.rows[..6],
+        [
+            ("individuals", 0),
+            ("nodes", 3),
+            ("migrations", 0),
+            ("sites", 1),
+            ("mutations", 0),
+            ("populations", 0),
+        ]
+    );
+    #[cfg(feature = "provenance")]
+    assert_eq!(counter.rows[6..], [("provenances", 0)]);
+    #[cfg(not(feature = "provenance"))]
+    assert_eq!(counter.rows.len(), 6);
+
+    // Tree sequences visit the same tables
+    let treeseq = tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    let mut from_treeseq = Counter::default();
+    treeseq.visit_tables(&mut from_treeseq).unwrap();
+    assert_eq!(from_treeseq.rows, counter.rows);
+    assert_eq!(from_treeseq.edge_span, counter.edge_span);
+
+    // A visitor that does nothing
+    struct Nothing;
+    impl TableVisitor for Nothing {}
+    treeseq.visit_tables(&mut Nothing).unwrap();
+}
+
+#[test]
+fn test_strip_metadata() {
+    use tskit::metadata::MetadataRoundtrip;
+    use tskit::StripMetadataOptions;
+
+    // Metadata stored as raw bytes
+    #[derive(Debug, PartialEq)]
+    struct Secret(Vec<u8>);
+
+    impl MetadataRoundtrip for Secret {
+        fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+            Ok(self.0.clone())
+        }
+        fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+            Ok(Self(md.to_vec()))
+        }
+    }
+
+    impl tskit::metadata::IndividualMetadata for Secret {}
+    impl tskit::metadata::NodeMetadata for Secret {}
+    impl tskit::metadata::EdgeMetadata for Secret {}
+    impl tskit::metadata::MigrationMetadata for Secret {}
+    impl tskit::metadata::SiteMetadata for Secret {}
+    impl tskit::metadata::MutationMetadata for Secret {}
+    impl tskit::metadata::PopulationMetadata for Secret {}
+
+    let secret = Secret(b"sample name".to_vec());
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let i = tables
+        .add_individual_with_metadata(0, None, None, &secret)
+        .unwrap();
+    let pop = tables.add_population_with_metadata(&secret).unwrap();
+    let p = tables
+        .add_node_with_metadata(0, 1.0, pop, i, &secret)
+        .unwrap();
+    let c = tables
+        .add_node_with_metadata(tskit::NodeFlags::new_sample(), 0.0, pop, i, &secret)
+        .unwrap();
+    tables
+        .add_edge_with_metadata(0., 100., p, c, &secret)
+        .unwrap();
+    tables
+        .add_migration_with_metadata((0., 100.), c, (pop, pop), 0.5, &secret)
+        .unwrap();
+    let s = tables
+        .add_site_with_metadata(50., Some(b"A"), &secret)
+        .unwrap();
+    tables
+        .add_mutation_with_metadata(s, c, -1, 0.5, Some(b"T"), &secret)
+        .unwrap();
+    let schema = b"{\"codec\":\"json\"}";
+    // SAFETY: the tables are initialized
+    unsafe {
+        let ll = tables.as_mut_ptr();
+        assert_eq!(
+            tskit::bindings::tsk_table_collection_set_metadata(
+                ll,
+                secret.0.as_ptr().cast(),
+                secret.0.len() as u64
+            ),
+            0
+        );
+        assert_eq!(
+            tskit::bindings::tsk_node_table_set_metadata_schema(
+                &mut (*ll).nodes,
+                schema.as_ptr().cast(),
+                schema.len() as u64
+            ),
+            0
+        );
+    }
+    let original = tables.deepcopy().unwrap();
+
+    tables
+        .strip_metadata(StripMetadataOptions::default().nodes().populations())
+        .unwrap();
+    assert_eq!(tables.nodes().num_rows(), 2);
+    assert!(tables.nodes().iter().all(|row| row.metadata.is_none()));
+    assert!(tables.populations().metadata::<Secret>(pop).is_none());
+    assert_eq!(tables.nodes().time_slice(), original.nodes().time_slice());
+    assert_eq!(
+        tables.nodes().individual_slice(),
+        original.nodes().individual_slice()
+    );
+    // SAFETY: the tables are initialized
+    assert_eq!(
+        unsafe { (*tables.as_ptr()).nodes.metadata_schema_length },
+        0
+    );
+    assert_eq!(unsafe { (*tables.as_ptr()).metadata_length }, 11);
+    for (table, rows) in [
+        (
+            "individuals",
+            tables.individuals().metadata::<Secret>(i).unwrap().unwrap(),
+        ),
+        (
+            "edges",
+            tables.edges().metadata::<Secret>(0).unwrap().unwrap(),
+        ),
+        (
+            "sites",
+            tables.sites().metadata::<Secret>(s).unwrap().unwrap(),
+        ),
+        (
+            "mutations",
+            tables.mutations().metadata::<Secret>(0).unwrap().unwrap(),
+        ),
+        (
+            "migrations",
+            tables.migrations().metadata::<Secret>(0).unwrap().unwrap(),
+        ),
+    ] {
+        assert_eq!(rows, secret, "{}", table);
+    }
+    // Rows may be added after stripping
+    let n = tables
+        .add_node_with_metadata(0, 2.0, pop, -1, &secret)
+        .unwrap();
+    assert_eq!(
+        tables.nodes().metadata::<Secret>(n).unwrap().unwrap(),
+        secret
+    );
+    assert!(tables.nodes().metadata::<Secret>(p).is_none());
+
+    let mut tables = original.deepcopy().unwrap();
+    tables.strip_metadata(StripMetadataOptions::all()).unwrap();
+    assert!(tables
+        .individuals()
+        .iter()
+        .all(|row| row.metadata.is_none()));
+    assert!(tables.nodes().iter().all(|row| row.metadata.is_none()));
+    assert!(tables.edges().iter().all(|row| row.metadata.is_none()));
+    assert!(tables.migrations().iter().all(|row| row.metadata.is_none()));
+    assert!(tables.sites().iter().all(|row| row.metadata.is_none()));
+    assert!(tables.mutations().iter().all(|row| row.metadata.is_none()));
+    assert!(tables
+        .populations()
+        .iter()
+        .all(|row| row.metadata.is_none()));
+    assert_eq!(unsafe { (*tables.as_ptr()).metadata_length }, 0);
+    assert_eq!(
+        tables.sites().ancestral_state(s),
+        original.sites().ancestral_state(s)
+    );
+    tables.build_index().unwrap();
+    tables
+        .check_integrity(tskit::TableIntegrityCheckFlags::default())
+        .unwrap();
+    // Without metadata, the tables equal the original ignoring metadata
+    assert!(tables.equals(
+        &original,
+        tskit::TableEqualityOptions::default()
+            .ignore_metadata()
+            .ignore_ts_metadata()
+    ));
+}
+
+#[test]
+fn test_fallible_table_constructors() {
+    assert_eq!(tskit::EdgeTable::new().unwrap().num_rows(), 0);
+    assert_eq!(tskit::NodeTable::new().unwrap().num_rows(), 0);
+    assert_eq!(tskit::SiteTable::new().unwrap().num_rows(), 0);
+    assert_eq!(tskit::MutationTable::new().unwrap().num_rows(), 0);
+    assert_eq!(tskit::MigrationTable::new().unwrap().num_rows(), 0);
+    assert_eq!(tskit::IndividualTable::new().unwrap().num_rows(), 0);
+    assert_eq!(tskit::PopulationTable::new().unwrap().num_rows(), 0);
+    #[cfg(feature = "provenance")]
+    assert_eq!(
+        tskit::provenance::ProvenanceTable::new()
+            .unwrap()
+            .num_rows(),
+        0
+    );
+}
+
+#[test]
+fn test_column_statistics() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    assert!(tables.nodes().time_minmax().is_none());
+    assert_eq!(tables.edges().total_span(), 0.0);
+    assert!(tables.mutations().count_by_site().is_empty());
+
+    let p = tables.add_node(0, 2.5, -1, -1).unwrap();
+    let c0 = tables.add_node(0, 0.5, -1, -1).unwrap();
+    let c1 = tables.add_node(0, 1.0, -1, -1).unwrap();
+    tables.add_edge(0., 100., p, c0).unwrap();
+    tables.add_edge(20., 60., p, c1).unwrap();
+    let s0 = tables.add_site(10., None).unwrap();
+    let s1 = tables.add_site(30., None).unwrap();
+    tables.add_mutation(s1, c1, -1, 1.5, None).unwrap();
+    tables.add_mutation(s1, c1, -1, 1.25, None).unwrap();
+
+    assert_eq!(tables.nodes().time_minmax(), Some((0.5.into(), 2.5.into())));
+    assert_eq!(tables.edges().total_span(), 140.0);
+    let counts = tables.mutations().count_by_site();
+    assert_eq!(counts.get(&s0), None);
+    assert_eq!(counts.get(&s1), Some(&2));
+}
+
+#[test]
+fn test_from_newick() {
+    use std::collections::HashMap;
+    use tskit::{NewickLabel, NewickSample};
+
+    let sample = |individual: &str, population: &str| NewickSample {
+        individual: Some(individual.to_string()),
+        population: Some(population.to_string()),
+    };
+    let samples = HashMap::from([
+        ("tip_0".to_string(), sample("NA0", "YRI")),
+        ("tip 1".to_string(), sample("NA1", "CEU")),
+        ("tip_2".to_string(), sample("NA1", "CEU")),
+        ("absent".to_string(), sample("NA2", "CHB")),
+    ]);
+    let tables = tskit::TableCollection::from_newick(
+        "((tip_0:1.5,'tip 1':1.5)[&comment]anc:0.5, tip_2:2.0, tip_3:2)root;",
+        10.,
+        &samples,
+    )
+    .unwrap();
+
+    let nodes = tables.nodes();
+    assert_eq!(nodes.num_rows(), 6);
+    let labels = (0..6)
+        .map(|i| nodes.metadata::<NewickLabel>(i).unwrap().unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec!["root", "anc", "tip_0", "tip 1", "tip_2", "tip_3"]
+    );
+    let times = (0..6)
+        .map(|i| f64::from(nodes.time(i).unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(times, vec![2.0, 1.5, 0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(nodes.samples_as_vector().len(), 4);
+
+    // Individuals and populations are created on first use
+    assert_eq!(tables.individuals().num_rows(), 2);
+    assert_eq!(nodes.individual(3), nodes.individual(4));
+    assert_eq!(nodes.individual(5), Some(tskit::IndividualId::NULL));
+    let populations = tables.populations();
+    assert_eq!(populations.num_rows(), 2);
+    assert_eq!(
+        populations.find_by_name::<NewickLabel>("CEU").unwrap(),
+        nodes.population(4)
+    );
+    assert!(populations
+        .find_by_name::<NewickLabel>("CHB")
+        .unwrap()
+        .is_none());
+
+    let treeseq = tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 1);
+
+    // A deep caterpillar tree does not overflow the stack
+    let n = 10000;
+    let mut newick = "(".repeat(n - 1);
+    newick.push_str("t0:1");
+    for i in 1..n {
+        newick.push_str(&format!(",t{i}:{i}):1"));
+    }
+    newick.push(';');
+    let tables = tskit::TableCollection::from_newick(&newick, 1., &HashMap::new()).unwrap();
+    assert_eq!(tables.nodes().num_rows().as_usize(), 2 * n - 1);
+    assert_eq!(tables.nodes().samples_as_vector().len(), n);
+
+    for bad in [
+        "",
+        "(A:1,B:1)",
+        "(A:1,B:1));",
+        "((A:1,B:1);",
+        "(A:1,B);",
+        "(A:1,B:-1);",
+        "(A:1,B:x);",
+        "(A:1,A:1);",
+        "(A:1,B:1); C",
+        "(A:1 B:1);",
+        "(A:1,B:1:1);",
+        "('A:1,B:1);",
+    ] {
+        assert!(
+            matches!(
+                tskit::TableCollection::from_newick(bad, 1., &HashMap::new()),
+                Err(tskit::TskitError::ValueError { .. })
+            ),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn test_set_indexes() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let parent = tables.add_node(0, 2.0, -1, -1).unwrap();
+    for (left, right) in [(0., 60.), (40., 100.), (0., 100.)] {
+        let child = tables
+            .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+            .unwrap();
+        tables.add_edge(left, right, parent, child).unwrap();
+    }
+    // Indexes of an empty edge table are valid
+    let mut empty = tskit::TableCollection::new(100.).unwrap();
+    empty.set_indexes(&[], &[]).unwrap();
+    assert!(empty.is_indexed());
+
+    let mut indexed = tables.deepcopy().unwrap();
+    indexed.build_index().unwrap();
+    let insertion = indexed.edge_insertion_order().unwrap().to_vec();
+    let removal = indexed.edge_removal_order().unwrap().to_vec();
+
+    assert!(!tables.is_indexed());
+    tables.set_indexes(&insertion, &removal).unwrap();
+    assert_eq!(tables.edge_insertion_order(), Some(&insertion[..]));
+    assert_eq!(tables.edge_removal_order(), Some(&removal[..]));
+    assert!(tables.equals(&indexed, tskit::TableEqualityOptions::default()));
+    let treeseq = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 3);
+
+    // Invalid indexes leave the existing ones in place
+    for bad in [
+        vec![insertion[0], insertion[1]],
+        vec![insertion[0], insertion[1], insertion[1]],
+        vec![insertion[0], insertion[1], tskit::EdgeId::NULL],
+        vec![insertion[0], insertion[1], 3.into()],
+    ] {
+        assert!(matches!(
+            tables.set_indexes(&bad, &removal),
+            Err(tskit::TskitError::ValueError { .. })
+        ));
+        assert!(tables.set_indexes(&insertion, &bad).is_err());
+        assert_eq!(tables.edge_insertion_order(), Some(&insertion[..]));
+    }
+
+    // Indexes that are permutations but do not match
+    // the edges are found by the tree checks.
+    let mut reversed = insertion.clone();
+    reversed.reverse();
+    tables.set_indexes(&reversed, &removal).unwrap();
+    assert!(tables
+        .check_integrity(tskit::TableIntegrityCheckFlags::CHECK_TREES)
+        .is_err());
+    assert!(tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .is_err());
+}
+
+#[test]
+fn test_edge_interval_index() {
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(101);
+    let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    for _ in 0..500 {
+        let a = rng.gen_range(0.0..1000.0);
+        let b = rng.gen_range(0.0..1000.0);
+        let (left, right) = if a < b { (a, b) } else { (b, a) };
+        let parent = rng.gen_range(0..20);
+        let child = rng.gen_range(20..40);
+        tables.add_edge(left, right, parent, child).unwrap();
+    }
+    let index = tables.edges().interval_index();
+    assert_eq!(index.num_edges(), 500);
+
+    let naive = |left: f64, right: f64, keep: &dyn Fn(usize) -> bool| {
+        let edges = tables.edges();
+        let mut rv = (0..500)
+            .map(tskit::EdgeId::from)
+            .filter(|&e| {
+                keep(e.as_usize())
+                    && edges.left(e).unwrap() < right
+                    && edges.right(e).unwrap() > left
+            })
+            .collect::<Vec<_>>();
+        rv.sort_by(|&a, &b| {
+            edges
+                .left(a)
+                .unwrap()
+                .partial_cmp(&edges.left(b).unwrap())
+                .unwrap()
+        });
+        rv
+    };
+
+    let parents = tables.edges().parent_slice().to_vec();
+    let children = tables.edges().child_slice().to_vec();
+    for _ in 0..100 {
+        let a = rng.gen_range(0.0..1000.0);
+        let b = rng.gen_range(0.0..1000.0);
+        let (left, right) = if a < b { (a, b) } else { (b, a) };
+        assert_eq!(
+            index.overlapping(left, right),
+            naive(left, right, &|_| true)
+        );
+        let node = tskit::NodeId::from(rng.gen_range(0..40));
+        assert_eq!(
+            index.parent_edges(node, left, right),
+            naive(left, right, &|e| parents[e] == node)
+        );
+        assert_eq!(
+            index.child_edges(node, left, right),
+            naive(left, right, &|e| children[e] == node)
+        );
+    }
+
+    // Edges are half-open intervals
+    let left = tables.edges().left(0).unwrap();
+    assert!(!index
+        .overlapping(f64::from(left) - 1.0, f64::from(left))
+        .contains(&0.into()));
+    assert!(index.overlapping(500., 500.).is_empty());
+    assert!(index.parent_edges(-1, 0., 1000.).is_empty());
+    assert!(index.parent_edges(1000, 0., 1000.).is_empty());
+
+    let empty = tskit::TableCollection::new(10.).unwrap();
+    assert!(empty
+        .edges()
+        .interval_index()
+        .overlapping(0., 10.)
+        .is_empty());
+
+    // Cached by tree sequences
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let child = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 100., parent, child).unwrap();
+    let treeseq = tables
+        .tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let cache = treeseq.cache();
+    let index = cache.edge_interval_index();
+    assert!(std::rc::Rc::ptr_eq(&index, &cache.edge_interval_index()));
+    assert_eq!(index.child_edges(child, 10., 20.), [tskit::EdgeId::from(0)]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_snapshots() {
+    use tskit::metadata::MetadataRoundtrip;
+
+    struct Raw(Vec<u8>);
+    impl MetadataRoundtrip for Raw {
+        fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+            Ok(self.0.clone())
+        }
+        fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+            Ok(Self(md.to_vec()))
+        }
+    }
+    impl tskit::metadata::NodeMetadata for Raw {}
+    impl tskit::metadata::PopulationMetadata for Raw {}
+    impl tskit::metadata::MutationMetadata for Raw {}
+    impl tskit::metadata::TableCollectionMetadata for Raw {}
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let pop = tables
+        .add_population_with_metadata(&Raw(b"{\"name\": \"pop0\"}".to_vec()))
+        .unwrap();
+    let i0 = tables.add_individual(0, [1.0, 2.5], None).unwrap();
+    let i1 = tables.add_individual(1 << 16, None, [i0]).unwrap();
+    let parent = tables
+        .add_node_with_metadata(0, 2.0, pop, i0, &Raw(vec![0, 159, 146, 150]))
+        .unwrap();
+    let child = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, pop, i1)
+        .unwrap();
+    tables.add_edge(0., 100., parent, child).unwrap();
+    tables
+        .add_migration((0., 50.), child, (pop, pop), 1.0)
+        .unwrap();
+    let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    let s1 = tables.add_site(20., None).unwrap();
+    let unknown_time = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    tables
+        .add_mutation_with_metadata(s0, child, -1, 1.0, Some(b"T"), &Raw(b"x".to_vec()))
+        .unwrap();
+    tables
+        .add_mutation(s1, child, -1, unknown_time, Some(&[255u8][..]))
+        .unwrap();
+
+    let json = tables.to_json().unwrap();
+    let restored = tskit::TableCollection::from_json(&json).unwrap();
+    assert!(restored.equals(&tables, tskit::TableEqualityOptions::default()));
+    assert!(json.contains("\"derived_state\": \"T\""));
+    assert!(json.contains("\"time\": null"));
+    assert_eq!(restored.to_json().unwrap(), json);
+
+    // Top-level fields, schemas, and non-finite floats
+    let schema = tskit::metadata::MetadataSchema::permissive_json();
+    tables.set_metadata(&Raw(b"{\"x\": 1}".to_vec())).unwrap();
+    tables.set_metadata_schema(&schema).unwrap();
+    tables.set_nodes_metadata_schema(&schema).unwrap();
+    tables.set_migrations_metadata_schema(&schema).unwrap();
+    let time_units = "generations";
+    let reference = b"ACGT";
+    let url = "https://example.com/ref.fa";
+    // SAFETY: the tables are initialized and the values are copied
+    unsafe {
+        let ptr = tables.as_mut_ptr();
+        tskit::bindings::tsk_table_collection_set_time_units(
+            ptr,
+            time_units.as_ptr().cast(),
+            time_units.len() as _,
+        );
+        tskit::bindings::tsk_reference_sequence_set_data(
+            &mut (*ptr).reference_sequence,
+            reference.as_ptr().cast(),
+            reference.len() as _,
+        );
+        tskit::bindings::tsk_reference_sequence_set_url(
+            &mut (*ptr).reference_sequence,
+            url.as_ptr().cast(),
+            url.len() as _,
+        );
+    }
+    tables.add_node(0, f64::NAN, -1, -1).unwrap();
+    tables
+        .add_individual(0, [f64::INFINITY, f64::NEG_INFINITY], None)
+        .unwrap();
+    tables.add_mutation(s1, child, -1, f64::NAN, None).unwrap();
+    let json = tables.to_json().unwrap();
+    assert!(json.contains("\"time_units\": \"generations\""));
+    assert!(json.contains("\"NaN\""));
+    assert!(json.contains("\"-inf\""));
+    let restored = tskit::TableCollection::from_json(&json).unwrap();
+    assert!(restored.equals(&tables, tskit::TableEqualityOptions::default()));
+    assert_eq!(
+        restored.digest(tskit::TableEqualityOptions::default()),
+        tables.digest(tskit::TableEqualityOptions::default())
+    );
+    assert_eq!(restored.metadata_schema(), tables.metadata_schema());
+    assert_eq!(restored.nodes().metadata_schema(), Some(schema.as_ref()));
+    assert!(restored.edges().metadata_schema().is_none());
+    assert_eq!(restored.raw_metadata(), Some(&b"{\"x\": 1}"[..]));
+    // NaN mutation times are not unknown times
+    assert_eq!(
+        f64::from(restored.mutations().time(2).unwrap()).to_bits(),
+        f64::NAN.to_bits()
+    );
+    assert_eq!(restored.to_json().unwrap(), json);
+
+    // Individual tables
+    let json = tables.nodes().to_json().unwrap();
+    let nodes = tskit::NodeTable::from_json(&json).unwrap();
+    assert_eq!(nodes.num_rows(), 3);
+    assert_eq!(
+        nodes.metadata::<Raw>(parent).unwrap().unwrap().0,
+        vec![0, 159, 146, 150]
+    );
+    let individuals =
+        tskit::IndividualTable::from_json(&tables.individuals().to_json().unwrap()).unwrap();
+    assert_eq!(individuals.parents(i1), Some(&[i0][..]));
+    assert_eq!(
+        individuals.location(i0),
+        Some(&[tskit::Location::from(1.0), 2.5.into()][..])
+    );
+    let mutations =
+        tskit::MutationTable::from_json(&tables.mutations().to_json().unwrap()).unwrap();
+    assert!(f64::from(mutations.time(1).unwrap()).is_nan());
+    let migrations =
+        tskit::MigrationTable::from_json(&tables.migrations().to_json().unwrap()).unwrap();
+    assert_eq!(migrations.num_rows(), 1);
+    let populations =
+        tskit::PopulationTable::from_json(&tables.populations().to_json().unwrap()).unwrap();
+    assert_eq!(populations.num_rows(), 1);
+    let edges =
+        tskit::EdgeTable::from_json(r#"[{"left": 0, "right": 1, "parent": 0, "child": 1}]"#)
+            .unwrap();
+    assert_eq!(edges.right(0), Some(1.0.into()));
+    let sites = tskit::SiteTable::from_json("[]").unwrap();
+    assert_eq!(sites.num_rows(), 0);
+
+    assert!(tskit::TableCollection::from_json("{}").is_err());
+    assert!(tskit::TableCollection::from_json(r#"{"sequence_length": 0}"#).is_err());
+    assert!(tskit::NodeTable::from_json(r#"[{"flags": 0}]"#).is_err());
+    assert!(tskit::EdgeTable::from_json("not json").is_err());
+}
+
+#[test]
+fn test_table_collection_digest() {
+    use tskit::metadata::MetadataRoundtrip;
+    use tskit::TableEqualityOptions;
+
+    struct Raw(Vec<u8>);
+    impl MetadataRoundtrip for Raw {
+        fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+            Ok(self.0.clone())
+        }
+        fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+            Ok(Self(md.to_vec()))
+        }
+    }
+    impl tskit::metadata::NodeMetadata for Raw {}
+
+    let options = TableEqualityOptions::default();
+    let empty = tskit::TableCollection::new(100.).unwrap();
+    assert_ne!(
+        empty.digest(options),
+        tskit::TableCollection::new(50.).unwrap().digest(options)
+    );
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let child = tables
+        .add_node_with_metadata(tskit::NodeFlags::new_sample(), 0.0, -1, -1, &Raw(vec![1]))
+        .unwrap();
+    tables.add_edge(0., 100., parent, child).unwrap();
+    tables.add_individual(0, [0.5], None).unwrap();
+    let site = tables.add_site(10., Some(b"A")).unwrap();
+    tables
+        .add_mutation(site, child, -1, 0.5, Some(b"T"))
+        .unwrap();
+    assert_ne!(tables.digest(options), empty.digest(options));
+
+    let copy = tables.deepcopy().unwrap();
+    assert_eq!(tables.digest(options), copy.digest(options));
+    assert_eq!(
+        copy.digest(options).as_bytes(),
+        tables.digest(options).as_bytes()
+    );
+
+    // Moving bytes between ragged rows changes the digest
+    let mut with_metadata = tskit::TableCollection::new(100.).unwrap();
+    with_metadata
+        .add_node_with_metadata(0, 1.0, -1, -1, &Raw(vec![]))
+        .unwrap();
+    with_metadata
+        .add_node_with_metadata(tskit::NodeFlags::new_sample(), 0.0, -1, -1, &Raw(vec![1]))
+        .unwrap();
+    let mut without_metadata = tskit::TableCollection::new(100.).unwrap();
+    without_metadata
+        .add_node_with_metadata(0, 1.0, -1, -1, &Raw(vec![1]))
+        .unwrap();
+    without_metadata
+        .add_node_with_metadata(tskit::NodeFlags::new_sample(), 0.0, -1, -1, &Raw(vec![]))
+        .unwrap();
+    assert_ne!(
+        with_metadata.digest(options),
+        without_metadata.digest(options)
+    );
+
+    // Metadata may be ignored
+    let mut other = tskit::TableCollection::new(100.).unwrap();
+    other.add_node(0, 1.0, -1, -1).unwrap();
+    other
+        .add_node_with_metadata(tskit::NodeFlags::new_sample(), 0.0, -1, -1, &Raw(vec![2]))
+        .unwrap();
+    other.add_edge(0., 100., parent, child).unwrap();
+    other.add_individual(0, [0.5], None).unwrap();
+    let site = other.add_site(10., Some(b"A")).unwrap();
+    other
+        .add_mutation(site, child, -1, 0.5, Some(b"T"))
+        .unwrap();
+    assert!(!tables.equals(&other, options));
+    assert_ne!(tables.digest(options), other.digest(options));
+    let ignore_metadata = options.ignore_metadata();
+    assert!(tables.equals(&other, ignore_metadata));
+    assert_eq!(
+        tables.digest(ignore_metadata),
+        other.digest(ignore_metadata)
+    );
+    assert_ne!(tables.digest(options), tables.digest(ignore_metadata));
+
+    // Table indexes do not contribute
+    let mut indexed = tables.deepcopy().unwrap();
+    indexed.build_index().unwrap();
+    assert_eq!(indexed.digest(options), tables.digest(options));
+
+    let treeseq = indexed
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    assert_eq!(treeseq.digest(options), tables.digest(options));
+}
+
+#[test]
+fn test_kastore_extras_round_trip() {
+    use tskit::{KastoreArray, KastoreExtras, TableEqualityOptions, TableOutputOptions};
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let child = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 100., parent, child).unwrap();
+    tables.build_index().unwrap();
+
+    let mut extras = KastoreExtras::default();
+    assert!(extras.is_empty());
+    extras.insert("mytool/labels", b"ab".to_vec()).unwrap();
+    extras.insert("mytool/weights", vec![0.5, -1.0]).unwrap();
+    extras.insert("mytool/empty", Vec::<i64>::new()).unwrap();
+    assert_eq!(
+        extras.insert("mytool/labels", vec![1_i8]).unwrap(),
+        Some(KastoreArray::UInt8(b"ab".to_vec()))
+    );
+    assert!(extras.insert("", vec![1_i8]).is_err());
+    assert!(extras.insert("edges/left", vec![1_i8]).is_err());
+    assert!(extras.insert("uuid", vec![1_i8]).is_err());
+    assert_eq!(extras.len(), 3);
+
+    let filename = "test_kastore_extras_round_trip.trees";
+    tables
+        .dump_with_extras(filename, TableOutputOptions::default(), &extras)
+        .unwrap();
+    let loaded = tskit::TableCollection::new_from_file(filename).unwrap();
+    assert!(loaded.equals(&tables, TableEqualityOptions::default()));
+    let loaded_extras = KastoreExtras::from_file(filename).unwrap();
+    assert_eq!(loaded_extras, extras);
+    assert_eq!(
+        loaded_extras.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+        ["mytool/empty", "mytool/labels", "mytool/weights"]
+    );
+    assert_eq!(loaded_extras.get("mytool/labels").map(|a| a.len()), Some(1));
+
+    // Dumping without extras drops them
+    tables
+        .dump(filename, TableOutputOptions::default())
+        .unwrap();
+    assert!(KastoreExtras::from_file(filename).unwrap().is_empty());
+
+    // Extras pass through tree sequences
+    let treeseq = loaded
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    let mut extras = loaded_extras;
+    assert!(extras.remove("mytool/empty").is_some());
+    treeseq
+        .dump_with_extras(filename, TableOutputOptions::default(), &extras)
+        .unwrap();
+    assert_eq!(KastoreExtras::from_file(filename).unwrap(), extras);
+    let treeseq = tskit::TreeSequence::load(filename).unwrap();
+    assert_eq!(treeseq.num_trees(), 1);
+
+    std::fs::remove_file(filename).unwrap();
+    assert!(KastoreExtras::from_file(filename).is_err());
+}
+
+#[test]
+fn test_canonical_dump() {
+    use tskit::{CanonicalDumpOptions, TableEqualityOptions, TableOutputOptions};
+
+    // Location NaNs with different payloads
+    let nan = |payload: u64| f64::from_bits(f64::NAN.to_bits() | payload);
+    let make_tables = |payload: u64| {
+        let mut tables = tskit::TableCollection::new(100.).unwrap();
+        tables.add_individual(0, [nan(payload), 1.0], None).unwrap();
+        let p = tables.add_node(0, 1.0, -1, 0).unwrap();
+        let c = tables
+            .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, 0)
+            .unwrap();
+        tables.add_edge(0., 100., p, c).unwrap();
+        let s = tables.add_site(50., Some(b"A")).unwrap();
+        let unknown_time = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+        tables
+            .add_mutation(s, c, -1, unknown_time, Some(b"T"))
+            .unwrap();
+        tables.build_index().unwrap();
+        tables
+    };
+    let tables = make_tables(1);
+    let other = make_tables(2);
+
+    let dump = |tables: &tskit::TableCollection, options: Option<CanonicalDumpOptions>| {
+        let filename = "test_canonical_dump.trees";
+        match options {
+            Some(options) => tables.dump_canonical(filename, options).unwrap(),
+            None => tables
+                .dump(filename, TableOutputOptions::default())
+                .unwrap(),
+        };
+        let bytes = std::fs::read(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        bytes
+    };
+
+    // Default dumps have a random uuid
+    assert_ne!(dump(&tables, None), dump(&tables, None));
+
+    let canonical = Some(CanonicalDumpOptions::default());
+    let bytes = dump(&tables, canonical);
+    assert_eq!(bytes, dump(&tables, canonical));
+    assert_eq!(bytes, dump(&other, canonical));
+    assert_eq!(bytes, dump(&tables.deepcopy().unwrap(), canonical));
+
+    // Tree sequences write the same bytes as their tables
+    let filename = "test_canonical_dump_treeseq.trees";
+    let treeseq = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    treeseq
+        .dump_canonical(filename, CanonicalDumpOptions::default())
+        .unwrap();
+    assert_eq!(std::fs::read(filename).unwrap(), bytes);
+
+    // The file loads, with NaN payloads normalised and unknown times kept
+    let loaded = tskit::TableCollection::new_from_file(filename).unwrap();
+    std::fs::remove_file(filename).unwrap();
+    assert!(!loaded.equals(&tables, TableEqualityOptions::default()));
+    assert_eq!(
+        f64::from(loaded.individuals().location(0).unwrap()[0]).to_bits(),
+        f64::NAN.to_bits()
+    );
+    assert_eq!(
+        f64::from(loaded.mutations().time_slice()[0]).to_bits(),
+        tskit::bindings::TSK_UNKNOWN_TIME_HEX
+    );
+    assert_eq!(
+        loaded.digest(TableEqualityOptions::default()),
+        make_tables(0).digest(TableEqualityOptions::default())
+    );
+    // The tables themselves are not changed
+    assert_eq!(
+        f64::from(tables.individuals().location(0).unwrap()[0]).to_bits(),
+        nan(1).to_bits()
+    );
+
+    // Rows in a different order are written as the same bytes
+    // only if rows are sorted
+    let mut sorted = make_tables(1);
+    let mut reversed = make_tables(1);
+    for pos in [10., 20.] {
+        sorted.add_site(pos, Some(b"A")).unwrap();
+    }
+    for pos in [20., 10.] {
+        reversed.add_site(pos, Some(b"A")).unwrap();
+    }
+    assert_ne!(dump(&sorted, canonical), dump(&reversed, canonical));
+    let sort_rows = Some(CanonicalDumpOptions::default().sort_rows(true));
+    assert_eq!(dump(&sorted, sort_rows), dump(&reversed, sort_rows));
+    // The tables themselves are not sorted
+    assert_eq!(reversed.sites().position(1), Some(20.0.into()));
+
+    #[cfg(feature = "provenance")]
+    {
+        let mut tables = make_tables(1);
+        let mut other = make_tables(1);
+        tables.add_provenance("a record").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        other.add_provenance("a record").unwrap();
+        assert_ne!(dump(&tables, canonical), dump(&other, canonical));
+
+        let options = CanonicalDumpOptions::default().fixed_provenance_timestamps(true);
+        let bytes = dump(&tables, Some(options));
+        assert_eq!(bytes, dump(&other, Some(options)));
+        let filename = "test_canonical_dump_provenance.trees";
+        tables.dump_canonical(filename, options).unwrap();
+        let loaded = tskit::TableCollection::new_from_file(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        let provenance = loaded.provenances().row(0).unwrap();
+        assert_eq!(provenance.timestamp, "1970-01-01T00:00:00Z");
+        assert_eq!(provenance.record, "a record");
+    }
+}
+
+#[test]
+fn test_individual_table_topological_sort() {
+    use tskit::{IndividualId, IndividualTableSortOptions};
+
+    // Grandchild, child, and grandparent, plus an unrelated individual
+    let mut individuals = tskit::IndividualTable::new().unwrap();
+    individuals.add_row(0, [0.5], [1]).unwrap();
+    individuals.add_row(1, [1.5], [2]).unwrap();
+    individuals.add_row(2, [2.5], None).unwrap();
+    individuals.add_row(3, [3.5], None).unwrap();
+    assert!(!individuals.is_topologically_sorted());
+
+    // The same rows in a table collection
+    let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    for row in individuals.iter() {
+        tables
+            .add_individual(row.flags, row.location.as_deref(), row.parents.as_deref())
+            .unwrap();
+    }
+    for i in 0..4 {
+        tables.add_node(0, 0.0, -1, i).unwrap();
+    }
+
+    let new_ids = individuals
+        .topological_sort(IndividualTableSortOptions::default())
+        .unwrap();
+    assert!(individuals.is_topologically_sorted());
+    assert_eq!(new_ids.len(), 4);
+    for (old, new) in new_ids.iter().enumerate() {
+        assert_eq!(
+            individuals.location(*new),
+            Some(&[tskit::Location::from(old as f64 + 0.5)][..])
+        );
+    }
+    assert!(individuals
+        .parents(new_ids[0])
+        .unwrap()
+        .iter()
+        .all(|&p| p == new_ids[1]));
+
+    // Sorting the collection gives the same table and node references
+    tables
+        .topological_sort_individuals(IndividualTableSortOptions::default())
+        .unwrap();
+    assert!(tables.individuals().iter().eq(individuals.iter()));
+    assert_eq!(tables.nodes().individual_slice(), new_ids.as_slice());
+
+    // Sorting a sorted table changes nothing
+    let new_ids = individuals
+        .topological_sort(IndividualTableSortOptions::default())
+        .unwrap();
+    assert!(new_ids
+        .iter()
+        .enumerate()
+        .all(|(i, &n)| n == IndividualId::from(i as i32)));
+
+    // Empty tables are sorted
+    let mut empty = tskit::IndividualTable::new().unwrap();
+    assert!(empty.is_topologically_sorted());
+    assert!(empty
+        .topological_sort(IndividualTableSortOptions::default())
+        .unwrap()
+        .is_empty());
+
+    // Cycles and bad parents are errors
+    let mut cycle = tskit::IndividualTable::new().unwrap();
+    cycle.add_row(0, None, [1]).unwrap();
+    cycle.add_row(0, None, [0]).unwrap();
+    assert!(cycle
+        .topological_sort(IndividualTableSortOptions::default())
+        .is_err());
+    let mut bad_parent = tskit::IndividualTable::new().unwrap();
+    bad_parent.add_row(0, None, [5]).unwrap();
+    assert!(!bad_parent.is_topologically_sorted());
+    assert!(bad_parent
+        .topological_sort(IndividualTableSortOptions::default())
+        .is_err());
+}
+
+#[test]
+fn test_individual_locations() {
+    let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    let a = tables.add_individual(0, [0.0, 0.0, 0.0], None).unwrap();
+    let b = tables.add_individual(0, [1.0, 2.0, 2.0], None).unwrap();
+    let c = tables.add_individual(0, [-1.0, 0.0, 0.0], None).unwrap();
+
+    let individuals = tables.individuals();
+    assert_eq!(individuals.location_dimension().unwrap(), 3);
+    assert_eq!(individuals.location_distance(a, b).unwrap(), 3.0);
+    assert_eq!(individuals.location_distance(b, a).unwrap(), 3.0);
+    assert_eq!(individuals.location_distance(a, a).unwrap(), 0.0);
+    assert!(matches!(
+        individuals.location_distance(a, 3),
+        Err(tskit::TskitError::IndexError)
+    ));
+    assert!(matches!(
+        individuals.location_distance(tskit::IndividualId::NULL, a),
+        Err(tskit::TskitError::IndexError)
+    ));
+
+    let matrix = individuals.location_matrix().unwrap();
+    assert_eq!(matrix.shape(), (3, 3));
+    assert_eq!(matrix.num_individuals, 3);
+    assert_eq!(matrix.dimension, 3);
+    assert_eq!(matrix.location(2), Some(&[-1.0, 0.0, 0.0][..]));
+    assert_eq!(matrix.location(3), None);
+    for (i, j) in [(a, b), (a, c), (b, c)] {
+        assert_eq!(
+            matrix.distance(i.as_usize(), j.as_usize()),
+            Some(individuals.location_distance(i, j).unwrap())
+        );
+    }
+    assert_eq!(matrix.distance(0, 3), None);
+    #[cfg(feature = "ndarray")]
+    {
+        let array = matrix.to_ndarray().unwrap();
+        assert_eq!(array.shape(), &[3, 3]);
+        assert_eq!(array.row(2).to_vec(), matrix.location(2).unwrap());
+        let mut truncated = matrix.clone();
+        truncated.coordinates.pop();
+        assert!(truncated.to_ndarray().is_err());
+    }
+
+    // Individuals without locations have no coordinates
+    let mut empty = tskit::IndividualTable::new().unwrap();
+    assert_eq!(empty.location_dimension().unwrap(), 0);
+    let matrix = empty.location_matrix().unwrap();
+    assert_eq!(matrix.shape(), (0, 0));
+    let i = empty.add_row(0, None, None).unwrap();
+    let matrix = empty.location_matrix().unwrap();
+    assert_eq!(matrix.shape(), (1, 0));
+    assert_eq!(matrix.location(0), Some(&[][..]));
+    assert_eq!(empty.location_distance(i, i).unwrap(), 0.0);
+
+    // Mixed dimensions are errors
+    let d = tables.add_individual(0, [1.0], None).unwrap();
+    let individuals = tables.individuals();
+    assert!(individuals.location_dimension().is_err());
+    assert!(individuals.location_matrix().is_err());
+    assert!(individuals.location_distance(a, d).is_err());
+}
+
+#[test]
+fn test_index_is_valid() {
+    use tskit::{TableSortOptions, TreeSequenceFlags, TskitError};
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let p0 = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let p1 = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let c0 = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    let c1 = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 100., p1, c0).unwrap();
+    tables.add_edge(0., 50., p0, c1).unwrap();
+    tables.add_edge(50., 100., p0, p1).unwrap();
+    tables.full_sort(TableSortOptions::default()).unwrap();
+
+    // Never indexed: the C error is returned
+    assert!(!tables.index_is_valid());
+    match tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(TreeSequenceFlags::default())
+    {
+        Err(TskitError::ErrorCode { code }) => {
+            assert_eq!(code, tskit::bindings::TSK_ERR_TABLES_NOT_INDEXED)
+        }
+        _ => panic!("expected an error code"),
+    }
+
+    tables.build_index().unwrap();
+    assert!(tables.index_is_valid());
+    let insertion = tables.edge_insertion_order().unwrap().to_vec();
+    let removal = tables.edge_removal_order().unwrap().to_vec();
+
+    // Valid permutations in the wrong order are stale
+    let mut reversed = insertion.clone();
+    reversed.reverse();
+    tables.set_indexes(&reversed, &removal).unwrap();
+    assert!(tables.is_indexed());
+    assert!(!tables.index_is_valid());
+    assert!(matches!(
+        tables
+            .deepcopy()
+            .unwrap()
+            .tree_sequence(TreeSequenceFlags::default()),
+        Err(TskitError::StaleIndexes)
+    ));
+    // Rebuilding the indexes fixes them
+    let treeseq = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 2);
+    tables.set_indexes(&insertion, &removal).unwrap();
+    assert!(tables.index_is_valid());
+
+    // Adding an edge leaves the indexes stale, too
+    let mut edited = tables.deepcopy().unwrap();
+    let c2 = edited
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    // The edges remain sorted, as p0 is the oldest parent
+    edited.add_edge(0., 100., p0, c2).unwrap();
+    assert!(!edited.is_indexed());
+    assert!(!edited.index_is_valid());
+    assert!(matches!(
+        edited.tree_sequence(TreeSequenceFlags::default()),
+        Err(TskitError::StaleIndexes)
+    ));
+
+    // Editing node times reorders the removal of edges
+    // with the same right coordinate
+    let mut edited = tables.deepcopy().unwrap();
+    edited.nodes_mut().time_slice_mut()[p1.as_usize()] = 3.0.into();
+    assert!(!edited.index_is_valid());
+
+    // Empty tables have valid indexes once built
+    let mut empty = tskit::TableCollection::new(1.).unwrap();
+    empty.build_index().unwrap();
+    assert!(empty.index_is_valid());
+}
+
+#[test]
+fn test_copy_row() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let pop = tables.add_population().unwrap();
+    let parent = tables.add_individual(0, [1.0], None).unwrap();
+    let individual = tables.add_individual(1, [2.0, 3.0], [parent]).unwrap();
+    tables.add_node(0, 1.0, pop, parent).unwrap();
+    let node = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, pop, individual)
+        .unwrap();
+    let edge = tables.add_edge(0., 50., 0, node).unwrap();
+    let site = tables.add_site(10., Some(b"A")).unwrap();
+    let mutation = tables
+        .add_mutation(site, node, tskit::MutationId::NULL, 0.5, Some(b"TT"))
+        .unwrap();
+    let migration = tables
+        .add_migration((0., 50.), node, (pop, pop), 0.5)
+        .unwrap();
+
+    // Rows are appended, so ids are those of the destination
+    let mut individuals = tskit::IndividualTable::new().unwrap();
+    individuals.add_row(0, None, None).unwrap();
+    let copied = individuals
+        .copy_row(tables.individuals(), individual)
+        .unwrap();
+    assert_eq!(copied, 1);
+    let row = individuals.row(copied).unwrap();
+    let expected = tables.individuals().row(individual).unwrap();
+    assert_eq!(row.flags, expected.flags);
+    assert_eq!(row.location, expected.location);
+    // References are copied unchanged
+    assert_eq!(row.parents, Some(vec![parent]));
+
+    let mut nodes = tskit::NodeTable::new().unwrap();
+    assert_eq!(nodes.copy_row(tables.nodes(), node).unwrap(), 0);
+    assert_eq!(nodes.individual(0), Some(individual));
+    assert_eq!(nodes.time(0), Some(0.0.into()));
+
+    // Metadata is copied without decoding
+    let label = tskit::NewickLabel::from("tip");
+    let labelled = tables
+        .add_node_with_metadata(tskit::NodeFlags::new_sample(), 0.0, pop, -1, &label)
+        .unwrap();
+    let copied = nodes.copy_row(tables.nodes(), labelled).unwrap();
+    assert_eq!(
+        nodes
+            .metadata::<tskit::NewickLabel>(copied)
+            .unwrap()
+            .unwrap(),
+        label
+    );
+
+    let mut edges = tskit::EdgeTable::new().unwrap();
+    assert_eq!(edges.copy_row(tables.edges(), edge).unwrap(), 0);
+    assert_eq!(edges.right(0), Some(50.0.into()));
+
+    let mut sites = tskit::SiteTable::new().unwrap();
+    sites.copy_row(tables.sites(), site).unwrap();
+    assert_eq!(sites.ancestral_state(0), Some(&b"A"[..]));
+
+    let mut mutations = tskit::MutationTable::new().unwrap();
+    mutations.copy_row(tables.mutations(), mutation).unwrap();
+    assert_eq!(mutations.derived_state(0), Some(&b"TT"[..]));
+
+    let mut migrations = tskit::MigrationTable::new().unwrap();
+    migrations.copy_row(tables.migrations(), migration).unwrap();
+    assert_eq!(migrations.node(0), Some(node));
+
+    let mut populations = tskit::PopulationTable::new().unwrap();
+    populations.copy_row(tables.populations(), pop).unwrap();
+    assert_eq!(populations.num_rows(), 1);
+
+    // Copying from an owned table into another
+    let mut more_nodes = tskit::NodeTable::new().unwrap();
+    more_nodes.copy_row(&nodes, 1).unwrap();
+    more_nodes.copy_row(&nodes, 0).unwrap();
+    assert_eq!(more_nodes.num_rows(), 2);
+    assert_eq!(
+        more_nodes.row(0).unwrap().metadata,
+        nodes.row(1).unwrap().metadata
+    );
+
+    // Out of range rows are errors, and nothing is copied
+    assert!(nodes.copy_row(tables.nodes(), 3).is_err());
+    assert!(nodes.copy_row(tables.nodes(), tskit::NodeId::NULL).is_err());
+    assert_eq!(nodes.num_rows(), 2);
+}
+
+#[test]
+fn test_table_columns() {
+    use tskit::{ColumnDataType, TableColumns};
+
+    fn names<T: TableColumns>() -> Vec<&'static str> {
+        T::COLUMNS.iter().map(|c| c.name).collect()
+    }
+
+    assert_eq!(
+        names::<tskit::IndividualTable>(),
+        ["flags", "location", "parents", "metadata"]
+    );
+    assert_eq!(
+        names::<tskit::NodeTable>(),
+        ["flags", "time", "population", "individual", "metadata"]
+    );
+    assert_eq!(
+        names::<tskit::MigrationTable>(),
+        ["left", "right", "node", "source", "dest", "time", "metadata"]
+    );
+    assert_eq!(
+        names::<tskit::MutationTable>(),
+        [
+            "site",
+            "node",
+            "parent",
+            "time",
+            "derived_state",
+            "metadata"
+        ]
+    );
+    assert_eq!(names::<tskit::PopulationTable>(), ["metadata"]);
+    #[cfg(feature = "provenance")]
+    assert_eq!(
+        names::<tskit::provenance::ProvenanceTable>(),
+        ["timestamp", "record"]
+    );
+
+    // Every table but provenances has ragged metadata
+    fn check_metadata<T: TableColumns>() {
+        let metadata = T::column("metadata").unwrap();
+        assert_eq!(metadata.data_type, ColumnDataType::UInt8);
+        assert!(metadata.ragged);
+    }
+    check_metadata::<tskit::IndividualTable>();
+    check_metadata::<tskit::NodeTable>();
+    check_metadata::<tskit::EdgeTable>();
+    check_metadata::<tskit::MigrationTable>();
+    check_metadata::<tskit::SiteTable>();
+    check_metadata::<tskit::MutationTable>();
+    check_metadata::<tskit::PopulationTable>();
+
+    // Ids are stored as i32
+    for c in tskit::MutationTable::COLUMNS
+        .iter()
+        .chain(tskit::IndividualTable::COLUMNS)
+        .filter(|c| c.rust_type.ends_with("Id"))
+    {
+        assert_eq!(c.data_type, ColumnDataType::Int32, "{}", c.name);
+    }
+    let parents = tskit::IndividualTable::column("parents").unwrap();
+    assert!(parents.ragged);
+    assert_eq!(parents.rust_type, "IndividualId");
+    // Text columns are described as str
+    for c in [
+        tskit::SiteTable::column("ancestral_state"),
+        tskit::MutationTable::column("derived_state"),
+    ] {
+        assert_eq!(c.unwrap().rust_type, "str");
+    }
+    assert_eq!(tskit::MigrationTable::TABLE_NAME, "migrations");
+    assert!(tskit::EdgeTable::column("metadata_offset").is_none());
+}
+
+#[test]
+fn test_subset_and_canonicalise() {
+    use tskit::{IndividualId, NodeFlags, NodeId, PopulationId, SubsetIdMaps, SubsetOptions};
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let p0 = tables.add_population().unwrap();
+    let _p1 = tables.add_population().unwrap();
+    let p2 = tables.add_population().unwrap();
+    let i0 = tables.add_individual(0, [0.0], None).unwrap();
+    let i1 = tables.add_individual(0, [1.0], [i0]).unwrap();
+    let i2 = tables.add_individual(0, [2.0], None).unwrap();
+    let i3 = tables.add_individual(0, [3.0], None).unwrap();
+    let n0 = tables.add_node(0, 2.0, p2, i0).unwrap();
+    let n1 = tables.add_node(0, 1.0, p0, -1).unwrap();
+    let n2 = tables
+        .add_node(NodeFlags::new_sample(), 0.0, p2, i1)
+        .unwrap();
+    let n3 = tables
+        .add_node(NodeFlags::new_sample(), 0.0, p0, i3)
+        .unwrap();
+    let n4 = tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, i1)
+        .unwrap();
+    tables.add_edge(0., 100., n1, n2).unwrap();
+    tables.add_edge(0., 100., n1, n3).unwrap();
+    tables.add_edge(0., 100., n0, n1).unwrap();
+    tables.add_edge(0., 100., n0, n4).unwrap();
+    for (position, node) in [(10., n2), (20., n4)] {
+        let site = tables.add_site(position, Some(b"A")).unwrap();
+        tables
+            .add_mutation(site, node, -1, 0.0, Some(b"T"))
+            .unwrap();
+    }
+
+    // The maps agree with the rows of the output tables
+    let check = |output: &tskit::TableCollection, maps: &SubsetIdMaps| {
+        assert_eq!(maps.nodes.len(), 5);
+        assert_eq!(maps.individuals.len(), 4);
+        assert_eq!(maps.populations.len(), 3);
+        let kept = maps.nodes.iter().filter(|n| !n.is_null()).count();
+        assert_eq!(output.nodes().num_rows(), kept as u64);
+        for (old, &new) in maps.nodes.iter().enumerate() {
+            if new.is_null() {
+                continue;
+            }
+            let old = NodeId::from(old as i32);
+            assert_eq!(output.nodes().time(new), tables.nodes().time(old));
+            let population = tables.nodes().population(old).unwrap();
+            assert_eq!(
+                output.nodes().population(new).unwrap(),
+                population
+                    .to_usize()
+                    .map_or(PopulationId::NULL, |p| maps.populations[p])
+            );
+            let individual = tables.nodes().individual(old).unwrap();
+            assert_eq!(
+                output.nodes().individual(new).unwrap(),
+                individual
+                    .to_usize()
+                    .map_or(IndividualId::NULL, |i| maps.individuals[i])
+            );
+        }
+        for (old, &new) in maps.individuals.iter().enumerate() {
+            if !new.is_null() {
+                assert_eq!(
+                    output.individuals().location(new),
+                    tables.individuals().location(old as i32)
+                );
+            }
+        }
+    };
+
+    let mut output = tables.deepcopy().unwrap();
+    let maps = output
+        .subset(&[n3, n2, n1], SubsetOptions::default())
+        .unwrap();
+    check(&output, &maps);
+    assert_eq!(
+        maps.nodes,
+        [NodeId::NULL, 2.into(), 1.into(), 0.into(), NodeId::NULL]
+    );
+    assert_eq!(
+        maps.individuals,
+        [IndividualId::NULL, 0.into(), IndividualId::NULL, 1.into()]
+    );
+    assert_eq!(maps.populations, [0.into(), PopulationId::NULL, 1.into()]);
+    assert_eq!(output.edges().num_rows(), 2);
+    assert_eq!(output.mutations().num_rows(), 1);
+    assert_eq!(output.sites().position(0), Some(10.0.into()));
+
+    let mut output = tables.deepcopy().unwrap();
+    let maps = output
+        .subset_keep_node_order(&[n3, n2, n1], SubsetOptions::default())
+        .unwrap();
+    check(&output, &maps);
+    assert_eq!(
+        maps.nodes,
+        [NodeId::NULL, 0.into(), 1.into(), 2.into(), NodeId::NULL]
+    );
+
+    let mut output = tables.deepcopy().unwrap();
+    let maps = output
+        .subset(&[n3, n2], SubsetOptions::default().keep_unreferenced())
+        .unwrap();
+    check(&output, &maps);
+    assert_eq!(maps.individuals, [0, 1, 2, 3].map(IndividualId::from));
+    assert_eq!(maps.populations, [0, 2, 1].map(PopulationId::from));
+
+    let mut output = tables.deepcopy().unwrap();
+    let maps = output
+        .subset(&[n4], SubsetOptions::default().no_change_populations())
+        .unwrap();
+    check(&output, &maps);
+    assert_eq!(maps.populations, [0, 1, 2].map(PopulationId::from));
+    assert_eq!(output.populations().num_rows(), 3);
+
+    let mut output = tables.deepcopy().unwrap();
+    assert!(matches!(
+        output.subset(&[n1, n1], SubsetOptions::default()),
+        Err(tskit::TskitError::ValueError { .. })
+    ));
+    assert!(matches!(
+        output.subset(&[n1, 5.into()], SubsetOptions::default()),
+        Err(tskit::TskitError::IndexError)
+    ));
+    assert!(output.equals(&tables, tskit::TableEqualityOptions::default()));
+
+    // Canonicalising keeps nodes
+    for options in [
+        SubsetOptions::default(),
+        SubsetOptions::default().keep_unreferenced(),
+    ] {
+        let mut output = tables.deepcopy().unwrap();
+        let maps = output.canonicalise(options).unwrap();
+        check(&output, &maps);
+        assert_eq!(maps.nodes, [n0, n1, n2, n3, n4]);
+        let num_individuals = maps.individuals.iter().filter(|i| !i.is_null()).count();
+        assert_eq!(output.individuals().num_rows(), num_individuals as u64);
+        assert_eq!(
+            maps.individuals[i2.as_usize()].is_null(),
+            !options.contains(SubsetOptions::KEEP_UNREFERENCED)
+        );
+        output
+            .check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING)
+            .unwrap();
+    }
+    let mut output = tables.deepcopy().unwrap();
+    assert!(output
+        .canonicalise(SubsetOptions::default().no_change_populations())
+        .is_err());
+}
+
+#[test]
+fn test_repair_times() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    // A chain of nodes at the same time, as after rounding
+    let root = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let middle = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let bottom = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let sample = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 100., root, middle).unwrap();
+    tables.add_edge(0., 100., middle, bottom).unwrap();
+    tables.add_edge(0., 100., bottom, sample).unwrap();
+    let site = tables.add_site(10., None).unwrap();
+    // The parent mutation is younger than its child
+    let parent = tables.add_mutation(site, bottom, -1, 2.0, None).unwrap();
+    tables
+        .add_mutation(site, sample, parent, 2.0 + 1e-12, None)
+        .unwrap();
+    let unknown = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    let site = tables.add_site(20., None).unwrap();
+    tables
+        .add_mutation(site, sample, -1, unknown, None)
+        .unwrap();
+
+    let before = tables.deepcopy().unwrap();
+    assert!(tables.repair_times(1e-15).is_err());
+    assert!(tables.repair_times(-1.0).is_err());
+    assert!(tables.equals(&before, tskit::TableEqualityOptions::default()));
+
+    let adjustments = tables.repair_times(1e-9).unwrap();
+    assert_eq!(adjustments.len(), 4);
+    assert!(adjustments
+        .iter()
+        .all(|a| a.change() > 0.0 && a.change() < 1e-9));
+    // Each node is repaired before the mutations on it
+    let repaired = adjustments
+        .iter()
+        .map(|a| match a {
+            tskit::TimeAdjustment::Node { id, .. } => (i32::from(*id), "node"),
+            tskit::TimeAdjustment::Mutation { id, .. } => (i32::from(*id), "mutation"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        repaired,
+        [(2, "node"), (0, "mutation"), (1, "node"), (0, "node")]
+    );
+    let times = tables.nodes().time_slice();
+    assert!(times[0] > times[1] && times[1] > times[2] && times[2] > 2.0 + 1e-12);
+    let node_time = tables.nodes().time(bottom).unwrap();
+    let times = tables.mutations().time_slice();
+    // The parent mutation is as old as its node
+    assert_eq!(times[0], node_time);
+    assert!(times[0] > times[1]);
+    assert!(f64::from(times[2]).is_nan());
+
+    // Nothing to repair
+    assert!(tables.repair_times(0.0).unwrap().is_empty());
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+    tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let a = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let b = tables.add_node(0, 1.0, -1, -1).unwrap();
+    tables.add_edge(0., 100., a, b).unwrap();
+    tables.add_edge(0., 100., b, a).unwrap();
+    assert!(tables.repair_times(1.0).is_err());
+}
+
+#[test]
+fn test_union() {
+    use tskit::{NodeId, TableUnionOptions};
+
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let pop = tables.add_population().unwrap();
+    let parent = tables.add_node(0, 1.0, pop, -1).unwrap();
+    let c0 = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, pop, -1)
+        .unwrap();
+    tables.add_edge(0., 100., parent, c0).unwrap();
+
+    // The new node is in a new population
+    let mut other = tables.deepcopy().unwrap();
+    let new_pop = other.add_population().unwrap();
+    let c1 = other
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, new_pop, -1)
+        .unwrap();
+    other.add_edge(0., 100., parent, c1).unwrap();
+    let site = other.add_site(50., Some(b"A")).unwrap();
+    other.add_mutation(site, c1, -1, 0.5, Some(b"T")).unwrap();
+    let mapping = [parent, c0, NodeId::NULL];
+
+    let mut merged = tables.deepcopy().unwrap();
+    merged
+        .union(&other, &mapping, TableUnionOptions::default())
+        .unwrap();
+    assert_eq!(merged.nodes().num_rows(), 3);
+    assert_eq!(merged.populations().num_rows(), 2);
+    assert_eq!(merged.mutations().node(0), Some(NodeId::from(2)));
+    let treeseq = merged
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 1);
+    assert_eq!(treeseq.num_samples(), 2);
+
+    // The new node keeps its population id, which does not exist in tables
+    let mut merged = tables.deepcopy().unwrap();
+    assert!(merged
+        .union(&other, &mapping, TableUnionOptions::default().no_add_pop())
+        .is_err());
+    let mut merged = tables.deepcopy().unwrap();
+    let mut same_pop = tables.deepcopy().unwrap();
+    same_pop
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, pop, -1)
+        .unwrap();
+    merged
+        .union(
+            &same_pop,
+            &mapping,
+            TableUnionOptions::default().no_add_pop(),
+        )
+        .unwrap();
+    assert_eq!(merged.populations().num_rows(), 1);
+    assert_eq!(merged.nodes().population(2), Some(pop));
+
+    // The mapping must have one entry per node of other
+    assert!(matches!(
+        tables.union(&other, &mapping[..2], TableUnionOptions::default()),
+        Err(tskit::TskitError::ValueError { .. })
+    ));
+
+    // Node c0 of tables is not the same as c1 of other
+    let mapping = [parent, c0, c0];
+    let mut merged = tables.deepcopy().unwrap();
+    assert!(merged
+        .union(&other, &mapping, TableUnionOptions::default())
+        .is_err());
+    assert!(merged
+        .union(&other, &mapping, TableUnionOptions::NO_CHECK_SHARED)
+        .is_ok());
+
+    // Migrations are not supported
+    let mut merged = tables.deepcopy().unwrap();
+    merged
+        .add_migration((0., 100.), c0, (pop, pop), 0.5)
+        .unwrap();
+    assert!(merged
+        .union(
+            &other,
+            &[parent, c0, NodeId::NULL],
+            TableUnionOptions::default()
+        )
+        .is_err());
+}
+
+#[test]
+fn test_ragged_column_slices() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    assert!(tables.nodes().metadata_slice().is_empty());
+    assert_eq!(tables.nodes().metadata_offset_slice(), &[0]);
+
+    let i0 = tables.add_individual(0, [1.0, 2.0], None).unwrap();
+    let i1 = tables.add_individual(0, None, [i0]).unwrap();
+    tables.add_individual(0, [3.0], [i0, i1]).unwrap();
+    let site = tables.add_site(10., Some(b"AT")).unwrap();
+    tables.add_site(20., None).unwrap();
+    tables.add_site(30., Some(b"G")).unwrap();
+    tables.add_mutation(site, 0, -1, 0.0, Some(b"C")).unwrap();
+    tables.add_mutation(site, 0, -1, 0.0, Some(b"GGG")).unwrap();
+
+    let individuals = tables.individuals();
+    assert_eq!(
+        individuals.location_slice(),
+        [1.0, 2.0, 3.0].map(tskit::Location::from)
+    );
+    assert_eq!(individuals.location_offset_slice(), &[0, 2, 2, 3]);
+    assert_eq!(individuals.parents_slice(), [i0, i0, i1]);
+    assert_eq!(individuals.parents_offset_slice(), &[0, 0, 1, 3]);
+    for row in 0..3 {
+        let o = individuals.parents_offset_slice();
+        assert_eq!(
+            individuals.parents(row).unwrap_or(&[]),
+            &individuals.parents_slice()[o[row as usize] as usize..o[row as usize + 1] as usize]
+        );
+    }
+
+    let sites = tables.sites();
+    assert_eq!(sites.ancestral_state_slice(), b"ATG");
+    assert_eq!(sites.ancestral_state_offset_slice(), &[0, 2, 2, 3]);
+    let mutations = tables.mutations();
+    assert_eq!(mutations.derived_state_slice(), b"CGGG");
+    assert_eq!(mutations.derived_state_offset_slice(), &[0, 1, 4]);
+    assert!(mutations.metadata_slice().is_empty());
+    assert_eq!(mutations.metadata_offset_slice(), &[0, 0, 0]);
+}
+
+#[test]
+fn test_dump_text() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let child = tables
+        .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 100., parent, child).unwrap();
+    let site = tables.add_site(50., Some(b"A")).unwrap();
+    tables
+        .add_mutation(site, child, -1, 0.5, Some(b"T"))
+        .unwrap();
+    tables.build_index().unwrap();
+
+    let mut sites = vec![];
+    tables.sites().dump_text(&mut sites).unwrap();
+    let sites = String::from_utf8(sites).unwrap();
+    assert_eq!(
+        sites
+            .lines()
+            .skip_while(|l| !l.starts_with("id"))
+            .collect::<Vec<_>>(),
+        [
+            "id\tposition\tancestral_state\tmetadata",
+            "0\t50.000000\tA\t"
+        ]
+    );
+
+    let mut text = vec![];
+    tables.dump_text(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains(&sites));
+    let mut names = vec!["individuals", "nodes", "edges", "migrations"];
+    names.extend(["sites", "mutations", "populations"]);
+    #[cfg(feature = "provenance")]
+    names.push("provenances");
+    let opened = text
+        .lines()
+        .filter(|l| l.ends_with('#') && !l.contains("metadata_schema"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        opened,
+        names.iter().map(|n| format!("#{}#", n)).collect::<Vec<_>>()
+    );
+    for name in &names {
+        assert!(text.contains(&format!("#end#{}\n", name)));
+    }
+
+    let treeseq = tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    let mut from_treeseq = vec![];
+    treeseq.dump_text(&mut from_treeseq).unwrap();
+    assert_eq!(String::from_utf8(from_treeseq).unwrap(), text);
+}
+
+#[test]
+fn test_metadata_schemas() {
+    struct Note(Vec<u8>);
+    impl tskit::metadata::MetadataRoundtrip for Note {
+        fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+            Ok(self.0.clone())
+        }
+        fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+            Ok(Self(md.to_vec()))
+        }
+    }
+    impl tskit::metadata::TableCollectionMetadata for Note {}
+
+    let schema = tskit::metadata::MetadataSchema::permissive_json();
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    assert!(tables.metadata_schema().is_none());
+    assert!(tables.raw_metadata().is_none());
+    tables.set_metadata_schema(&schema).unwrap();
+    tables.set_metadata(&Note(b"{\"a\":1}".to_vec())).unwrap();
+    tables
+        .set_individuals_metadata_schema("individuals")
+        .unwrap();
+    tables.set_nodes_metadata_schema("nodes").unwrap();
+    tables.set_edges_metadata_schema("edges").unwrap();
+    tables.set_migrations_metadata_schema("migrations").unwrap();
+    tables.set_sites_metadata_schema("sites").unwrap();
+    tables.set_mutations_metadata_schema("mutations").unwrap();
+    tables
+        .set_populations_metadata_schema("populations")
+        .unwrap();
+
+    let check = |tables: &tskit::TableCollection| {
+        assert_eq!(tables.metadata_schema(), Some(schema.as_str()));
+        assert_eq!(tables.metadata::<Note>().unwrap().unwrap().0, b"{\"a\":1}");
+        assert_eq!(tables.individuals().metadata_schema(), Some("individuals"));
+        assert_eq!(tables.nodes().metadata_schema(), Some("nodes"));
+        assert_eq!(tables.edges().metadata_schema(), Some("edges"));
+        assert_eq!(tables.migrations().metadata_schema(), Some("migrations"));
+        assert_eq!(tables.sites().metadata_schema(), Some("sites"));
+        assert_eq!(tables.mutations().metadata_schema(), Some("mutations"));
+        assert_eq!(tables.populations().metadata_schema(), Some("populations"));
+    };
+    check(&tables);
+    check(&tables.deepcopy().unwrap());
+
+    let filename = "test_metadata_schemas.trees";
+    tables.build_index().unwrap();
+    tables
+        .dump(filename, tskit::TableOutputOptions::default())
+        .unwrap();
+    let loaded = tskit::TableCollection::new_from_file(filename).unwrap();
+    std::fs::remove_file(filename).unwrap();
+    check(&loaded);
+    let treeseq = loaded
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    assert_eq!(treeseq.nodes().metadata_schema(), Some("nodes"));
+
+    // An empty schema removes the schema
+    tables.nodes_mut().set_metadata_schema("").unwrap();
+    assert!(tables.nodes().metadata_schema().is_none());
+    tables.set_metadata_schema("").unwrap();
+    assert!(tables.metadata_schema().is_none());
+
+    let mut sites = tskit::SiteTable::new().unwrap();
+    sites.set_metadata_schema(&schema).unwrap();
+    assert_eq!(sites.metadata_schema(), Some(r#"{"codec":"json"}"#));
+}
@@ -347,6 +347,152 @@ fn test_iterate_samples_two_trees() {
     }
 }
 
+#[test]
+fn test_tree_num_samples_and_spans() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let spans = treeseq.tree_spans();
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans.iter().map(|&s| f64::from(s)).sum::<f64>(), 1000.);
+
+    let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    let mut i = 0;
+    while let Some(tree) = tree_iter.next() {
+        assert_eq!(tree.num_samples().unwrap(), treeseq.num_samples());
+        assert_eq!(tree.span(), spans[i]);
+        assert_eq!(tree.span_as_fraction(), f64::from(spans[i]) / 1000.);
+        i += 1;
+    }
+    assert_eq!(i, 2);
+
+    let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
+    while let Some(tree) = tree_iter.next() {
+        assert!(tree.num_samples().is_err());
+    }
+}
+
+#[test]
+fn test_breakpoint_density() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let lefts = treeseq
+        .tree_spans()
+        .iter()
+        .scan(0.0, |left, span| {
+            let l = *left;
+            *left += f64::from(*span);
+            Some(l)
+        })
+        .collect::<Vec<f64>>();
+    assert_eq!(lefts.len(), 2);
+    let transition = lefts[1];
+
+    let counts = treeseq.breakpoint_density([0., 1000.]).unwrap();
+    assert_eq!(counts, [treeseq.num_trees().as_usize() - 1]);
+
+    // Transitions belong to the window starting at them
+    let counts = treeseq.breakpoint_density([0., transition, 1000.]).unwrap();
+    assert_eq!(counts, [0, 1]);
+    let counts = treeseq
+        .breakpoint_density([0., transition - 1., transition + 1., 1000.])
+        .unwrap();
+    assert_eq!(counts, [0, 1, 0]);
+
+    for windows in [
+        vec![],
+        vec![0.],
+        vec![1., 1000.],
+        vec![0., 999.],
+        vec![0., 500., 500., 1000.],
+        vec![0., 1001.],
+    ] {
+        assert!(treeseq.breakpoint_density(windows).is_err());
+    }
+
+    // A single tree has no transitions
+    let mut tables = TableCollection::new(100.).unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert_eq!(treeseq.breakpoint_density([0., 50., 100.]).unwrap(), [0, 0]);
+}
+
+#[test]
+fn test_transition_sizes() {
+    let mut tables = TableCollection::new(1000.).unwrap();
+    tables
+        .add_node(0, 2.0, PopulationId::NULL, IndividualId::NULL)
+        .unwrap();
+    tables
+        .add_node(0, 1.0, PopulationId::NULL, IndividualId::NULL)
+        .unwrap();
+    for _ in 0..4 {
+        tables
+            .add_node(
+                NodeFlags::new_sample(),
+                0.0,
+                PopulationId::NULL,
+                IndividualId::NULL,
+            )
+            .unwrap();
+    }
+    tables.add_edge(0., 500., 0, 2).unwrap();
+    tables.add_edge(500., 1000., 1, 2).unwrap();
+    // Splitting an edge does not change the parent of its child
+    tables.add_edge(0., 250., 0, 3).unwrap();
+    tables.add_edge(250., 1000., 0, 3).unwrap();
+    tables.add_edge(0., 1000., 1, 4).unwrap();
+    tables.add_edge(0., 1000., 1, 5).unwrap();
+    tables.add_edge(500., 1000., 0, 1).unwrap();
+    tables.full_sort(TableSortOptions::default()).unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let transitions = treeseq.transition_sizes().unwrap();
+    assert_eq!(transitions.len(), 3);
+    assert_eq!(
+        transitions
+            .iter()
+            .map(|t| (t.left, t.right))
+            .collect::<Vec<_>>(),
+        [(0., 250.), (250., 500.), (500., 1000.)].map(|(l, r)| (l.into(), r.into()))
+    );
+    assert_eq!(
+        transitions
+            .iter()
+            .map(|t| (t.num_edge_removals, t.num_edge_insertions))
+            .collect::<Vec<_>>(),
+        [(0, 4), (1, 1), (1, 2)]
+    );
+    assert_eq!(
+        transitions
+            .iter()
+            .map(|t| t.num_parent_changes)
+            .collect::<Vec<_>>(),
+        [4, 0, 2]
+    );
+
+    // Compare with the trees
+    let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    let mut previous = vec![NodeId::NULL; treeseq.nodes().num_rows().as_usize()];
+    let mut i = 0;
+    while let Some(tree) = tree_iter.next() {
+        assert_eq!(tree.num_edges(), transitions[i].num_edges as u64);
+        let parents = &tree.parent_array()[..previous.len()];
+        let changes = parents
+            .iter()
+            .zip(previous.iter())
+            .filter(|(a, b)| a != b)
+            .count();
+        assert_eq!(changes, transitions[i].num_parent_changes);
+        previous = parents.to_vec();
+        i += 1;
+    }
+    assert_eq!(i, transitions.len());
+
+    let mut diffs = treeseq.edge_differences_iter().unwrap();
+    let diff = diffs.next().unwrap();
+    assert_eq!(diff.num_edge_insertions(), 4);
+    assert_eq!(diff.transition(0), transitions[0]);
+}
+
 #[test]
 fn test_kc_distance_naive_test() {
     let ts1 = treeseq_from_small_table_collection();
@@ -357,6 +503,80 @@ fn test_kc_distance_naive_test() {
     assert!((kc - 0.).abs() < f64::EPSILON);
 }
 
+#[test]
+fn test_kc_distance_profile() {
+    // Nodes 2 to 5 are samples. Node 6 is older than node 1.
+    fn make_tables(edges: &[(f64, f64, i32, i32)]) -> TreeSequence {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 2.0, -1, -1).unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        for _ in 0..4 {
+            tables
+                .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+                .unwrap();
+        }
+        tables.add_node(0, 1.5, -1, -1).unwrap();
+        for &(left, right, parent, child) in edges {
+            tables.add_edge(left, right, parent, child).unwrap();
+        }
+        tables.full_sort(TableSortOptions::default()).unwrap();
+        tables
+            .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+            .unwrap()
+    }
+
+    let ts1 = make_tables(&[
+        (0., 1000., 0, 1),
+        (0., 1000., 0, 6),
+        (0., 1000., 6, 2),
+        (0., 1000., 6, 3),
+        (0., 1000., 1, 4),
+        (0., 1000., 1, 5),
+    ]);
+    // The same topology until position 300,
+    // after which nodes 1 and 3 swap parents
+    let ts2 = make_tables(&[
+        (0., 300., 0, 1),
+        (300., 1000., 6, 1),
+        (0., 1000., 0, 6),
+        (0., 1000., 6, 2),
+        (0., 300., 6, 3),
+        (300., 1000., 0, 3),
+        (0., 1000., 1, 4),
+        (0., 1000., 1, 5),
+    ]);
+
+    let profile = ts1.kc_distance_profile(&ts1, 0.0).unwrap();
+    assert_eq!(profile, vec![(0.0.into(), 1000.0.into(), 0.0)]);
+
+    for (a, b) in [(&ts1, &ts2), (&ts2, &ts1)] {
+        let profile = a.kc_distance_profile(b, 0.0).unwrap();
+        assert_eq!(profile.len(), 2);
+        assert_eq!((profile[0].0, profile[0].1), (0.0.into(), 300.0.into()));
+        assert_eq!(profile[0].2, 0.0);
+        assert_eq!((profile[1].0, profile[1].1), (300.0.into(), 1000.0.into()));
+        assert!(profile[1].2 > 0.0);
+
+        // The average over the genome is the tree sequence distance
+        let mean = profile
+            .iter()
+            .map(|(left, right, d)| f64::from(*right - *left) * d)
+            .sum::<f64>()
+            / 1000.;
+        let kc = a.kc_distance(b, 0.0).unwrap();
+        assert!((mean - kc).abs() < 1e-9);
+    }
+
+    // Trees with multiple roots
+    let ts3 = treeseq_from_small_table_collection_two_trees();
+    assert!(ts3.kc_distance_profile(&ts3, 0.0).is_err());
+
+    let mut tables = TableCollection::new(500.).unwrap();
+    tables.build_index().unwrap();
+    let short = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert!(ts1.kc_distance_profile(&short, 0.0).is_err());
+}
+
 #[test]
 fn test_dump_tables() {
     let tables = make_small_table_collection_two_trees();
@@ -442,6 +662,64 @@ fn test_tree_iteration_at_invalid_index() {
         .is_err());
 }
 
+#[test]
+fn test_tree_random_access() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut tree = treeseq
+        .tree_at_position(502., TreeFlags::default())
+        .unwrap();
+    assert_eq!(tree.interval(), (500.0.into(), 1000.0.into()));
+    // The returned tree is current, so advancing moves past it.
+    assert_eq!(
+        tree.get().map(|t| t.interval()),
+        Some((500.0.into(), 1000.0.into()))
+    );
+    assert!(tree.next().is_none());
+    let mut tree = treeseq.tree_at_index(0, TreeFlags::default()).unwrap();
+    assert_eq!(tree.interval(), (0.0.into(), 500.0.into()));
+    assert_eq!(
+        tree.get().map(|t| t.interval()),
+        Some((0.0.into(), 500.0.into()))
+    );
+    assert_eq!(
+        tree.next().map(|t| t.interval()),
+        Some((500.0.into(), 1000.0.into()))
+    );
+    assert!(tree.next().is_none());
+    assert!(treeseq
+        .tree_at_position(1000., TreeFlags::default())
+        .is_err());
+    assert!(treeseq.tree_at_index(-1, TreeFlags::default()).is_err());
+
+    let mut tree = treeseq.first_tree(TreeFlags::default()).unwrap();
+    tree.seek(999.).unwrap();
+    assert_eq!(tree.interval(), (500.0.into(), 1000.0.into()));
+    assert!(tree.seek(-1.).is_err());
+    assert_eq!(tree.interval(), (500.0.into(), 1000.0.into()));
+    tree.seek(0.).unwrap();
+    assert_eq!(tree.interval(), (0.0.into(), 500.0.into()));
+    assert_eq!(
+        tree.next().map(|t| t.interval()),
+        Some((500.0.into(), 1000.0.into()))
+    );
+    assert!(tree.next().is_none());
+}
+
+#[test]
+fn test_first_and_last_tree() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut first = treeseq.first_tree(TreeFlags::default()).unwrap();
+    assert_eq!(first.interval().0, 0.);
+    assert!(first.interval().1 < 1000.);
+    let last = treeseq.last_tree(TreeFlags::default()).unwrap();
+    assert_eq!(last.interval().1, 1000.);
+    assert_eq!(first.interval().1, last.interval().0);
+    // The first tree is positioned, so advancing moves to the last tree.
+    let next = first.next().unwrap();
+    assert_eq!(next.interval().0, last.interval().0);
+    assert!(first.next().is_none());
+}
+
 #[test]
 fn build_arc() {
     let t = treeseq_from_small_table_collection_two_trees();
@@ -471,6 +749,194 @@ fn test_simplify_tables() {
     }
 }
 
+#[test]
+fn test_simplify_keeping() {
+    let mut tables = make_small_table_collection_two_trees();
+    let samples = tables.samples_as_vector();
+    let original = tables.deepcopy().unwrap();
+
+    // Keeping only the samples is ordinary simplification
+    let mut expected = tables.deepcopy().unwrap();
+    let expected_idmap = expected
+        .simplify(&samples, SimplificationOptions::default(), true)
+        .unwrap()
+        .unwrap()
+        .to_vec();
+    let idmap = tables
+        .simplify_keeping(&samples, SimplificationOptions::default(), |row| {
+            row.flags.is_sample()
+        })
+        .unwrap();
+    assert_eq!(idmap, expected_idmap);
+    assert!(tables.equals(&expected, TableEqualityOptions::default()));
+
+    // Keep node 1 from two samples, so that it would otherwise be
+    // removed as a unary node
+    let mut tables = original.deepcopy().unwrap();
+    let mut calls = vec![];
+    let idmap = tables
+        .simplify_keeping(
+            &[NodeId::from(4), NodeId::from(5)],
+            SimplificationOptions::default(),
+            |row| {
+                calls.push(row.id);
+                row.time == 1.0
+            },
+        )
+        .unwrap()
+        .to_vec();
+    // The predicate sees the nodes that are not in the samples
+    assert_eq!(calls, [0, 1, 2, 3].map(NodeId::from));
+    let kept = idmap[1];
+    assert!(!kept.is_null());
+    assert!(idmap[0].is_null());
+    assert!(tables.nodes().flags(kept).unwrap().is_sample());
+    assert_eq!(tables.nodes().num_rows(), 3);
+    assert_eq!(tables.edges().num_rows(), 2);
+    assert!(tables
+        .edges()
+        .parent_slice()
+        .iter()
+        .all(|&parent| parent == kept));
+}
+
+#[test]
+fn test_simplify_no_filter_nodes() {
+    let original = make_small_table_collection_two_trees();
+    let samples = [NodeId::from(4), NodeId::from(5)];
+    let num_nodes = original.nodes().num_rows();
+
+    // Node ids are unchanged, and only the nodes passed
+    // to simplification are samples
+    let mut tables = original.deepcopy().unwrap();
+    let idmap = tables
+        .simplify(
+            &samples,
+            SimplificationOptions::default().no_filter_nodes(),
+            true,
+        )
+        .unwrap()
+        .unwrap()
+        .to_vec();
+    assert_eq!(tables.nodes().num_rows(), num_nodes);
+    for (i, &node) in idmap.iter().enumerate() {
+        assert_eq!(node, NodeId::from(i as i32));
+        assert_eq!(
+            tables.nodes().flags(node).unwrap().is_sample(),
+            samples.contains(&node)
+        );
+    }
+    assert_eq!(tables.nodes().time_slice(), original.nodes().time_slice());
+    assert!(tables
+        .edges()
+        .parent_slice()
+        .iter()
+        .chain(tables.edges().child_slice())
+        .all(|&node| node != NodeId::from(0)));
+
+    // Sample flags may be kept as well
+    let mut tables = original.deepcopy().unwrap();
+    tables
+        .simplify(
+            &samples,
+            SimplificationOptions::default()
+                .no_filter_nodes()
+                .no_update_sample_flags(),
+            false,
+        )
+        .unwrap();
+    assert_eq!(tables.nodes().flags_slice(), original.nodes().flags_slice());
+}
+
+#[test]
+fn test_simplify_keep_input_roots() {
+    // A root above the MRCA of the samples
+    let mut tables = TableCollection::new(100.).unwrap();
+    let pop = tables.add_population().unwrap();
+    let root = tables.add_node(0, 3.0, pop, -1).unwrap();
+    let mrca = tables.add_node(0, 2.0, pop, -1).unwrap();
+    let unary = tables.add_node(0, 2.5, pop, -1).unwrap();
+    let c0 = tables
+        .add_node(NodeFlags::new_sample(), 0.0, pop, -1)
+        .unwrap();
+    let c1 = tables
+        .add_node(NodeFlags::new_sample(), 0.0, pop, -1)
+        .unwrap();
+    tables.add_edge(0., 100., root, unary).unwrap();
+    tables.add_edge(0., 100., unary, mrca).unwrap();
+    tables.add_edge(0., 100., mrca, c0).unwrap();
+    tables.add_edge(0., 100., mrca, c1).unwrap();
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+    let samples = [c0, c1];
+    let options = SimplificationOptions::default().keep_input_roots();
+
+    let check = |idmap: &[NodeId], edges: &tskit::EdgeTable| {
+        assert!(idmap[unary.as_usize()].is_null());
+        let root = idmap[root.as_usize()];
+        let mrca = idmap[mrca.as_usize()];
+        assert!(!root.is_null());
+        assert_eq!(edges.num_rows(), 3);
+        assert!((0..3).any(|e| edges.parent(e) == Some(root) && edges.child(e) == Some(mrca)));
+    };
+
+    let mut simplified = tables.deepcopy().unwrap();
+    let idmap = simplified
+        .simplify(&samples, options, true)
+        .unwrap()
+        .unwrap()
+        .to_vec();
+    check(&idmap, simplified.edges());
+
+    let mut simplified = tables.deepcopy().unwrap();
+    let idmap = simplified
+        .simplify(&samples, SimplificationOptions::default(), true)
+        .unwrap()
+        .unwrap()
+        .to_vec();
+    assert!(idmap[root.as_usize()].is_null());
+    assert_eq!(simplified.edges().num_rows(), 2);
+
+    // Unary nodes on the path to the root may be kept as well
+    let mut simplified = tables.deepcopy().unwrap();
+    let idmap = simplified
+        .simplify(&samples, options.keep_unary(), true)
+        .unwrap()
+        .unwrap()
+        .to_vec();
+    assert!(!idmap[unary.as_usize()].is_null());
+    assert_eq!(simplified.edges().num_rows(), 4);
+
+    // Tree sequences, including those with migrations,
+    // which are simplified as tables
+    let treeseq = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(TreeSequenceFlags::default())
+        .unwrap();
+    let (simplified, idmap) = treeseq.simplify(&samples, options, true).unwrap();
+    check(&idmap.unwrap(), simplified.edges());
+
+    let other = tables.add_population().unwrap();
+    tables
+        .add_migration((0., 100.), root, (pop, other), 3.0)
+        .unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    let (simplified, idmap) = treeseq.simplify(&samples, options, true).unwrap();
+    let idmap = idmap.unwrap();
+    check(&idmap, simplified.edges());
+    assert_eq!(simplified.migrations().num_rows(), 1);
+    assert_eq!(
+        simplified.migrations().node(0),
+        Some(idmap[root.as_usize()])
+    );
+    let mut tree_iter = simplified.tree_iterator(0).unwrap();
+    let tree = tree_iter.next().unwrap();
+    assert_eq!(tree.roots_to_vec(), vec![idmap[root.as_usize()]]);
+}
+
 #[test]
 fn test_simplify_treeseq() {
     let ts = treeseq_from_small_table_collection_two_trees();
@@ -484,3 +950,2297 @@ fn test_simplify_treeseq() {
         assert_ne!(idmap[usize::try_from(i).unwrap()], NodeId::NULL);
     }
 }
+
+#[test]
+fn test_arg_edges() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let edges = treeseq.arg_edges(false);
+    assert_eq!(
+        edges.len(),
+        usize::try_from(treeseq.edges().num_rows()).unwrap()
+    );
+    for (e, row) in edges.iter().zip(treeseq.edges().iter()) {
+        assert_eq!(e.child, row.child);
+        assert_eq!(e.parent, row.parent);
+        assert_eq!(e.left, row.left);
+        assert_eq!(e.right, row.right);
+        assert_eq!(e.child_time, treeseq.nodes().time(e.child).unwrap());
+        assert_eq!(e.parent_time, treeseq.nodes().time(e.parent).unwrap());
+    }
+
+    let squashed = treeseq.arg_edges(true);
+    assert!(squashed.len() <= edges.len());
+    let total_span =
+        |v: &[tskit::ArgEdge]| v.iter().map(|e| f64::from(e.right - e.left)).sum::<f64>();
+    assert_eq!(total_span(&edges), total_span(&squashed));
+    for w in squashed.windows(2) {
+        assert!((w[0].child, w[0].parent) < (w[1].child, w[1].parent) || w[0].right < w[1].left);
+    }
+}
+
+#[test]
+fn test_statistics_with_genome_mask() {
+    let mut tables = make_small_table_collection_two_trees();
+    for (i, pos) in [100., 400., 600., 900.].into_iter().enumerate() {
+        let site = tables.add_site(pos, Some(b"A")).unwrap();
+        tables
+            .add_mutation(site, (i % 2 + 2) as i32, -1, 0.5, Some(b"T"))
+            .unwrap();
+    }
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let samples = tskit::SampleSets::new().add_set(treeseq.sample_nodes().iter().copied());
+    let options = tskit::StatisticsOptions::default();
+    let site = tskit::StatsMode::Site;
+    let masked = |mask| tskit::Windows::genome().mask(mask);
+
+    let unmasked = treeseq
+        .segregating_sites(&samples, &tskit::Windows::genome(), site, options)
+        .unwrap();
+    assert_eq!(unmasked, vec![4.0]);
+
+    let mask = tskit::GenomeMask::new([(0., 150.), (850., 1000.)]).unwrap();
+    let windows = masked(mask);
+    let values = treeseq
+        .segregating_sites(&samples, &windows, site, options)
+        .unwrap();
+    assert_eq!(values, vec![2.0]);
+
+    let normalised = treeseq
+        .segregating_sites(&samples, &windows, site, options.span_normalise())
+        .unwrap();
+    assert!((normalised[0] - 2.0 / 700.).abs() < 1e-12);
+
+    // An empty mask is the same as no mask
+    let empty = masked(tskit::GenomeMask::default());
+    assert_eq!(
+        treeseq
+            .segregating_sites(&samples, &empty, site, options)
+            .unwrap(),
+        unmasked
+    );
+
+    // Nothing is accessible, so span-normalised values are undefined
+    let everything = masked(tskit::GenomeMask::new([(0., 1000.)]).unwrap());
+    assert!(matches!(
+        treeseq.segregating_sites(&samples, &everything, site, options.span_normalise()),
+        Err(tskit::TskitError::ValueError { .. })
+    ));
+    assert_eq!(
+        treeseq
+            .segregating_sites(&samples, &everything, site, options)
+            .unwrap(),
+        vec![0.0]
+    );
+
+    let too_long = masked(tskit::GenomeMask::new([(900., 1100.)]).unwrap());
+    assert!(treeseq
+        .segregating_sites(&samples, &too_long, site, options)
+        .is_err());
+}
+
+#[test]
+fn test_statistics_windows_and_modes() {
+    let mut tables = make_small_table_collection_two_trees();
+    for (i, pos) in [100., 400., 600., 900.].into_iter().enumerate() {
+        let site = tables.add_site(pos, Some(b"A")).unwrap();
+        tables
+            .add_mutation(site, (i % 2 + 2) as i32, -1, 0.5, Some(b"T"))
+            .unwrap();
+    }
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let samples = treeseq.sample_nodes();
+    let sample_sets = tskit::SampleSets::new()
+        .add_set(samples.iter().copied())
+        .add_set(samples[..2].iter().copied());
+    let options = tskit::StatisticsOptions::default();
+    let windows = tskit::Windows::breakpoints([0., 250., 500., 1000.]);
+
+    // One row per window, and each row is the value for that window
+    for mode in [tskit::StatsMode::Site, tskit::StatsMode::Branch] {
+        let values = treeseq
+            .diversity(&sample_sets, &windows, mode, options)
+            .unwrap();
+        assert_eq!(values.len(), 3 * sample_sets.num_sets());
+        let mut sum = vec![0.0; sample_sets.num_sets()];
+        for (row, (left, right)) in values.chunks_exact(sample_sets.num_sets()).zip([
+            (0., 250.),
+            (250., 500.),
+            (500., 1000.),
+        ]) {
+            let window = tskit::Windows::intervals([(left, right)]);
+            let expected = treeseq
+                .diversity(&sample_sets, &window, mode, options)
+                .unwrap();
+            assert_eq!(row, &expected[..]);
+            sum.iter_mut().zip(row).for_each(|(s, x)| *s += x);
+        }
+        let whole = treeseq
+            .diversity(&sample_sets, &tskit::Windows::genome(), mode, options)
+            .unwrap();
+        for (s, w) in sum.iter().zip(&whole) {
+            assert!((s - w).abs() < 1e-9);
+        }
+    }
+
+    // The mode replaces any mode set in the options
+    assert_eq!(
+        treeseq
+            .segregating_sites(
+                &sample_sets,
+                &windows,
+                tskit::StatsMode::Site,
+                options.branch()
+            )
+            .unwrap(),
+        treeseq
+            .segregating_sites(&sample_sets, &windows, tskit::StatsMode::Site, options)
+            .unwrap(),
+    );
+
+    // In node mode, each row has values for each node
+    let values = treeseq
+        .diversity(&sample_sets, &windows, tskit::StatsMode::Node, options)
+        .unwrap();
+    assert_eq!(
+        values.len(),
+        3 * usize::try_from(treeseq.nodes().num_rows()).unwrap() * sample_sets.num_sets()
+    );
+    assert!(treeseq
+        .tajimas_d(&sample_sets, &windows, tskit::StatsMode::Node)
+        .is_err());
+
+    // Derived statistics have one row per window
+    let d = treeseq
+        .tajimas_d(&sample_sets, &windows, tskit::StatsMode::Branch)
+        .unwrap();
+    assert_eq!(d.len(), 6);
+    let fst = treeseq
+        .fst(&sample_sets, &[(0, 1)], &windows, tskit::StatsMode::Branch)
+        .unwrap();
+    assert_eq!(fst.len(), 3);
+
+    // Invalid windows
+    for bad in [
+        tskit::Windows::intervals::<f64>([]),
+        tskit::Windows::intervals([(10., 10.)]),
+        tskit::Windows::intervals([(-1., 10.)]),
+        tskit::Windows::intervals([(900., 1100.)]),
+    ] {
+        assert!(treeseq
+            .diversity(&sample_sets, &bad, tskit::StatsMode::Site, options)
+            .is_err());
+    }
+}
+
+#[test]
+fn test_windowed_statistics() {
+    let mut tables = make_small_table_collection_two_trees();
+    for (i, pos) in [100., 400., 600., 900.].into_iter().enumerate() {
+        let site = tables.add_site(pos, Some(b"A")).unwrap();
+        tables
+            .add_mutation(site, (i % 2 + 2) as i32, -1, 0.5, Some(b"T"))
+            .unwrap();
+    }
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let samples = [treeseq.sample_nodes()]
+        .into_iter()
+        .collect::<tskit::SampleSets>();
+    let site = tskit::StatsMode::Site;
+    let options = tskit::StatisticsOptions::default();
+
+    // Unsorted and overlapping windows, in batches smaller than the input
+    let windows = [(150., 850.), (0., 1000.), (500., 600.), (550., 1000.)];
+    let values = treeseq
+        .windowed_segregating_sites(&samples, site, options, windows)
+        .batch_size(3)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(values.len(), windows.len());
+    for ((left, right, value), (l, r)) in values.iter().zip(windows) {
+        assert_eq!((*left, *right), (l.into(), r.into()));
+        let expected = treeseq
+            .segregating_sites(
+                &samples,
+                &tskit::Windows::intervals([(l, r)]),
+                site,
+                options,
+            )
+            .unwrap();
+        assert_eq!(value, &expected);
+    }
+
+    // Span normalisation uses the span of each window
+    let bed = "track name=test\nchr1\t150\t850\n\n# comment\nchr1\t0\t1000\tname\n";
+    let values = treeseq
+        .windowed_diversity(
+            &samples,
+            site,
+            options.span_normalise(),
+            tskit::read_bed_windows(std::io::Cursor::new(bed)),
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let whole = treeseq
+        .diversity(
+            &samples,
+            &tskit::Windows::genome(),
+            site,
+            options.span_normalise(),
+        )
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    assert!((values[1].2[0] - whole[0]).abs() < 1e-12);
+
+    // Values stored as f32 are rounded from the f64 values
+    let values32 = treeseq
+        .windowed_diversity(
+            &samples,
+            site,
+            options.span_normalise(),
+            tskit::read_bed_windows(std::io::Cursor::new(bed)),
+        )
+        .values_as::<f32>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(values32.len(), values.len());
+    for ((l32, r32, v32), (l, r, v)) in values32.iter().zip(&values) {
+        assert_eq!((l32, r32), (l, r));
+        assert_eq!(v32, &v.iter().map(|&x| x as f32).collect::<Vec<_>>());
+    }
+
+    // The iterator ends after the first invalid window
+    for bad in [(10., 10.), (-1., 10.), (900., 1100.)] {
+        let mut values = treeseq.windowed_segregating_sites(
+            &samples,
+            site,
+            options,
+            [(0., 100.), bad, (0., 200.)],
+        );
+        assert!(values.next().unwrap().is_ok());
+        assert!(values.next().unwrap().is_err());
+        assert!(values.next().is_none());
+    }
+
+    let mut values = treeseq.windowed_diversity(
+        &samples,
+        site,
+        options,
+        tskit::read_bed_windows("chr1\t0\tx\n".as_bytes()),
+    );
+    assert!(values.next().unwrap().is_err());
+    assert!(values.next().is_none());
+
+    // Masked windows match the masked whole-genome statistic
+    let mask = tskit::GenomeMask::new([(50., 150.), (550., 700.)]).unwrap();
+    let values = treeseq
+        .windowed_diversity(&samples, site, options.span_normalise(), windows)
+        .mask(mask.clone())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    for ((_, _, value), (l, r)) in values.iter().zip(windows) {
+        let expected = treeseq
+            .diversity(
+                &samples,
+                &tskit::Windows::intervals([(l, r)]).mask(mask.clone()),
+                site,
+                options.span_normalise(),
+            )
+            .unwrap();
+        assert_eq!(value, &expected);
+    }
+    let mask = tskit::GenomeMask::new([(900., 1100.)]).unwrap();
+    let mut values = treeseq
+        .windowed_diversity(&samples, site, options, windows)
+        .mask(mask);
+    assert!(matches!(
+        values.next(),
+        Some(Err(tskit::TskitError::RangeError(_)))
+    ));
+    assert!(values.next().is_none());
+}
+
+#[test]
+fn test_split_tree_sequence() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let breakpoint = f64::from(treeseq.tree_spans()[0]);
+    let chunks = treeseq
+        .split(&[(0., breakpoint), (breakpoint, 1000.)], true)
+        .unwrap();
+    assert_eq!(chunks.len(), 2);
+    let sequence_length =
+        |ts: &TreeSequence| ts.tree_spans().iter().map(|s| f64::from(*s)).sum::<f64>();
+    assert_eq!(sequence_length(&chunks[0]), breakpoint);
+    assert_eq!(sequence_length(&chunks[1]), 1000. - breakpoint);
+    for chunk in &chunks {
+        assert_eq!(chunk.num_trees(), 1);
+        assert_eq!(chunk.num_samples(), treeseq.num_samples());
+    }
+
+    let chunks = treeseq.split(&[(250., 750.)], false).unwrap();
+    assert_eq!(chunks[0].num_trees(), 4);
+
+    assert!(treeseq.split(&[(10., 10.)], false).is_err());
+    assert!(treeseq.split(&[(10., 1001.)], false).is_err());
+
+    // An interval without edges
+    let mut tables = TableCollection::new(100.).unwrap();
+    let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let child = tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 50., parent, child).unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let chunks = treeseq.split(&[(60., 100.)], true).unwrap();
+    assert_eq!(chunks[0].edges().num_rows(), 0);
+    assert_eq!(chunks[0].nodes().num_rows(), 1);
+}
+
+#[test]
+fn test_concat_tree_sequences() {
+    let mut tables = make_small_table_collection_two_trees();
+    for pos in [100., 900.] {
+        let site = tables.add_site(pos, Some(b"A")).unwrap();
+        tables.add_mutation(site, 2, -1, 0.5, Some(b"T")).unwrap();
+    }
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let breakpoint = f64::from(treeseq.tree_spans()[0]);
+    let chunks = treeseq
+        .split(&[(0., breakpoint), (breakpoint, 1000.)], true)
+        .unwrap();
+    let joined = TreeSequence::concat(&chunks).unwrap();
+    assert_eq!(joined.num_trees(), 2);
+    assert_eq!(joined.tree_spans(), treeseq.tree_spans());
+    assert_eq!(joined.sample_nodes(), chunks[0].sample_nodes());
+    let positions = joined
+        .sites()
+        .position_slice()
+        .iter()
+        .map(|p| f64::from(*p))
+        .collect::<Vec<_>>();
+    assert_eq!(positions, vec![100., 900.]);
+    for mutation in joined.mutations_iter() {
+        assert!(joined.nodes().flags(mutation.node).unwrap().is_sample());
+    }
+
+    let single = TreeSequence::concat(&chunks[..1]).unwrap();
+    assert_eq!(single.num_trees(), 1);
+    assert!(TreeSequence::concat(&[]).is_err());
+}
+
+#[test]
+fn test_reorder_samples() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut reversed_samples = treeseq.sample_nodes().to_vec();
+    reversed_samples.reverse();
+    let reversed = treeseq.reorder_samples(&reversed_samples).unwrap();
+    assert_eq!(reversed.num_trees(), treeseq.num_trees());
+    assert_eq!(reversed.edges().num_rows(), treeseq.edges().num_rows());
+    assert_eq!(reversed.sample_nodes(), &[0, 1, 2, 3]);
+
+    // New sample k is the old sample reversed_samples[k]
+    let parent_times = |ts: &TreeSequence, samples: &[NodeId]| {
+        let mut rv = vec![];
+        let mut trees = ts.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = trees.next() {
+            for s in samples {
+                let p = tree.parent(*s).unwrap();
+                rv.push(ts.nodes().time(p).unwrap());
+            }
+        }
+        rv
+    };
+    assert_eq!(
+        parent_times(&treeseq, &reversed_samples),
+        parent_times(&reversed, reversed.sample_nodes())
+    );
+    assert_ne!(
+        parent_times(&treeseq, treeseq.sample_nodes()),
+        parent_times(&reversed, reversed.sample_nodes())
+    );
+}
+
+#[test]
+fn test_variants_with_missing_data() {
+    use tskit::Genotype;
+    use tskit::VariantOptions;
+
+    let mut tables = make_small_table_collection_two_trees();
+    // An isolated sample
+    let isolated = tables
+        .add_node(
+            NodeFlags::new_sample(),
+            0.0,
+            PopulationId::NULL,
+            IndividualId::NULL,
+        )
+        .unwrap();
+    let s0 = tables.add_site(100., Some(b"A")).unwrap();
+    let s1 = tables.add_site(700., Some(b"G")).unwrap();
+    tables
+        .add_mutation(s0, 4, MutationId::NULL, 0.5, Some(b"C"))
+        .unwrap();
+    tables
+        .add_mutation(s1, 1, MutationId::NULL, 1.5, Some(b"T"))
+        .unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let mut variants = treeseq.variants(None, VariantOptions::default()).unwrap();
+    let mut genotypes = vec![];
+    while let Some(variant) = variants.next() {
+        assert!(variant.has_missing_data());
+        assert_eq!(variant.num_missing(), 1);
+        assert_eq!(variant.samples().last(), Some(&isolated));
+        assert_eq!(variant.genotype(4).unwrap(), Genotype::Missing);
+        assert_eq!(variant.allele_counts().iter().sum::<usize>(), 4);
+        genotypes.push(variant.genotypes().to_vec());
+        assert_eq!(
+            variant.genotypes_as::<i16>().unwrap(),
+            variant
+                .genotypes()
+                .iter()
+                .map(|&g| g as i16)
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            variant.genotypes_as::<u8>(),
+            Err(tskit::TskitError::ValueError { .. })
+        ));
+    }
+    assert_eq!(genotypes, vec![vec![0, 0, 1, 0, -1], vec![1, 0, 1, 1, -1]]);
+
+    let mut variants = treeseq
+        .variants(None, VariantOptions::ISOLATED_NOT_MISSING)
+        .unwrap();
+    variants.decode(s1).unwrap();
+    assert_eq!(variants.site(), s1);
+    assert_eq!(variants.position(), Some(700.0.into()));
+    assert_eq!(variants.genotypes(), &[1, 0, 1, 1, 0]);
+    assert_eq!(variants.allele_frequencies(), vec![0.4, 0.6]);
+    assert!(variants.decode(2).is_err());
+
+    // Statistics with and without missing data
+    let pi = treeseq
+        .site_diversity(None, tskit::MissingData::Exclude)
+        .unwrap();
+    assert_eq!(pi, [0.5, 0.5]);
+    let pi = treeseq
+        .site_diversity(None, tskit::MissingData::Ancestral)
+        .unwrap();
+    assert_eq!(pi, [0.4, 0.6]);
+    let samples = [treeseq.sample_nodes()[0], isolated];
+    let pi = treeseq
+        .site_diversity(Some(&samples), tskit::MissingData::Exclude)
+        .unwrap();
+    assert!(pi.iter().all(|x| x.is_nan()));
+    let pi = treeseq
+        .site_diversity(Some(&samples), tskit::MissingData::Ancestral)
+        .unwrap();
+    assert_eq!(pi, [0.0, 1.0]);
+
+    let samples = [NodeId::from(4), isolated];
+    let mut variants = treeseq
+        .variants(Some(&samples), VariantOptions::default())
+        .unwrap();
+    let variant = variants.next().unwrap();
+    assert_eq!(variant.samples(), &samples);
+    assert_eq!(
+        variant
+            .typed_genotypes()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![Genotype::Allele(1), Genotype::Missing]
+    );
+    assert_eq!(variant.allele(variant.num_alleles()), None);
+}
+
+#[test]
+fn test_genotype_chunks() {
+    use tskit::VariantOptions;
+
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    assert_eq!(
+        treeseq
+            .genotype_chunks(2, None, VariantOptions::default())
+            .unwrap()
+            .count(),
+        0
+    );
+    assert!(matches!(
+        treeseq.genotype_chunks(0, None, VariantOptions::default()),
+        Err(tskit::TskitError::ValueError { .. })
+    ));
+
+    let mut tables = make_small_table_collection_two_trees();
+    let unknown_time = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    for (position, node) in [(100., 4), (300., 0), (700., 1)] {
+        let site = tables.add_site(position, Some(b"A")).unwrap();
+        tables
+            .add_mutation(site, node, MutationId::NULL, unknown_time, Some(b"T"))
+            .unwrap();
+    }
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let chunks = treeseq
+        .genotype_chunks(2, None, VariantOptions::default())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].sites, vec![SiteId::from(0), SiteId::from(1)]);
+    assert_eq!(chunks[0].num_samples, 4);
+    assert_eq!(chunks[0].genotypes, vec![0, 0, 1, 0, 1, 1, 0, 0]);
+    assert_eq!(chunks[0].site_genotypes(1), Some(&[1, 1, 0, 0][..]));
+    assert_eq!(chunks[0].site_genotypes(2), None);
+    assert_eq!(chunks[1].sites, vec![SiteId::from(2)]);
+    assert_eq!(chunks[1].positions, vec![Position::from(700.)]);
+    assert_eq!(chunks[1].genotypes, vec![1, 0, 1, 1]);
+
+    // A single chunk matches decoding each variant
+    let samples = [NodeId::from(5), NodeId::from(2)];
+    let chunks = treeseq
+        .genotype_chunks(10, Some(&samples), VariantOptions::default())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].num_sites(), 3);
+    let mut variants = treeseq
+        .variants(Some(&samples), VariantOptions::default())
+        .unwrap();
+    let mut index = 0;
+    while let Some(variant) = variants.next() {
+        assert_eq!(chunks[0].site_genotypes(index), Some(variant.genotypes()));
+        assert_eq!(
+            chunks[0].alleles[index],
+            variant.alleles().map(|a| a.to_vec()).collect::<Vec<_>>()
+        );
+        index += 1;
+    }
+
+    // Genotypes stored as i8, including missing data
+    let mut tables = treeseq.dump_tables().unwrap();
+    let isolated = tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    let expected = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(TreeSequenceFlags::default())
+        .unwrap()
+        .genotype_chunks(2, None, VariantOptions::default())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    // A site with more alleles than fit in an i8
+    let site = tables.add_site(800., Some(b"A")).unwrap();
+    for i in 0..200 {
+        tables
+            .add_mutation(
+                site,
+                4,
+                MutationId::NULL,
+                unknown_time,
+                Some(format!("T{i}").as_bytes()),
+            )
+            .unwrap();
+    }
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    let isolated = treeseq
+        .sample_nodes()
+        .iter()
+        .position(|&s| s == isolated)
+        .unwrap();
+    let mut chunks = treeseq
+        .genotype_chunks(2, None, VariantOptions::default())
+        .unwrap()
+        .genotypes_as::<i8>();
+    let chunk = chunks.next().unwrap().unwrap();
+    assert_eq!(chunk.sites, expected[0].sites);
+    assert_eq!(
+        chunk.genotypes,
+        expected[0]
+            .genotypes
+            .iter()
+            .map(|&g| g as i8)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(chunk.site_genotypes(0).unwrap()[isolated], -1);
+    // The second chunk contains the site with too many alleles
+    assert!(matches!(
+        chunks.next(),
+        Some(Err(tskit::TskitError::ValueError { .. }))
+    ));
+    assert!(chunks.next().is_none());
+
+    // Unsigned types cannot store missing data
+    assert!(treeseq
+        .genotype_chunks(2, None, VariantOptions::default())
+        .unwrap()
+        .genotypes_as::<u8>()
+        .next()
+        .unwrap()
+        .is_err());
+}
+
+#[test]
+fn test_branch_point_sampling() {
+    use rand::{Rng, SeedableRng};
+
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let nodes = treeseq.nodes();
+
+    // Laying out the branches of the first tree end to end
+    // gives each lineage a share of [0, 1) equal to its length.
+    let tree = treeseq.first_tree(TreeFlags::default()).unwrap();
+    assert_eq!(tree.total_branch_length(false).unwrap(), 6.0);
+    let mut counts = vec![0; 6];
+    for i in 0..600 {
+        let u = (i as f64 + 0.5) / 600.;
+        let point = tree.branch_point(u).unwrap().unwrap();
+        let parent = tree.parent(point.node).unwrap();
+        assert!(point.time >= nodes.time(point.node).unwrap());
+        assert!(point.time < nodes.time(parent).unwrap());
+        counts[point.node.as_usize()] += 1;
+    }
+    assert_eq!(counts, vec![0, 0, 200, 200, 100, 100]);
+    assert!(tree.branch_point(1.0).is_err());
+    assert!(tree.branch_point(-0.5).is_err());
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(101);
+    let points = treeseq
+        .sample_branch_points(100, || rng.gen::<f64>())
+        .unwrap();
+    assert_eq!(points.len(), 100);
+    assert!(points.windows(2).all(|w| w[0].0 <= w[1].0));
+    for (position, point) in points {
+        let tree = treeseq
+            .tree_iterator_at_position(TreeFlags::default(), position)
+            .unwrap();
+        let parent = tree.parent(point.node).unwrap();
+        assert_ne!(parent, NodeId::NULL);
+        assert!(point.time >= nodes.time(point.node).unwrap());
+        assert!(point.time <= nodes.time(parent).unwrap());
+    }
+    assert!(treeseq.sample_branch_points(1, || 1.0).is_err());
+}
+
+#[test]
+fn test_node_span_summaries() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let summaries = treeseq.node_span_summaries().unwrap();
+    assert_eq!(summaries.len(), 6);
+    assert_eq!(summaries[0].ancestral_span, 2000.);
+    assert_eq!(summaries[0].mean_descendant_samples, 3.0);
+    assert_eq!(summaries[1].ancestral_span, 2500.);
+    assert_eq!(summaries[1].mean_descendant_samples, 2.5);
+    for s in &summaries[2..] {
+        assert_eq!(s.ancestral_span, 0.);
+        assert!(s.mean_descendant_samples.is_nan());
+    }
+
+    let edges = treeseq.edges();
+    let summaries = treeseq.edge_span_summaries().unwrap();
+    assert_eq!(summaries.len(), 6);
+    for (e, s) in summaries.iter().enumerate() {
+        let e = EdgeId::from(e as i32);
+        assert_eq!(s.edge, e);
+        assert_eq!(s.span, edges.right(e).unwrap() - edges.left(e).unwrap());
+        let expected = match edges.child(e).unwrap().as_usize() {
+            1 => 3.0,
+            _ => 1.0,
+        };
+        assert_eq!(s.mean_descendant_samples, expected);
+    }
+    // The ancestral span of a node is the sum over its edges
+    let total = summaries
+        .iter()
+        .filter(|s| edges.parent(s.edge) == Some(NodeId::from(0)))
+        .map(|s| f64::from(s.span))
+        .sum::<f64>();
+    assert_eq!(total, 2000.);
+}
+
+#[test]
+fn test_tree_sequence_from_tables_ref() {
+    let mut tables = make_small_table_collection_two_trees();
+    let treeseq = TreeSequence::from_tables_ref(&tables, TreeSequenceFlags::default()).unwrap();
+    assert_eq!(treeseq.nodes().num_rows(), tables.nodes().num_rows());
+    assert_eq!(treeseq.edges().num_rows(), tables.edges().num_rows());
+
+    // The tables remain usable and independent of the tree sequence.
+    tables
+        .add_node(
+            NodeFlags::new_sample(),
+            0.0,
+            PopulationId::NULL,
+            IndividualId::NULL,
+        )
+        .unwrap();
+    tables.build_index().unwrap();
+    let second = TreeSequence::from_tables_ref(&tables, TreeSequenceFlags::default()).unwrap();
+    assert_eq!(treeseq.num_samples(), 4);
+    assert_eq!(second.num_samples(), 5);
+    assert_eq!(treeseq.num_trees(), second.num_trees());
+    let dumped = treeseq.dump_tables().unwrap();
+    assert_eq!(dumped.nodes().num_rows(), 6);
+}
+
+#[test]
+fn test_tree_sequence_cache() {
+    use std::rc::Rc;
+    use tskit::StatisticsOptions;
+
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut cache = treeseq.cache();
+    let spans = cache.tree_spans();
+    assert_eq!(&spans[..], &treeseq.tree_spans()[..]);
+    assert!(Rc::ptr_eq(&spans, &cache.tree_spans()));
+    assert_eq!(
+        &cache.population_samples(PopulationId::NULL)[..],
+        treeseq.sample_nodes()
+    );
+    assert!(cache.population_samples(0).is_empty());
+
+    let samples = treeseq.sample_nodes();
+    let sample_sets = [&samples[..2], &samples[2..]]
+        .into_iter()
+        .collect::<tskit::SampleSets>();
+    let genome = tskit::Windows::genome();
+    let branch = tskit::StatsMode::Branch;
+    let options = StatisticsOptions::default().span_normalise();
+    let d = cache
+        .divergence_matrix(&sample_sets, &genome, branch, options)
+        .unwrap();
+    assert_eq!(d.len(), 4);
+    assert_eq!(d[1], d[2]);
+    assert_eq!(
+        &d[..],
+        &treeseq
+            .divergence_matrix(&sample_sets, &genome, branch, options)
+            .unwrap()[..]
+    );
+    let pi = treeseq
+        .diversity(&sample_sets, &genome, branch, options)
+        .unwrap();
+    assert_eq!(vec![d[0], d[3]], pi);
+    assert!(Rc::ptr_eq(
+        &d,
+        &cache
+            .divergence_matrix(&sample_sets, &genome, branch, options)
+            .unwrap()
+    ));
+
+    // Other parameters are cached separately
+    let other = cache
+        .divergence_matrix(&sample_sets, &genome, branch, StatisticsOptions::default())
+        .unwrap();
+    assert!(!Rc::ptr_eq(&d, &other));
+    let windows = tskit::Windows::breakpoints([0., 500., 1000.]);
+    let other = cache
+        .divergence_matrix(&sample_sets, &windows, branch, options)
+        .unwrap();
+    assert_eq!(other.len(), 8);
+    let masked = windows
+        .clone()
+        .mask(tskit::GenomeMask::new([(0., 100.)]).unwrap());
+    assert!(!Rc::ptr_eq(
+        &other,
+        &cache
+            .divergence_matrix(&sample_sets, &masked, branch, options)
+            .unwrap()
+    ));
+    assert!(cache
+        .divergence_matrix(&sample_sets, &genome, tskit::StatsMode::Node, options)
+        .is_err());
+
+    cache.clear();
+    assert!(!Rc::ptr_eq(&spans, &cache.tree_spans()));
+    assert!(!Rc::ptr_eq(
+        &d,
+        &cache
+            .divergence_matrix(&sample_sets, &genome, branch, options)
+            .unwrap()
+    ));
+}
+
+#[test]
+fn test_derived_statistics() {
+    use tskit::StatisticsOptions;
+
+    let treeseq = tskit::testing::random_binary(8, 100., 42).unwrap();
+    let samples = treeseq.sample_nodes();
+    let sets = [&samples[..4], &samples[4..]];
+    let sample_sets = tskit::SampleSets::from(&sets[..]);
+    let all_samples = [samples].into_iter().collect::<tskit::SampleSets>();
+    let genome = tskit::Windows::genome();
+    let branch = tskit::StatsMode::Branch;
+    let options = StatisticsOptions::default();
+
+    let matrix = treeseq
+        .divergence_matrix(&sample_sets, &genome, branch, options)
+        .unwrap();
+    let d = treeseq
+        .divergence(&sample_sets, &[(0, 1), (1, 1)], &genome, branch, options)
+        .unwrap();
+    assert_eq!(d, vec![matrix[1], matrix[3]]);
+    assert!(matches!(
+        treeseq.divergence(&sample_sets, &[(0, 2)], &genome, branch, options),
+        Err(tskit::TskitError::IndexError)
+    ));
+
+    let pi = treeseq
+        .diversity(&sample_sets, &genome, branch, options)
+        .unwrap();
+    let fst = treeseq
+        .fst(&sample_sets, &[(0, 1)], &genome, branch)
+        .unwrap();
+    let within = pi[0] + pi[1];
+    assert!((fst[0] - (1.0 - 2.0 * within / (within + 2.0 * d[0]))).abs() < 1e-12);
+    assert!(treeseq
+        .fst(&sample_sets, &[(0, 1)], &genome, tskit::StatsMode::Node)
+        .is_err());
+
+    let tajimas_d = treeseq.tajimas_d(&all_samples, &genome, branch).unwrap();
+    assert!(tajimas_d[0].is_finite());
+    let one_sample = tskit::SampleSets::new().add_set([samples[0]]);
+    assert!(treeseq.tajimas_d(&one_sample, &genome, branch).unwrap()[0].is_nan());
+
+    // ((0,1),(2,3)) with a singleton and a doubleton site:
+    // n = 4, S = 2 and pi = 1/2 + 2/3.
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    for _ in 0..4 {
+        tables
+            .add_node(tskit::NodeFlags::new_sample(), 0.0, -1, -1)
+            .unwrap();
+    }
+    tables.add_node(0, 1.0, -1, -1).unwrap();
+    tables.add_node(0, 1.0, -1, -1).unwrap();
+    tables.add_node(0, 2.0, -1, -1).unwrap();
+    for (parent, child) in [(4, 0), (4, 1), (5, 2), (5, 3), (6, 4), (6, 5)] {
+        tables.add_edge(0., 100., parent, child).unwrap();
+    }
+    for (position, node, time) in [(10., 0, 0.5), (20., 4, 1.5)] {
+        let site = tables.add_site(position, Some(b"A")).unwrap();
+        tables
+            .add_mutation(site, node, -1, time, Some(b"T"))
+            .unwrap();
+    }
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+    let fixed = tables
+        .tree_sequence(tskit::TreeSequenceFlags::default())
+        .unwrap();
+    let fixed_samples = [fixed.sample_nodes()]
+        .into_iter()
+        .collect::<tskit::SampleSets>();
+    let d = fixed
+        .tajimas_d(&fixed_samples, &genome, tskit::StatsMode::Site)
+        .unwrap();
+    assert!((d[0] - 0.5915801398995593).abs() < 1e-12);
+
+    // Each branch subtends between 1 and n - 1 samples
+    let afs = treeseq
+        .allele_frequency_spectrum(&all_samples, &genome, branch, options.polarised())
+        .unwrap();
+    assert_eq!(afs.len(), samples.len() + 1);
+    assert_eq!(afs[0], 0.0);
+    assert_eq!(afs[samples.len()], 0.0);
+    let total = treeseq
+        .segregating_sites(&all_samples, &genome, branch, options)
+        .unwrap();
+    assert!((afs.iter().sum::<f64>() - total[0]).abs() < 1e-9);
+    let joint = treeseq
+        .allele_frequency_spectrum(&sample_sets, &genome, branch, options)
+        .unwrap();
+    assert_eq!(joint.len(), 25);
+}
+
+#[test]
+fn test_ibd_segments() {
+    use tskit::IbdOptions;
+
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let samples = treeseq.sample_nodes().to_vec();
+
+    // The MRCA of each pair in each tree
+    let mut expected = vec![];
+    let mut tree = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    while let Some(tree) = tree.next() {
+        let (left, right) = tree.interval();
+        for (i, &a) in samples.iter().enumerate() {
+            for &b in &samples[i + 1..] {
+                let ancestors = tree.parents(a).collect::<Vec<_>>();
+                // The first tree has two roots
+                if let Some(node) = tree.parents(b).find(|u| ancestors.contains(u)) {
+                    expected.push(tskit::IdentitySegment {
+                        left,
+                        right,
+                        sample_a: a,
+                        sample_b: b,
+                        node,
+                    });
+                }
+            }
+        }
+    }
+    // Sort, and merge adjacent segments with the same MRCA
+    let merge = |mut segments: Vec<tskit::IdentitySegment>| {
+        segments.sort_by(|x, y| {
+            (x.sample_a, x.sample_b, x.node)
+                .cmp(&(y.sample_a, y.sample_b, y.node))
+                .then(x.left.partial_cmp(&y.left).unwrap())
+        });
+        let mut merged: Vec<tskit::IdentitySegment> = vec![];
+        for s in segments {
+            match merged.last_mut() {
+                Some(m)
+                    if (m.sample_a, m.sample_b, m.node, m.right)
+                        == (s.sample_a, s.sample_b, s.node, s.left) =>
+                {
+                    m.right = s.right
+                }
+                _ => merged.push(s),
+            }
+        }
+        merged
+    };
+
+    let ibd = treeseq
+        .ibd_segments_within(None, IbdOptions::default())
+        .unwrap();
+    let segments = ibd.iter().collect::<Vec<_>>();
+    assert_eq!(merge(segments.clone()), merge(expected.clone()));
+    assert_eq!(ibd.num_segments(), segments.len());
+    assert_eq!(ibd.pairs().len(), 6);
+    assert_eq!(
+        ibd.total_span(),
+        expected.iter().map(|s| s.span()).sum::<f64>()
+    );
+    assert_eq!(ibd.get(5, 4).count(), 1);
+    assert_eq!(ibd.get(4, 4).count(), 0);
+
+    // Filters
+    let options = IbdOptions::default().max_time(1.0);
+    let ibd = treeseq.ibd_segments_within(None, options).unwrap();
+    assert!(ibd.iter().all(|s| s.node == 1));
+    let options = IbdOptions::default().min_span(500.);
+    let ibd = treeseq.ibd_segments_within(None, options).unwrap();
+    assert!(ibd.iter().all(|s| s.span() > 500.));
+    let options = IbdOptions::default().store_segments(false);
+    let ibd = treeseq.ibd_segments_within(None, options).unwrap();
+    assert_eq!(ibd.num_segments(), segments.len());
+    assert_eq!(ibd.iter().count(), 0);
+
+    // Only pairs between the sets
+    let ibd = treeseq
+        .ibd_segments_between(&[&samples[..1], &samples[1..3]], IbdOptions::default())
+        .unwrap();
+    assert_eq!(
+        ibd.pairs(),
+        [(samples[0], samples[1]), (samples[0], samples[2])]
+    );
+    let ibd = treeseq
+        .ibd_segments_within(Some(&samples[2..]), IbdOptions::default())
+        .unwrap();
+    assert_eq!(ibd.pairs(), [(samples[2], samples[3])]);
+
+    assert!(treeseq
+        .ibd_segments_within(Some(&[samples[0], samples[0]]), IbdOptions::default())
+        .is_err());
+    assert!(treeseq
+        .ibd_segments_between(&[&samples[..1], &samples[..2]], IbdOptions::default())
+        .is_err());
+    let ibd = treeseq
+        .ibd_segments_between(&[&samples], IbdOptions::default())
+        .unwrap();
+    assert_eq!(ibd.num_segments(), 0);
+}
+
+#[test]
+fn test_genome_scan() {
+    use tskit::{ScanProgress, ScanSegment};
+
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut segments = vec![];
+    let mut reports = vec![];
+    treeseq
+        .genome_scan()
+        .windows([0., 250., 600., 1000.])
+        .unwrap()
+        .progress(|p: &ScanProgress| reports.push(*p))
+        .run(TreeFlags::default(), |segment, tree| {
+            segments.push((*segment, tree.interval()));
+            Ok(())
+        })
+        .unwrap();
+    let expected = [
+        (0, 0., 250.),
+        (1, 250., 500.),
+        (1, 500., 600.),
+        (2, 600., 1000.),
+    ];
+    assert_eq!(
+        segments.iter().map(|(s, _)| *s).collect::<Vec<_>>(),
+        expected
+            .iter()
+            .map(|&(window, left, right)| ScanSegment {
+                window,
+                left: left.into(),
+                right: right.into()
+            })
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(segments[1].1, (0.0.into(), 500.0.into()));
+    assert_eq!(segments[2].1, (500.0.into(), 1000.0.into()));
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].trees_completed, 1);
+    assert_eq!(reports[0].fraction(), 0.5);
+    assert_eq!(reports[1].num_trees, 2);
+    assert_eq!(reports[1].fraction(), 1.0);
+
+    // Only the last tree is reported
+    let mut reports = vec![];
+    let mut num_segments = 0;
+    treeseq
+        .genome_scan()
+        .report_every(5)
+        .progress(|p: &ScanProgress| reports.push(p.trees_completed))
+        .run(TreeFlags::default(), |_, _| {
+            num_segments += 1;
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(num_segments, 2);
+    assert_eq!(reports, vec![2]);
+
+    // Errors from the callback stop the scan
+    let mut calls = 0;
+    let rv = treeseq.genome_scan().run(TreeFlags::default(), |_, _| {
+        calls += 1;
+        Err(tskit::TskitError::LibraryError("stop".to_string()))
+    });
+    assert!(rv.is_err());
+    assert_eq!(calls, 1);
+
+    for bad in [
+        vec![0., 1000.5],
+        vec![1., 1000.],
+        vec![0., 500., 500., 1000.],
+        vec![0.],
+    ] {
+        assert!(treeseq.genome_scan().windows(bad).is_err());
+    }
+}
+
+#[cfg(feature = "indicatif")]
+#[test]
+fn test_genome_scan_progress_bar() {
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let bar = indicatif::ProgressBar::hidden();
+    treeseq
+        .genome_scan()
+        .progress(bar.clone())
+        .run(TreeFlags::default(), |_, _| Ok(()))
+        .unwrap();
+    assert_eq!(bar.length(), Some(2));
+    assert_eq!(bar.position(), 2);
+    assert!(bar.is_finished());
+}
+
+#[test]
+fn test_extract_subtree() {
+    let mut tables = make_small_table_collection_two_trees();
+    let s0 = tables.add_site(100., Some(b"A")).unwrap();
+    let s1 = tables.add_site(700., Some(b"G")).unwrap();
+    let s2 = tables.add_site(800., Some(b"C")).unwrap();
+    tables
+        .add_mutation(s0, 4, MutationId::NULL, 0.5, Some(b"C"))
+        .unwrap();
+    tables
+        .add_mutation(s1, 1, MutationId::NULL, 1.5, Some(b"T"))
+        .unwrap();
+    tables
+        .add_mutation(s2, 3, MutationId::NULL, 0.5, Some(b"A"))
+        .unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+
+    // First tree: node 1 has children 4 and 5
+    let tree = trees.next().unwrap();
+    let clade = tree.extract_subtree(1).unwrap();
+    assert_eq!(clade.sequence_length(), 500.0);
+    assert_eq!(clade.nodes().num_rows(), 3);
+    assert_eq!(clade.nodes().time(0), Some(1.0.into()));
+    assert_eq!(clade.edges().num_rows(), 2);
+    assert_eq!(clade.sites().num_rows(), 1);
+    assert_eq!(clade.sites().position(0), Some(100.0.into()));
+    assert_eq!(clade.mutations().num_rows(), 1);
+    assert_eq!(clade.mutations().node(0), Some(1.into()));
+
+    // Second tree: node 1 has children 2, 4, and 5
+    let tree = trees.next().unwrap();
+    let clade = tree.extract_subtree(1).unwrap();
+    assert_eq!(clade.sequence_length(), 500.0);
+    assert_eq!(clade.nodes().num_rows(), 4);
+    assert_eq!(clade.edges().num_rows(), 3);
+    for e in 0..3 {
+        assert_eq!(clade.edges().left(e), Some(0.0.into()));
+        assert_eq!(clade.edges().right(e), Some(500.0.into()));
+        assert_eq!(clade.edges().parent(e), Some(0.into()));
+    }
+    // The mutation on the root of the clade is kept
+    assert_eq!(clade.sites().num_rows(), 1);
+    assert_eq!(clade.sites().position(0), Some(200.0.into()));
+    assert_eq!(clade.mutations().node(0), Some(0.into()));
+    let clade_treeseq = clade.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert_eq!(clade_treeseq.num_trees(), 1);
+    assert_eq!(clade_treeseq.num_samples(), 3);
+
+    // A leaf
+    let clade = tree.extract_subtree(2).unwrap();
+    assert_eq!(clade.nodes().num_rows(), 1);
+    assert_eq!(clade.edges().num_rows(), 0);
+    assert_eq!(clade.mutations().num_rows(), 0);
+
+    assert!(tree.extract_subtree(6).is_err());
+    assert!(tree.extract_subtree(NodeId::NULL).is_err());
+}
+
+#[test]
+fn test_individual_samples() {
+    use tskit::VariantOptions;
+
+    let mut tables = TableCollection::new(1000.).unwrap();
+    let i0 = tables.add_individual(0, None, None).unwrap();
+    let i1 = tables.add_individual(0, None, None).unwrap();
+    // An individual without sample nodes
+    tables.add_individual(0, None, None).unwrap();
+    let parent = tables.add_node(0, 1.0, PopulationId::NULL, i0).unwrap();
+    for individual in [i1, i0, i1, i1] {
+        let child = tables
+            .add_node(NodeFlags::new_sample(), 0.0, PopulationId::NULL, individual)
+            .unwrap();
+        tables.add_edge(0., 1000., parent, child).unwrap();
+    }
+    let s = tables.add_site(100., Some(b"A")).unwrap();
+    tables
+        .add_mutation(s, 3, MutationId::NULL, 0.5, Some(b"C"))
+        .unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+
+    // The non-sample node of individual 0 is not included
+    let individuals = treeseq.individual_samples(None).unwrap();
+    assert_eq!(individuals.len(), 2);
+    assert_eq!(individuals.individuals(), &[i0, i1]);
+    assert_eq!(individuals.samples(), [2, 1, 3, 4].map(NodeId::from));
+    assert_eq!(
+        individuals.individual_samples(1),
+        Some(&individuals.samples()[1..])
+    );
+    assert_eq!(individuals.ploidy(0), Some(1));
+    assert_eq!(individuals.ploidy(1), Some(3));
+    assert_eq!(individuals.ploidy(2), None);
+    assert!(treeseq.individual_samples(Some(3)).is_err());
+
+    let values = [0, 1, 2, 3];
+    let split = individuals.split(&values).unwrap().collect::<Vec<_>>();
+    assert_eq!(split, vec![&[0][..], &[1, 2, 3][..]]);
+    assert!(individuals.split(&values[1..]).is_none());
+
+    let mut variants = treeseq
+        .variants(Some(individuals.samples()), VariantOptions::default())
+        .unwrap();
+    let variant = variants.next().unwrap();
+    let genotypes = variant
+        .individual_genotypes(&individuals)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(genotypes, vec![(i0, &[0][..]), (i1, &[0, 1, 0][..])]);
+
+    // Genotypes decoded in another order
+    let mut variants = treeseq.variants(None, VariantOptions::default()).unwrap();
+    let variant = variants.next().unwrap();
+    assert!(variant.individual_genotypes(&individuals).is_err());
+
+    // Samples without individuals cannot be paired
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    assert!(treeseq.individual_samples(None).is_err());
+}
+
+#[test]
+fn test_dosages() {
+    use tskit::VariantOptions;
+
+    let missing = tskit::bindings::TSK_MISSING_DATA;
+    let mut tables = TableCollection::new(1000.).unwrap();
+    let individuals = (0..3)
+        .map(|_| tables.add_individual(0, None, None).unwrap())
+        .collect::<Vec<_>>();
+    let parent = tables.add_node(0, 1.0, PopulationId::NULL, -1).unwrap();
+    let mut nodes = vec![];
+    for (i, individual) in [2, 0, 1, 0, 1, 2].into_iter().enumerate() {
+        let child = tables
+            .add_node(
+                NodeFlags::new_sample(),
+                0.0,
+                PopulationId::NULL,
+                individuals[individual],
+            )
+            .unwrap();
+        // The last sample is isolated, so its genotypes are missing
+        if i < 5 {
+            tables.add_edge(0., 1000., parent, child).unwrap();
+        }
+        nodes.push(child);
+    }
+    // A multi-allelic site, with both alternate alleles counted
+    let s0 = tables.add_site(100., Some(b"A")).unwrap();
+    for (node, state) in [(nodes[1], b"C"), (nodes[3], b"G"), (nodes[2], b"C")] {
+        tables
+            .add_mutation(s0, node, MutationId::NULL, 0.5, Some(state))
+            .unwrap();
+    }
+    let s1 = tables.add_site(200., Some(b"A")).unwrap();
+    tables
+        .add_mutation(s1, parent, MutationId::NULL, 1.5, Some(b"T"))
+        .unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+
+    let samples = treeseq.individual_samples(Some(2)).unwrap();
+    let dosages = treeseq
+        .dosages(&samples, VariantOptions::default())
+        .unwrap();
+    assert_eq!(dosages.num_sites(), 2);
+    assert_eq!(dosages.num_individuals(), 3);
+    assert_eq!(dosages.sites, [s0, s1]);
+    assert_eq!(dosages.individuals, individuals);
+    assert_eq!(dosages.site_dosages(0), Some(&[2, 1, missing][..]));
+    assert_eq!(dosages.site_dosages(1), Some(&[2, 2, missing][..]));
+    assert_eq!(dosages.site_dosages(2), None);
+    assert_eq!(dosages.get(1, 0), Some(2));
+    assert_eq!(dosages.get(0, 3), None);
+
+    // Without individuals, each site has no dosages
+    let empty = tskit::DosageMatrix {
+        sites: vec![s0, s1],
+        individuals: vec![],
+        dosages: vec![],
+    };
+    assert_eq!(empty.site_dosages(1), Some(&[][..]));
+    assert_eq!(empty.site_dosages(2), None);
+
+    // Isolated samples carry the ancestral state
+    let options = VariantOptions::default().isolated_not_missing();
+    let dosages = treeseq.dosages(&samples, options).unwrap();
+    assert_eq!(dosages.dosages, [2, 1, 0, 2, 2, 1]);
+
+    let mut variants = treeseq.variants(Some(samples.samples()), options).unwrap();
+    let variant = variants.next().unwrap();
+    assert_eq!(variant.individual_dosages(&samples).unwrap(), [2, 1, 0]);
+    let mut variants = treeseq.variants(None, options).unwrap();
+    let variant = variants.next().unwrap();
+    assert!(variant.individual_dosages(&samples).is_err());
+}
+
+#[test]
+fn test_mutation_population_frequencies() {
+    let mut tables = TableCollection::new(100.).unwrap();
+    let pops = (0..4)
+        .map(|_| tables.add_population().unwrap())
+        .collect::<Vec<_>>();
+    // The last sample has no population
+    for pop in [
+        pops[0],
+        pops[0],
+        pops[1],
+        pops[1],
+        pops[2],
+        PopulationId::NULL,
+    ] {
+        tables
+            .add_node(NodeFlags::new_sample(), 0.0, pop, IndividualId::NULL)
+            .unwrap();
+    }
+    for time in [1.0, 2.0, 3.0] {
+        tables
+            .add_node(0, time, PopulationId::NULL, IndividualId::NULL)
+            .unwrap();
+    }
+    for (left, right, parent, child) in [
+        (0., 100., 6, 0),
+        (0., 50., 6, 2),
+        (50., 100., 6, 4),
+        (0., 100., 7, 6),
+        (0., 100., 7, 1),
+        (0., 100., 7, 3),
+        (50., 100., 7, 2),
+        (0., 100., 8, 7),
+        (0., 50., 8, 4),
+        (0., 100., 8, 5),
+    ] {
+        tables.add_edge(left, right, parent, child).unwrap();
+    }
+    let s0 = tables.add_site(10., Some(b"A")).unwrap();
+    let m0 = tables.add_mutation(s0, 6, -1, 1.5, Some(b"C")).unwrap();
+    let m1 = tables.add_mutation(s0, 2, m0, 0.5, Some(b"G")).unwrap();
+    let s1 = tables.add_site(60., Some(b"A")).unwrap();
+    // Stacked mutations on node 7
+    let m2 = tables.add_mutation(s1, 7, -1, 2.5, Some(b"C")).unwrap();
+    let m3 = tables.add_mutation(s1, 7, m2, 2.2, Some(b"G")).unwrap();
+    tables.add_mutation(s1, 4, m3, 0.5, Some(b"T")).unwrap();
+    // A site without mutations
+    tables.add_site(90., Some(b"A")).unwrap();
+    tables.full_sort(TableSortOptions::default()).unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables
+        .deepcopy()
+        .unwrap()
+        .tree_sequence(TreeSequenceFlags::default())
+        .unwrap();
+
+    let frequencies = treeseq.mutation_population_frequencies().unwrap();
+    assert_eq!(frequencies.len(), 5);
+    for (m, f) in frequencies.iter().enumerate() {
+        assert_eq!(f.mutation, MutationId::from(m as i32));
+        assert_eq!(f.counts.len(), 4);
+        assert_eq!(f.frequencies.len(), 4);
+        assert!(f.frequencies[3].is_nan());
+    }
+    assert_eq!(
+        frequencies.iter().map(|f| f.site).collect::<Vec<_>>(),
+        [s0, s0, s1, s1, s1]
+    );
+    let counts = frequencies
+        .iter()
+        .map(|f| f.counts[..3].to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        counts,
+        [
+            vec![1, 0, 0],
+            vec![0, 1, 0],
+            vec![0, 0, 0],
+            vec![2, 2, 0],
+            vec![0, 0, 1]
+        ]
+    );
+    assert_eq!(frequencies[m1.as_usize()].frequencies[..3], [0.0, 0.5, 0.0]);
+    assert_eq!(frequencies[m3.as_usize()].frequencies[..3], [1.0, 1.0, 0.0]);
+    assert_eq!(frequencies[4].frequencies[2], 1.0);
+
+    // Carriers are found from the trees, not the mutation parents
+    let unknown = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    let mut tables = TableCollection::new(100.).unwrap();
+    let pop = tables.add_population().unwrap();
+    let a = tables
+        .add_node(NodeFlags::new_sample(), 0.0, pop, -1)
+        .unwrap();
+    let b = tables
+        .add_node(NodeFlags::new_sample(), 0.0, pop, -1)
+        .unwrap();
+    let q = tables.add_node(0, 1.0, PopulationId::NULL, -1).unwrap();
+    let r = tables.add_node(0, 2.0, PopulationId::NULL, -1).unwrap();
+    tables.add_edge(0., 100., q, a).unwrap();
+    tables.add_edge(0., 100., r, b).unwrap();
+    tables.add_edge(0., 100., r, q).unwrap();
+    let site = tables.add_site(50., Some(b"A")).unwrap();
+    let m0 = tables
+        .add_mutation(site, q, -1, unknown, Some(b"C"))
+        .unwrap();
+    // The parent mutation is below its child
+    tables
+        .add_mutation(site, r, m0, unknown, Some(b"G"))
+        .unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let frequencies = treeseq.mutation_population_frequencies().unwrap();
+    assert_eq!(frequencies[0].counts, [1]);
+    assert_eq!(frequencies[1].counts, [1]);
+    let counts = treeseq
+        .allele_counts(
+            &tskit::SampleSets::new().add_set([a, b]),
+            &tskit::Windows::genome(),
+        )
+        .unwrap();
+    assert_eq!(counts.site_counts(0), Some(&[2][..]));
+}
+
+#[test]
+fn test_haplotypes() {
+    use tskit::{HaplotypeOptions, MultiCharacterAlleles, VariantOptions};
+
+    let mut tables = TableCollection::new(1000.).unwrap();
+    let parent = tables.add_node(0, 1.0, PopulationId::NULL, -1).unwrap();
+    let mut nodes = vec![];
+    for i in 0..3 {
+        let child = tables
+            .add_node(NodeFlags::new_sample(), 0.0, PopulationId::NULL, -1)
+            .unwrap();
+        // The last sample is isolated, so its genotypes are missing
+        if i < 2 {
+            tables.add_edge(0., 1000., parent, child).unwrap();
+        }
+        nodes.push(child);
+    }
+    let s0 = tables.add_site(100., Some(b"A")).unwrap();
+    tables
+        .add_mutation(s0, nodes[0], MutationId::NULL, 0.5, Some(b"G"))
+        .unwrap();
+    // A microsatellite with repeat counts as alleles
+    let s1 = tables.add_site(200., Some(b"12")).unwrap();
+    tables
+        .add_mutation(s1, nodes[1], MutationId::NULL, 0.5, Some(b"14"))
+        .unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    let variant_options = VariantOptions::default();
+
+    // Multi-character alleles are errors by default
+    match treeseq.haplotypes(None, variant_options, &HaplotypeOptions::default()) {
+        Err(tskit::TskitError::ValueError { got, .. }) => assert!(got.contains("\"12\"")),
+        _ => panic!("expected a ValueError"),
+    }
+
+    let options = HaplotypeOptions::default()
+        .allele_encoding("12", b'2')
+        .allele_encoding("14", b'4');
+    let haplotypes = treeseq.haplotypes(None, variant_options, &options).unwrap();
+    assert_eq!(haplotypes, [b"G2".to_vec(), b"A4".to_vec(), b"NN".to_vec()]);
+
+    // Samples are written in the order given
+    let samples = [nodes[2], nodes[0]];
+    let haplotypes = treeseq
+        .haplotypes(Some(&samples), variant_options, &options)
+        .unwrap();
+    assert_eq!(haplotypes, [b"NN".to_vec(), b"G2".to_vec()]);
+
+    // Encodings take precedence over single-byte alleles
+    let options = options.allele_encoding("G", b'g').missing_character(b'.');
+    let haplotypes = treeseq.haplotypes(None, variant_options, &options).unwrap();
+    assert_eq!(haplotypes, [b"g2".to_vec(), b"A4".to_vec(), b"..".to_vec()]);
+
+    // Isolated samples carry the ancestral state
+    let haplotypes = treeseq
+        .haplotypes(None, variant_options.isolated_not_missing(), &options)
+        .unwrap();
+    assert_eq!(haplotypes[2], b"A2");
+
+    let options =
+        HaplotypeOptions::default().multi_character_alleles(MultiCharacterAlleles::Replace(b'?'));
+    let haplotypes = treeseq.haplotypes(None, variant_options, &options).unwrap();
+    assert_eq!(haplotypes, [b"G?".to_vec(), b"A?".to_vec(), b"NN".to_vec()]);
+
+    // Alleles may not be written as the missing character
+    for options in [
+        HaplotypeOptions::default().missing_character(b'A'),
+        options.clone().missing_character(b'?'),
+        options.allele_encoding("G", b'N'),
+    ] {
+        assert!(treeseq.haplotypes(None, variant_options, &options).is_err());
+    }
+
+    // Without sites, haplotypes are empty
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let haplotypes = treeseq
+        .haplotypes(None, variant_options, &HaplotypeOptions::default())
+        .unwrap();
+    assert_eq!(haplotypes.len(), treeseq.num_samples().as_usize());
+    assert!(haplotypes.iter().all(|h| h.is_empty()));
+}
+
+#[test]
+fn test_packed_genotypes() {
+    use rand::Rng;
+    use rand::SeedableRng;
+    use tskit::PackedGenotypes;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(17);
+    // More than one word per site
+    let num_samples = 130;
+    let num_sites = 7;
+    for with_missing in [false, true] {
+        let dense = (0..num_sites * num_samples)
+            .map(|_| {
+                if with_missing && rng.gen_bool(0.1) {
+                    -1
+                } else {
+                    rng.gen_range(0..2)
+                }
+            })
+            .collect::<Vec<i32>>();
+        let packed = PackedGenotypes::from_dense(&dense, num_samples).unwrap();
+        assert_eq!(packed.num_sites(), num_sites);
+        assert_eq!(packed.num_samples(), num_samples);
+        assert_eq!(packed.has_missing_data(), with_missing);
+        assert_eq!(packed.bits_per_genotype(), if with_missing { 2 } else { 1 });
+        assert_eq!(packed.to_dense(), dense);
+
+        let row = |i: usize| &dense[i * num_samples..(i + 1) * num_samples];
+        for a in 0..num_sites {
+            let count = |g| row(a).iter().filter(|&&x| x == g).count();
+            assert_eq!(packed.allele_counts(a), Some([count(0), count(1)]));
+            assert_eq!(packed.num_missing(a), Some(count(-1)));
+            for b in 0..num_sites {
+                let mut counts = [0; 4];
+                for (&x, &y) in row(a).iter().zip(row(b)) {
+                    if x >= 0 && y >= 0 {
+                        counts[(2 * x + y) as usize] += 1;
+                    }
+                }
+                assert_eq!(packed.haplotype_counts(a, b), Some(counts));
+            }
+        }
+        assert!(packed.allele_counts(num_sites).is_none());
+        assert!(packed.haplotype_counts(0, num_sites).is_none());
+
+        // Pairwise differences between samples
+        let transposed = packed.transpose();
+        assert_eq!(transposed.num_sites(), num_samples);
+        assert_eq!(transposed.transpose(), packed);
+        let [_, n01, n10, _] = transposed.haplotype_counts(3, 100).unwrap();
+        let differences = (0..num_sites)
+            .filter(|&s| {
+                let (x, y) = (row(s)[3], row(s)[100]);
+                x >= 0 && y >= 0 && x != y
+            })
+            .count();
+        assert_eq!(n01 + n10, differences);
+    }
+
+    // Perfect linkage
+    let packed = PackedGenotypes::from_dense(&[0, 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1], 4).unwrap();
+    assert!((packed.r2(0, 1).unwrap() - 1.0).abs() < 1e-12);
+    assert_eq!(packed.r2(0, 2), Some(0.0));
+
+    assert!(PackedGenotypes::from_dense(&[0, 1, 2, 0], 2).is_err());
+    assert!(PackedGenotypes::from_dense(&[0, 1, 1], 2).is_err());
+    assert!(PackedGenotypes::from_dense(&[0, 1], 0).is_err());
+
+    // From the genotypes of a tree sequence
+    let mut tables = make_small_table_collection_two_trees();
+    let s0 = tables.add_site(100., Some(b"A")).unwrap();
+    let s1 = tables.add_site(700., Some(b"G")).unwrap();
+    tables
+        .add_mutation(s0, 4, MutationId::NULL, 0.5, Some(b"C"))
+        .unwrap();
+    tables
+        .add_mutation(s1, 1, MutationId::NULL, 1.5, Some(b"T"))
+        .unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    let chunk = treeseq
+        .genotype_chunks(10, None, tskit::VariantOptions::default())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let packed = PackedGenotypes::from_chunk(&chunk).unwrap();
+    assert_eq!(packed.to_dense(), chunk.genotypes);
+    assert_eq!(packed.allele_counts(0), Some([3, 1]));
+    assert_eq!(packed.allele_counts(1), Some([1, 3]));
+}
+
+#[test]
+fn test_individuals_population_and_time() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let p0 = tables.add_population().unwrap();
+    let p1 = tables.add_population().unwrap();
+    let i0 = tables.add_individual(0, None, None).unwrap();
+    let i1 = tables.add_individual(0, None, None).unwrap();
+    let no_nodes = tables.add_individual(0, None, None).unwrap();
+    let parent = tables.add_node(0, 2.0, p0, i1).unwrap();
+    for individual in [i0, i0, tskit::IndividualId::NULL] {
+        let c = tables
+            .add_node(tskit::NodeFlags::new_sample(), 0.0, p1, individual)
+            .unwrap();
+        tables.add_edge(0., 100., parent, c).unwrap();
+    }
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let populations = treeseq.individuals_population().unwrap();
+    assert_eq!(populations, [p1, p0, tskit::PopulationId::NULL]);
+    let times = treeseq.individuals_time().unwrap();
+    assert_eq!(times[..2], [0.0, 2.0].map(tskit::Time::from));
+    assert!(f64::from(times[usize::try_from(no_nodes).unwrap()]).is_nan());
+    assert_eq!(
+        treeseq.sample_individuals(),
+        [i0, i0, tskit::IndividualId::NULL]
+    );
+
+    // The nodes of an individual must agree
+    let mut tables = treeseq.dump_tables().unwrap();
+    tables
+        .add_node(tskit::NodeFlags::default(), 1.0, p0, i0)
+        .unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert!(treeseq.individuals_population().is_err());
+    assert!(treeseq.individuals_time().is_err());
+}
+
+#[test]
+fn test_tree_as_newick() {
+    let newick = "((A:1,B:1)ab:0.5,C:1.5)root;";
+    let tables = tskit::TableCollection::from_newick(newick, 10., &Default::default()).unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    let nodes = treeseq.nodes();
+    let labels = |u: tskit::NodeId| {
+        nodes
+            .metadata::<tskit::NewickLabel>(u)
+            .and_then(Result::ok)
+            .map(|l| l.0)
+    };
+    let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    let tree = trees.next().unwrap();
+
+    let options = tskit::NewickOptions::default()
+        .precision(1)
+        .node_labels(labels);
+    assert_eq!(
+        tree.as_newick(&options).unwrap(),
+        "((A:1.0,B:1.0)ab:0.5,C:1.5)root;"
+    );
+    let exported = tree
+        .as_newick(&tskit::NewickOptions::default().node_labels(labels))
+        .unwrap();
+    let roundtrip =
+        tskit::TableCollection::from_newick(&exported, 10., &Default::default()).unwrap();
+    let expected = tskit::TableCollection::from_newick(newick, 10., &Default::default()).unwrap();
+    assert!(roundtrip.equals(&expected, tskit::TableEqualityOptions::default()));
+
+    // A subtree, with the default labels of sample node ids
+    let ab = tree.children(tree.roots_to_vec()[0]).next().unwrap();
+    let options = tskit::NewickOptions::default().precision(0).root(ab);
+    let samples = tree.children(ab).map(|u| u.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        tree.as_newick(&options).unwrap(),
+        format!("({}:1,{}:1);", samples[0], samples[1])
+    );
+    assert!(tree
+        .as_newick(&tskit::NewickOptions::default().root(100))
+        .is_err());
+
+    // The first tree of this fixture has two roots
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    let tree = trees.next().unwrap();
+    assert!(tree.as_newick(&tskit::NewickOptions::default()).is_err());
+}
+
+#[test]
+fn test_newick_writer() {
+    let mut tables = TableCollection::new(100.).unwrap();
+    let r = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let q = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let samples = (0..3)
+        .map(|_| {
+            tables
+                .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    tables.add_edge(0., 100., r, q).unwrap();
+    tables.add_edge(0., 40., r, samples[2]).unwrap();
+    tables.add_edge(40., 100., r, samples[0]).unwrap();
+    tables.add_edge(0., 40., q, samples[0]).unwrap();
+    tables.add_edge(0., 100., q, samples[1]).unwrap();
+    tables.add_edge(40., 100., q, samples[2]).unwrap();
+    tables.full_sort(TableSortOptions::default()).unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 2);
+
+    let options = || tskit::NewickOptions::default().precision(2);
+    let mut expected = vec![];
+    let mut newick = tskit::NewickWriter::newick(vec![], options());
+    let mut nexus = tskit::NewickWriter::nexus(vec![], options(), treeseq.sample_nodes()).unwrap();
+    let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    while let Some(tree) = trees.next() {
+        expected.push(tree.as_newick(&options()).unwrap());
+        newick.write_tree(tree).unwrap();
+        nexus.write_tree(tree).unwrap();
+    }
+    assert_eq!(newick.num_trees(), 2);
+    assert_eq!(nexus.num_trees(), 2);
+
+    let newick = String::from_utf8(newick.finish().unwrap()).unwrap();
+    assert_eq!(newick, format!("{}\n{}\n", expected[0], expected[1]));
+    let nexus = String::from_utf8(nexus.finish().unwrap()).unwrap();
+    assert_eq!(
+        nexus,
+        format!(
+            "#NEXUS\nBEGIN TAXA;\n  DIMENSIONS NTAX=3;\n  TAXLABELS {} {} {};\nEND;\n\
+             BEGIN TREES;\n  TREE t0^40 = [&R] {}\n  TREE t40^100 = [&R] {}\nEND;\n",
+            samples[0], samples[1], samples[2], expected[0], expected[1]
+        )
+    );
+
+    // Trees that cannot be written are not counted
+    let treeseq = treeseq_from_small_table_collection_two_trees();
+    let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    let tree = trees.next().unwrap();
+    let mut writer = tskit::NewickWriter::newick(vec![], options());
+    assert!(writer.write_tree(tree).is_err());
+    assert_eq!(writer.num_trees(), 0);
+    assert!(writer.finish().unwrap().is_empty());
+
+    // Errors from the writer are returned
+    let mut buffer = [0_u8; 4];
+    assert!(matches!(
+        tskit::NewickWriter::nexus(&mut buffer[..], options(), &[]),
+        Err(tskit::TskitError::LibraryError(_))
+    ));
+
+    // Taxa are labelled as in the trees
+    let first = samples[0];
+    let options = tskit::NewickOptions::default()
+        .node_labels(|u| (u == first).then(|| "sample zero".to_string()));
+    let writer = tskit::NewickWriter::nexus(vec![], options, &samples).unwrap();
+    let nexus = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert!(nexus.contains("  DIMENSIONS NTAX=1;\n  TAXLABELS 'sample zero';\n"));
+}
+
+#[test]
+fn test_tree_newick() {
+    // Large enough to need more than the initial buffer
+    let treeseq = tskit::testing::caterpillar(300, 10.).unwrap();
+    let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+    let tree = trees.next().unwrap();
+    let root = tree.roots_to_vec()[0];
+    let samples = treeseq.sample_nodes();
+    let options = tskit::NewickOptions::default()
+        .precision(3)
+        .node_labels(|u| samples.contains(&u).then(|| format!("n{}", u)));
+    let newick = tree.newick(root, 3, tskit::NewickFlags::default()).unwrap();
+    assert!(newick.len() > 1024);
+    assert_eq!(newick, tree.as_newick(&options).unwrap());
+
+    let leaf = samples[0];
+    assert_eq!(
+        tree.newick(leaf, 1, tskit::NewickFlags::LEGACY_MS_LABELS)
+            .unwrap(),
+        format!("{};", leaf.as_usize() + 1)
+    );
+    assert!(tree
+        .newick(
+            treeseq.num_nodes().as_usize() as i32,
+            1,
+            tskit::NewickFlags::default()
+        )
+        .is_err());
+}
+
+#[test]
+fn test_trees_with_sites() {
+    let mut tables = TableCollection::new(100.).unwrap();
+    let r = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let q = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let a = tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    let b = tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.add_edge(0., 100., r, a).unwrap();
+    tables.add_edge(0., 30., r, b).unwrap();
+    tables.add_edge(30., 60., q, b).unwrap();
+    tables.add_edge(60., 100., r, b).unwrap();
+    // Sites at the left of the first and last trees,
+    // no sites in the second tree, and a site without mutations
+    for (position, mutations) in [
+        (0., vec![(a, 1.5), (b, 0.5)]),
+        (10., vec![]),
+        (60., vec![(b, 1.0)]),
+        (99., vec![(a, 0.5)]),
+    ] {
+        let site = tables.add_site(position, Some(b"A")).unwrap();
+        for (node, time) in mutations {
+            tables
+                .add_mutation(site, node, -1, time, Some(b"T"))
+                .unwrap();
+        }
+    }
+    tables.full_sort(TableSortOptions::default()).unwrap();
+    let treeseq = tables
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+        .unwrap();
+    assert_eq!(treeseq.num_trees(), 3);
+
+    let mut intervals = vec![];
+    let mut positions = vec![];
+    let mut num_mutations = 0;
+    let mut trees = treeseq.trees_with_sites(TreeFlags::default()).unwrap();
+    while let Some(bundle) = trees.next() {
+        let (left, right) = bundle.interval();
+        assert_eq!(bundle.tree().interval(), (left, right));
+        intervals.push((f64::from(left), f64::from(right)));
+        let sites = bundle.sites().collect::<Vec<_>>();
+        assert_eq!(sites.len(), bundle.num_sites());
+        assert_eq!(
+            sites.iter().map(|s| s.num_mutations()).sum::<usize>(),
+            bundle.num_mutations()
+        );
+        for site in &sites {
+            assert!(site.position >= left && site.position < right);
+            assert_eq!(treeseq.sites().position(site.id), Some(site.position));
+            let expected = treeseq
+                .mutations_iter()
+                .filter(|m| m.site == site.id)
+                .map(|m| m.id)
+                .collect::<Vec<_>>();
+            assert_eq!(site.mutations().collect::<Vec<_>>(), expected);
+        }
+        num_mutations += bundle.num_mutations();
+        positions.push(
+            sites
+                .iter()
+                .map(|s| f64::from(s.position))
+                .collect::<Vec<_>>(),
+        );
+    }
+    assert_eq!(intervals, [(0., 30.), (30., 60.), (60., 100.)]);
+    assert_eq!(positions, [vec![0., 10.], vec![], vec![60., 99.]]);
+    assert_eq!(num_mutations, treeseq.mutations().num_rows().as_usize());
+}
+
+#[test]
+fn test_sites_at_position() {
+    let mut tables = make_small_table_collection_two_trees();
+    let unknown_time = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    // Sites at the sequence boundaries, without mutations,
+    // and with several mutations
+    for (position, nodes) in [
+        (0., vec![2]),
+        (10., vec![]),
+        (10.5, vec![4, 4, 1]),
+        (500., vec![3]),
+        (999., vec![5, 0]),
+    ] {
+        let site = tables.add_site(position, Some(b"A")).unwrap();
+        for node in nodes {
+            tables
+                .add_mutation(site, node, MutationId::NULL, unknown_time, Some(b"T"))
+                .unwrap();
+        }
+    }
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    for site in treeseq.sites_iter() {
+        assert_eq!(treeseq.site_at_position(site.position), Some(site.id));
+        let expected = treeseq
+            .mutations_iter()
+            .filter(|m| m.site == site.id)
+            .map(|m| m.id)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            treeseq
+                .mutations_at_position(site.position)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+    for position in [5., 10.25, 999.5, -1., 1000.] {
+        assert!(treeseq.site_at_position(position).is_none());
+        assert_eq!(treeseq.mutations_at_position(position).count(), 0);
+    }
+}
+
+#[test]
+fn test_population_summaries() {
+    let mut tables = tskit::TableCollection::new(100.).unwrap();
+    let pops = (0..3)
+        .map(|_| tables.add_population().unwrap())
+        .collect::<Vec<_>>();
+    let individuals = (0..2)
+        .map(|_| tables.add_individual(0, None, None).unwrap())
+        .collect::<Vec<_>>();
+    let root = tables.add_node(0, 3.0, -1, -1).unwrap();
+    let internal = tables.add_node(0, 1.0, -1, -1).unwrap();
+    tables.add_edge(0., 100., root, internal).unwrap();
+    for (i, (pop, ind)) in [
+        (pops[0], individuals[0]),
+        (pops[0], individuals[0]),
+        (pops[0], individuals[1]),
+        (pops[1], tskit::IndividualId::NULL),
+        (pops[1], individuals[1]),
+        (tskit::PopulationId::NULL, tskit::IndividualId::NULL),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let c = tables
+            .add_node(tskit::NodeFlags::new_sample(), 0.0, pop, ind)
+            .unwrap();
+        let parent = if i % 2 == 0 { internal } else { root };
+        tables.add_edge(0., 100., parent, c).unwrap();
+    }
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+    let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let windows = tskit::Windows::breakpoints([0., 50., 100.]);
+    let branch = tskit::StatsMode::Branch;
+    let options = tskit::StatisticsOptions::default();
+    let summaries = treeseq
+        .population_summaries(&windows, branch, options)
+        .unwrap();
+    assert_eq!(summaries.len(), 3);
+    let counts = summaries
+        .iter()
+        .map(|s| (s.population, s.num_samples, s.num_individuals))
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [(pops[0], 3, 2), (pops[1], 2, 1), (pops[2], 0, 0)]);
+
+    for (p, summary) in summaries.iter().enumerate().take(2) {
+        let samples = treeseq
+            .sample_nodes()
+            .iter()
+            .copied()
+            .filter(|&u| treeseq.nodes().population(u) == Some(pops[p]))
+            .collect::<Vec<_>>();
+        let expected = treeseq
+            .diversity(&[&samples].into_iter().collect(), &windows, branch, options)
+            .unwrap();
+        assert_eq!(summary.diversity, expected);
+    }
+    assert_eq!(summaries[2].diversity.len(), 2);
+    assert!(summaries[2].diversity.iter().all(|d| d.is_nan()));
+    assert!(treeseq
+        .population_summaries(&windows, tskit::StatsMode::Node, options)
+        .is_err());
+    assert!(treeseq
+        .population_summaries(&tskit::Windows::intervals([(0., 200.)]), branch, options)
+        .is_err());
+}
+
+#[test]
+fn test_testing_trees() {
+    use tskit::testing;
+
+    for num_samples in [1, 2, 5, 8] {
+        for ts in [
+            testing::balanced(num_samples, 10.).unwrap(),
+            testing::caterpillar(num_samples, 10.).unwrap(),
+            testing::random_binary(num_samples, 10., 7).unwrap(),
+        ] {
+            assert_eq!(ts.num_samples().as_usize(), num_samples);
+            assert_eq!(ts.num_trees(), tskit::SizeType::from(1));
+            assert_eq!(ts.nodes().num_rows().as_usize(), 2 * num_samples - 1);
+            let mut trees = ts.tree_iterator(TreeFlags::default()).unwrap();
+            let tree = trees.next().unwrap();
+            assert_eq!(tree.roots_to_vec().len(), 1);
+            assert!(tree
+                .traverse_nodes(NodeTraversalOrder::Preorder)
+                .all(|u| u.as_usize() < num_samples || tree.children(u).count() == 2));
+        }
+    }
+
+    // The heights of the trees of 5 samples
+    let root_time = |ts: tskit::TreeSequence| *ts.nodes().time_slice().last().unwrap();
+    assert_eq!(root_time(testing::balanced(5, 1.).unwrap()), 3.0);
+    assert_eq!(root_time(testing::caterpillar(5, 1.).unwrap()), 4.0);
+
+    let times = |seed| {
+        let ts = testing::random_binary(20, 1., seed).unwrap();
+        (
+            ts.nodes().time_slice().to_vec(),
+            ts.edges().child_slice().to_vec(),
+        )
+    };
+    assert_eq!(times(1), times(1));
+    assert_ne!(times(1), times(2));
+
+    assert!(testing::balanced(0, 1.).is_err());
+    assert!(testing::random_binary(3, -1., 0).is_err());
+}
+
+#[test]
+fn test_treeseq_counts() {
+    let mut tables = TableCollection::new(10.).unwrap();
+    let pop = tables.add_population().unwrap();
+    let ind = tables.add_individual(0, None, None).unwrap();
+    let parent = tables.add_node(0, 1.0, pop, -1).unwrap();
+    for _ in 0..2 {
+        let child = tables
+            .add_node(NodeFlags::new_sample(), 0.0, pop, ind)
+            .unwrap();
+        tables.add_edge(0., 10., parent, child).unwrap();
+    }
+    let site = tables.add_site(5., Some(b"A")).unwrap();
+    tables.add_mutation(site, 1, -1, 0.5, Some(b"G")).unwrap();
+    tables.add_migration((0., 10.), 1, (pop, pop), 0.5).unwrap();
+    tables.full_sort(TableSortOptions::default()).unwrap();
+    tables.build_index().unwrap();
+    let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let counts = [
+        (ts.num_nodes(), ts.nodes().num_rows(), 3),
+        (ts.num_edges(), ts.edges().num_rows(), 2),
+        (ts.num_sites(), ts.sites().num_rows(), 1),
+        (ts.num_mutations(), ts.mutations().num_rows(), 1),
+        (ts.num_individuals(), ts.individuals().num_rows(), 1),
+        (ts.num_populations(), ts.populations().num_rows(), 1),
+        (ts.num_migrations(), ts.migrations().num_rows(), 1),
+        (ts.num_provenances(), tskit::SizeType::from(0), 0),
+    ];
+    for (count, num_rows, expected) in counts {
+        assert_eq!(count, num_rows);
+        assert_eq!(count, expected);
+    }
+}
+
+#[cfg(feature = "petgraph")]
+#[test]
+fn test_petgraph() {
+    let ts = tskit::testing::random_binary(6, 10., 3).unwrap();
+    let mut trees = ts.tree_iterator(TreeFlags::default()).unwrap();
+    let tree = trees.next().unwrap();
+    let graph = tree.to_petgraph().unwrap();
+    assert_eq!(graph.node_count(), 11);
+    for edge in graph.edge_indices() {
+        let (parent, child) = graph.edge_endpoints(edge).unwrap();
+        assert_eq!(tree.parent(graph[child].id), Some(graph[parent].id));
+        assert!(graph[parent].time > graph[child].time);
+        assert_eq!(graph[edge].span(), 10.0);
+    }
+    let samples = graph
+        .node_weights()
+        .filter(|node| node.flags.is_sample())
+        .count();
+    assert_eq!(samples, 6);
+
+    // The whole ARG round trips
+    let arg = ts.to_petgraph(0., 10.).unwrap();
+    assert_eq!(arg.edge_count(), ts.num_edges().as_usize());
+    let tables = TableCollection::from_petgraph(&arg, 10.).unwrap();
+    let roundtrip = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert_eq!(roundtrip.num_trees(), ts.num_trees());
+    assert_eq!(roundtrip.nodes().time_slice(), ts.nodes().time_slice());
+
+    assert!(ts.to_petgraph(5., 5.).is_err());
+    assert!(ts.to_petgraph(0., 11.).is_err());
+}
+
+#[test]
+fn test_allele_counts() {
+    let mut tables = TableCollection::new(100.).unwrap();
+    let r = tables.add_node(0, 3.0, -1, -1).unwrap();
+    let a = tables.add_node(0, 2.0, -1, -1).unwrap();
+    let b = tables.add_node(0, 1.0, -1, -1).unwrap();
+    let s = (0..4)
+        .map(|_| {
+            tables
+                .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for (left, right, parent, child) in [
+        (0., 100., r, a),
+        (0., 50., r, s[3]),
+        (50., 100., r, s[2]),
+        (0., 100., a, b),
+        (0., 50., a, s[2]),
+        (50., 100., a, s[3]),
+        (0., 100., b, s[0]),
+        (0., 100., b, s[1]),
+    ] {
+        tables.add_edge(left, right, parent, child).unwrap();
+    }
+    // A back mutation
+    let site = tables.add_site(10., Some(b"A")).unwrap();
+    let m = tables.add_mutation(site, a, -1, 2.5, Some(b"T")).unwrap();
+    tables.add_mutation(site, b, m, 1.5, Some(b"A")).unwrap();
+    // A third allele
+    let site = tables.add_site(60., Some(b"A")).unwrap();
+    tables.add_mutation(site, a, -1, 2.5, Some(b"T")).unwrap();
+    tables
+        .add_mutation(site, s[3], -1, 0.5, Some(b"G"))
+        .unwrap();
+    // A silent mutation
+    let site = tables.add_site(70., Some(b"A")).unwrap();
+    tables.add_mutation(site, b, -1, 1.5, Some(b"A")).unwrap();
+    tables.add_site(80., Some(b"A")).unwrap();
+    tables.full_sort(TableSortOptions::default()).unwrap();
+    tables.build_index().unwrap();
+    let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let sample_sets = [&s[..2], &s[2..], &s[3..]];
+    let sets = sample_sets.iter().collect::<tskit::SampleSets>();
+    let counts = ts
+        .allele_counts(&sets, &tskit::Windows::breakpoints([0., 65., 100.]))
+        .unwrap();
+    assert_eq!(counts.num_sites(), 4);
+    assert_eq!(counts.num_windows(), 2);
+    assert_eq!(counts.counts, [0, 1, 0, 2, 1, 1, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(counts.window_offsets, [0, 2, 4]);
+
+    // The counts of the genotypes
+    let mut variants = ts.variants(None, tskit::VariantOptions::default()).unwrap();
+    let mut site = 0;
+    while let Some(variant) = variants.next() {
+        for (k, set) in sample_sets.iter().enumerate() {
+            let derived = set
+                .iter()
+                .filter(|&&u| variant.genotypes()[u.as_usize() - 3] != 0)
+                .count();
+            assert_eq!(counts.site_counts(site).unwrap()[k] as usize, derived);
+        }
+        site += 1;
+    }
+
+    // Overlapping windows repeat sites, and masked sites are left out
+    let mask = tskit::GenomeMask::new([(55., 65.)]).unwrap();
+    let windows = tskit::Windows::intervals([(50., 100.), (0., 75.)]).mask(mask);
+    let masked = ts.allele_counts(&sets, &windows).unwrap();
+    let ids = |sites: &[usize]| {
+        sites
+            .iter()
+            .map(|&s| tskit::SiteId::from(s as i32))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(masked.window_sites(0), Some(&ids(&[2, 3])[..]));
+    assert_eq!(masked.window_sites(1), Some(&ids(&[0, 2])[..]));
+    let expected = [counts.site_counts(0), counts.site_counts(2)]
+        .map(Option::unwrap)
+        .concat();
+    assert_eq!(masked.window_counts(1), Some(&expected[..]));
+
+    let invalid = tskit::SampleSets::new().add_set([7]);
+    assert!(ts
+        .allele_counts(&invalid, &tskit::Windows::genome())
+        .is_err());
+    assert!(matches!(
+        ts.allele_counts(&sets, &tskit::Windows::intervals([(0., 150.)])),
+        Err(tskit::TskitError::RangeError(_))
+    ));
+}
+
+#[cfg(feature = "tools")]
+#[test]
+fn test_tools() {
+    let mut tables = TableCollection::new(100.).unwrap();
+    let p = tables.add_node(0, 1.0, -1, -1).unwrap();
+    for _ in 0..2 {
+        let individual = tables.add_individual(0, None, None).unwrap();
+        for _ in 0..2 {
+            let c = tables
+                .add_node(NodeFlags::new_sample(), 0.0, -1, individual)
+                .unwrap();
+            tables.add_edge(0., 100., p, c).unwrap();
+        }
+    }
+    let site = tables.add_site(10., Some(b"A")).unwrap();
+    tables.add_mutation(site, 2, -1, 0.5, Some(b"T")).unwrap();
+    let site = tables.add_site(20., Some(b"A")).unwrap();
+    tables.add_mutation(site, 3, -1, 0.5, Some(b"G")).unwrap();
+    tables.add_mutation(site, 4, -1, 0.5, Some(b"C")).unwrap();
+    tables.build_index().unwrap();
+    let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+    let summary = tskit::tools::summary(&ts);
+    assert_eq!(summary.num_individuals, 2);
+    assert_eq!(summary.num_mutations, 3);
+
+    let report = tskit::tools::traversal_report(&ts).unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].num_roots, 1);
+    assert_eq!(report[0].max_depth, 1);
+    assert_eq!(
+        report[0].to_string().split('\t').count(),
+        tskit::tools::TreeReport::HEADER.split('\t').count()
+    );
+
+    // Diploid individuals
+    let mut vcf = vec![];
+    tskit::tools::write_vcf(&ts, "1", &mut vcf).unwrap();
+    let vcf = String::from_utf8(vcf).unwrap();
+    let records = vcf
+        .lines()
+        .skip_while(|l| l.starts_with("##"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        records,
+        [
+            "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\ttsk_0\ttsk_1",
+            "1\t10\t0\tA\tT\t.\tPASS\t.\tGT\t0|1\t0|0",
+            "1\t20\t1\tA\tG,C\t.\tPASS\t.\tGT\t0|0\t1|2",
+        ]
+    );
+
+    // The program writes the same VCF
+    let treefile = "test_tools.trees";
+    ts.dump(treefile, tskit::TableOutputOptions::default())
+        .unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_tskit-tools"))
+        .args(["vcf", treefile])
+        .output()
+        .unwrap();
+    let summary = std::process::Command::new(env!("CARGO_BIN_EXE_tskit-tools"))
+        .args(["summary", treefile])
+        .output()
+        .unwrap();
+    std::fs::remove_file(treefile).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), vcf);
+    assert!(String::from_utf8(summary.stdout)
+        .unwrap()
+        .contains("individuals 2\n"));
+
+    // Sites that round to the same VCF position
+    let mut tables = ts.dump_tables().unwrap();
+    tables.add_site(20.2, Some(b"A")).unwrap();
+    tables
+        .full_sort(tskit::TableSortOptions::default())
+        .unwrap();
+    tables.build_index().unwrap();
+    let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert!(matches!(
+        tskit::tools::write_vcf(&ts, "1", &mut vec![]),
+        Err(tskit::TskitError::ValueError { .. })
+    ));
+
+    // A sample node without an individual
+    let mut tables = TableCollection::new(100.).unwrap();
+    tables.add_individual(0, None, None).unwrap();
+    tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, 0)
+        .unwrap();
+    tables
+        .add_node(NodeFlags::new_sample(), 0.0, -1, -1)
+        .unwrap();
+    tables.build_index().unwrap();
+    let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    assert!(matches!(
+        tskit::tools::write_vcf(&ts, "1", &mut vec![]),
+        Err(tskit::TskitError::ValueError { .. })
+    ));
+}